@@ -0,0 +1,275 @@
+//! `ast-surgeon apply`: run operations on files, write them back or show
+//! what would change.
+
+use ast_surgeon_core::operations::{Operation, OperationError};
+use ast_surgeon_lang::registry::detect_language;
+use ast_surgeon_lang::style::resolve_style_config;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Exit code when `--check` finds files that would change.
+pub const EXIT_CHANGES: u8 = 1;
+/// Exit code when an operation or file failed.
+pub const EXIT_ERROR: u8 = 2;
+
+pub struct ApplyOptions {
+    pub dry_run: bool,
+    pub check: bool,
+}
+
+impl ApplyOptions {
+    fn writes(&self) -> bool {
+        !self.dry_run && !self.check
+    }
+}
+
+/// What happened to one file.
+#[derive(Debug, PartialEq)]
+pub enum FileOutcome {
+    Unchanged,
+    /// The new content, plus warnings from the operations.
+    Changed {
+        content: String,
+        warnings: Vec<String>,
+    },
+    /// The operations found no target in a file given on the
+    /// command line.
+    Skipped(String),
+}
+
+/// Run the operations in `ops_json` and report per file on stdout/stderr.
+/// Returns the exit code.
+pub fn run(ops_json: &str, files: &[PathBuf], options: &ApplyOptions) -> Result<u8, String> {
+    let targets = plan(ops_json, files)?;
+    let mut failed = false;
+    let mut changed = false;
+
+    for (path, target) in &targets {
+        let display = path.display();
+        let source = match std::fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("{display}: Failed to read: {e}");
+                failed = true;
+                continue;
+            }
+        };
+        match apply_file(path, &source, &target.ops, target.from_args) {
+            Ok(FileOutcome::Unchanged) => {}
+            Ok(FileOutcome::Skipped(reason)) => eprintln!("{display}: skipped: {reason}"),
+            Ok(FileOutcome::Changed { content, warnings }) => {
+                changed = true;
+                for warning in warnings {
+                    eprintln!("{display}: warning: {warning}");
+                }
+                if options.dry_run {
+                    print!("{}", unified_diff(path, &source, &content));
+                }
+                if options.check {
+                    eprintln!("{display}: would change");
+                }
+                if options.writes() {
+                    if let Err(e) = std::fs::write(path, &content) {
+                        eprintln!("{display}: Failed to write: {e}");
+                        failed = true;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{display}: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    Ok(if failed {
+        EXIT_ERROR
+    } else if options.check && changed {
+        EXIT_CHANGES
+    } else {
+        0
+    })
+}
+
+/// Operations for one file, and whether the file came from the command
+/// line (where a missing target is a skip, not an error).
+pub struct Target {
+    pub ops: Vec<Operation>,
+    pub from_args: bool,
+}
+
+/// Operations that span several files and only run through the
+/// `fe_surgeon` MCP tool.
+const CROSS_FILE_OPS: &[&str] = &[
+    "rename_css_module_class",
+    "extract_i18n",
+    "migrate_to_typescript",
+];
+
+/// Group operations by file. Operations with a `file` field run on that
+/// file; the others run on every file in `files`. A `file` field is a
+/// plain path: globs are left to the shell, via `files`.
+pub fn plan(ops_json: &str, files: &[PathBuf]) -> Result<BTreeMap<PathBuf, Target>, String> {
+    let value: Value =
+        serde_json::from_str(ops_json).map_err(|e| format!("Invalid operations JSON: {e}"))?;
+    let op_values = match value {
+        Value::Array(ops) => ops,
+        Value::Object(mut fields) => match fields.remove("operations") {
+            Some(Value::Array(ops)) => ops,
+            _ => return Err("Expected an array of operations or an `operations` field".into()),
+        },
+        _ => return Err("Expected an array of operations or an `operations` field".into()),
+    };
+    if op_values.is_empty() {
+        return Err("No operations given".into());
+    }
+
+    let mut targets: BTreeMap<PathBuf, Target> = BTreeMap::new();
+    for (index, op_value) in op_values.into_iter().enumerate() {
+        let file = op_value
+            .get("file")
+            .and_then(|f| f.as_str())
+            .map(PathBuf::from);
+        if let Some(name) = op_value.get("op").and_then(|o| o.as_str()) {
+            if CROSS_FILE_OPS.contains(&name) {
+                return Err(format!(
+                    "Operation {index}: `{name}` edits several files and is only available \
+                     through the fe_surgeon MCP tool"
+                ));
+            }
+        }
+        if let Some(file) = &file {
+            let pattern = file.to_string_lossy();
+            if pattern.contains(['*', '?', '[', '{']) && !file.is_file() {
+                return Err(format!(
+                    "Operation {index}: `file` globs are not expanded; leave out `file` \
+                     and pass the files on the command line instead"
+                ));
+            }
+        }
+        let op: Operation = serde_json::from_value(op_value).map_err(|e| {
+            format!(
+                "Operation {index}: {e} (custom operations and recipes are only \
+                 available through the fe_surgeon MCP tool)"
+            )
+        })?;
+        match file {
+            Some(file) => add_op(&mut targets, file, op, false),
+            None if files.is_empty() => {
+                return Err(format!(
+                    "Operation {index} has no `file` and no files were given"
+                ));
+            }
+            None => {
+                for file in files {
+                    add_op(&mut targets, file.clone(), op.clone(), true);
+                }
+            }
+        }
+    }
+    Ok(targets)
+}
+
+/// Apply operations to one file's source.
+pub fn apply_file(
+    path: &Path,
+    source: &str,
+    ops: &[Operation],
+    from_args: bool,
+) -> Result<FileOutcome, String> {
+    let lang = detect_language(&path.to_string_lossy()).map_err(|e| e.to_string())?;
+    let (style, style_warnings) = resolve_style_config(path, None);
+    match ast_surgeon_lang::execute_operations(source, lang, ops, &style) {
+        Ok(result) if result.content == source => Ok(FileOutcome::Unchanged),
+        Ok(result) => Ok(FileOutcome::Changed {
+            content: result.content,
+            warnings: style_warnings.into_iter().chain(result.warnings).collect(),
+        }),
+        Err(OperationError::TargetNotFound { description }) if from_args => Ok(
+            FileOutcome::Skipped(format!("target not found: {description}")),
+        ),
+        Err(OperationError::Blocked { warnings }) => Err(format!(
+            "Operation blocked, no changes made: {}",
+            warnings.join("; ")
+        )),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// --- Helper functions ---
+
+fn add_op(targets: &mut BTreeMap<PathBuf, Target>, file: PathBuf, op: Operation, from_args: bool) {
+    let target = targets.entry(file).or_insert(Target {
+        ops: vec![],
+        from_args,
+    });
+    // An explicitly named file makes a missing target an error again
+    target.from_args &= from_args;
+    target.ops.push(op);
+}
+
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let name = path.to_string_lossy();
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{name}"), &format!("b/{name}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_and_apply_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.ts");
+        let b = dir.path().join("b.ts");
+        let original = "function load() {\n  return 1;\n}\n";
+        std::fs::write(&a, original).unwrap();
+        std::fs::write(&b, "const x = 1;\n").unwrap();
+
+        let ops = r#"{"operations": [{"op": "make_async", "function_name": "load"}]}"#;
+        let targets = plan(ops, &[a.clone(), b.clone()]).unwrap();
+        assert_eq!(targets.len(), 2);
+
+        let source = std::fs::read_to_string(&a).unwrap();
+        let outcome = apply_file(&a, &source, &targets[&a].ops, true).unwrap();
+        let FileOutcome::Changed { content, .. } = outcome else {
+            panic!("expected a change, got {outcome:?}");
+        };
+        assert_eq!(content, "async function load() {\n  return 1;\n}\n");
+        assert_eq!(
+            unified_diff(Path::new("a.ts"), &source, &content),
+            "--- a/a.ts\n+++ b/a.ts\n@@ -1,3 +1,3 @@\n-function load() {\n+async function load() {\n   return 1;\n }\n"
+        );
+
+        let source = std::fs::read_to_string(&b).unwrap();
+        let outcome = apply_file(&b, &source, &targets[&b].ops, true).unwrap();
+        assert!(matches!(outcome, FileOutcome::Skipped(_)));
+        assert!(apply_file(&b, &source, &targets[&b].ops, false).is_err());
+
+        let check = ApplyOptions {
+            dry_run: false,
+            check: true,
+        };
+        assert_eq!(run(ops, &[a.clone(), b], &check), Ok(EXIT_CHANGES));
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), original);
+
+        assert!(plan(r#"[{"op": "make_async", "function_name": "f"}]"#, &[]).is_err());
+    }
+
+    #[test]
+    fn test_plan_rejects_what_only_the_mcp_tool_runs() {
+        let glob = r#"[{"op": "make_async", "file": "src/*.ts", "function_name": "f"}]"#;
+        assert!(matches!(plan(glob, &[]), Err(e) if e.contains("globs are not expanded")));
+
+        let cross_file = r#"[{"op": "extract_i18n", "file": "src/App.tsx", "namespace": "app"}]"#;
+        assert!(matches!(plan(cross_file, &[]), Err(e) if e.contains("fe_surgeon")));
+
+        let custom = r#"[{"op": "wrap_in_memo", "file": "src/App.tsx"}]"#;
+        assert!(matches!(plan(custom, &[]), Err(e) if e.contains("fe_surgeon")));
+    }
+}
@@ -0,0 +1,113 @@
+//! ast-surgeon: run surgeon operations and queries from the command line,
+//! e.g. in pre-commit hooks, without an MCP client.
+
+use clap::Parser;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod apply;
+mod query;
+
+#[derive(Parser)]
+#[command(
+    name = "ast-surgeon",
+    about = "AST-level code operations for frontend files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(clap::Subcommand)]
+enum Commands {
+    /// Apply operations to files
+    ///
+    /// Operations on the same file apply in one pass, so a later one does
+    /// not see the result of an earlier one. A `file` field is a plain path;
+    /// to run an operation on many files, leave out `file` and pass them as
+    /// arguments (globs are expanded by the shell). Custom operations,
+    /// recipes and cross-file operations (rename_css_module_class,
+    /// extract_i18n, migrate_to_typescript) are only available through the
+    /// fe_surgeon MCP tool.
+    Apply {
+        /// Operations JSON (an array, or an object with `operations`);
+        /// `-` or omitted reads stdin
+        #[arg(long, short)]
+        ops: Option<PathBuf>,
+
+        /// Print a unified diff instead of writing files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write nothing; exit with 1 if any file would change
+        #[arg(long)]
+        check: bool,
+
+        /// Files to apply operations without a `file` field to
+        files: Vec<PathBuf>,
+    },
+    /// Print the captures of a tree-sitter query
+    Query {
+        /// Query in tree-sitter S-expression syntax
+        query: String,
+
+        /// Run the query inside tagged templates of this language instead,
+        /// e.g. `css` for styled-components / emotion styles. In Vue and
+        /// Svelte files the query runs on `<script>` blocks, or on the
+        /// blocks in this language (`css` for `<style>`, `html` for
+        /// `<template>`)
+        #[arg(long)]
+        embedded: Option<String>,
+
+        /// Print captures as JSON
+        #[arg(long)]
+        json: bool,
+
+        files: Vec<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let code = match cli.command {
+        Commands::Apply {
+            ops,
+            dry_run,
+            check,
+            files,
+        } => read_ops(ops).and_then(|text| {
+            let options = apply::ApplyOptions { dry_run, check };
+            apply::run(&text, &files, &options)
+        }),
+        Commands::Query {
+            query,
+            embedded,
+            json,
+            files,
+        } => query::run(&query, &files, embedded.as_deref(), json),
+    };
+
+    match code {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("ast-surgeon: {e}");
+            ExitCode::from(apply::EXIT_ERROR)
+        }
+    }
+}
+
+fn read_ops(path: Option<PathBuf>) -> Result<String, String> {
+    match path {
+        Some(path) if path.as_os_str() != "-" => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display())),
+        _ => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Failed to read operations from stdin: {e}"))?;
+            Ok(text)
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! `ast-surgeon query`: print the captures of a tree-sitter query, one per
+//! line as `file:line:column: @capture: text`, or as JSON.
+
+use ast_surgeon_lang::registry::detect_language;
+use ast_surgeon_lang::SupportedLanguage;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Capture {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub capture: String,
+    pub text: String,
+}
+
+/// Run `query` on every file, or inside their `embedded` templates.
+/// Returns 0 when something matched and 1 otherwise, like grep.
+pub fn run(
+    query: &str,
+    files: &[PathBuf],
+    embedded: Option<&str>,
+    json: bool,
+) -> Result<u8, String> {
+    let embedded = embedded
+        .map(SupportedLanguage::from_str)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let mut captures = Vec::new();
+    let mut failed = false;
+    for path in files {
+        match std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read: {e}"))
+            .and_then(|source| query_source(&path.to_string_lossy(), &source, query, embedded))
+        {
+            Ok(found) => captures.extend(found),
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                failed = true;
+            }
+        }
+    }
+
+    if json {
+        let output = serde_json::to_string_pretty(&captures).map_err(|e| e.to_string())?;
+        println!("{output}");
+    } else {
+        for c in &captures {
+            let first_line = c.text.lines().next().unwrap_or_default();
+            println!(
+                "{}:{}:{}: @{}: {first_line}",
+                c.file, c.line, c.column, c.capture
+            );
+        }
+    }
+
+    Ok(if failed {
+        crate::apply::EXIT_ERROR
+    } else if captures.is_empty() {
+        1
+    } else {
+        0
+    })
+}
+
+/// Captures of `query` in one file's source, in document order. Lines and
+/// columns are 1-based.
+pub fn query_source(
+    file: &str,
+    source: &str,
+    query: &str,
+    embedded: Option<SupportedLanguage>,
+) -> Result<Vec<Capture>, String> {
+    let lang = detect_language(file).map_err(|e| e.to_string())?;
+    let captures =
+        ast_surgeon_lang::query_source(source, lang, query, embedded).map_err(|e| e.to_string())?;
+    Ok(captures
+        .into_iter()
+        .map(|c| Capture {
+            file: file.to_string(),
+            line: c.line,
+            column: c.column,
+            capture: c.name,
+            text: c.text,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_source_reports_captures_in_order() {
+        let source = "import { a } from './a';\nconsole.log(a);\nconsole.warn(a);\n";
+        let captures = query_source(
+            "src/app.ts",
+            source,
+            "(call_expression function: (member_expression property: (property_identifier) @method))",
+            None,
+        )
+        .unwrap();
+        let found: Vec<(usize, usize, &str)> = captures
+            .iter()
+            .map(|c| (c.line, c.column, c.text.as_str()))
+            .collect();
+        assert_eq!(found, vec![(2, 9, "log"), (3, 9, "warn")]);
+
+        assert!(query_source("src/app.ts", source, "(not_a_node) @x", None).is_err());
+    }
+
+    #[test]
+    fn test_query_embedded_css() {
+        let source = "const Box = styled.div`\n  color: red;\n  gap: ${(p) => p.gap};\n`\n";
+        let captures = query_source(
+            "src/Box.tsx",
+            source,
+            "(declaration (property_name) @property)",
+            Some(SupportedLanguage::Css),
+        )
+        .unwrap();
+        let found: Vec<(usize, usize, &str)> = captures
+            .iter()
+            .map(|c| (c.line, c.column, c.text.as_str()))
+            .collect();
+        assert_eq!(found, vec![(2, 3, "color"), (3, 3, "gap")]);
+    }
+}
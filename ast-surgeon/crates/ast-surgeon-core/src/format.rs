@@ -1,14 +1,125 @@
-//! Formatting preservation: indentation inference and comment attachment.
+//! Formatting preservation: the style profile new code follows, and
+//! comment attachment.
+//!
+//! A [`StyleProfile`] is resolved per file: settings from the project's
+//! formatter configuration ([`StyleConfig`], read by the host and passed to
+//! [`crate::execute_operations`]) win, everything else is inferred from the
+//! source.
 
+use serde::{Deserialize, Serialize};
 use tree_sitter::Node;
 
 /// Detected indentation style for a file.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndentStyle {
     Spaces(u8),
     Tabs,
 }
 
+/// Whether multi-line lists end with a comma (Prettier's `trailingComma`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingCommas {
+    None,
+    /// Objects and arrays, not parameter lists.
+    Es5,
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+/// How new code is formatted in one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleProfile {
+    pub indent: IndentStyle,
+    pub quote: char,
+    pub semicolons: bool,
+    pub trailing_commas: TrailingCommas,
+    pub print_width: usize,
+    pub line_ending: LineEnding,
+}
+
+impl StyleProfile {
+    /// Infer every setting from the source alone.
+    pub fn infer(source: &str) -> Self {
+        StyleConfig::default().resolve(source)
+    }
+
+    /// `";"` or `""`, for statement ends.
+    pub fn semi(&self) -> &'static str {
+        if self.semicolons {
+            ";"
+        } else {
+            ""
+        }
+    }
+}
+
+/// Formatter settings found in project configuration (`.editorconfig`,
+/// Prettier, Biome). Unset fields are inferred from the source.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleConfig {
+    pub indent: Option<IndentStyle>,
+    pub quote: Option<char>,
+    pub semicolons: Option<bool>,
+    pub trailing_commas: Option<TrailingCommas>,
+    pub print_width: Option<usize>,
+    pub line_ending: Option<LineEnding>,
+}
+
+impl StyleConfig {
+    /// Fill the unset fields from `source`. A file that already has line
+    /// breaks keeps its own line ending, so a CRLF checkout of an LF
+    /// project does not end up with mixed endings.
+    pub fn resolve(&self, source: &str) -> StyleProfile {
+        let line_ending = if source.contains('\n') {
+            infer_line_ending(source)
+        } else {
+            self.line_ending.unwrap_or(LineEnding::Lf)
+        };
+        StyleProfile {
+            indent: self
+                .indent
+                .clone()
+                .unwrap_or_else(|| infer_indent_style(source)),
+            quote: self.quote.unwrap_or_else(|| infer_quote(source)),
+            semicolons: self.semicolons.unwrap_or_else(|| infer_semicolons(source)),
+            trailing_commas: self
+                .trailing_commas
+                .unwrap_or_else(|| infer_trailing_commas(source)),
+            print_width: self.print_width.unwrap_or(DEFAULT_PRINT_WIDTH),
+            line_ending,
+        }
+    }
+}
+
+/// Prettier's and Biome's default line width.
+pub const DEFAULT_PRINT_WIDTH: usize = 80;
+
+/// Convert the bare `\n` line breaks of generated text to `\r\n`.
+pub fn to_line_ending(text: &str, line_ending: LineEnding) -> String {
+    match line_ending {
+        LineEnding::Lf => text.to_string(),
+        LineEnding::Crlf => {
+            let mut result = String::with_capacity(text.len());
+            let mut previous = None;
+            for c in text.chars() {
+                if c == '\n' && previous != Some('\r') {
+                    result.push('\r');
+                }
+                result.push(c);
+                previous = Some(c);
+            }
+            result
+        }
+    }
+}
+
 /// Indentation context at a specific insertion point.
 #[derive(Debug, Clone)]
 pub struct IndentContext {
@@ -49,6 +160,7 @@ pub fn infer_indent_style(source: &str) -> IndentStyle {
             .iter()
             .enumerate()
             .skip(1)
+            .filter(|(_, &count)| count > 0)
             .max_by_key(|(_, &count)| count)
             .map(|(i, _)| i as u8)
             .unwrap_or(2);
@@ -120,6 +232,53 @@ fn count_leading_spaces(line: &str) -> usize {
     line.chars().take_while(|c| *c == ' ').count()
 }
 
+/// The quote used by the file's imports; single when there are none.
+fn infer_quote(source: &str) -> char {
+    let single = source.matches("from '").count();
+    let double = source.matches("from \"").count();
+    if single >= double {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// Whether any of the first 30 lines ends with a semicolon.
+fn infer_semicolons(source: &str) -> bool {
+    source
+        .lines()
+        .take(30)
+        .any(|l| l.trim_end().ends_with(';'))
+}
+
+/// `All` when a multi-line call or parameter list ends with a comma,
+/// `Es5` when only objects or arrays do.
+fn infer_trailing_commas(source: &str) -> TrailingCommas {
+    let mut closers = Vec::new();
+    for (i, _) in source.match_indices(",\n") {
+        let next = source[i + 2..].trim_start();
+        if let Some(c) = next.chars().next().filter(|c| matches!(c, '}' | ']' | ')')) {
+            closers.push(c);
+        }
+    }
+    if closers.contains(&')') {
+        TrailingCommas::All
+    } else if closers.is_empty() {
+        TrailingCommas::None
+    } else {
+        TrailingCommas::Es5
+    }
+}
+
+fn infer_line_ending(source: &str) -> LineEnding {
+    let crlf = source.matches("\r\n").count();
+    if crlf * 2 > source.matches('\n').count() {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    }
+}
+
 /// Span of a comment in source.
 #[derive(Debug, Clone)]
 pub struct CommentSpan {
@@ -214,6 +373,12 @@ mod tests {
         assert_eq!(infer_indent_style(source), IndentStyle::Tabs);
     }
 
+    #[test]
+    fn test_infer_defaults_to_2_spaces_without_indentation() {
+        let source = "const a = 1;\nconst b = 2;\n";
+        assert_eq!(infer_indent_style(source), IndentStyle::Spaces(2));
+    }
+
     #[test]
     fn test_extract_line_prefix() {
         let source = "  const x = 1;\n    const y = 2;";
@@ -233,4 +398,46 @@ mod tests {
         assert_eq!(indent_deeper("  ", &IndentStyle::Spaces(2)), "    ");
         assert_eq!(indent_deeper("\t", &IndentStyle::Tabs), "\t\t");
     }
+
+    #[test]
+    fn test_infer_single_quotes() {
+        let source = "import { a } from './a';\nimport { b } from './b';";
+        assert_eq!(StyleProfile::infer(source).quote, '\'');
+    }
+
+    #[test]
+    fn test_infer_double_quotes() {
+        let source = "import { a } from \"./a\";\nimport { b } from \"./b\";";
+        assert_eq!(StyleProfile::infer(source).quote, '"');
+    }
+
+    #[test]
+    fn test_infer_semicolons() {
+        let source = "import { a } from './a';\nconst x = 1;\nconst y = 2;";
+        assert!(StyleProfile::infer(source).semicolons);
+    }
+
+    #[test]
+    fn test_style_config_overrides_inference() {
+        let source = "import { a } from \"a\"\nconst x = {\n\ta,\n}\n";
+        let inferred = StyleProfile::infer(source);
+        assert_eq!(inferred.indent, IndentStyle::Tabs);
+        assert_eq!(inferred.quote, '"');
+        assert!(!inferred.semicolons);
+        assert_eq!(inferred.trailing_commas, TrailingCommas::Es5);
+
+        let config = StyleConfig {
+            quote: Some('\''),
+            semicolons: Some(true),
+            line_ending: Some(LineEnding::Crlf),
+            ..StyleConfig::default()
+        };
+        let profile = config.resolve(source);
+        assert_eq!(profile.quote, '\'');
+        assert_eq!(profile.semi(), ";");
+        assert_eq!(profile.indent, IndentStyle::Tabs);
+        // The file's own LF line breaks win over the configured CRLF
+        assert_eq!(profile.line_ending, LineEnding::Lf);
+        assert_eq!(to_line_ending("a\nb\r\n", LineEnding::Crlf), "a\r\nb\r\n");
+    }
 }
@@ -10,19 +10,24 @@ pub mod operations;
 pub mod validate;
 
 use edit::{EditSet, TextEdit};
+use format::StyleConfig;
 use operations::{ChangeDescription, Executable, Operation, OperationError, OperationResult};
 use tree_sitter::Tree;
+use validate::ParseCheck;
 
 /// Execute a list of operations on a source string with a pre-parsed tree.
 ///
 /// All operations compute edits against the ORIGINAL source, then edits
 /// are merged and applied in a single pass. The result is re-parsed
-/// and verified.
+/// and verified according to `check`. Generated code follows `style`,
+/// the formatter settings of the file, where it sets them.
 pub fn execute_operations(
     source: &str,
     tree: &Tree,
     ops: &[Operation],
     language: &tree_sitter::Language,
+    check: ParseCheck,
+    style: &StyleConfig,
 ) -> Result<OperationResult, OperationError> {
     if ops.is_empty() {
         return Ok(OperationResult {
@@ -32,14 +37,31 @@ pub fn execute_operations(
         });
     }
 
+    let executables = ops
+        .iter()
+        .map(operation_to_executable)
+        .collect::<Result<Vec<_>, _>>()?;
+    execute_executables(source, tree, &executables, language, check, style)
+}
+
+/// Execute already-constructed operations, e.g. ones that are not part of
+/// the [`Operation`] vocabulary because a caller drives them across files.
+pub fn execute_executables(
+    source: &str,
+    tree: &Tree,
+    executables: &[Box<dyn Executable>],
+    language: &tree_sitter::Language,
+    check: ParseCheck,
+    style: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
     // Compute edits for each operation
     let mut all_edits: Vec<TextEdit> = Vec::new();
-    let all_warnings: Vec<String> = Vec::new();
+    let mut all_warnings: Vec<String> = Vec::new();
 
-    for op in ops {
-        let executable = operation_to_executable(op)?;
-        let edits = executable.compute_edits(source, tree)?;
+    for executable in executables {
+        let (edits, warnings) = executable.compute_edits_with_style(source, tree, style)?;
         all_edits.extend(edits);
+        all_warnings.extend(warnings);
     }
 
     if all_edits.is_empty() {
@@ -50,6 +72,14 @@ pub fn execute_operations(
         });
     }
 
+    // Generated text uses `\n`; match the file's line endings
+    let line_ending = style.resolve(source).line_ending;
+    if line_ending == format::LineEnding::Crlf {
+        for edit in &mut all_edits {
+            edit.replacement = format::to_line_ending(&edit.replacement, line_ending);
+        }
+    }
+
     // Merge all edits into a single EditSet (detects overlaps)
     let edit_set = EditSet::new(all_edits, source.len())?;
 
@@ -77,8 +107,23 @@ pub fn execute_operations(
         })
         .collect();
 
-    // Verify the result parses cleanly
-    match validate::verify_parse(&new_source, language) {
+    // Verify the result parses cleanly. Under a stand-in grammar a source
+    // that already had errors (e.g. Less `@variables` under the CSS
+    // grammar) only has to not get worse.
+    if check != ParseCheck::Clean && tree.root_node().has_error() {
+        let new_tree = check
+            .parse(&new_source, language)
+            .map_err(|_| OperationError::InvalidResult { errors: vec![] })?;
+        if validate::count_errors(&new_tree) > validate::count_errors(tree) {
+            return Err(OperationError::InvalidResult { errors: vec![] });
+        }
+        return Ok(OperationResult {
+            content: new_source,
+            changes,
+            warnings: all_warnings,
+        });
+    }
+    match validate::verify_parse(&check.parse_input(&new_source), language) {
         Ok(_) => {}
         Err(validate::ValidationError::SyntaxErrors { errors, .. }) => {
             // This is the "should never happen" case -- our edits produced bad syntax.
@@ -98,7 +143,7 @@ pub fn execute_operations(
 }
 
 /// Convert an Operation enum variant to a boxed Executable.
-fn operation_to_executable(op: &Operation) -> Result<Box<dyn Executable>, OperationError> {
+pub fn operation_to_executable(op: &Operation) -> Result<Box<dyn Executable>, OperationError> {
     match op {
         Operation::RenameSymbol {
             from, to, scope, ..
@@ -230,5 +275,271 @@ fn operation_to_executable(op: &Operation) -> Result<Box<dyn Executable>, Operat
                 type_annotation.clone(),
             )))
         }
+        Operation::AddTypeMember {
+            type_name, member, ..
+        } => Ok(Box::new(operations::types::AddTypeMember::new(
+            type_name.clone(),
+            member.clone(),
+        ))),
+        Operation::RemoveTypeMember {
+            type_name,
+            member_name,
+            ..
+        } => Ok(Box::new(operations::types::RemoveTypeMember::new(
+            type_name.clone(),
+            member_name.clone(),
+        ))),
+        Operation::SetMemberOptional {
+            type_name,
+            member_name,
+            optional,
+            ..
+        } => Ok(Box::new(operations::types::SetMemberOptional::new(
+            type_name.clone(),
+            member_name.clone(),
+            *optional,
+        ))),
+        Operation::AddUnionMember {
+            type_name, member, ..
+        } => Ok(Box::new(operations::types::AddUnionMember::new(
+            type_name.clone(),
+            member.clone(),
+        ))),
+        Operation::AddEnumMember {
+            enum_name,
+            member_name,
+            value,
+            ..
+        } => Ok(Box::new(operations::types::AddEnumMember::new(
+            enum_name.clone(),
+            member_name.clone(),
+            value.clone(),
+        ))),
+        Operation::ExtractInlineType {
+            target,
+            function_name,
+            type_name,
+            ..
+        } => Ok(Box::new(operations::types::ExtractInlineType::new(
+            target.clone(),
+            function_name.clone(),
+            type_name.clone(),
+        ))),
+        Operation::AddClassMember {
+            class_name,
+            member_kind,
+            code,
+            anchor,
+            placement,
+            ..
+        } => {
+            let kind = operations::class_members::ClassMemberKind::from_str(member_kind)?;
+            let placement = operations::class_members::Placement::from_str(placement)?;
+            Ok(Box::new(operations::class_members::AddClassMember::new(
+                class_name.clone(),
+                kind,
+                code.clone(),
+                anchor.clone(),
+                placement,
+            )))
+        }
+        Operation::RemoveClassMember {
+            class_name,
+            member_name,
+            ..
+        } => Ok(Box::new(operations::class_members::RemoveClassMember::new(
+            class_name.clone(),
+            member_name.clone(),
+        ))),
+        Operation::RenameClassMember {
+            class_name,
+            from,
+            to,
+            ..
+        } => Ok(Box::new(operations::class_members::RenameClassMember::new(
+            class_name.clone(),
+            from.clone(),
+            to.clone(),
+        ))),
+        Operation::ConvertFunctionStyle {
+            function_name, to, ..
+        } => {
+            let style = operations::function_style::FunctionStyle::from_str(to)?;
+            Ok(Box::new(
+                operations::function_style::ConvertFunctionStyle::new(function_name.clone(), style),
+            ))
+        }
+        Operation::PromiseToAsync { function_name, .. } => Ok(Box::new(
+            operations::promise_to_async::PromiseToAsync::new(function_name.clone()),
+        )),
+        Operation::ClassToFunctionComponent {
+            component_name,
+            use_callback,
+            ..
+        } => Ok(Box::new(
+            operations::class_component::ClassToFunctionComponent::new(
+                component_name.clone(),
+                *use_callback,
+            ),
+        )),
+        Operation::AddRule {
+            selector,
+            declarations,
+            media,
+            ..
+        } => Ok(Box::new(operations::css::AddRule::new(
+            selector.clone(),
+            declarations.clone(),
+            media.clone(),
+        ))),
+        Operation::RemoveRule {
+            selector, media, ..
+        } => Ok(Box::new(operations::css::RemoveRule::new(
+            selector.clone(),
+            media.clone(),
+        ))),
+        Operation::SetDeclaration {
+            selector,
+            property,
+            value,
+            media,
+            ..
+        } => Ok(Box::new(operations::css::SetDeclaration::new(
+            selector.clone(),
+            property.clone(),
+            value.clone(),
+            media.clone(),
+        ))),
+        Operation::RemoveDeclaration {
+            selector,
+            property,
+            media,
+            ..
+        } => Ok(Box::new(operations::css::RemoveDeclaration::new(
+            selector.clone(),
+            property.clone(),
+            media.clone(),
+        ))),
+        Operation::RenameSelector { from, to, .. } => Ok(Box::new(
+            operations::css::RenameSelector::new(from.clone(), to.clone()),
+        )),
+        Operation::SetCustomProperty { name, value, .. } => Ok(Box::new(
+            operations::css::SetCustomProperty::new(name.clone(), value.clone()),
+        )),
+        Operation::VueAddToSetup { code, .. } => {
+            Ok(Box::new(operations::vue::AddToSetup::new(code.clone())))
+        }
+        Operation::VueWrapInRef { variable_name, .. } => Ok(Box::new(
+            operations::vue::WrapInRef::new(variable_name.clone()),
+        )),
+        Operation::SvelteAddReactive {
+            name,
+            expression,
+            runes,
+            ..
+        } => Ok(Box::new(operations::svelte::AddReactive::new(
+            name.clone(),
+            expression.clone(),
+            *runes,
+        ))),
+        Operation::SvelteAddStore {
+            name,
+            initial_value,
+            kind,
+            ..
+        } => Ok(Box::new(operations::svelte::AddStore::new(
+            name.clone(),
+            initial_value.clone(),
+            kind.clone(),
+        ))),
+        Operation::JsonSet { pointer, value, .. } => Ok(Box::new(
+            operations::json::JsonSet::new(pointer.clone(), value.clone()),
+        )),
+        Operation::JsonRemove { pointer, .. } => Ok(Box::new(
+            operations::json::JsonRemove::new(pointer.clone()),
+        )),
+        Operation::JsonAppend { pointer, value, .. } => Ok(Box::new(
+            operations::json::JsonAppend::new(pointer.clone(), value.clone()),
+        )),
+        Operation::AddDependency {
+            name, version, dev, ..
+        } => Ok(Box::new(operations::json::AddDependency::new(
+            name.clone(),
+            version.clone(),
+            *dev,
+        ))),
+        Operation::ConfigSet { path, value, .. } => Ok(Box::new(
+            operations::config::ConfigSet::new(path.clone(), value.clone()),
+        )),
+        Operation::ConfigRemove { path, .. } => Ok(Box::new(
+            operations::config::ConfigRemove::new(path.clone()),
+        )),
+        Operation::ConfigAppend {
+            path,
+            value,
+            import_from,
+            named_import,
+            ..
+        } => Ok(Box::new(operations::config::ConfigAppend::new(
+            path.clone(),
+            value.clone(),
+            import_from.clone(),
+            *named_import,
+        ))),
+        Operation::AddDirective { directive, .. } => Ok(Box::new(
+            operations::directives::AddDirective::new(directive.clone()),
+        )),
+        Operation::RemoveDirective { directive, .. } => Ok(Box::new(
+            operations::directives::RemoveDirective::new(directive.clone()),
+        )),
+        Operation::CjsToEsm { .. } => Ok(Box::new(operations::cjs_to_esm::CjsToEsm::new())),
+        Operation::AddTestCase {
+            describe_path,
+            name,
+            body,
+            kind,
+            ..
+        } => Ok(Box::new(operations::test_cases::AddTestCase::new(
+            operations::test_cases::parse_test_path(describe_path),
+            name.clone(),
+            body.clone(),
+            kind.clone(),
+        ))),
+        Operation::SkipTest {
+            test_path, enabled, ..
+        } => Ok(Box::new(operations::test_cases::ToggleTestModifier::new(
+            operations::test_cases::parse_test_path(test_path),
+            operations::test_cases::TestModifier::Skip,
+            *enabled,
+        ))),
+        Operation::OnlyTest {
+            test_path, enabled, ..
+        } => Ok(Box::new(operations::test_cases::ToggleTestModifier::new(
+            operations::test_cases::parse_test_path(test_path),
+            operations::test_cases::TestModifier::Only,
+            *enabled,
+        ))),
+        Operation::UpsertDocComment {
+            target,
+            description,
+            ..
+        } => Ok(Box::new(operations::doc_comment::UpsertDocComment::new(
+            target.clone(),
+            description.clone(),
+        ))),
+        Operation::FixTypeImports { style, .. } => Ok(Box::new(
+            operations::imports::FixTypeImports::new(style.clone()),
+        )),
+        Operation::RemoveTestCase { test_path, .. } => Ok(Box::new(
+            operations::test_cases::RemoveTestCase::new(operations::test_cases::parse_test_path(
+                test_path,
+            )),
+        )),
+        Operation::Custom {
+            definition, params, ..
+        } => Ok(Box::new(operations::custom::CustomOperation::new(
+            definition.clone(),
+            params,
+        )?)),
     }
 }
@@ -0,0 +1,552 @@
+//! CommonJS to ES module conversion.
+//!
+//! Top-level `require` declarations become imports and top-level
+//! `module.exports` / `exports.x` assignments become exports. Anything that
+//! can't be converted without changing behaviour (requires inside
+//! functions or conditionals, computed module names, `exports` used as a
+//! value) is left in place and reported as a warning.
+
+use crate::edit::TextEdit;
+use crate::format::StyleConfig;
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+/// The cjs_to_esm operation.
+#[derive(Default)]
+pub struct CjsToEsm;
+
+impl CjsToEsm {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Executable for CjsToEsm {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        Ok(convert_module(source, tree, style))
+    }
+}
+
+/// Convert the top-level `require` declarations and `module.exports` /
+/// `exports.x` assignments of a file. Returns the edits plus a warning
+/// for everything left in place.
+pub(crate) fn convert_module(
+    source: &str,
+    tree: &Tree,
+    style: &StyleConfig,
+) -> (Vec<TextEdit>, Vec<String>) {
+    let root = tree.root_node();
+    let (mut edits, mut warnings) = convert_requires(source, tree, style);
+    let semi = style.resolve(source).semi();
+
+    let mut declared = top_level_names(&root, source);
+    let default_exports = root
+        .named_children(&mut root.walk())
+        .filter(|s| matches!(export_target(s, source), Some((_, None))))
+        .count();
+
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        let Some((value, name)) = export_target(&statement, source) else {
+            continue;
+        };
+        let line = statement.start_position().row + 1;
+        let replacement = match name {
+            None if default_exports > 1 => {
+                warnings.push(format!(
+                    "line {}: module.exports is assigned more than once; left in place",
+                    line
+                ));
+                continue;
+            }
+            None => default_export(&value, source, semi),
+            Some(name) => {
+                if value.kind() == "identifier" {
+                    let local = node_text(&value, source);
+                    if local == name {
+                        format!("export {{ {} }}{}", name, semi)
+                    } else {
+                        format!("export {{ {} as {} }}{}", local, name, semi)
+                    }
+                } else if !declared.insert(name.to_string()) {
+                    warnings.push(format!(
+                        "line {}: exports.{} would clash with an existing `{}`; left in place",
+                        line, name, name
+                    ));
+                    continue;
+                } else {
+                    format!(
+                        "export const {} = {}{}",
+                        name,
+                        node_text(&value, source),
+                        semi
+                    )
+                }
+            }
+        };
+        edits.push(TextEdit {
+            start: statement.start_byte(),
+            end: statement.end_byte(),
+            replacement,
+            label: "exports -> export".to_string(),
+            priority: 0,
+        });
+    }
+
+    let converted: Vec<(usize, usize)> = edits.iter().map(|e| (e.start, e.end)).collect();
+    report_leftover_exports(&root, source, &converted, &mut warnings);
+    (edits, warnings)
+}
+
+/// Convert the top-level `require` declarations of a file to imports.
+/// Returns the edits plus one warning per `require` call left in place.
+fn convert_requires(
+    source: &str,
+    tree: &Tree,
+    style: &StyleConfig,
+) -> (Vec<TextEdit>, Vec<String>) {
+    let root = tree.root_node();
+    let quote = detect_require_quote(source, style);
+    let semicolons = style.resolve(source).semicolons;
+
+    let mut edits = Vec::new();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        if let Some(import) = require_to_import(&statement, source, quote, semicolons) {
+            edits.push(TextEdit {
+                start: statement.start_byte(),
+                end: statement.end_byte(),
+                replacement: import,
+                label: "require -> import".to_string(),
+                priority: 0,
+            });
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut calls = Vec::new();
+    collect_require_calls(&root, source, &mut calls);
+    for call in calls {
+        if edits
+            .iter()
+            .any(|e| e.start <= call.start_byte() && call.end_byte() <= e.end)
+        {
+            continue;
+        }
+        let dynamic = call
+            .child_by_field_name("arguments")
+            .and_then(|a| a.named_child(0))
+            .is_none_or(|a| a.kind() != "string");
+        warnings.push(format!(
+            "line {}: `{}` is {} and was left in place",
+            call.start_position().row + 1,
+            node_text(&call, source),
+            if dynamic {
+                "dynamic"
+            } else {
+                "not a top-level declaration"
+            }
+        ));
+    }
+    (edits, warnings)
+}
+
+/// `const x = require('m')` -> `import x from 'm'`,
+/// `const { a, b: c } = require('m')` -> `import { a, b as c } from 'm'`,
+/// `const x = require('m').a` -> `import { a as x } from 'm'`,
+/// `require('m')` -> `import 'm'`. `None` for anything else.
+pub(crate) fn require_to_import(
+    statement: &Node,
+    source: &str,
+    quote: char,
+    semicolons: bool,
+) -> Option<String> {
+    let semi = if semicolons { ";" } else { "" };
+    match statement.kind() {
+        "expression_statement" => {
+            let module = required_module(&statement.named_child(0)?, source)?;
+            Some(format!(
+                "import {}{}",
+                requote(&module, source, quote),
+                semi
+            ))
+        }
+        "lexical_declaration" | "variable_declaration" => {
+            if statement.named_child_count() != 1 {
+                return None;
+            }
+            let declarator = statement.named_child(0)?;
+            let value = declarator.child_by_field_name("value")?;
+            let name = declarator.child_by_field_name("name")?;
+            let (module, clause) = match (value.kind(), name.kind()) {
+                ("member_expression", "identifier") => {
+                    let module = required_module(&value.child_by_field_name("object")?, source)?;
+                    let property = value
+                        .child_by_field_name("property")
+                        .filter(|p| p.kind() == "property_identifier")?;
+                    let (imported, local) =
+                        (node_text(&property, source), node_text(&name, source));
+                    let clause = match imported {
+                        "default" => local.to_string(),
+                        _ if imported == local => format!("{{ {} }}", local),
+                        _ => format!("{{ {} as {} }}", imported, local),
+                    };
+                    (module, clause)
+                }
+                (_, "identifier") => (
+                    required_module(&value, source)?,
+                    node_text(&name, source).to_string(),
+                ),
+                (_, "object_pattern") => {
+                    let module = required_module(&value, source)?;
+                    let mut specifiers = Vec::new();
+                    let mut cursor = name.walk();
+                    for property in name.named_children(&mut cursor) {
+                        match property.kind() {
+                            "shorthand_property_identifier_pattern" => {
+                                specifiers.push(node_text(&property, source).to_string())
+                            }
+                            "pair_pattern" => {
+                                let key = property.child_by_field_name("key")?;
+                                let value = property
+                                    .child_by_field_name("value")
+                                    .filter(|v| v.kind() == "identifier")?;
+                                specifiers.push(format!(
+                                    "{} as {}",
+                                    node_text(&key, source),
+                                    node_text(&value, source)
+                                ));
+                            }
+                            "comment" => {}
+                            _ => return None,
+                        }
+                    }
+                    (module, format!("{{ {} }}", specifiers.join(", ")))
+                }
+                _ => return None,
+            };
+            Some(format!(
+                "import {} from {}{}",
+                clause,
+                requote(&module, source, quote),
+                semi
+            ))
+        }
+        _ => None,
+    }
+}
+
+// --- Helper functions ---
+
+/// The string argument of a `require('m')` call.
+fn required_module<'a>(call: &Node<'a>, source: &str) -> Option<Node<'a>> {
+    if call.kind() != "call_expression" {
+        return None;
+    }
+    let function = call.child_by_field_name("function")?;
+    let arguments = call.child_by_field_name("arguments")?;
+    let module = arguments.named_child(0)?;
+    (node_text(&function, source) == "require"
+        && arguments.named_child_count() == 1
+        && module.kind() == "string")
+        .then_some(module)
+}
+
+/// The configured quote style, or the file's, counting `require('...')` as
+/// well as `from '...'` since CommonJS files usually have no imports yet.
+fn detect_require_quote(source: &str, style: &StyleConfig) -> char {
+    if let Some(quote) = style.quote {
+        return quote;
+    }
+    let single = source.matches("require('").count();
+    let double = source.matches("require(\"").count();
+    if single + double == 0 || source.contains("from '") || source.contains("from \"") {
+        style.resolve(source).quote
+    } else if single >= double {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// A string literal in `quote`, unless that would need escaping.
+fn requote(string: &Node, source: &str, quote: char) -> String {
+    let text = node_text(string, source);
+    let inner = &text[1..text.len() - 1];
+    if inner.contains(quote) || inner.contains('\\') {
+        text.to_string()
+    } else {
+        format!("{}{}{}", quote, inner, quote)
+    }
+}
+
+fn collect_require_calls<'a>(node: &Node<'a>, source: &str, calls: &mut Vec<Node<'a>>) {
+    if node.kind() == "call_expression"
+        && node
+            .child_by_field_name("function")
+            .is_some_and(|f| node_text(&f, source) == "require")
+    {
+        calls.push(*node);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_require_calls(&child, source, calls);
+    }
+}
+
+/// For a top-level `module.exports = v` statement: `(v, None)`; for
+/// `exports.x = v` or `module.exports.x = v`: `(v, Some("x"))`.
+fn export_target<'a, 's>(
+    statement: &Node<'a>,
+    source: &'s str,
+) -> Option<(Node<'a>, Option<&'s str>)> {
+    if statement.kind() != "expression_statement" {
+        return None;
+    }
+    let assignment = statement
+        .named_child(0)
+        .filter(|a| a.kind() == "assignment_expression")?;
+    let left = assignment.child_by_field_name("left")?;
+    let value = assignment.child_by_field_name("right")?;
+    if node_text(&left, source) == "module.exports" {
+        return Some((value, None));
+    }
+    if left.kind() != "member_expression" {
+        return None;
+    }
+    let object = left.child_by_field_name("object")?;
+    let property = left.child_by_field_name("property")?;
+    matches!(node_text(&object, source), "exports" | "module.exports")
+        .then(|| (value, Some(node_text(&property, source))))
+}
+
+/// `export default v`, plus named exports when `v` is an object of plain
+/// identifiers so destructuring importers keep working.
+fn default_export(value: &Node, source: &str, semi: &str) -> String {
+    let text = node_text(value, source);
+    if matches!(
+        value.kind(),
+        "function_expression" | "function" | "class" | "generator_function"
+    ) {
+        return format!("export default {}", text);
+    }
+    let default = format!("export default {}{}", text, semi);
+    if value.kind() != "object" || value.named_child_count() == 0 {
+        return default;
+    }
+    let mut names = Vec::new();
+    let mut cursor = value.walk();
+    for property in value.named_children(&mut cursor) {
+        match property.kind() {
+            "shorthand_property_identifier" => names.push(node_text(&property, source).to_string()),
+            "pair" => {
+                let (Some(key), Some(local)) = (
+                    property.child_by_field_name("key"),
+                    property.child_by_field_name("value"),
+                ) else {
+                    return default;
+                };
+                if key.kind() != "property_identifier" || local.kind() != "identifier" {
+                    return default;
+                }
+                let (key, local) = (node_text(&key, source), node_text(&local, source));
+                names.push(if key == local {
+                    key.to_string()
+                } else {
+                    format!("{} as {}", local, key)
+                });
+            }
+            "comment" => {}
+            _ => return default,
+        }
+    }
+    format!("export {{ {} }}{}\n{}", names.join(", "), semi, default)
+}
+
+/// Names bound at the top level, so `exports.x = ...` doesn't redeclare one.
+fn top_level_names(root: &Node, source: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut cursor = root.walk();
+    for statement in root.named_children(&mut cursor) {
+        match statement.kind() {
+            "lexical_declaration" | "variable_declaration" => {
+                let mut inner = statement.walk();
+                for declarator in statement.named_children(&mut inner) {
+                    collect_pattern_names(
+                        &declarator.child_by_field_name("name"),
+                        source,
+                        &mut names,
+                    );
+                }
+            }
+            "function_declaration" | "generator_function_declaration" | "class_declaration" => {
+                if let Some(name) = statement.child_by_field_name("name") {
+                    names.insert(node_text(&name, source).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+fn collect_pattern_names(pattern: &Option<Node>, source: &str, names: &mut HashSet<String>) {
+    let Some(pattern) = pattern else {
+        return;
+    };
+    match pattern.kind() {
+        "identifier" | "shorthand_property_identifier_pattern" => {
+            names.insert(node_text(pattern, source).to_string());
+        }
+        _ => {
+            let mut cursor = pattern.walk();
+            for child in pattern.named_children(&mut cursor) {
+                let child = child.child_by_field_name("value").unwrap_or(child);
+                collect_pattern_names(&Some(child), source, names);
+            }
+        }
+    }
+}
+
+/// Warn about `module.exports` / `exports` uses outside converted statements.
+fn report_leftover_exports(
+    node: &Node,
+    source: &str,
+    converted: &[(usize, usize)],
+    warnings: &mut Vec<String>,
+) {
+    if converted
+        .iter()
+        .any(|(start, end)| *start <= node.start_byte() && node.end_byte() <= *end)
+    {
+        return;
+    }
+    let is_exports = match node.kind() {
+        "member_expression" => node_text(node, source) == "module.exports",
+        "identifier" => {
+            node_text(node, source) == "exports"
+                && node.parent().is_none_or(|p| {
+                    p.kind() != "member_expression" || node_text(&p, source) != "module.exports"
+                })
+        }
+        _ => false,
+    };
+    if is_exports {
+        let statement = enclosing_statement(node);
+        warnings.push(format!(
+            "line {}: `{}` was left in place",
+            node.start_position().row + 1,
+            node_text(&statement, source)
+                .lines()
+                .next()
+                .unwrap_or("")
+                .trim()
+        ));
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        report_leftover_exports(&child, source, converted, warnings);
+    }
+}
+
+fn enclosing_statement<'a>(node: &Node<'a>) -> Node<'a> {
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        if current.kind().ends_with("statement") || current.kind().ends_with("declaration") {
+            break;
+        }
+        current = parent;
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(source: &str) -> (String, Vec<String>) {
+        let tree = parse_ts(source);
+        let (edits, warnings) = CjsToEsm::new()
+            .compute_edits_with_warnings(source, &tree)
+            .unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(!parse_ts(&result).root_node().has_error(), "{}", result);
+        (result, warnings)
+    }
+
+    #[test]
+    fn test_requires_become_imports_in_file_style() {
+        let source = "const path = require(\"path\")\nconst { join, resolve: res } = require(\"path\")\nconst merge = require(\"lodash\").merge\nrequire(\"dotenv/config\")\n\nmodule.exports = { root: join(__dirname) }\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "import path from \"path\"\nimport { join, resolve as res } from \"path\"\nimport { merge } from \"lodash\"\nimport \"dotenv/config\"\n\nexport default { root: join(__dirname) }\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_named_exports() {
+        let source = "const helper = () => 1;\n\nexports.helper = helper;\nexports.run = function run() {\n  return helper();\n};\nmodule.exports.version = '1.0';\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "const helper = () => 1;\n\nexport { helper };\nexport const run = function run() {\n  return helper();\n};\nexport const version = '1.0';\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_object_of_identifiers_exports_named_and_default() {
+        let source = "function a() {}\nfunction b() {}\n\nmodule.exports = { a, c: b };\n";
+        let (result, _) = run(source);
+        assert!(result.ends_with("export { a, b as c };\nexport default { a, c: b };\n"));
+    }
+
+    #[test]
+    fn test_dynamic_and_nested_requires_are_reported() {
+        let source = "const fs = require('fs');\n\nfunction load(name) {\n  if (name) {\n    return require('./' + name);\n  }\n  return require('./default');\n}\n\nmodule.exports = load;\nmodule.exports.cache = {};\nif (process.env.DEBUG) exports.debug = true;\n";
+        let (result, warnings) = run(source);
+        assert!(result.starts_with("import fs from 'fs';\n"));
+        assert!(result.contains("export default load;\nexport const cache = {};\n"));
+        assert_eq!(
+            warnings,
+            vec![
+                "line 5: `require('./' + name)` is dynamic and was left in place",
+                "line 7: `require('./default')` is not a top-level declaration and was left in place",
+                "line 12: `exports.debug = true;` was left in place",
+            ]
+        );
+    }
+}
@@ -0,0 +1,1385 @@
+//! `class_to_function_component` operation.
+//!
+//! Migrates a React class component to a function component:
+//! - `state` (field or constructor assignment) becomes `useState` calls,
+//!   and `this.setState({ .. })` statements call the matching setters;
+//! - `componentDidMount`/`componentWillUnmount` become one `useEffect`
+//!   with a cleanup, `componentDidUpdate` an effect that skips the first
+//!   run (its dependencies come from `prevProps`/`prevState` checks);
+//! - `this.props.x` becomes destructured props (`defaultProps` become
+//!   destructuring defaults);
+//! - methods become plain functions or `useCallback`s, other instance
+//!   fields become refs.
+//!
+//! Anything without a faithful hook equivalent (`getSnapshotBeforeUpdate`,
+//! error boundaries, functional `setState`, arbitrary uses of `prevProps`, ...)
+//! blocks the conversion with a warning per construct instead of producing
+//! broken code.
+
+use std::collections::HashMap;
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig};
+use crate::operations::class_members::{class_member_name, class_members, find_class};
+use crate::operations::imports::AddImport;
+use crate::operations::node_utils::{has_child_kind, named_children, node_text};
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// Lifecycle methods and statics that have no hook equivalent.
+const BLOCKING_MEMBERS: &[(&str, &str)] = &[
+    (
+        "getSnapshotBeforeUpdate",
+        "getSnapshotBeforeUpdate has no hook equivalent",
+    ),
+    (
+        "componentDidCatch",
+        "error boundaries (componentDidCatch) must stay class components",
+    ),
+    (
+        "getDerivedStateFromError",
+        "error boundaries (getDerivedStateFromError) must stay class components",
+    ),
+    (
+        "getDerivedStateFromProps",
+        "getDerivedStateFromProps needs a manual rewrite",
+    ),
+    (
+        "shouldComponentUpdate",
+        "shouldComponentUpdate needs a manual React.memo comparison",
+    ),
+    ("componentWillMount", "componentWillMount is not supported"),
+    (
+        "componentWillReceiveProps",
+        "componentWillReceiveProps is not supported",
+    ),
+    (
+        "componentWillUpdate",
+        "componentWillUpdate is not supported",
+    ),
+    (
+        "contextType",
+        "static contextType needs a manual useContext",
+    ),
+];
+
+/// The class_to_function_component operation.
+pub struct ClassToFunctionComponent {
+    pub component_name: String,
+    /// Wrap methods in `useCallback` instead of emitting plain functions.
+    pub use_callback: bool,
+}
+
+impl ClassToFunctionComponent {
+    pub fn new(component_name: String, use_callback: bool) -> Self {
+        Self {
+            component_name,
+            use_callback,
+        }
+    }
+}
+
+impl Executable for ClassToFunctionComponent {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = tree.root_node();
+        let class = find_class(root, source, &self.component_name)?;
+        let heritage =
+            component_superclass(&class, source).ok_or_else(|| OperationError::InvalidParams {
+                message: format!(
+                    "Class '{}' does not extend React.Component or PureComponent",
+                    self.component_name
+                ),
+            })?;
+        let body =
+            class
+                .child_by_field_name("body")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Class '{}' has no body", self.component_name),
+                })?;
+
+        let mut component = Component::default();
+        let mut blockers = Vec::new();
+        let semi = profile.semi();
+
+        // --- Classify members ---
+        for member in class_members(&body) {
+            let Some(name) = class_member_name(&member, source) else {
+                blockers.push(at(&member, "unsupported class member"));
+                continue;
+            };
+            if let Some((_, reason)) = BLOCKING_MEMBERS.iter().find(|(n, _)| *n == name) {
+                blockers.push(at(&member, reason));
+                continue;
+            }
+            if name.starts_with("UNSAFE_") {
+                blockers.push(at(&member, &format!("{} is not supported", name)));
+                continue;
+            }
+            let value = member.child_by_field_name("value");
+            let is_static = has_child_kind(&member, "static");
+
+            if is_static {
+                match (name, value) {
+                    ("defaultProps", Some(v)) if v.kind() == "object" => {
+                        for pair in named_children(&v) {
+                            match (pair.kind(), pair.child_by_field_name("key")) {
+                                ("pair", Some(key)) => {
+                                    let default = pair.child_by_field_name("value").unwrap();
+                                    component.defaults.insert(
+                                        node_text(&key, source).to_string(),
+                                        node_text(&default, source).to_string(),
+                                    );
+                                }
+                                _ => blockers.push(at(&pair, "unsupported defaultProps entry")),
+                            }
+                        }
+                    }
+                    (_, Some(v)) if member.kind() != "method_definition" => {
+                        component.statics.push((name.to_string(), v));
+                    }
+                    _ => blockers.push(at(
+                        &member,
+                        &format!("static member '{}' is not supported", name),
+                    )),
+                }
+                continue;
+            }
+
+            match member.kind() {
+                "method_definition" => {
+                    if has_child_kind(&member, "get") || has_child_kind(&member, "set") {
+                        blockers.push(at(
+                            &member,
+                            &format!("accessor '{}' is not supported", name),
+                        ));
+                        continue;
+                    }
+                    match name {
+                        "constructor" => {
+                            self.read_constructor(&member, source, &mut component, &mut blockers)
+                        }
+                        "render" => component.render = member.child_by_field_name("body"),
+                        "componentDidMount" => component.did_mount = Some(member),
+                        "componentWillUnmount" => component.will_unmount = Some(member),
+                        "componentDidUpdate" => component.did_update = Some(member),
+                        _ => component.methods.push((name.to_string(), member)),
+                    }
+                }
+                _ => match value {
+                    Some(v) if name == "state" => {
+                        if v.kind() != "object" {
+                            blockers
+                                .push(at(&v, "state must be initialized with an object literal"));
+                        } else {
+                            read_state(&v, source, &mut component, &mut blockers);
+                        }
+                    }
+                    Some(v) if matches!(v.kind(), "arrow_function" | "function_expression") => {
+                        component.methods.push((name.to_string(), member));
+                    }
+                    Some(v) if is_create_ref(&v, source) => {
+                        component.ref_objects.push(name.to_string());
+                    }
+                    _ => component.refs.push((name.to_string(), value)),
+                },
+            }
+        }
+
+        let Some(render) = component.render else {
+            blockers.push(format!(
+                "Class '{}' has no render method",
+                self.component_name
+            ));
+            return Err(OperationError::Blocked { warnings: blockers });
+        };
+
+        // --- Rewrite bodies ---
+        let mut emitter = Emitter {
+            source,
+            component: &component,
+            props: Vec::new(),
+            props_object: false,
+            deps: Vec::new(),
+            blockers,
+        };
+
+        let class_prefix = format::extract_line_prefix(source, class.start_byte());
+        let unit = format::indent_deeper("", &profile.indent);
+        let inner = format!("{}{}", class_prefix, unit);
+        let mut sections: Vec<Vec<String>> = Vec::new();
+        let mut hooks = Vec::new();
+
+        // State and refs
+        let mut decls = Vec::new();
+        for (key, init) in &component.state {
+            let init = init
+                .map(|n| emitter.emit(&n))
+                .unwrap_or_else(|| key.clone());
+            decls.push(format!(
+                "const [{}, {}] = useState({}){}",
+                key,
+                setter_name(key),
+                normalize(&init, &line_prefix_of(source, init_start(&component, key))),
+                semi
+            ));
+            push_unique(&mut hooks, "useState");
+        }
+        for name in &component.ref_objects {
+            decls.push(format!("const {} = useRef(null){}", name, semi));
+            push_unique(&mut hooks, "useRef");
+        }
+        for (name, value) in &component.refs {
+            let init = value
+                .map(|v| normalize(&emitter.emit(&v), &line_prefix_of(source, v.start_byte())))
+                .unwrap_or_else(|| "undefined".to_string());
+            decls.push(format!("const {} = useRef({}){}", name, init, semi));
+            push_unique(&mut hooks, "useRef");
+        }
+        sections.push(decls);
+
+        // Methods
+        let mut methods = Vec::new();
+        for (name, member) in &component.methods {
+            emitter.deps.clear();
+            let func = emitter.method_as_function(member);
+            let func = normalize(&func, &line_prefix_of(source, member.start_byte()));
+            if self.use_callback {
+                let deps: Vec<&String> = emitter.deps.iter().filter(|d| *d != name).collect();
+                let deps = deps
+                    .iter()
+                    .map(|d| d.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                methods.push(format!(
+                    "const {} = useCallback({}, [{}]){}",
+                    name, func, deps, semi
+                ));
+                push_unique(&mut hooks, "useCallback");
+            } else {
+                methods.push(format!("const {} = {}{}", name, func, semi));
+            }
+        }
+        sections.push(methods);
+
+        // Effects
+        let mut effects = Vec::new();
+        if component.did_mount.is_some() || component.will_unmount.is_some() {
+            let mut lines = component
+                .did_mount
+                .map(|m| emitter.block_statements(&m))
+                .unwrap_or_default();
+            if let Some(unmount) = component.will_unmount {
+                let cleanup = emitter.block_statements(&unmount);
+                lines.push(format!(
+                    "return () => {{\n{}\n}}{}",
+                    indent_lines(&cleanup, &unit),
+                    semi
+                ));
+            }
+            effects.push(format!(
+                "useEffect(() => {{\n{}\n}}, []){}",
+                indent_lines(&lines, &unit),
+                semi
+            ));
+            push_unique(&mut hooks, "useEffect");
+        }
+        if let Some((lines, deps)) = component.did_update.and_then(|m| emitter.did_update(&m)) {
+            // An effect also runs after mount, which componentDidUpdate doesn't
+            let mut first = "isFirstRender".to_string();
+            let mut n = 1;
+            while component.is_state(&first)
+                || component.is_method(&first)
+                || component.is_ref(&first)
+                || component.ref_objects.contains(&first)
+            {
+                n += 1;
+                first = format!("isFirstRender{}", n);
+            }
+            sections[0].push(format!("const {} = useRef(true){}", first, semi));
+            push_unique(&mut hooks, "useRef");
+
+            let mut body = vec![format!(
+                "if ({first}.current) {{\n{unit}{first}.current = false{semi}\n{unit}return{semi}\n}}"
+            )];
+            body.extend(lines);
+            let deps = deps
+                .map(|d| format!(", [{}]", d.join(", ")))
+                .unwrap_or_default();
+            effects.push(format!(
+                "useEffect(() => {{\n{}\n}}{}){}",
+                indent_lines(&body, &unit),
+                deps,
+                semi
+            ));
+            push_unique(&mut hooks, "useEffect");
+        }
+        sections.push(effects);
+
+        // Render
+        let render_lines = emitter.block_statements(&render);
+
+        // Static fields are assigned after the function
+        let mut statics = Vec::new();
+        for (name, value) in &component.statics {
+            let text = normalize(
+                &emitter.emit(value),
+                &line_prefix_of(source, value.start_byte()),
+            );
+            statics.push(format!(
+                "{}{}.{} = {}{}",
+                class_prefix,
+                self.component_name,
+                name,
+                format::indent_code(&text, &class_prefix),
+                semi
+            ));
+        }
+
+        if !emitter.blockers.is_empty() {
+            return Err(OperationError::Blocked {
+                warnings: emitter.blockers,
+            });
+        }
+
+        // --- Props ---
+        let props_type = heritage
+            .props_type
+            .as_ref()
+            .map(|t| format!(": {}", t))
+            .unwrap_or_default();
+        let destructured: Vec<String> = emitter
+            .props
+            .iter()
+            .map(|p| match component.defaults.get(p) {
+                Some(default) => format!("{} = {}", p, default),
+                None => p.clone(),
+            })
+            .collect();
+        let params = if emitter.props_object {
+            if !destructured.is_empty() {
+                sections.insert(
+                    0,
+                    vec![format!(
+                        "const {{ {} }} = props{}",
+                        destructured.join(", "),
+                        semi
+                    )],
+                );
+            }
+            format!("props{}", props_type)
+        } else if destructured.is_empty() {
+            String::new()
+        } else {
+            format!("{{ {} }}{}", destructured.join(", "), props_type)
+        };
+
+        let mut body_lines: Vec<String> = Vec::new();
+        for section in sections.into_iter().filter(|s| !s.is_empty()) {
+            body_lines.extend(section);
+            body_lines.push(String::new());
+        }
+        body_lines.extend(render_lines);
+        while body_lines.last().is_some_and(|l| l.is_empty()) {
+            body_lines.pop();
+        }
+
+        let function = format!(
+            "function {}({}) {{\n{}\n{}}}",
+            self.component_name,
+            params,
+            indent_lines(&body_lines, &inner),
+            class_prefix
+        );
+
+        let mut edits = vec![TextEdit {
+            start: class.start_byte(),
+            end: class.end_byte(),
+            replacement: function,
+            label: format!(
+                "convert class component '{}' to a function component",
+                self.component_name
+            ),
+            priority: 0,
+        }];
+
+        if !statics.is_empty() {
+            let statement = class
+                .parent()
+                .filter(|p| p.kind() == "export_statement")
+                .unwrap_or(class);
+            edits.push(TextEdit {
+                start: statement.end_byte(),
+                end: statement.end_byte(),
+                replacement: format!("\n\n{}", statics.join("\n")),
+                label: format!("move statics of '{}'", self.component_name),
+                priority: 0,
+            });
+        }
+
+        edits.extend(react_import_edits(source, tree, style, &hooks, &heritage)?);
+        Ok((edits, vec![]))
+    }
+}
+
+impl ClassToFunctionComponent {
+    /// Read `this.state = {..}` from the constructor; only `super()`,
+    /// state initialization and `this.x = this.x.bind(this)` are allowed.
+    fn read_constructor<'a>(
+        &self,
+        constructor: &Node<'a>,
+        source: &str,
+        component: &mut Component<'a>,
+        blockers: &mut Vec<String>,
+    ) {
+        let Some(body) = constructor.child_by_field_name("body") else {
+            return;
+        };
+        for stmt in named_children(&body) {
+            if stmt.kind() == "comment" {
+                continue;
+            }
+            let expr = (stmt.kind() == "expression_statement")
+                .then(|| stmt.named_child(0))
+                .flatten();
+            match expr {
+                Some(e)
+                    if e.kind() == "call_expression"
+                        && node_text(&e, source).starts_with("super(") => {}
+                Some(e) if e.kind() == "assignment_expression" => {
+                    let left = e.child_by_field_name("left").unwrap();
+                    let right = e.child_by_field_name("right").unwrap();
+                    let target = this_member(&left, source);
+                    if target == Some("state") && right.kind() == "object" {
+                        read_state(&right, source, component, blockers);
+                    } else if target.is_some()
+                        && node_text(&right, source)
+                            == format!("this.{}.bind(this)", target.unwrap())
+                    {
+                        // Binding is unnecessary for functions
+                    } else {
+                        blockers.push(at(&stmt, "unsupported constructor logic"));
+                    }
+                }
+                _ => blockers.push(at(&stmt, "unsupported constructor logic")),
+            }
+        }
+    }
+}
+
+// --- Helper functions ---
+
+#[derive(Default)]
+struct Component<'a> {
+    /// State keys with their initializers (`None` for shorthand entries).
+    state: Vec<(String, Option<Node<'a>>)>,
+    methods: Vec<(String, Node<'a>)>,
+    /// Instance fields, rewritten to `useRef(value)` + `.current`.
+    refs: Vec<(String, Option<Node<'a>>)>,
+    /// `React.createRef()` fields, rewritten to `useRef(null)`.
+    ref_objects: Vec<String>,
+    defaults: HashMap<String, String>,
+    statics: Vec<(String, Node<'a>)>,
+    render: Option<Node<'a>>,
+    did_mount: Option<Node<'a>>,
+    will_unmount: Option<Node<'a>>,
+    did_update: Option<Node<'a>>,
+}
+
+impl Component<'_> {
+    fn is_state(&self, name: &str) -> bool {
+        self.state.iter().any(|(k, _)| k == name)
+    }
+
+    fn is_method(&self, name: &str) -> bool {
+        self.methods.iter().any(|(k, _)| k == name)
+    }
+
+    fn is_ref(&self, name: &str) -> bool {
+        self.refs.iter().any(|(k, _)| k == name)
+    }
+}
+
+struct Heritage {
+    props_type: Option<String>,
+    /// Named import used as the superclass (`Component`), if any.
+    imported_name: Option<String>,
+}
+
+/// Rewrites `this`-based code into function-component code.
+struct Emitter<'s, 'c, 'a> {
+    source: &'s str,
+    component: &'c Component<'a>,
+    /// Props read via `this.props.x`, in order of first use.
+    props: Vec<String>,
+    /// Whether `this.props` is used as a whole.
+    props_object: bool,
+    /// Reactive names referenced by the method being emitted.
+    deps: Vec<String>,
+    blockers: Vec<String>,
+}
+
+impl Emitter<'_, '_, '_> {
+    /// Rewritten text of `node`, whitespace preserved.
+    fn emit(&mut self, node: &Node) -> String {
+        if let Some(text) = self.rewrite(node) {
+            return text;
+        }
+        if node.child_count() == 0 {
+            return node_text(node, self.source).to_string();
+        }
+
+        let mut out = String::new();
+        let mut pos = node.start_byte();
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            let gap = &self.source[pos..child.start_byte()];
+            let text = self.emit(&child);
+            if text.is_empty() && child.is_named() && gap.contains('\n') {
+                // Dropped statement: drop its line too
+                out.push_str(&gap[..gap.rfind('\n').unwrap()]);
+            } else {
+                out.push_str(gap);
+            }
+            out.push_str(&text);
+            pos = child.end_byte();
+        }
+        out.push_str(&self.source[pos..node.end_byte()]);
+        out
+    }
+
+    fn rewrite(&mut self, node: &Node) -> Option<String> {
+        let source = self.source;
+        match node.kind() {
+            "this" => {
+                self.blockers.push(at(node, "unsupported use of `this`"));
+                Some("this".to_string())
+            }
+            "expression_statement" => {
+                let call = node
+                    .named_child(0)
+                    .filter(|c| c.kind() == "call_expression")?;
+                let callee = call.child_by_field_name("function")?;
+                if this_member(&callee, source) != Some("setState") {
+                    return None;
+                }
+                Some(self.set_state(node, &call))
+            }
+            "lexical_declaration" | "variable_declaration" => {
+                // `const { a, b } = this.props` -> drop, names become props
+                if node.named_child_count() != 1 {
+                    return None;
+                }
+                let declarator = node.named_child(0)?;
+                let value = declarator.child_by_field_name("value")?;
+                let pattern = declarator.child_by_field_name("name")?;
+                let target = this_member(&value, source)?;
+                if pattern.kind() != "object_pattern" {
+                    return None;
+                }
+                let names: Vec<Node> = named_children(&pattern);
+                if !names
+                    .iter()
+                    .all(|n| n.kind() == "shorthand_property_identifier_pattern")
+                {
+                    return None;
+                }
+                for n in names {
+                    let name = node_text(&n, source).to_string();
+                    match target {
+                        "props" => self.use_prop(&name, &n),
+                        "state" if self.component.is_state(&name) => {
+                            push_unique(&mut self.deps, &name)
+                        }
+                        _ => return None,
+                    }
+                }
+                Some(String::new())
+            }
+            "call_expression" => {
+                // `this.handle.bind(this)` -> `handle`
+                let callee = node.child_by_field_name("function")?;
+                let args = node.child_by_field_name("arguments")?;
+                if callee.kind() != "member_expression"
+                    || callee
+                        .child_by_field_name("property")
+                        .map(|p| node_text(&p, source))
+                        != Some("bind")
+                    || node_text(&args, source) != "(this)"
+                {
+                    return None;
+                }
+                let object = callee.child_by_field_name("object")?;
+                let name = this_member(&object, source)?;
+                if !self.component.is_method(name) {
+                    return None;
+                }
+                push_unique(&mut self.deps, name);
+                Some(name.to_string())
+            }
+            "member_expression" => {
+                let object = node.child_by_field_name("object")?;
+                let property = node.child_by_field_name("property")?;
+                let prop = node_text(&property, source);
+                if object.kind() == "this" {
+                    return Some(match prop {
+                        "props" => {
+                            self.props_object = true;
+                            push_unique(&mut self.deps, "props");
+                            "props".to_string()
+                        }
+                        "state" => {
+                            let keys: Vec<&str> = self
+                                .component
+                                .state
+                                .iter()
+                                .map(|(k, _)| k.as_str())
+                                .collect();
+                            for k in &keys {
+                                push_unique(&mut self.deps, k);
+                            }
+                            format!("({{ {} }})", keys.join(", "))
+                        }
+                        name if self.component.is_method(name) => {
+                            push_unique(&mut self.deps, name);
+                            name.to_string()
+                        }
+                        name if self.component.ref_objects.iter().any(|r| r == name) => {
+                            name.to_string()
+                        }
+                        name if self.component.is_ref(name) => format!("{}.current", name),
+                        name => {
+                            self.blockers.push(at(
+                                node,
+                                &format!("unknown instance member `this.{}`", name),
+                            ));
+                            node_text(node, source).to_string()
+                        }
+                    });
+                }
+                match this_member(&object, source) {
+                    Some("props") => {
+                        self.use_prop(prop, &property);
+                        Some(prop.to_string())
+                    }
+                    Some("state") => {
+                        if !self.component.is_state(prop) {
+                            self.blockers.push(at(
+                                node,
+                                &format!("`this.state.{}` is never initialized", prop),
+                            ));
+                        }
+                        push_unique(&mut self.deps, prop);
+                        Some(prop.to_string())
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn use_prop(&mut self, name: &str, node: &Node) {
+        if self.component.is_state(name) || self.component.is_method(name) {
+            self.blockers.push(at(
+                node,
+                &format!(
+                    "prop '{}' clashes with a state key or method of the same name",
+                    name
+                ),
+            ));
+        }
+        push_unique(&mut self.props, name);
+        push_unique(&mut self.deps, name);
+    }
+
+    /// `this.setState({ a: 1, b })` -> `setA(1); setB(b);`
+    fn set_state(&mut self, stmt: &Node, call: &Node) -> String {
+        let source = self.source;
+        let args = call
+            .child_by_field_name("arguments")
+            .map(|a| named_children(&a))
+            .unwrap_or_default();
+        let update = match args.as_slice() {
+            [update] if update.kind() == "object" => *update,
+            [update] => {
+                self.blockers
+                    .push(at(update, "functional setState needs a manual rewrite"));
+                return node_text(stmt, source).to_string();
+            }
+            _ => {
+                self.blockers.push(at(
+                    call,
+                    "setState with a callback needs a manual useEffect",
+                ));
+                return node_text(stmt, source).to_string();
+            }
+        };
+
+        let semi = if node_text(stmt, source).ends_with(';') {
+            ";"
+        } else {
+            ""
+        };
+        let prefix = line_prefix_of(source, stmt.start_byte());
+        let mut calls = Vec::new();
+        for entry in named_children(&update) {
+            let (key, value) = match entry.kind() {
+                "pair" => {
+                    let key = entry.child_by_field_name("key").unwrap();
+                    let value = entry.child_by_field_name("value").unwrap();
+                    (node_text(&key, source).to_string(), self.emit(&value))
+                }
+                "shorthand_property_identifier" => {
+                    let name = node_text(&entry, source).to_string();
+                    (name.clone(), name)
+                }
+                "comment" => continue,
+                _ => {
+                    self.blockers.push(at(&entry, "unsupported setState entry"));
+                    continue;
+                }
+            };
+            if !self.component.is_state(&key) {
+                self.blockers.push(at(
+                    &entry,
+                    &format!("setState key '{}' is not in the initial state", key),
+                ));
+            }
+            calls.push(format!("{}({}){}", setter_name(&key), value, semi));
+        }
+        calls.join(&format!("\n{}", prefix))
+    }
+
+    /// A method or method-valued field as an arrow function expression.
+    fn method_as_function(&mut self, member: &Node) -> String {
+        let source = self.source;
+        if let Some(value) = member.child_by_field_name("value") {
+            return self.emit(&value);
+        }
+        let part = |field: &str| {
+            member
+                .child_by_field_name(field)
+                .map(|n| node_text(&n, source).to_string())
+                .unwrap_or_default()
+        };
+        let is_async = has_child_kind(member, "async");
+        let body = member
+            .child_by_field_name("body")
+            .map(|b| self.emit(&b))
+            .unwrap_or_else(|| "{}".to_string());
+        format!(
+            "{}{}{}{} => {}",
+            if is_async { "async " } else { "" },
+            part("type_parameters"),
+            member
+                .child_by_field_name("parameters")
+                .map(|p| self.emit(&p))
+                .unwrap_or_else(|| "()".to_string()),
+            part("return_type"),
+            body
+        )
+    }
+
+    /// Effect statements and dependencies for `componentDidUpdate`.
+    ///
+    /// Without `prevProps`/`prevState` uses the effect runs after every
+    /// update (no dependency array). A body that is a single
+    /// `if (prevProps.a !== this.props.a || ..)` becomes the `if` body with
+    /// the compared names as dependencies; other uses of the previous
+    /// values are blocked.
+    fn did_update(&mut self, member: &Node) -> Option<(Vec<String>, Option<Vec<String>>)> {
+        let source = self.source;
+        let params = member
+            .child_by_field_name("parameters")
+            .map(|p| named_children(&p))
+            .unwrap_or_default();
+        if params.len() > 2 {
+            self.blockers.push(at(
+                member,
+                "componentDidUpdate snapshot argument needs getSnapshotBeforeUpdate",
+            ));
+            return None;
+        }
+        let mut prev = Vec::new();
+        for (param, target) in params.iter().zip(["props", "state"]) {
+            let pattern = param.child_by_field_name("pattern").unwrap_or(*param);
+            if pattern.kind() != "identifier" {
+                self.blockers.push(at(
+                    param,
+                    "componentDidUpdate parameters must be plain names",
+                ));
+                return None;
+            }
+            prev.push((node_text(&pattern, source), target));
+        }
+
+        let body = member.child_by_field_name("body")?;
+        if prev
+            .iter()
+            .all(|(name, _)| count_identifier(&body, source, name) == 0)
+        {
+            return Some((self.block_statements(member), None));
+        }
+
+        let statements: Vec<Node> = named_children(&body)
+            .into_iter()
+            .filter(|n| n.kind() != "comment")
+            .collect();
+        let guarded = match statements.as_slice() {
+            [stmt]
+                if stmt.kind() == "if_statement"
+                    && stmt.child_by_field_name("alternative").is_none() =>
+            {
+                let condition = stmt.child_by_field_name("condition")?;
+                let consequence = stmt.child_by_field_name("consequence")?;
+                let deps = self.changed_keys(&condition, &prev);
+                deps.filter(|_| {
+                    prev.iter()
+                        .all(|(name, _)| count_identifier(&consequence, source, name) == 0)
+                })
+                .map(|deps| (consequence, deps))
+            }
+            _ => None,
+        };
+        let Some((consequence, deps)) = guarded else {
+            self.blockers.push(at(
+                member,
+                "componentDidUpdate uses previous props/state beyond `if (prev.x !== this.x)` checks; rewrite it as a useEffect first",
+            ));
+            return None;
+        };
+        let lines = if consequence.kind() == "statement_block" {
+            self.block_statements(&consequence)
+        } else {
+            let text = self.emit(&consequence);
+            vec![normalize(
+                &text,
+                &line_prefix_of(source, consequence.start_byte()),
+            )]
+        };
+        Some((lines, Some(deps)))
+    }
+
+    /// Names compared in `prevProps.a !== this.props.a || prevState.b != this.state.b`.
+    fn changed_keys(&mut self, node: &Node, prev: &[(&str, &str)]) -> Option<Vec<String>> {
+        let source = self.source;
+        match node.kind() {
+            "parenthesized_expression" => self.changed_keys(&node.named_child(0)?, prev),
+            "binary_expression" => {
+                let operator = node.child_by_field_name("operator")?;
+                let left = node.child_by_field_name("left")?;
+                let right = node.child_by_field_name("right")?;
+                match node_text(&operator, source) {
+                    "||" => {
+                        let mut keys = self.changed_keys(&left, prev)?;
+                        for key in self.changed_keys(&right, prev)? {
+                            push_unique(&mut keys, &key);
+                        }
+                        Some(keys)
+                    }
+                    "!==" | "!=" => {
+                        let (old, current) = if left.kind() == "member_expression"
+                            && left
+                                .child_by_field_name("object")
+                                .is_some_and(|o| o.kind() == "identifier")
+                        {
+                            (left, right)
+                        } else {
+                            (right, left)
+                        };
+                        let object = old.child_by_field_name("object")?;
+                        let key = node_text(&old.child_by_field_name("property")?, source);
+                        let (_, target) = prev
+                            .iter()
+                            .find(|(name, _)| *name == node_text(&object, source))?;
+                        let current_object = current.child_by_field_name("object")?;
+                        if this_member(&current_object, source) != Some(target)
+                            || current
+                                .child_by_field_name("property")
+                                .map(|p| node_text(&p, source))
+                                != Some(key)
+                        {
+                            return None;
+                        }
+                        match *target {
+                            "props" => self.use_prop(key, &current),
+                            _ if self.component.is_state(key) => {}
+                            _ => return None,
+                        }
+                        Some(vec![key.to_string()])
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Statements of a method (or a statement block) rewritten and
+    /// normalized to column 0, with blank lines kept as empty entries.
+    fn block_statements(&mut self, node: &Node) -> Vec<String> {
+        let block = if node.kind() == "statement_block" {
+            *node
+        } else {
+            match node.child_by_field_name("body") {
+                Some(b) => b,
+                None => return vec![],
+            }
+        };
+        let mut lines = Vec::new();
+        let mut prev_row: Option<usize> = None;
+        for stmt in named_children(&block) {
+            let text = self.emit(&stmt);
+            if text.is_empty() {
+                continue;
+            }
+            if prev_row.is_some_and(|row| stmt.start_position().row > row + 1) {
+                lines.push(String::new());
+            }
+            prev_row = Some(stmt.end_position().row);
+            lines.push(normalize(
+                &text,
+                &line_prefix_of(self.source, stmt.start_byte()),
+            ));
+        }
+        lines
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, name: &str) {
+    if !list.iter().any(|n| n == name) {
+        list.push(name.to_string());
+    }
+}
+
+/// Blocker message with a line number.
+fn at(node: &Node, message: &str) -> String {
+    format!("line {}: {}", node.start_position().row + 1, message)
+}
+
+/// `x` for `this.x`.
+fn this_member<'s>(node: &Node, source: &'s str) -> Option<&'s str> {
+    if node.kind() != "member_expression" {
+        return None;
+    }
+    let object = node.child_by_field_name("object")?;
+    if object.kind() != "this" {
+        return None;
+    }
+    node.child_by_field_name("property")
+        .map(|p| node_text(&p, source))
+}
+
+fn setter_name(key: &str) -> String {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) => format!("set{}{}", first.to_uppercase(), chars.as_str()),
+        None => "set".to_string(),
+    }
+}
+
+fn line_prefix_of(source: &str, byte: usize) -> String {
+    format::extract_line_prefix(source, byte)
+}
+
+/// Strip `prefix` from continuation lines so the text starts at column 0.
+fn normalize(text: &str, prefix: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line
+            } else {
+                line.strip_prefix(prefix).unwrap_or(line.trim_start())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent_lines(lines: &[String], indent: &str) -> String {
+    lines
+        .iter()
+        .map(|l| {
+            if l.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, format::indent_code(l, indent))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn read_state<'a>(
+    object: &Node<'a>,
+    source: &str,
+    component: &mut Component<'a>,
+    blockers: &mut Vec<String>,
+) {
+    for entry in named_children(object) {
+        match entry.kind() {
+            "pair" => {
+                let key = entry.child_by_field_name("key").unwrap();
+                component.state.push((
+                    node_text(&key, source).to_string(),
+                    entry.child_by_field_name("value"),
+                ));
+            }
+            "shorthand_property_identifier" => {
+                component
+                    .state
+                    .push((node_text(&entry, source).to_string(), None));
+            }
+            "comment" => {}
+            _ => blockers.push(at(&entry, "unsupported state entry")),
+        }
+    }
+}
+
+/// Start of the initializer of a state key (for re-indentation).
+fn init_start(component: &Component, key: &str) -> usize {
+    component
+        .state
+        .iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, v)| v.map(|n| n.start_byte()))
+        .unwrap_or(0)
+}
+
+fn is_create_ref(value: &Node, source: &str) -> bool {
+    value.kind() == "call_expression"
+        && value
+            .child_by_field_name("function")
+            .is_some_and(|f| matches!(node_text(&f, source), "createRef" | "React.createRef"))
+}
+
+/// `extends Component<Props>` / `React.PureComponent` and its props type.
+fn component_superclass(class: &Node, source: &str) -> Option<Heritage> {
+    let heritage = named_children(class)
+        .into_iter()
+        .find(|c| c.kind() == "class_heritage")?;
+    // TypeScript wraps the superclass in an extends_clause
+    let clause = named_children(&heritage)
+        .into_iter()
+        .find(|c| c.kind() == "extends_clause")
+        .unwrap_or(heritage);
+    let value = clause
+        .child_by_field_name("value")
+        .or_else(|| clause.named_child(0))?;
+    let name = node_text(&value, source);
+    let imported_name = match name {
+        "Component" | "PureComponent" => Some(name.to_string()),
+        "React.Component" | "React.PureComponent" => None,
+        _ => return None,
+    };
+    let props_type = clause
+        .child_by_field_name("type_arguments")
+        .and_then(|args| args.named_child(0))
+        .map(|t| node_text(&t, source).to_string());
+    Some(Heritage {
+        props_type,
+        imported_name,
+    })
+}
+
+/// Add the hooks to the `react` import and drop the superclass import
+/// when nothing else uses it.
+fn react_import_edits(
+    source: &str,
+    tree: &Tree,
+    style: &StyleConfig,
+    hooks: &[String],
+    heritage: &Heritage,
+) -> Result<Vec<TextEdit>, OperationError> {
+    let root = tree.root_node();
+    let react_import = named_children(&root).into_iter().find(|n| {
+        n.kind() == "import_statement"
+            && n.child_by_field_name("source").is_some_and(|s| {
+                let text = node_text(&s, source);
+                text.len() >= 2 && &text[1..text.len() - 1] == "react"
+            })
+    });
+
+    let unused = heritage.imported_name.as_ref().filter(|name| {
+        // The class heritage is the only remaining reference
+        count_identifier(&root, source, name) == 1
+    });
+    let named_imports = react_import.and_then(|imp| {
+        let clause = named_children(&imp)
+            .into_iter()
+            .find(|c| c.kind() == "import_clause")?;
+        named_children(&clause)
+            .into_iter()
+            .find(|c| c.kind() == "named_imports")
+    });
+
+    if let (Some(unused), Some(named)) = (unused, named_imports) {
+        let mut specifiers: Vec<String> = named_children(&named)
+            .iter()
+            .filter(|s| s.kind() == "import_specifier")
+            .map(|s| node_text(s, source).to_string())
+            .filter(|s| s != unused)
+            .collect();
+        for hook in hooks {
+            if !specifiers.contains(hook) {
+                specifiers.push(hook.clone());
+            }
+        }
+        let replacement = if specifiers.is_empty() {
+            String::new()
+        } else {
+            format!("{{ {} }}", specifiers.join(", "))
+        };
+        if !replacement.is_empty() {
+            return Ok(vec![TextEdit {
+                start: named.start_byte(),
+                end: named.end_byte(),
+                replacement,
+                label: "update react import".to_string(),
+                priority: 0,
+            }]);
+        }
+    }
+
+    if hooks.is_empty() {
+        return Ok(vec![]);
+    }
+    AddImport::new("react".to_string(), hooks.to_vec(), None, false)
+        .compute_edits_with_style(source, tree, style)
+        .map(|(edits, _)| edits)
+}
+
+/// References to `name` outside import statements.
+fn count_identifier(node: &Node, source: &str, name: &str) -> usize {
+    if node.kind() == "import_statement" {
+        return 0;
+    }
+    let own = usize::from(
+        matches!(node.kind(), "identifier" | "type_identifier") && node_text(node, source) == name,
+    );
+    let mut cursor = node.walk();
+    let children: usize = node
+        .children(&mut cursor)
+        .map(|c| count_identifier(&c, source, name))
+        .sum();
+    own + children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_tsx(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        let lang = tree_sitter_typescript::LANGUAGE_TSX.into();
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn convert(
+        source: &str,
+        name: &str,
+        use_callback: bool,
+    ) -> Result<(String, Vec<String>), OperationError> {
+        let tree = parse_tsx(source);
+        let op = ClassToFunctionComponent::new(name.into(), use_callback);
+        let (edits, warnings) = op.compute_edits_with_warnings(source, &tree)?;
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(
+            !parse_tsx(&result).root_node().has_error(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        Ok((result, warnings))
+    }
+
+    const COUNTER: &str = r#"import React, { Component } from 'react';
+
+export default class Counter extends Component<Props> {
+  static defaultProps = { step: 1 };
+
+  state = { count: 0, label: 'n' };
+  timer = null;
+
+  componentDidMount() {
+    this.timer = setInterval(this.tick, 1000);
+  }
+
+  componentWillUnmount() {
+    clearInterval(this.timer);
+  }
+
+  tick = () => {
+    this.setState({ count: this.state.count + this.props.step });
+  };
+
+  render() {
+    const { title } = this.props;
+
+    return (
+      <h1>
+        {title}: {this.state.count}
+      </h1>
+    );
+  }
+}
+"#;
+
+    #[test]
+    fn test_converts_state_lifecycle_props_and_methods() {
+        let (result, warnings) = convert(COUNTER, "Counter", false).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            result,
+            r#"import React, { useState, useRef, useEffect } from 'react';
+
+export default function Counter({ step = 1, title }: Props) {
+  const [count, setCount] = useState(0);
+  const [label, setLabel] = useState('n');
+  const timer = useRef(null);
+
+  const tick = () => {
+    setCount(count + step);
+  };
+
+  useEffect(() => {
+    timer.current = setInterval(tick, 1000);
+    return () => {
+      clearInterval(timer.current);
+    };
+  }, []);
+
+  return (
+    <h1>
+      {title}: {count}
+    </h1>
+  );
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_use_callback_with_dependencies() {
+        let (result, _) = convert(COUNTER, "Counter", true).unwrap();
+        assert!(
+            result.contains("  const tick = useCallback(() => {\n    setCount(count + step);\n  }, [count, step]);\n"),
+            "{}",
+            result
+        );
+        assert!(result.contains("{ useState, useRef, useCallback, useEffect } from 'react'"));
+    }
+
+    #[test]
+    fn test_constructor_state_and_bound_methods() {
+        let source = "import React from 'react';\n\nclass Toggle extends React.Component {\n  constructor(props) {\n    super(props);\n    this.state = { on: false };\n    this.toggle = this.toggle.bind(this);\n  }\n\n  toggle() {\n    this.setState({ on: !this.state.on });\n  }\n\n  render() {\n    return <button onClick={this.toggle}>{String(this.state.on)}</button>;\n  }\n}\n";
+        let (result, _) = convert(source, "Toggle", false).unwrap();
+        assert_eq!(
+            result,
+            "import React, { useState } from 'react';\n\nfunction Toggle() {\n  const [on, setOn] = useState(false);\n\n  const toggle = () => {\n    setOn(!on);\n  };\n\n  return <button onClick={toggle}>{String(on)}</button>;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_props_object_use_keeps_props_param() {
+        let source = "class Link extends React.PureComponent<LinkProps> {\n  render() {\n    return <a {...this.props} title={this.props.label} />;\n  }\n}\n";
+        let (result, _) = convert(source, "Link", false).unwrap();
+        assert!(result.contains("function Link(props: LinkProps) {\n  const { label } = props;\n\n  return <a {...props} title={label} />;\n}"), "{}", result);
+    }
+
+    #[test]
+    fn test_did_update_runs_after_every_update() {
+        for did_update in ["componentDidUpdate()", "componentDidUpdate(prevProps)"] {
+            let source = format!("class Title extends React.Component {{\n  {} {{\n    document.title = this.props.title;\n  }}\n\n  render() {{\n    return null;\n  }}\n}}\n", did_update);
+            let (result, _) = convert(&source, "Title", false).unwrap();
+            assert!(
+                result.contains("function Title({ title }) {\n  const isFirstRender = useRef(true);\n\n  useEffect(() => {\n    if (isFirstRender.current) {\n      isFirstRender.current = false;\n      return;\n    }\n    document.title = title;\n  });\n"),
+                "{}",
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn test_did_update_deps_from_prev_comparisons() {
+        let source = "class Profile extends React.Component {\n  state = { tab: 'info' };\n\n  componentDidUpdate(prevProps, prevState) {\n    if (prevProps.userId !== this.props.userId || this.state.tab !== prevState.tab) {\n      load(this.props.userId, this.state.tab);\n    }\n  }\n\n  render() {\n    return null;\n  }\n}\n";
+        let (result, _) = convert(source, "Profile", false).unwrap();
+        assert!(
+            result.contains("  useEffect(() => {\n    if (isFirstRender.current) {\n      isFirstRender.current = false;\n      return;\n    }\n    load(userId, tab);\n  }, [userId, tab]);\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("function Profile({ userId }) {"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_did_update_with_other_prev_uses_is_blocked() {
+        let source = "class Log extends React.Component {\n  componentDidUpdate(prevProps) {\n    console.log(prevProps.value, this.props.value);\n  }\n\n  render() {\n    return null;\n  }\n}\n";
+        match convert(source, "Log", false) {
+            Err(OperationError::Blocked { warnings }) => {
+                assert_eq!(warnings.len(), 1);
+                assert!(warnings[0].contains("componentDidUpdate"));
+            }
+            other => panic!("expected Blocked, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_error_boundary_is_blocked() {
+        let source = "class Boundary extends React.Component {\n  static getDerivedStateFromError() {\n    return { failed: true };\n  }\n\n  componentDidCatch(error) {\n    log(error);\n  }\n\n  render() {\n    return this.props.children;\n  }\n}\n";
+        let tree = parse_tsx(source);
+        let op = ClassToFunctionComponent::new("Boundary".into(), false);
+        match op.compute_edits(source, &tree) {
+            Err(OperationError::Blocked { warnings }) => {
+                assert_eq!(warnings.len(), 2);
+                assert!(warnings[1].contains("componentDidCatch"));
+            }
+            other => panic!("expected Blocked, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_and_functional_set_state_are_blocked() {
+        let source = "class List extends React.Component {\n  state = { n: 0 };\n\n  getSnapshotBeforeUpdate() {\n    return null;\n  }\n\n  add = () => {\n    this.setState((s) => ({ n: s.n + 1 }));\n  };\n\n  render() {\n    return null;\n  }\n}\n";
+        let tree = parse_tsx(source);
+        let op = ClassToFunctionComponent::new("List".into(), false);
+        match op.compute_edits(source, &tree) {
+            Err(OperationError::Blocked { warnings }) => {
+                assert!(warnings
+                    .iter()
+                    .any(|w| w.contains("getSnapshotBeforeUpdate")));
+                assert!(warnings.iter().any(|w| w.contains("functional setState")));
+            }
+            other => panic!("expected Blocked, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_non_component_class_is_rejected() {
+        let source = "class Store {\n  render() {}\n}\n";
+        let tree = parse_tsx(source);
+        let op = ClassToFunctionComponent::new("Store".into(), false);
+        assert!(matches!(
+            op.compute_edits(source, &tree),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+}
@@ -0,0 +1,1171 @@
+//! `add_class_member`, `remove_class_member` and `rename_class_member` operations.
+//!
+//! New members are placed by convention: fields before the constructor,
+//! the constructor before methods, methods and getters last -- unless an
+//! explicit anchor member is given.
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig, StyleProfile};
+use crate::operations::node_utils::{declares_name, node_text};
+use crate::operations::signature::{collect_param_nodes, find_formal_parameters};
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// What kind of class member to add.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassMemberKind {
+    /// A field, e.g. `private count = 0`.
+    Property,
+    /// A method, e.g. `reset() { ... }`. A method named `constructor` is
+    /// placed like a constructor.
+    Method,
+    /// A getter, e.g. `get total() { ... }` (the `get` keyword is optional).
+    Getter,
+    /// A constructor parameter property, e.g. `private readonly api: Api`.
+    ParameterProperty,
+}
+
+impl ClassMemberKind {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, OperationError> {
+        match s.to_lowercase().as_str() {
+            "property" | "field" => Ok(Self::Property),
+            "method" => Ok(Self::Method),
+            "getter" => Ok(Self::Getter),
+            "parameter_property" | "constructor_parameter" => Ok(Self::ParameterProperty),
+            other => Err(OperationError::InvalidParams {
+                message: format!(
+                    "Invalid member_kind '{}', expected 'property', 'method', 'getter', or 'parameter_property'",
+                    other
+                ),
+            }),
+        }
+    }
+}
+
+/// Which side of the anchor member a new member goes on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+    Before,
+    After,
+}
+
+impl Placement {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, OperationError> {
+        match s.to_lowercase().as_str() {
+            "before" => Ok(Self::Before),
+            "after" => Ok(Self::After),
+            other => Err(OperationError::InvalidParams {
+                message: format!(
+                    "Invalid placement '{}', expected 'before' or 'after'",
+                    other
+                ),
+            }),
+        }
+    }
+}
+
+/// The add_class_member operation.
+pub struct AddClassMember {
+    pub class_name: String,
+    pub kind: ClassMemberKind,
+    /// Source text of the member, written at column 0.
+    pub code: String,
+    /// Existing member to place the new one next to.
+    pub anchor: Option<String>,
+    pub placement: Placement,
+}
+
+impl AddClassMember {
+    pub fn new(
+        class_name: String,
+        kind: ClassMemberKind,
+        code: String,
+        anchor: Option<String>,
+        placement: Placement,
+    ) -> Self {
+        Self {
+            class_name,
+            kind,
+            code,
+            anchor,
+            placement,
+        }
+    }
+
+    fn label(&self, name: &str) -> String {
+        format!("add member '{}' to class '{}'", name, self.class_name)
+    }
+}
+
+impl Executable for AddClassMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let code = self.code.trim().trim_end_matches(';').trim_end();
+        let code = match self.kind {
+            ClassMemberKind::Getter if !code.starts_with("get ") => format!("get {}", code),
+            _ => code.to_string(),
+        };
+        let name = declared_name(&code).ok_or_else(|| OperationError::InvalidParams {
+            message: format!("Could not determine member name from '{}'", self.code),
+        })?;
+
+        let class = find_class(tree.root_node(), source, &self.class_name)?;
+        let body =
+            class
+                .child_by_field_name("body")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Class '{}' has no body", self.class_name),
+                })?;
+        let members = class_members(&body);
+
+        if self.kind == ClassMemberKind::ParameterProperty {
+            if parameter_properties(&members, source)
+                .iter()
+                .any(|p| param_name(p, source) == Some(name))
+            {
+                return Ok((vec![], vec![])); // Already declared -- no-op
+            }
+            return self
+                .add_parameter_property(source, &class, &body, &members, &code, name, &profile)
+                .map(|edits| (edits, vec![]));
+        }
+
+        if members.iter().any(|m| {
+            class_member_name(m, source) == Some(name) && !is_accessor_pair(m, &code, source)
+        }) {
+            return Ok((vec![], vec![])); // Already declared -- no-op
+        }
+
+        let is_field = self.kind == ClassMemberKind::Property;
+        let text = if is_field {
+            format!("{}{}", code, field_terminator(&profile, &members))
+        } else {
+            code.clone()
+        };
+
+        let (target, placement) = match &self.anchor {
+            Some(anchor) => {
+                let target = members
+                    .iter()
+                    .find(|m| class_member_name(m, source) == Some(anchor.as_str()))
+                    .copied()
+                    .ok_or_else(|| OperationError::TargetNotFound {
+                        description: format!(
+                            "Anchor member '{}' not found in class '{}'",
+                            anchor, self.class_name
+                        ),
+                    })?;
+                (Some(target), self.placement.clone())
+            }
+            None => default_position(&members, source, is_field, name == "constructor"),
+        };
+
+        let edit = insert_member(
+            source,
+            &profile,
+            &body,
+            &members,
+            target,
+            placement,
+            &text,
+            !is_field,
+            self.label(name),
+        );
+        Ok((vec![edit], vec![]))
+    }
+}
+
+impl AddClassMember {
+    #[allow(clippy::too_many_arguments)]
+    fn add_parameter_property(
+        &self,
+        source: &str,
+        class: &Node,
+        body: &Node,
+        members: &[Node],
+        code: &str,
+        name: &str,
+        profile: &StyleProfile,
+    ) -> Result<Vec<TextEdit>, OperationError> {
+        let constructor = members
+            .iter()
+            .find(|m| class_member_name(m, source) == Some("constructor"));
+
+        let Some(constructor) = constructor else {
+            if has_superclass(class) {
+                return Err(OperationError::InvalidParams {
+                    message: format!(
+                        "Class '{}' extends another class and has no constructor; add one with a super() call first",
+                        self.class_name
+                    ),
+                });
+            }
+            let (target, placement) = default_position(members, source, false, true);
+            return Ok(vec![insert_member(
+                source,
+                profile,
+                body,
+                members,
+                target,
+                placement,
+                &format!("constructor({}) {{}}", code),
+                true,
+                self.label(name),
+            )]);
+        };
+
+        let params =
+            find_formal_parameters(constructor).ok_or_else(|| OperationError::TargetNotFound {
+                description: format!("Constructor of '{}' has no parameter list", self.class_name),
+            })?;
+        let existing = collect_param_nodes(&params);
+        let label = self.label(name);
+
+        let Some(last) = existing.last() else {
+            return Ok(vec![TextEdit {
+                start: params.start_byte() + 1,
+                end: params.end_byte() - 1,
+                replacement: code.to_string(),
+                label,
+                priority: 0,
+            }]);
+        };
+
+        if params.start_position().row == params.end_position().row {
+            return Ok(vec![TextEdit {
+                start: last.end_byte(),
+                end: last.end_byte(),
+                replacement: format!(", {}", code),
+                label,
+                priority: 0,
+            }]);
+        }
+
+        // One parameter per line: mirror the trailing-comma style
+        let indent = format::extract_line_prefix(source, last.start_byte());
+        let trailing_comma = last.next_sibling().filter(|n| n.kind() == ",");
+        Ok(vec![match trailing_comma {
+            Some(comma) => TextEdit {
+                start: comma.end_byte(),
+                end: comma.end_byte(),
+                replacement: format!("\n{}{},", indent, code),
+                label,
+                priority: 0,
+            },
+            None => TextEdit {
+                start: last.end_byte(),
+                end: last.end_byte(),
+                replacement: format!(",\n{}{}", indent, code),
+                label,
+                priority: 0,
+            },
+        }])
+    }
+}
+
+/// The remove_class_member operation.
+///
+/// Removes a field, method, accessor or constructor parameter property,
+/// together with its decorators and attached comments.
+pub struct RemoveClassMember {
+    pub class_name: String,
+    pub member_name: String,
+}
+
+impl RemoveClassMember {
+    pub fn new(class_name: String, member_name: String) -> Self {
+        Self {
+            class_name,
+            member_name,
+        }
+    }
+}
+
+impl Executable for RemoveClassMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let class = find_class(tree.root_node(), source, &self.class_name)?;
+        let body =
+            class
+                .child_by_field_name("body")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Class '{}' has no body", self.class_name),
+                })?;
+        let members = class_members(&body);
+        let label = format!(
+            "remove member '{}' from class '{}'",
+            self.member_name, self.class_name
+        );
+
+        // Getter/setter pairs share a name: remove all of them
+        let targets: Vec<Node> = members
+            .iter()
+            .filter(|m| class_member_name(m, source) == Some(self.member_name.as_str()))
+            .copied()
+            .collect();
+
+        if !targets.is_empty() {
+            return Ok(targets
+                .iter()
+                .map(|m| remove_member_lines(source, m, label.clone()))
+                .collect());
+        }
+
+        let param = parameter_properties(&members, source)
+            .into_iter()
+            .find(|p| param_name(p, source) == Some(self.member_name.as_str()))
+            .ok_or_else(|| OperationError::TargetNotFound {
+                description: format!(
+                    "Member '{}' not found in class '{}'",
+                    self.member_name, self.class_name
+                ),
+            })?;
+        Ok(vec![remove_parameter(&param, label)])
+    }
+}
+
+/// The rename_class_member operation.
+///
+/// Renames the declaration and every `this.member` reference inside the
+/// class body. For a parameter property, bare references inside the
+/// constructor are renamed too.
+pub struct RenameClassMember {
+    pub class_name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl RenameClassMember {
+    pub fn new(class_name: String, from: String, to: String) -> Self {
+        Self {
+            class_name,
+            from,
+            to,
+        }
+    }
+}
+
+impl Executable for RenameClassMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        if self.from.is_empty() || self.to.is_empty() {
+            return Err(OperationError::InvalidParams {
+                message: "from and to must be non-empty".to_string(),
+            });
+        }
+        if self.from == self.to {
+            return Ok(vec![]); // No-op
+        }
+
+        let class = find_class(tree.root_node(), source, &self.class_name)?;
+        let body =
+            class
+                .child_by_field_name("body")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Class '{}' has no body", self.class_name),
+                })?;
+        let members = class_members(&body);
+        let params = parameter_properties(&members, source);
+
+        let exists = |name: &str| {
+            members
+                .iter()
+                .any(|m| class_member_name(m, source) == Some(name))
+                || params.iter().any(|p| param_name(p, source) == Some(name))
+        };
+        if !exists(&self.from) {
+            return Err(OperationError::TargetNotFound {
+                description: format!(
+                    "Member '{}' not found in class '{}'",
+                    self.from, self.class_name
+                ),
+            });
+        }
+        if exists(&self.to) {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "Class '{}' already has a member named '{}'",
+                    self.class_name, self.to
+                ),
+            });
+        }
+
+        let label = format!("rename member {} -> {}", self.from, self.to);
+        let mut edits = Vec::new();
+        let rename = |node: Node, edits: &mut Vec<TextEdit>| {
+            edits.push(TextEdit {
+                start: node.start_byte(),
+                end: node.end_byte(),
+                replacement: self.to.clone(),
+                label: label.clone(),
+                priority: 0,
+            })
+        };
+
+        for member in &members {
+            if class_member_name(member, source) == Some(self.from.as_str()) {
+                if let Some(name) = member
+                    .child_by_field_name("name")
+                    .or_else(|| member.child_by_field_name("property"))
+                {
+                    rename(name, &mut edits);
+                }
+            }
+        }
+
+        for param in &params {
+            if param_name(param, source) == Some(self.from.as_str()) {
+                if let Some(pattern) = param.child_by_field_name("pattern") {
+                    rename(pattern, &mut edits);
+                }
+                // Bare references to the parameter inside the constructor body
+                let constructor_body = param
+                    .parent()
+                    .and_then(|p| p.parent())
+                    .and_then(|c| c.child_by_field_name("body"));
+                if let Some(cb) = constructor_body {
+                    collect_identifiers(cb, source, &self.from, &mut |n| {
+                        if n.kind() == "shorthand_property_identifier" {
+                            // `{ step }` keeps its key: `{ step: delta }`
+                            edits.push(TextEdit {
+                                start: n.end_byte(),
+                                end: n.end_byte(),
+                                replacement: format!(": {}", self.to),
+                                label: label.clone(),
+                                priority: 0,
+                            });
+                        } else {
+                            rename(n, &mut edits);
+                        }
+                    });
+                }
+            }
+        }
+
+        collect_this_members(body, source, &self.from, &mut |n| rename(n, &mut edits));
+
+        Ok(edits)
+    }
+}
+
+// --- Helper functions ---
+
+/// Find a class declaration (or `const Name = class {}`) by name.
+pub(crate) fn find_class<'a>(
+    root: Node<'a>,
+    source: &str,
+    name: &str,
+) -> Result<Node<'a>, OperationError> {
+    find_class_recursive(root, source, name).ok_or_else(|| OperationError::TargetNotFound {
+        description: format!("Class '{}' not found", name),
+    })
+}
+
+fn find_class_recursive<'a>(node: Node<'a>, source: &str, name: &str) -> Option<Node<'a>> {
+    match node.kind() {
+        "class_declaration" | "abstract_class_declaration" => {
+            if let Some(n) = node.child_by_field_name("name") {
+                if node_text(&n, source) == name {
+                    return Some(node);
+                }
+            }
+        }
+        "variable_declarator" => {
+            let named = node
+                .child_by_field_name("name")
+                .is_some_and(|n| node_text(&n, source) == name);
+            if let Some(value) = node.child_by_field_name("value") {
+                if named && value.kind() == "class" {
+                    return Some(value);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            if let Some(found) = find_class_recursive(cursor.node(), source, name) {
+                return Some(found);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+    None
+}
+
+fn has_superclass(class: &Node) -> bool {
+    let mut cursor = class.walk();
+    let found = class.children(&mut cursor).any(|c| {
+        c.kind() == "class_heritage" && c.child(0).is_some_and(|h| h.kind() == "extends_clause")
+    });
+    found
+}
+
+/// Member nodes of a class body (fields, methods, index signatures).
+pub(crate) fn class_members<'a>(body: &Node<'a>) -> Vec<Node<'a>> {
+    let mut members = Vec::new();
+    let mut cursor = body.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            if child.is_named() && !matches!(child.kind(), "comment" | "decorator") {
+                members.push(child);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+    members
+}
+
+pub(crate) fn class_member_name<'s>(member: &Node, source: &'s str) -> Option<&'s str> {
+    // JavaScript's `field_definition` names its key `property`
+    member
+        .child_by_field_name("name")
+        .or_else(|| member.child_by_field_name("property"))
+        .map(|n| node_text(&n, source))
+}
+
+fn is_method(member: &Node) -> bool {
+    matches!(
+        member.kind(),
+        "method_definition" | "abstract_method_signature" | "method_signature"
+    )
+}
+
+fn is_field(member: &Node) -> bool {
+    matches!(
+        member.kind(),
+        "public_field_definition" | "field_definition" | "property_signature"
+    )
+}
+
+/// A getter being added next to an existing setter (or vice versa) is not a duplicate.
+fn is_accessor_pair(existing: &Node, code: &str, source: &str) -> bool {
+    let text = node_text(existing, source);
+    (code.starts_with("get ") && text.starts_with("set "))
+        || (code.starts_with("set ") && text.starts_with("get "))
+}
+
+/// Constructor parameters carrying an accessibility or `readonly` modifier.
+fn parameter_properties<'a>(members: &[Node<'a>], source: &str) -> Vec<Node<'a>> {
+    let Some(constructor) = members
+        .iter()
+        .find(|m| class_member_name(m, source) == Some("constructor"))
+    else {
+        return vec![];
+    };
+    let Some(params) = find_formal_parameters(constructor) else {
+        return vec![];
+    };
+    collect_param_nodes(&params)
+        .into_iter()
+        .filter(|p| {
+            let mut cursor = p.walk();
+            let modified = p
+                .children(&mut cursor)
+                .any(|c| matches!(c.kind(), "accessibility_modifier" | "readonly"));
+            modified
+        })
+        .collect()
+}
+
+fn param_name<'s>(param: &Node, source: &'s str) -> Option<&'s str> {
+    param
+        .child_by_field_name("pattern")
+        .map(|n| node_text(&n, source))
+}
+
+/// Extract the declared name from member source text, e.g.
+/// `private static readonly count: number = 0` -> `count`.
+fn declared_name(code: &str) -> Option<&str> {
+    const MODIFIERS: &[&str] = &[
+        "public",
+        "private",
+        "protected",
+        "static",
+        "readonly",
+        "abstract",
+        "override",
+        "declare",
+        "async",
+        "get",
+        "set",
+        "accessor",
+    ];
+    let mut rest = code.trim_start();
+    loop {
+        let word_end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '#'))
+            .unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        let after = rest[word_end..].trim_start();
+        let is_modifier = MODIFIERS.contains(&word)
+            && after.chars().next().is_some_and(|c| {
+                c.is_alphanumeric() || c == '_' || c == '$' || c == '#' || c == '*'
+            });
+        if is_modifier {
+            rest = after.trim_start_matches('*').trim_start();
+            continue;
+        }
+        return if word.is_empty() { None } else { Some(word) };
+    }
+}
+
+/// `;` if the class's fields (or the file) use semicolons.
+fn field_terminator(profile: &StyleProfile, members: &[Node]) -> &'static str {
+    let fields: Vec<&Node> = members.iter().filter(|m| is_field(m)).collect();
+    if fields.is_empty() {
+        return profile.semi();
+    }
+    if fields
+        .iter()
+        .any(|f| f.next_sibling().is_some_and(|s| s.kind() == ";"))
+    {
+        ";"
+    } else {
+        ""
+    }
+}
+
+/// Conventional position for a new member when no anchor is given.
+fn default_position<'a>(
+    members: &[Node<'a>],
+    source: &str,
+    is_field: bool,
+    is_constructor: bool,
+) -> (Option<Node<'a>>, Placement) {
+    let constructor = members
+        .iter()
+        .find(|m| class_member_name(m, source) == Some("constructor"))
+        .copied();
+    let last_field = members.iter().rev().find(|m| self::is_field(m)).copied();
+    let first_method = members.iter().find(|m| is_method(m)).copied();
+
+    if is_field {
+        if let Some(f) = last_field {
+            return (Some(f), Placement::After);
+        }
+        if let Some(c) = constructor.or(first_method) {
+            return (Some(c), Placement::Before);
+        }
+        return (members.first().copied(), Placement::Before);
+    }
+
+    if is_constructor {
+        if let Some(f) = last_field {
+            return (Some(f), Placement::After);
+        }
+        return (first_method.or(members.first().copied()), Placement::Before);
+    }
+
+    (members.last().copied(), Placement::After)
+}
+
+/// Build the insertion edit for a new member next to `target`.
+#[allow(clippy::too_many_arguments)]
+fn insert_member(
+    source: &str,
+    profile: &StyleProfile,
+    body: &Node,
+    members: &[Node],
+    target: Option<Node>,
+    placement: Placement,
+    text: &str,
+    spaced: bool,
+    label: String,
+) -> TextEdit {
+    let indent = match members.first() {
+        Some(m) => format::extract_line_prefix(source, m.start_byte()),
+        None => format::indent_deeper(
+            &format::extract_line_prefix(source, body.start_byte()),
+            &profile.indent,
+        ),
+    };
+    let code = format::indent_code(text, &indent);
+
+    let Some(target) = target else {
+        // Empty class body
+        let body_prefix = format::extract_line_prefix(source, body.start_byte());
+        return TextEdit {
+            start: body.start_byte() + 1,
+            end: body.end_byte() - 1,
+            replacement: format!("\n{}{}\n{}", indent, code, body_prefix),
+            label,
+            priority: 0,
+        };
+    };
+
+    // Separate methods from their neighbours with a blank line; also
+    // when moving from the field group to the method group.
+    let blank = if spaced || is_method(&target) {
+        "\n"
+    } else {
+        ""
+    };
+
+    match placement {
+        Placement::After => {
+            let mut end = target.end_byte();
+            if let Some(semi) = target.next_sibling().filter(|s| s.kind() == ";") {
+                end = semi.end_byte();
+            }
+            if let Some(trailing) = format::find_attached_comments(source, &target).trailing {
+                end = end.max(trailing.end);
+            }
+            let line_end = source[end..].find('\n').map(|i| end + i).unwrap_or(end);
+            TextEdit {
+                start: line_end,
+                end: line_end,
+                replacement: format!("\n{}{}{}", blank, indent, code),
+                label,
+                priority: 0,
+            }
+        }
+        Placement::Before => {
+            let start = leading_start(source, &target);
+            let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            TextEdit {
+                start: line_start,
+                end: line_start,
+                replacement: format!("{}{}\n{}", indent, code, blank),
+                label,
+                priority: 0,
+            }
+        }
+    }
+}
+
+/// Start of a member including its decorators and leading comments.
+fn leading_start(source: &str, member: &Node) -> usize {
+    let mut first = *member;
+    while let Some(p) = first.prev_sibling().filter(|p| p.kind() == "decorator") {
+        first = p;
+    }
+    format::find_attached_comments(source, &first)
+        .leading
+        .first()
+        .map(|c| c.start)
+        .unwrap_or(first.start_byte())
+}
+
+/// Remove a member's full lines (decorators, comments, trailing `;`).
+pub(crate) fn remove_member_lines(source: &str, member: &Node, label: String) -> TextEdit {
+    let start = leading_start(source, member);
+    let mut end = member.end_byte();
+    if let Some(semi) = member.next_sibling().filter(|s| s.kind() == ";") {
+        end = semi.end_byte();
+    }
+    if let Some(trailing) = format::find_attached_comments(source, member).trailing {
+        end = end.max(trailing.end);
+    }
+
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    if !source[line_start..start].trim().is_empty() {
+        return TextEdit {
+            start,
+            end,
+            replacement: String::new(),
+            label,
+            priority: 0,
+        };
+    }
+    let mut line_end = source[end..]
+        .find('\n')
+        .map(|i| end + i + 1)
+        .unwrap_or(source.len());
+
+    // Collapse the blank line that separated this member from the next one
+    let prev_blank = source[..line_start]
+        .trim_end_matches([' ', '\t'])
+        .ends_with("\n\n")
+        || source[..line_start].trim_end().ends_with('{');
+    let next_line_end = source[line_end..].find('\n').map(|i| line_end + i + 1);
+    if let Some(nl) = next_line_end {
+        if prev_blank && source[line_end..nl].trim().is_empty() {
+            line_end = nl;
+        }
+    }
+
+    TextEdit {
+        start: line_start,
+        end: line_end,
+        replacement: String::new(),
+        label,
+        priority: 0,
+    }
+}
+
+/// Remove a single parameter together with its comma.
+fn remove_parameter(param: &Node, label: String) -> TextEdit {
+    let (start, end) = match (param.prev_sibling(), param.next_sibling()) {
+        (_, Some(next)) if next.kind() == "," => {
+            let after = next.next_sibling().map(|n| n.start_byte());
+            match after {
+                Some(a) if next.next_sibling().is_some_and(|n| n.kind() != ")") => {
+                    (param.start_byte(), a)
+                }
+                _ => (param.start_byte(), next.end_byte()),
+            }
+        }
+        (Some(prev), _) if prev.kind() == "," => (prev.start_byte(), param.end_byte()),
+        _ => (param.start_byte(), param.end_byte()),
+    };
+    TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+        label,
+        priority: 0,
+    }
+}
+
+/// Collect `this.<name>` property nodes below `node`. Functions, object
+/// methods and nested classes rebind `this` and are skipped; arrow
+/// functions keep it.
+fn collect_this_members<'a>(
+    node: Node<'a>,
+    source: &str,
+    name: &str,
+    on_match: &mut dyn FnMut(Node<'a>),
+) {
+    if node.kind() == "member_expression" {
+        let is_this = node
+            .child_by_field_name("object")
+            .is_some_and(|o| o.kind() == "this");
+        if let Some(prop) = node.child_by_field_name("property") {
+            if is_this && node_text(&prop, source) == name {
+                on_match(prop);
+            }
+        }
+    }
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            if !rebinds_this(&child) {
+                collect_this_members(child, source, name, on_match);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Whether `this` inside `node` refers to something other than the
+/// enclosing class instance.
+fn rebinds_this(node: &Node) -> bool {
+    match node.kind() {
+        "function_expression"
+        | "function_declaration"
+        | "generator_function"
+        | "generator_function_declaration"
+        | "class"
+        | "class_declaration"
+        | "abstract_class_declaration" => true,
+        // `{ m() { this } }`: `this` is the object literal
+        "method_definition" => node.parent().is_some_and(|p| p.kind() == "object"),
+        _ => false,
+    }
+}
+
+/// Collect references to the variable `name` below `node`: `identifier`s
+/// and `shorthand_property_identifier`s (`{ name }`). Functions and blocks
+/// that declare their own `name` are skipped.
+fn collect_identifiers<'a>(
+    node: Node<'a>,
+    source: &str,
+    name: &str,
+    on_match: &mut dyn FnMut(Node<'a>),
+) {
+    if declares_name(&node, source, name) {
+        return;
+    }
+    if matches!(node.kind(), "identifier" | "shorthand_property_identifier")
+        && node_text(&node, source) == name
+    {
+        on_match(node);
+    }
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            collect_identifiers(cursor.node(), source, name, on_match);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        let lang = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn apply(source: &str, edits: Vec<TextEdit>) -> String {
+        if edits.is_empty() {
+            return source.to_string();
+        }
+        let edit_set = EditSet::new(edits, source.len()).unwrap();
+        edit_set.apply(source)
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_ts(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = apply(source, edits);
+        assert!(
+            !parse_ts(&result).root_node().has_error(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        result
+    }
+
+    const COUNTER: &str = "class Counter {\n  private count = 0;\n\n  constructor(private readonly step: number) {\n    this.count = step;\n  }\n\n  increment() {\n    this.count += this.step;\n  }\n}\n";
+
+    fn add(kind: ClassMemberKind, code: &str) -> AddClassMember {
+        AddClassMember::new("Counter".into(), kind, code.into(), None, Placement::After)
+    }
+
+    // --- add_class_member tests ---
+
+    #[test]
+    fn test_add_property_after_fields() {
+        let result = run(&add(ClassMemberKind::Property, "private max = 10"), COUNTER);
+        assert!(result.contains("  private count = 0;\n  private max = 10;\n\n  constructor("));
+    }
+
+    #[test]
+    fn test_add_property_before_constructor_when_no_fields() {
+        let source = "class A {\n  constructor() {\n    init();\n  }\n}\n";
+        let op = AddClassMember::new(
+            "A".into(),
+            ClassMemberKind::Property,
+            "name = ''".into(),
+            None,
+            Placement::After,
+        );
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "class A {\n  name = '';\n\n  constructor() {\n    init();\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_method_at_end() {
+        let result = run(
+            &add(ClassMemberKind::Method, "reset() {\n  this.count = 0;\n}"),
+            COUNTER,
+        );
+        assert!(result.ends_with(
+            "  increment() {\n    this.count += this.step;\n  }\n\n  reset() {\n    this.count = 0;\n  }\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_add_getter_adds_keyword() {
+        let result = run(
+            &add(
+                ClassMemberKind::Getter,
+                "value(): number {\n  return this.count;\n}",
+            ),
+            COUNTER,
+        );
+        assert!(result.contains("  get value(): number {\n    return this.count;\n  }\n}"));
+    }
+
+    #[test]
+    fn test_add_method_before_anchor() {
+        let op = AddClassMember::new(
+            "Counter".into(),
+            ClassMemberKind::Method,
+            "decrement() {}".into(),
+            Some("increment".into()),
+            Placement::Before,
+        );
+        let result = run(&op, COUNTER);
+        assert!(result.contains("  decrement() {}\n\n  increment() {"));
+    }
+
+    #[test]
+    fn test_add_parameter_property() {
+        let result = run(
+            &add(
+                ClassMemberKind::ParameterProperty,
+                "private readonly log: Logger",
+            ),
+            COUNTER,
+        );
+        assert!(result
+            .contains("constructor(private readonly step: number, private readonly log: Logger)"));
+    }
+
+    #[test]
+    fn test_add_parameter_property_creates_constructor() {
+        let source = "class Api {\n  base = '/';\n\n  get() {}\n}\n";
+        let op = AddClassMember::new(
+            "Api".into(),
+            ClassMemberKind::ParameterProperty,
+            "private http: Http".into(),
+            None,
+            Placement::After,
+        );
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "class Api {\n  base = '/';\n\n  constructor(private http: Http) {}\n\n  get() {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_parameter_property_refuses_derived_class_without_constructor() {
+        let source = "class Api extends Base {\n  get() {}\n}\n";
+        let tree = parse_ts(source);
+        let op = AddClassMember::new(
+            "Api".into(),
+            ClassMemberKind::ParameterProperty,
+            "private http: Http".into(),
+            None,
+            Placement::After,
+        );
+        assert!(op.compute_edits(source, &tree).is_err());
+    }
+
+    #[test]
+    fn test_add_existing_member_is_noop() {
+        let tree = parse_ts(COUNTER);
+        let op = add(ClassMemberKind::Method, "increment() {}");
+        assert!(op.compute_edits(COUNTER, &tree).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_member_to_empty_class() {
+        let source = "export class Empty {}\n";
+        let op = AddClassMember::new(
+            "Empty".into(),
+            ClassMemberKind::Method,
+            "run() {}".into(),
+            None,
+            Placement::After,
+        );
+        let result = run(&op, source);
+        assert_eq!(result, "export class Empty {\n  run() {}\n}\n");
+    }
+
+    // --- remove_class_member tests ---
+
+    #[test]
+    fn test_remove_method_and_blank_line() {
+        let source = "class A {\n  a() {}\n\n  /** Docs */\n  @log()\n  b() {}\n\n  c() {}\n}\n";
+        let result = run(&RemoveClassMember::new("A".into(), "b".into()), source);
+        assert_eq!(result, "class A {\n  a() {}\n\n  c() {}\n}\n");
+    }
+
+    #[test]
+    fn test_remove_field() {
+        let result = run(
+            &RemoveClassMember::new("Counter".into(), "count".into()),
+            COUNTER,
+        );
+        assert!(result.starts_with("class Counter {\n  constructor("));
+    }
+
+    #[test]
+    fn test_remove_parameter_property() {
+        let source = "class A {\n  constructor(private a: A, public b: B) {}\n}\n";
+        let result = run(&RemoveClassMember::new("A".into(), "a".into()), source);
+        assert!(result.contains("constructor(public b: B) {}"));
+    }
+
+    #[test]
+    fn test_remove_missing_member_errors() {
+        let tree = parse_ts(COUNTER);
+        let op = RemoveClassMember::new("Counter".into(), "nope".into());
+        assert!(op.compute_edits(COUNTER, &tree).is_err());
+    }
+
+    // --- rename_class_member tests ---
+
+    #[test]
+    fn test_rename_field_updates_this_references() {
+        let source = "const count = 1;\nclass Counter {\n  count = 0;\n  inc() {\n    this.count++;\n    return count;\n  }\n}\nother.count = 2;\n";
+        let result = run(
+            &RenameClassMember::new("Counter".into(), "count".into(), "total".into()),
+            source,
+        );
+        assert_eq!(
+            result,
+            "const count = 1;\nclass Counter {\n  total = 0;\n  inc() {\n    this.total++;\n    return count;\n  }\n}\nother.count = 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_parameter_property() {
+        let result = run(
+            &RenameClassMember::new("Counter".into(), "step".into(), "delta".into()),
+            COUNTER,
+        );
+        assert!(result.contains("constructor(private readonly delta: number)"));
+        assert!(result.contains("this.count = delta;"));
+        assert!(result.contains("this.count += this.delta;"));
+    }
+
+    #[test]
+    fn test_rename_skips_this_of_nested_functions_and_classes() {
+        let source = "class Counter {\n  count = 0;\n  inc() {\n    const f = function () { return this.count; };\n    const g = () => this.count;\n    class Inner { count = 1; get() { return this.count; } }\n    const o = { m() { return this.count; } };\n  }\n}\n";
+        let result = run(
+            &RenameClassMember::new("Counter".into(), "count".into(), "total".into()),
+            source,
+        );
+        assert_eq!(
+            result,
+            "class Counter {\n  total = 0;\n  inc() {\n    const f = function () { return this.count; };\n    const g = () => this.total;\n    class Inner { count = 1; get() { return this.count; } }\n    const o = { m() { return this.count; } };\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_parameter_property_expands_shorthand_and_skips_shadowing() {
+        let source = "class Counter {\n  constructor(private step: number) {\n    init({ step });\n    if (step) {\n      const step = 2;\n      use(step);\n    }\n    [1].map((step) => step);\n    run(() => step);\n  }\n}\n";
+        let result = run(
+            &RenameClassMember::new("Counter".into(), "step".into(), "delta".into()),
+            source,
+        );
+        assert_eq!(
+            result,
+            "class Counter {\n  constructor(private delta: number) {\n    init({ step: delta });\n    if (delta) {\n      const step = 2;\n      use(step);\n    }\n    [1].map((step) => step);\n    run(() => delta);\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_to_existing_member_errors() {
+        let tree = parse_ts(COUNTER);
+        let op = RenameClassMember::new("Counter".into(), "count".into(), "increment".into());
+        assert!(op.compute_edits(COUNTER, &tree).is_err());
+    }
+}
@@ -0,0 +1,708 @@
+//! Config-object operations: `config_set`, `config_remove` and
+//! `config_append`.
+//!
+//! They edit the object a config file exports (`vite.config.ts`,
+//! `next.config.js`, `tailwind.config.ts`, `jest.config.js`, ...), found
+//! through `export default {...}`, `export default defineConfig({...})`
+//! (also with an arrow returning the object), `module.exports = ...`, or a
+//! variable exported by one of those. Properties are addressed by a dotted
+//! path such as `server.port`; values are JavaScript expressions.
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig, StyleProfile, TrailingCommas};
+use crate::operations::imports::AddImport;
+use crate::operations::json::{insert_member, member_prefix, members, remove_member};
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Language, Node, Parser, Tree};
+
+/// The config_set operation.
+///
+/// Sets the property at `path` to `value`, creating intermediate objects.
+pub struct ConfigSet {
+    pub path: String,
+    pub value: String,
+}
+
+impl ConfigSet {
+    pub fn new(path: String, value: String) -> Self {
+        Self { path, value }
+    }
+}
+
+impl Executable for ConfigSet {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let config = find_config_object(tree, source)?;
+        let keys = parse_path(&self.path)?;
+        let label = format!("set {}", self.path);
+        let edits = set_property(
+            source,
+            &profile,
+            &tree.language(),
+            config,
+            &keys,
+            self.value.trim(),
+            &label,
+        )?;
+        Ok((edits, vec![]))
+    }
+}
+
+/// The config_remove operation.
+pub struct ConfigRemove {
+    pub path: String,
+}
+
+impl ConfigRemove {
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+impl Executable for ConfigRemove {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let config = find_config_object(tree, source)?;
+        let keys = parse_path(&self.path)?;
+        let (last, parents) = keys.split_last().expect("parse_path is non-empty");
+        let not_found = || OperationError::TargetNotFound {
+            description: format!("No '{}' in the config object", self.path),
+        };
+        let object = resolve(config, parents, source)
+            .filter(|o| o.kind() == "object")
+            .ok_or_else(not_found)?;
+        let pair = find_pair(&object, last, source).ok_or_else(not_found)?;
+        Ok(remove_member(
+            source,
+            &object,
+            &pair,
+            format!("remove {}", self.path),
+        ))
+    }
+}
+
+/// The config_append operation.
+///
+/// Appends `value` to the array at `path` (created if missing) unless an
+/// equal element, or a call to the same plugin factory, is already there.
+/// With `import_from`, the factory called by `value` is imported from
+/// that module (`import` or `require`, matching the file).
+pub struct ConfigAppend {
+    pub path: String,
+    pub value: String,
+    pub import_from: Option<String>,
+    pub named_import: bool,
+}
+
+impl ConfigAppend {
+    pub fn new(
+        path: String,
+        value: String,
+        import_from: Option<String>,
+        named_import: bool,
+    ) -> Self {
+        Self {
+            path,
+            value,
+            import_from,
+            named_import,
+        }
+    }
+}
+
+impl Executable for ConfigAppend {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let config = find_config_object(tree, source)?;
+        let keys = parse_path(&self.path)?;
+        let profile = style.resolve(source);
+        let value = self.value.trim();
+        let label = format!("append to {}", self.path);
+        let mut warnings = Vec::new();
+
+        let mut edits = match resolve(config, &keys, source) {
+            Some(array) if array.kind() == "array" => {
+                let wanted = compact(value);
+                let callee = callee_of(value);
+                let existing = members(&array).into_iter().find(|element| {
+                    let text = compact(node_text(element, source));
+                    text == wanted || (callee.is_some() && callee_of(&text) == callee)
+                });
+                match existing {
+                    Some(element) => {
+                        if compact(node_text(&element, source)) != wanted {
+                            warnings.push(format!(
+                                "{} already contains {}; left unchanged",
+                                self.path,
+                                node_text(&element, source)
+                            ));
+                        }
+                        vec![]
+                    }
+                    None if members(&array).is_empty() => vec![TextEdit {
+                        start: array.start_byte() + 1,
+                        end: array.end_byte() - 1,
+                        replacement: value.to_string(),
+                        label: label.clone(),
+                        priority: 0,
+                    }],
+                    None => {
+                        let prefix = member_prefix(source, &profile.indent, &array);
+                        let text = format::indent_code(value, &prefix);
+                        insert_member(source, &profile.indent, &array, None, &text, &label)
+                    }
+                }
+            }
+            Some(other) => {
+                return Err(OperationError::InvalidParams {
+                    message: format!("{} is a {}, not an array", self.path, other.kind()),
+                })
+            }
+            None => set_property(
+                source,
+                &profile,
+                &tree.language(),
+                config,
+                &keys,
+                &format!("[{}]", value),
+                &label,
+            )?,
+        };
+
+        if let (false, Some(module)) = (edits.is_empty(), &self.import_from) {
+            let name = callee_of(value).ok_or_else(|| OperationError::InvalidParams {
+                message: format!("Cannot tell what to import from '{}'", value),
+            })?;
+            let name = name.split('.').next().unwrap_or(&name).to_string();
+            edits.extend(import_edits(
+                source,
+                tree,
+                style,
+                module,
+                &name,
+                self.named_import,
+            )?);
+        }
+        Ok((edits, warnings))
+    }
+}
+
+// --- Helper functions ---
+
+fn parse_path(path: &str) -> Result<Vec<String>, OperationError> {
+    let keys: Vec<String> = path.split('.').map(|k| k.trim().to_string()).collect();
+    if keys.iter().any(|k| k.is_empty()) {
+        return Err(OperationError::InvalidParams {
+            message: format!("Invalid property path '{}'", path),
+        });
+    }
+    Ok(keys)
+}
+
+/// Locate the exported config object literal.
+fn find_config_object<'a>(tree: &'a Tree, source: &str) -> Result<Node<'a>, OperationError> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let exported = root
+        .named_children(&mut cursor)
+        .find_map(|statement| match statement.kind() {
+            "export_statement" => statement.child_by_field_name("value"),
+            "expression_statement" => {
+                let assignment = statement.named_child(0)?;
+                let left = assignment.child_by_field_name("left")?;
+                (assignment.kind() == "assignment_expression"
+                    && node_text(&left, source) == "module.exports")
+                    .then(|| assignment.child_by_field_name("right"))?
+            }
+            _ => None,
+        });
+    exported
+        .and_then(|value| config_object_of(value, &root, source, 0))
+        .ok_or_else(|| OperationError::TargetNotFound {
+            description: "No exported config object (export default / module.exports)".to_string(),
+        })
+}
+
+/// Unwrap calls, arrows, casts and variable references down to an object.
+fn config_object_of<'a>(
+    node: Node<'a>,
+    root: &Node<'a>,
+    source: &str,
+    depth: usize,
+) -> Option<Node<'a>> {
+    if depth > 8 {
+        return None;
+    }
+    let next = match node.kind() {
+        "object" => return Some(node),
+        // defineConfig({...}), withPlugins({...})
+        "call_expression" => {
+            let arguments = node.child_by_field_name("arguments")?;
+            let mut cursor = arguments.walk();
+            let found = arguments
+                .named_children(&mut cursor)
+                .find_map(|a| config_object_of(a, root, source, depth + 1));
+            return found;
+        }
+        "arrow_function" => {
+            let body = node.child_by_field_name("body")?;
+            if body.kind() == "statement_block" {
+                let mut cursor = body.walk();
+                let returned = body
+                    .named_children(&mut cursor)
+                    .filter(|s| s.kind() == "return_statement")
+                    .last()?;
+                returned.named_child(0)?
+            } else {
+                body
+            }
+        }
+        "parenthesized_expression" | "satisfies_expression" | "as_expression" => {
+            node.named_child(0)?
+        }
+        "identifier" => variable_value(root, node_text(&node, source), source)?,
+        _ => return None,
+    };
+    config_object_of(next, root, source, depth + 1)
+}
+
+fn variable_value<'a>(root: &Node<'a>, name: &str, source: &str) -> Option<Node<'a>> {
+    let mut cursor = root.walk();
+    let statements: Vec<Node<'a>> = root.named_children(&mut cursor).collect();
+    statements.into_iter().find_map(|statement| {
+        let declaration = match statement.kind() {
+            "export_statement" => statement.child_by_field_name("declaration")?,
+            _ => statement,
+        };
+        if !matches!(
+            declaration.kind(),
+            "lexical_declaration" | "variable_declaration"
+        ) {
+            return None;
+        }
+        let mut cursor = declaration.walk();
+        let declarators: Vec<Node<'a>> = declaration.named_children(&mut cursor).collect();
+        declarators.into_iter().find_map(|d| {
+            let declared = d.child_by_field_name("name")?;
+            (node_text(&declared, source) == name)
+                .then(|| d.child_by_field_name("value"))
+                .flatten()
+        })
+    })
+}
+
+/// Value node at `keys` below `object`.
+fn resolve<'a>(object: Node<'a>, keys: &[String], source: &str) -> Option<Node<'a>> {
+    keys.iter().try_fold(object, |current, key| {
+        if current.kind() != "object" {
+            return None;
+        }
+        find_pair(&current, key, source)?.child_by_field_name("value")
+    })
+}
+
+fn find_pair<'a>(object: &Node<'a>, key: &str, source: &str) -> Option<Node<'a>> {
+    members(object).into_iter().find(|member| {
+        member.kind() == "pair"
+            && member
+                .child_by_field_name("key")
+                .is_some_and(|k| key_text(&k, source) == key)
+    })
+}
+
+fn key_text<'s>(key: &Node, source: &'s str) -> &'s str {
+    node_text(key, source).trim_matches(|c| c == '"' || c == '\'' || c == '`')
+}
+
+/// Set `keys` below `object` to `value`. Object literal values and
+/// created intermediate objects are laid out one member per line inside
+/// multi-line objects, and inline inside single-line ones.
+fn set_property(
+    source: &str,
+    profile: &StyleProfile,
+    language: &Language,
+    object: Node,
+    keys: &[String],
+    value: &str,
+    label: &str,
+) -> Result<Vec<TextEdit>, OperationError> {
+    let (first, rest) = keys.split_first().expect("parse_path is non-empty");
+    let unit = format::indent_deeper("", &profile.indent);
+    let multiline = if members(&object).is_empty() {
+        source.contains('\n')
+    } else {
+        node_text(&object, source).contains('\n')
+    };
+    let comma = if uses_trailing_commas(source, profile, &object) {
+        ","
+    } else {
+        ""
+    };
+
+    if let Some(pair) = find_pair(&object, first, source) {
+        let current =
+            pair.child_by_field_name("value")
+                .ok_or_else(|| OperationError::InvalidParams {
+                    message: format!("'{}' has no value", first),
+                })?;
+        if rest.is_empty() {
+            let prefix = format::extract_line_prefix(source, current.start_byte());
+            let text = match multiline {
+                true => expand_object(value, language, &prefix, &unit, comma),
+                false => None,
+            }
+            .unwrap_or_else(|| format::indent_code(value, &prefix));
+            if text == node_text(&current, source) {
+                return Ok(vec![]); // Already set -- no-op
+            }
+            return Ok(vec![TextEdit {
+                start: current.start_byte(),
+                end: current.end_byte(),
+                replacement: text,
+                label: label.to_string(),
+                priority: 0,
+            }]);
+        }
+        if current.kind() != "object" {
+            return Err(OperationError::InvalidParams {
+                message: format!("'{}' is a {}, not an object", first, current.kind()),
+            });
+        }
+        return set_property(source, profile, language, current, rest, value, label);
+    }
+
+    // Missing: `first: { rest...: value }`
+    let prefix = member_prefix(source, &profile.indent, &object);
+    if !multiline {
+        let mut text = value.to_string();
+        for key in rest.iter().rev() {
+            text = format!("{{ {}: {} }}", render_key(key, profile), text);
+        }
+        let text = format!("{}: {}", render_key(first, profile), text);
+        return Ok(insert_member(
+            source,
+            &profile.indent,
+            &object,
+            None,
+            &text,
+            label,
+        ));
+    }
+    let value_prefix = prefix.clone() + &unit.repeat(rest.len());
+    let mut text = expand_object(value, language, &value_prefix, &unit, comma)
+        .unwrap_or_else(|| format::indent_code(value, &value_prefix));
+    for (depth, key) in rest.iter().enumerate().rev() {
+        let outer = format!("{}{}", prefix, unit.repeat(depth));
+        text = format!(
+            "{{\n{}{}{}: {}{}\n{}}}",
+            outer,
+            unit,
+            render_key(key, profile),
+            text,
+            comma,
+            outer
+        );
+    }
+    let text = format!("{}: {}", render_key(first, profile), text);
+    Ok(insert_member(
+        source,
+        &profile.indent,
+        &object,
+        None,
+        &text,
+        label,
+    ))
+}
+
+/// An object literal `value` with one member per line, its closing brace
+/// at `prefix`. `None` for other values, objects holding comments, and
+/// single-member objects written on one line (`{ manualChunks }`).
+fn expand_object(
+    value: &str,
+    language: &Language,
+    prefix: &str,
+    unit: &str,
+    comma: &str,
+) -> Option<String> {
+    let wrapped = format!("({})", value);
+    let mut parser = Parser::new();
+    parser.set_language(language).ok()?;
+    let tree = parser.parse(&wrapped, None)?;
+    let root = tree.root_node();
+    if root.has_error() || root.named_child_count() != 1 {
+        return None;
+    }
+    let object = root.named_child(0)?.named_child(0)?.named_child(0)?;
+    if object.kind() != "object" {
+        return None;
+    }
+    let mut cursor = object.walk();
+    let items: Vec<Node> = object.named_children(&mut cursor).collect();
+    if items.iter().any(|n| n.kind() == "comment") || (items.len() < 2 && !value.contains('\n')) {
+        return None;
+    }
+
+    let inner = format!("{}{}", prefix, unit);
+    let lines: Vec<String> = items
+        .iter()
+        .map(|item| {
+            // Continuation lines keep their indentation relative to the member
+            let base = format::extract_line_prefix(&wrapped, item.start_byte());
+            let text: Vec<&str> = node_text(item, &wrapped)
+                .lines()
+                .map(|line| line.strip_prefix(base.as_str()).unwrap_or(line))
+                .collect();
+            format!("{}{}", inner, format::indent_code(&text.join("\n"), &inner))
+        })
+        .collect();
+    Some(format!("{{\n{}{}\n{}}}", lines.join(",\n"), comma, prefix))
+}
+
+fn render_key(key: &str, profile: &StyleProfile) -> String {
+    let mut chars = key.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        key.to_string()
+    } else {
+        format!("{}{}{}", profile.quote, key, profile.quote)
+    }
+}
+
+fn uses_trailing_commas(source: &str, profile: &StyleProfile, object: &Node) -> bool {
+    let multiline = node_text(object, source).contains('\n');
+    members(object)
+        .last()
+        .and_then(|last| last.next_sibling())
+        .is_some_and(|n| n.kind() == ",")
+        || (!multiline && profile.trailing_commas != TrailingCommas::None)
+}
+
+/// `react()` -> `react`, `tailwind.plugin({})` -> `tailwind.plugin`.
+fn callee_of(value: &str) -> Option<String> {
+    let callee = value.trim().split('(').next()?.trim();
+    let valid = !callee.is_empty()
+        && value.contains('(')
+        && callee
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.');
+    valid.then(|| callee.to_string())
+}
+
+fn compact(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Import `name` from `module`, as ESM or CommonJS depending on the file.
+fn import_edits(
+    source: &str,
+    tree: &Tree,
+    style: &StyleConfig,
+    module: &str,
+    name: &str,
+    named: bool,
+) -> Result<Vec<TextEdit>, OperationError> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let statements: Vec<Node> = root.named_children(&mut cursor).collect();
+    let is_esm = statements
+        .iter()
+        .any(|s| matches!(s.kind(), "import_statement" | "export_statement"));
+    if is_esm {
+        let (specifiers, default) = if named {
+            (vec![name.to_string()], None)
+        } else {
+            (vec![], Some(name.to_string()))
+        };
+        return AddImport::new(module.to_string(), specifiers, default, false)
+            .compute_edits_with_style(source, tree, style)
+            .map(|(edits, _)| edits);
+    }
+
+    // CommonJS: after the last top-level `require`, else at the top
+    let profile = style.resolve(source);
+    let quote = profile.quote;
+    if source.contains(&format!("require({}{}{})", quote, module, quote)) {
+        return Ok(vec![]);
+    }
+    let binding = if named {
+        format!("{{ {} }}", name)
+    } else {
+        name.to_string()
+    };
+    let semi = profile.semi();
+    let line = format!(
+        "const {} = require({}{}{}){}\n",
+        binding, quote, module, quote, semi
+    );
+    let after_requires = statements
+        .iter()
+        .rfind(|s| {
+            matches!(s.kind(), "lexical_declaration" | "variable_declaration")
+                && node_text(s, source).contains("require(")
+        })
+        .map(|s| {
+            source[s.end_byte()..]
+                .find('\n')
+                .map_or(source.len(), |i| s.end_byte() + i + 1)
+        });
+    let (at, text) = match after_requires {
+        Some(at) => (at, line),
+        None => (0, format!("{}\n", line)),
+    };
+    Ok(vec![TextEdit {
+        start: at,
+        end: at,
+        replacement: text,
+        label: format!("require '{}'", module),
+        priority: 0,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_ts(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(!parse_ts(&result).root_node().has_error(), "{}", result);
+        result
+    }
+
+    const VITE: &str = "import { defineConfig } from 'vite'\nimport react from '@vitejs/plugin-react'\n\nexport default defineConfig({\n  plugins: [react()],\n  server: {\n    port: 3000,\n  },\n})\n";
+
+    #[test]
+    fn test_set_existing_and_nested_missing() {
+        let op = ConfigSet::new("server.port".into(), "5173".into());
+        assert!(run(&op, VITE).contains("    port: 5173,\n"));
+
+        let op = ConfigSet::new(
+            "build.rollupOptions.output".into(),
+            "{ manualChunks }".into(),
+        );
+        assert!(run(&op, VITE).contains(
+            "  server: {\n    port: 3000,\n  },\n  build: {\n    rollupOptions: {\n      output: { manualChunks },\n    },\n  },\n})"
+        ));
+    }
+
+    #[test]
+    fn test_set_object_value_follows_parent_layout() {
+        let op = ConfigSet::new("server".into(), "{ port: 5173, host: true }".into());
+        assert!(run(&op, VITE).contains("  server: {\n    port: 5173,\n    host: true,\n  },\n})"));
+
+        let op = ConfigSet::new("resolve.alias".into(), "{\n'@': './src',\n}".into());
+        assert!(run(&op, VITE)
+            .contains("  resolve: {\n    alias: {\n      '@': './src',\n    },\n  },\n})"));
+
+        let source = "export default { base: '/' }\n";
+        let op = ConfigSet::new("server.port".into(), "3000".into());
+        assert_eq!(
+            run(&op, source),
+            "export default { base: '/', server: { port: 3000 } }\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_property() {
+        let op = ConfigRemove::new("server".into());
+        assert_eq!(
+            run(&op, VITE),
+            "import { defineConfig } from 'vite'\nimport react from '@vitejs/plugin-react'\n\nexport default defineConfig({\n  plugins: [react()],\n})\n"
+        );
+    }
+
+    #[test]
+    fn test_append_plugin_with_import_and_dedupe() {
+        let op = ConfigAppend::new(
+            "plugins".into(),
+            "tsconfigPaths()".into(),
+            Some("vite-tsconfig-paths".into()),
+            false,
+        );
+        let result = run(&op, VITE);
+        assert!(result.contains("  plugins: [react(), tsconfigPaths()],\n"));
+        assert!(result.contains("import tsconfigPaths from 'vite-tsconfig-paths'\n"));
+
+        let op = ConfigAppend::new(
+            "plugins".into(),
+            "react({ fastRefresh: false })".into(),
+            Some("@vitejs/plugin-react".into()),
+            false,
+        );
+        let tree = parse_ts(VITE);
+        let (edits, warnings) = op.compute_edits_with_warnings(VITE, &tree).unwrap();
+        assert!(edits.is_empty());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_commonjs_config_through_variable() {
+        let source = "/** @type {import('next').NextConfig} */\nconst nextConfig = {\n  reactStrictMode: true\n}\n\nmodule.exports = nextConfig\n";
+        let op = ConfigAppend::new(
+            "images.domains".into(),
+            "'cdn.example.com'".into(),
+            None,
+            false,
+        );
+        assert!(run(&op, source).contains(
+            "  reactStrictMode: true,\n  images: {\n    domains: ['cdn.example.com']\n  }\n}"
+        ));
+    }
+
+    #[test]
+    fn test_tailwind_satisfies_and_require_import() {
+        let source = "module.exports = {\n  content: ['./src/**/*.tsx'],\n  plugins: [],\n}\n";
+        let op = ConfigAppend::new(
+            "plugins".into(),
+            "require('@tailwindcss/forms')".into(),
+            None,
+            false,
+        );
+        assert!(run(&op, source).contains("  plugins: [require('@tailwindcss/forms')],\n"));
+
+        let source = "import type { Config } from 'tailwindcss'\n\nexport default {\n  content: [],\n} satisfies Config\n";
+        let op = ConfigSet::new("theme.extend.colors.brand".into(), "'#0af'".into());
+        assert!(run(&op, source).contains("      colors: {\n        brand: '#0af',\n"));
+    }
+}
@@ -0,0 +1,1185 @@
+//! CSS operations: `add_rule`, `remove_rule`, `set_declaration`,
+//! `remove_declaration`, `rename_selector` and `set_custom_property`.
+//!
+//! Rules are addressed by their selector text (whitespace-insensitive)
+//! and, optionally, the query of the `@media` block containing them.
+//! Without a `media` query only top-level rules are considered.
+//!
+//! The same operations work on SCSS and Less files: rules nested inside
+//! other rules are matched by their resolved selector, so `.card:hover`
+//! finds `.card { &:hover { ... } }`.
+
+use crate::edit::TextEdit;
+use crate::format::{self, IndentStyle, StyleConfig};
+use crate::operations::node_utils::{named_children, node_text};
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// The add_rule operation.
+///
+/// Adds `selector { declarations }` at the end of the stylesheet or of
+/// the matching `@media` block (created if missing). If the rule already
+/// exists, only the missing declarations are added.
+pub struct AddRule {
+    pub selector: String,
+    /// Declarations such as `"color: red"`.
+    pub declarations: Vec<String>,
+    pub media: Option<String>,
+}
+
+impl AddRule {
+    pub fn new(selector: String, declarations: Vec<String>, media: Option<String>) -> Self {
+        Self {
+            selector,
+            declarations,
+            media,
+        }
+    }
+}
+
+impl Executable for AddRule {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = stylesheet(tree)?;
+        let declarations = parse_declarations(&self.declarations)?;
+        let label = format!("add rule '{}'", self.selector);
+        let unit = format::indent_deeper("", &profile.indent);
+
+        let container = match &self.media {
+            Some(query) => match find_media_block(&root, source, query) {
+                Some(block) => block,
+                None => {
+                    // Create the @media block with the rule inside
+                    let rule = render_rule(&self.selector, &declarations, &unit, &unit);
+                    let text = format!("@media {} {{\n{}\n}}", query.trim(), rule);
+                    return Ok((
+                        vec![append_to_stylesheet(source, &root, &text, label)],
+                        vec![],
+                    ));
+                }
+            },
+            None => root,
+        };
+
+        if let Some(rule) = find_rules(&container, source, &self.selector).last() {
+            // Already there: fill in missing declarations only
+            let block = rule_block(rule)?;
+            let existing: Vec<String> = declarations_of(&block)
+                .iter()
+                .filter_map(|d| property_of(d, source))
+                .map(|p| p.to_lowercase())
+                .collect();
+            let missing: Vec<(String, String)> = declarations
+                .into_iter()
+                .filter(|(p, _)| !existing.contains(&p.to_lowercase()))
+                .collect();
+            if missing.is_empty() {
+                return Ok((vec![], vec![]));
+            }
+            return Ok((
+                vec![append_declarations(
+                    source,
+                    &profile.indent,
+                    &block,
+                    &missing,
+                    label,
+                )],
+                vec![],
+            ));
+        }
+
+        if container.kind() == "stylesheet" {
+            let text = render_rule(&self.selector, &declarations, "", &unit);
+            return Ok((
+                vec![append_to_stylesheet(source, &root, &text, label)],
+                vec![],
+            ));
+        }
+
+        // Inside an existing @media block
+        let members = named_children(&container);
+        let prefix = match members.first() {
+            Some(first) => format::extract_line_prefix(source, first.start_byte()),
+            None => format!(
+                "{}{}",
+                format::extract_line_prefix(source, container.start_byte()),
+                unit
+            ),
+        };
+        let rule = render_rule(&self.selector, &declarations, &prefix, &unit);
+        let close = container.end_byte() - 1;
+        let edit = match members.last() {
+            Some(last) => TextEdit {
+                start: last.end_byte(),
+                end: last.end_byte(),
+                replacement: format!("\n\n{}", rule),
+                label,
+                priority: 0,
+            },
+            None => TextEdit {
+                start: container.start_byte() + 1,
+                end: close,
+                replacement: format!(
+                    "\n{}\n{}",
+                    rule,
+                    format::extract_line_prefix(source, container.start_byte())
+                ),
+                label,
+                priority: 0,
+            },
+        };
+        Ok((vec![edit], vec![]))
+    }
+}
+
+/// The remove_rule operation.
+///
+/// Removes the rule whose selector list matches `selector`. When
+/// `selector` is only one item of a selector list, just that item is
+/// removed and the rule is kept for the others.
+pub struct RemoveRule {
+    pub selector: String,
+    pub media: Option<String>,
+}
+
+impl RemoveRule {
+    pub fn new(selector: String, media: Option<String>) -> Self {
+        Self { selector, media }
+    }
+}
+
+impl Executable for RemoveRule {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let root = stylesheet(tree)?;
+        let container = scope(&root, source, self.media.as_deref())?;
+        let label = format!("remove rule '{}'", self.selector);
+
+        let rules = find_rules(&container, source, &self.selector);
+        if !rules.is_empty() {
+            return Ok(rules
+                .iter()
+                .map(|r| remove_lines(source, r, label.clone()))
+                .collect());
+        }
+
+        // One selector out of a list: `.a, .b { }` -> `.b { }`
+        let wanted = normalize_selector(&self.selector);
+        let mut edits = Vec::new();
+        for rule in named_children(&container)
+            .into_iter()
+            .filter(|n| n.kind() == "rule_set")
+        {
+            let Some(selectors) = rule.named_child(0).filter(|s| s.kind() == "selectors") else {
+                continue;
+            };
+            let items = named_children(&selectors);
+            for (i, item) in items.iter().enumerate() {
+                if normalize_selector(node_text(item, source)) != wanted {
+                    continue;
+                }
+                let (start, end) = if i + 1 < items.len() {
+                    (item.start_byte(), items[i + 1].start_byte())
+                } else {
+                    let prev_end = items[i - 1].end_byte();
+                    (prev_end, item.end_byte())
+                };
+                edits.push(TextEdit {
+                    start,
+                    end,
+                    replacement: String::new(),
+                    label: label.clone(),
+                    priority: 0,
+                });
+            }
+        }
+
+        if edits.is_empty() {
+            return Err(rule_not_found(&self.selector, self.media.as_deref()));
+        }
+        Ok(edits)
+    }
+}
+
+/// The set_declaration operation.
+///
+/// Sets `property: value` in the rule matching `selector`, replacing the
+/// last existing declaration of that property or appending a new one.
+pub struct SetDeclaration {
+    pub selector: String,
+    pub property: String,
+    pub value: String,
+    pub media: Option<String>,
+}
+
+impl SetDeclaration {
+    pub fn new(selector: String, property: String, value: String, media: Option<String>) -> Self {
+        Self {
+            selector,
+            property,
+            value,
+            media,
+        }
+    }
+}
+
+impl Executable for SetDeclaration {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = stylesheet(tree)?;
+        let container = scope(&root, source, self.media.as_deref())?;
+        let rule = find_rules(&container, source, &self.selector)
+            .last()
+            .copied()
+            .ok_or_else(|| rule_not_found(&self.selector, self.media.as_deref()))?;
+        let block = rule_block(&rule)?;
+        set_declaration(source, &profile.indent, &block, &self.property, &self.value)
+            .map(|edits| (edits, vec![]))
+    }
+}
+
+/// The remove_declaration operation.
+pub struct RemoveDeclaration {
+    pub selector: String,
+    pub property: String,
+    pub media: Option<String>,
+}
+
+impl RemoveDeclaration {
+    pub fn new(selector: String, property: String, media: Option<String>) -> Self {
+        Self {
+            selector,
+            property,
+            media,
+        }
+    }
+}
+
+impl Executable for RemoveDeclaration {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let root = stylesheet(tree)?;
+        let container = scope(&root, source, self.media.as_deref())?;
+        let label = format!("remove declaration '{}'", self.property);
+
+        let mut edits = Vec::new();
+        for rule in find_rules(&container, source, &self.selector) {
+            let block = rule_block(&rule)?;
+            for decl in declarations_of(&block) {
+                if property_of(&decl, source)
+                    .is_some_and(|p| p.eq_ignore_ascii_case(&self.property))
+                {
+                    edits.push(remove_lines(source, &decl, label.clone()));
+                }
+            }
+        }
+
+        if edits.is_empty() {
+            return Err(OperationError::TargetNotFound {
+                description: format!(
+                    "Declaration '{}' not found in rule '{}'",
+                    self.property, self.selector
+                ),
+            });
+        }
+        Ok(edits)
+    }
+}
+
+/// The rename_selector operation.
+///
+/// Replaces every selector-list item equal to `from`, in every context.
+/// When both `from` and `to` are plain class selectors (`.card`), the
+/// class is also renamed inside compound selectors (`.card:hover`,
+/// `.list .card`).
+pub struct RenameSelector {
+    pub from: String,
+    pub to: String,
+}
+
+impl RenameSelector {
+    pub fn new(from: String, to: String) -> Self {
+        Self { from, to }
+    }
+}
+
+impl Executable for RenameSelector {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let root = stylesheet(tree)?;
+        let label = format!("rename selector {} -> {}", self.from, self.to);
+        let wanted = normalize_selector(&self.from);
+        let class_rename = plain_class(&self.from).zip(plain_class(&self.to));
+
+        let mut edits = Vec::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "selectors" {
+                for item in named_children(&node) {
+                    if normalize_selector(node_text(&item, source)) == wanted {
+                        edits.push(TextEdit {
+                            start: item.start_byte(),
+                            end: item.end_byte(),
+                            replacement: self.to.trim().to_string(),
+                            label: label.clone(),
+                            priority: 0,
+                        });
+                    } else if let Some((from, to)) = class_rename {
+                        collect_class_names(&item, source, from, &mut |n| {
+                            edits.push(TextEdit {
+                                start: n.start_byte(),
+                                end: n.end_byte(),
+                                replacement: to.to_string(),
+                                label: label.clone(),
+                                priority: 0,
+                            })
+                        });
+                    }
+                }
+                continue;
+            }
+            stack.extend(named_children(&node));
+        }
+
+        if edits.is_empty() {
+            return Err(OperationError::TargetNotFound {
+                description: format!("Selector '{}' not found", self.from),
+            });
+        }
+        Ok(edits)
+    }
+}
+
+/// The set_custom_property operation.
+///
+/// Adds or updates `--name: value` in the top-level `:root` rule,
+/// creating the rule at the top of the stylesheet if needed.
+pub struct SetCustomProperty {
+    pub name: String,
+    pub value: String,
+}
+
+impl SetCustomProperty {
+    pub fn new(name: String, value: String) -> Self {
+        Self { name, value }
+    }
+}
+
+impl Executable for SetCustomProperty {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = stylesheet(tree)?;
+        let name = if self.name.starts_with("--") {
+            self.name.clone()
+        } else {
+            format!("--{}", self.name)
+        };
+
+        if let Some(rule) = find_rules(&root, source, ":root").first() {
+            let block = rule_block(rule)?;
+            return set_declaration(source, &profile.indent, &block, &name, &self.value)
+                .map(|edits| (edits, vec![]));
+        }
+
+        // New :root rule after any @charset/@import statements
+        let unit = format::indent_deeper("", &profile.indent);
+        let rule = render_rule(
+            ":root",
+            &[(name.clone(), self.value.trim().to_string())],
+            "",
+            &unit,
+        );
+        let label = format!("add custom property '{}'", name);
+        let preamble = named_children(&root)
+            .into_iter()
+            .take_while(|n| {
+                matches!(
+                    n.kind(),
+                    "import_statement" | "charset_statement" | "namespace_statement"
+                )
+            })
+            .last();
+        let edit = match preamble {
+            Some(last) => TextEdit {
+                start: last.end_byte(),
+                end: last.end_byte(),
+                replacement: format!("\n\n{}", rule),
+                label,
+                priority: 0,
+            },
+            None if source.trim().is_empty() => TextEdit {
+                start: 0,
+                end: source.len(),
+                replacement: format!("{}\n", rule),
+                label,
+                priority: 0,
+            },
+            None => TextEdit {
+                start: 0,
+                end: 0,
+                replacement: format!("{}\n\n", rule),
+                label,
+                priority: 0,
+            },
+        };
+        Ok((vec![edit], vec![]))
+    }
+}
+
+// --- Helper functions ---
+
+/// The stylesheet root, or an error for non-CSS trees.
+fn stylesheet(tree: &Tree) -> Result<Node<'_>, OperationError> {
+    let root = tree.root_node();
+    if root.kind() != "stylesheet" {
+        return Err(OperationError::UnsupportedLanguage {
+            language: format!("CSS operations need a stylesheet, got '{}'", root.kind()),
+        });
+    }
+    Ok(root)
+}
+
+/// Collapse whitespace and drop it around combinators and commas.
+fn normalize_selector(selector: &str) -> String {
+    let collapsed = selector.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut out = String::with_capacity(collapsed.len());
+    for c in collapsed.chars() {
+        if matches!(c, ',' | '>' | '+' | '~') {
+            if out.ends_with(' ') {
+                out.pop();
+            }
+            out.push(c);
+        } else if c == ' ' && out.ends_with([',', '>', '+', '~']) {
+            continue;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Collapse whitespace and drop it inside features: `(max-width:600px)`.
+fn normalize_query(query: &str) -> String {
+    let collapsed = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut out = String::with_capacity(collapsed.len());
+    for c in collapsed.chars() {
+        if matches!(c, ':' | ')') && out.ends_with(' ') {
+            out.pop();
+        }
+        if c == ' ' && out.ends_with([':', '(']) {
+            continue;
+        }
+        out.push(c);
+    }
+    out.to_lowercase()
+}
+
+/// The block of the top-level `@media <query>` statement.
+fn find_media_block<'a>(root: &Node<'a>, source: &str, query: &str) -> Option<Node<'a>> {
+    let wanted = normalize_query(query.trim().trim_start_matches("@media"));
+    named_children(root)
+        .into_iter()
+        .filter(|n| n.kind() == "media_statement")
+        .find(|m| {
+            let block = named_children(m).into_iter().find(|c| c.kind() == "block");
+            let query_end = block.map(|b| b.start_byte()).unwrap_or(m.end_byte());
+            let text = &source[m.start_byte() + "@media".len()..query_end];
+            normalize_query(text) == wanted
+        })
+        .and_then(|m| named_children(&m).into_iter().find(|c| c.kind() == "block"))
+}
+
+/// The node whose children are searched for rules.
+fn scope<'a>(
+    root: &Node<'a>,
+    source: &str,
+    media: Option<&str>,
+) -> Result<Node<'a>, OperationError> {
+    match media {
+        None => Ok(*root),
+        Some(query) => {
+            find_media_block(root, source, query).ok_or_else(|| OperationError::TargetNotFound {
+                description: format!("@media {} not found", query.trim()),
+            })
+        }
+    }
+}
+
+fn rule_not_found(selector: &str, media: Option<&str>) -> OperationError {
+    let context = media
+        .map(|m| format!(" inside @media {}", m.trim()))
+        .unwrap_or_default();
+    OperationError::TargetNotFound {
+        description: format!("Rule '{}' not found{}", selector, context),
+    }
+}
+
+/// Rules inside `container` whose selector list matches, either as
+/// written or, for rules nested in other rules (SCSS/Less), as resolved
+/// against their parents: `.card { &:hover {} }` matches `.card:hover`.
+fn find_rules<'a>(container: &Node<'a>, source: &str, selector: &str) -> Vec<Node<'a>> {
+    let wanted = normalize_selector(selector);
+    let mut rules = Vec::new();
+    collect_rules(container, source, None, &mut |rule, written, resolved| {
+        if written == wanted || resolved.join(",") == wanted {
+            rules.push(rule);
+        }
+    });
+    rules
+}
+
+/// Walk rule sets below `container`, reporting each with its written
+/// selector text and its selector items resolved against enclosing rules.
+fn collect_rules<'a>(
+    container: &Node<'a>,
+    source: &str,
+    parents: Option<&[String]>,
+    on_rule: &mut dyn FnMut(Node<'a>, String, Vec<String>),
+) {
+    for rule in named_children(container)
+        .into_iter()
+        .filter(|n| n.kind() == "rule_set")
+    {
+        let Some(selectors) = rule
+            .named_child(0)
+            .filter(|s| s.kind() == "selectors" && !s.has_error())
+        else {
+            continue;
+        };
+        let items: Vec<String> = named_children(&selectors)
+            .iter()
+            .map(|item| normalize_selector(node_text(item, source)))
+            .collect();
+        let resolved = match parents {
+            Some(parents) => resolve_nested(parents, &items),
+            None => items,
+        };
+        on_rule(
+            rule,
+            normalize_selector(node_text(&selectors, source)),
+            resolved.clone(),
+        );
+        if let Ok(block) = rule_block(&rule) {
+            collect_rules(&block, source, Some(&resolved), on_rule);
+        }
+    }
+}
+
+/// Combine nested selector items with their parents' items, replacing
+/// `&` or prefixing with a descendant combinator.
+fn resolve_nested(parents: &[String], items: &[String]) -> Vec<String> {
+    let mut resolved = Vec::new();
+    for parent in parents {
+        for item in items {
+            let combined = if item.contains('&') {
+                item.replace('&', parent)
+            } else {
+                format!("{} {}", parent, item)
+            };
+            resolved.push(normalize_selector(&combined));
+        }
+    }
+    resolved
+}
+
+fn rule_block<'a>(rule: &Node<'a>) -> Result<Node<'a>, OperationError> {
+    named_children(rule)
+        .into_iter()
+        .find(|c| c.kind() == "block")
+        .ok_or_else(|| OperationError::TargetNotFound {
+            description: "Rule has no block".to_string(),
+        })
+}
+
+fn declarations_of<'a>(block: &Node<'a>) -> Vec<Node<'a>> {
+    named_children(block)
+        .into_iter()
+        .filter(|n| n.kind() == "declaration")
+        .collect()
+}
+
+fn property_of<'s>(decl: &Node, source: &'s str) -> Option<&'s str> {
+    named_children(decl)
+        .into_iter()
+        .find(|c| c.kind() == "property_name")
+        .map(|p| node_text(&p, source))
+}
+
+/// Split `"color: red"` into its property and value.
+fn parse_declarations(declarations: &[String]) -> Result<Vec<(String, String)>, OperationError> {
+    declarations
+        .iter()
+        .map(|d| {
+            let d = d.trim().trim_end_matches(';');
+            let (property, value) =
+                d.split_once(':')
+                    .ok_or_else(|| OperationError::InvalidParams {
+                        message: format!("Declaration '{}' must look like 'property: value'", d),
+                    })?;
+            Ok((property.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn render_rule(
+    selector: &str,
+    declarations: &[(String, String)],
+    prefix: &str,
+    unit: &str,
+) -> String {
+    if declarations.is_empty() {
+        return format!("{}{} {{}}", prefix, selector.trim());
+    }
+    let body: Vec<String> = declarations
+        .iter()
+        .map(|(p, v)| format!("{}{}{}: {};", prefix, unit, p, v))
+        .collect();
+    format!(
+        "{}{} {{\n{}\n{}}}",
+        prefix,
+        selector.trim(),
+        body.join("\n"),
+        prefix
+    )
+}
+
+fn append_to_stylesheet(source: &str, root: &Node, text: &str, label: String) -> TextEdit {
+    match named_children(root).last() {
+        Some(last) => TextEdit {
+            start: last.end_byte(),
+            end: last.end_byte(),
+            replacement: format!("\n\n{}", text),
+            label,
+            priority: 0,
+        },
+        None => TextEdit {
+            start: 0,
+            end: source.len(),
+            replacement: format!("{}\n", text),
+            label,
+            priority: 0,
+        },
+    }
+}
+
+/// Replace the value of the last `property` declaration, or append one.
+fn set_declaration(
+    source: &str,
+    indent: &IndentStyle,
+    block: &Node,
+    property: &str,
+    value: &str,
+) -> Result<Vec<TextEdit>, OperationError> {
+    let label = format!("set {}: {}", property, value.trim());
+    let existing = declarations_of(block)
+        .into_iter()
+        .rev()
+        .find(|d| property_of(d, source).is_some_and(|p| p.eq_ignore_ascii_case(property)));
+
+    let Some(decl) = existing else {
+        return Ok(vec![append_declarations(
+            source,
+            indent,
+            block,
+            &[(property.to_string(), value.trim().to_string())],
+            label,
+        )]);
+    };
+
+    // Replace everything between the colon and the semicolon
+    let mut cursor = decl.walk();
+    let children: Vec<Node> = decl.children(&mut cursor).collect();
+    let colon = children.iter().find(|c| c.kind() == ":").ok_or_else(|| {
+        OperationError::TargetNotFound {
+            description: format!("Malformed declaration '{}'", node_text(&decl, source)),
+        }
+    })?;
+    let end = children
+        .iter()
+        .find(|c| c.kind() == ";")
+        .map(|c| c.start_byte())
+        .unwrap_or(decl.end_byte());
+    let current = source[colon.end_byte()..end].trim();
+    if current == value.trim() {
+        return Ok(vec![]); // Already set -- no-op
+    }
+    Ok(vec![TextEdit {
+        start: colon.end_byte(),
+        end,
+        replacement: format!(" {}", value.trim()),
+        label,
+        priority: 0,
+    }])
+}
+
+/// Add declarations after the last declaration of a block (before any
+/// nested rules when it has none), matching its layout.
+fn append_declarations(
+    source: &str,
+    indent: &IndentStyle,
+    block: &Node,
+    declarations: &[(String, String)],
+    label: String,
+) -> TextEdit {
+    let members = named_children(block);
+    let close = block.end_byte() - 1;
+    let single_line = block.start_position().row == block.end_position().row;
+    let last_declaration = members.iter().rev().find(|m| m.kind() == "declaration");
+
+    // A trailing declaration may omit its semicolon
+    let needs_semi =
+        last_declaration.is_some_and(|d| !node_text(d, source).trim_end().ends_with(';'));
+    let semi = if needs_semi { ";" } else { "" };
+
+    if single_line {
+        let text: Vec<String> = declarations
+            .iter()
+            .map(|(p, v)| format!("{}: {};", p, v))
+            .collect();
+        return match (last_declaration, members.first()) {
+            (Some(last), _) => TextEdit {
+                start: last.end_byte(),
+                end: last.end_byte(),
+                replacement: format!("{} {}", semi, text.join(" ")),
+                label,
+                priority: 0,
+            },
+            (None, Some(first)) => TextEdit {
+                start: first.start_byte(),
+                end: first.start_byte(),
+                replacement: format!("{} ", text.join(" ")),
+                label,
+                priority: 0,
+            },
+            (None, None) => TextEdit {
+                start: block.start_byte() + 1,
+                end: close,
+                replacement: format!(" {} ", text.join(" ")),
+                label,
+                priority: 0,
+            },
+        };
+    }
+
+    let block_prefix = format::extract_line_prefix(source, block.start_byte());
+    let prefix = match members.first() {
+        Some(first) => format::extract_line_prefix(source, first.start_byte()),
+        None => format::indent_deeper(&block_prefix, indent),
+    };
+    let lines: Vec<String> = declarations
+        .iter()
+        .map(|(p, v)| format!("{}{}: {};", prefix, p, v))
+        .collect();
+    match (last_declaration, members.first()) {
+        (Some(last), _) => TextEdit {
+            start: last.end_byte(),
+            end: last.end_byte(),
+            replacement: format!("{}\n{}", semi, lines.join("\n")),
+            label,
+            priority: 0,
+        },
+        (None, Some(first)) => TextEdit {
+            start: first.start_byte(),
+            end: first.start_byte(),
+            replacement: format!("{}\n{}", lines.join("\n").trim_start(), prefix),
+            label,
+            priority: 0,
+        },
+        (None, None) => TextEdit {
+            start: block.start_byte() + 1,
+            end: close,
+            replacement: format!("\n{}\n{}", lines.join("\n"), block_prefix),
+            label,
+            priority: 0,
+        },
+    }
+}
+
+/// Remove a node; whole lines (and one adjoining blank line) when it
+/// stands on its own lines.
+fn remove_lines(source: &str, node: &Node, label: String) -> TextEdit {
+    let start = node.start_byte();
+    let end = node.end_byte();
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = source[end..]
+        .find('\n')
+        .map(|i| end + i + 1)
+        .unwrap_or(source.len());
+
+    if !source[line_start..start].trim().is_empty() || !source[end..line_end].trim().is_empty() {
+        // Shares its line with other code: remove it and the space before
+        let ws_start = source[..start].trim_end_matches([' ', '\t']).len();
+        return TextEdit {
+            start: ws_start.max(line_start),
+            end,
+            replacement: String::new(),
+            label,
+            priority: 0,
+        };
+    }
+
+    // Swallow one blank line so rules stay separated by exactly one
+    let mut end = line_end;
+    let next_blank = source[end..]
+        .find('\n')
+        .is_some_and(|i| source[end..end + i].trim().is_empty());
+    let prev_blank = line_start == 0 || source[..line_start].ends_with("\n\n");
+    if next_blank && (prev_blank || source[line_end..].trim().is_empty()) {
+        end += source[end..].find('\n').unwrap() + 1;
+    } else if source[line_end..].trim().is_empty() && prev_blank && line_start > 0 {
+        // Last rule in the file: drop the blank line before it instead
+        return TextEdit {
+            start: line_start - 1,
+            end: line_end.min(source.len()),
+            replacement: String::new(),
+            label,
+            priority: 0,
+        };
+    }
+
+    TextEdit {
+        start: line_start,
+        end,
+        replacement: String::new(),
+        label,
+        priority: 0,
+    }
+}
+
+/// `card` for `.card`, `None` for anything more complex.
+fn plain_class(selector: &str) -> Option<&str> {
+    let name = selector.trim().strip_prefix('.')?;
+    name.chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        .then_some(name)
+}
+
+fn collect_class_names<'a>(
+    node: &Node<'a>,
+    source: &str,
+    name: &str,
+    on_match: &mut dyn FnMut(Node<'a>),
+) {
+    if node.kind() == "class_selector" {
+        if let Some(class) = named_children(node)
+            .into_iter()
+            .find(|c| c.kind() == "class_name")
+        {
+            if node_text(&class, source) == name {
+                on_match(class);
+            }
+        }
+    }
+    for child in named_children(node) {
+        collect_class_names(&child, source, name, on_match);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use crate::validate::verify_parse;
+    use tree_sitter::Parser;
+
+    fn css() -> tree_sitter::Language {
+        tree_sitter_css::LANGUAGE.into()
+    }
+
+    fn parse_css(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser.set_language(&css()).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_css(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        if edits.is_empty() {
+            return source.to_string();
+        }
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(
+            verify_parse(&result, &css()).is_ok(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        result
+    }
+
+    const SHEET: &str = ".card {\n  color: red;\n  padding: 4px;\n}\n\n@media (max-width: 600px) {\n  .card {\n    padding: 0;\n  }\n}\n";
+
+    // --- add_rule tests ---
+
+    #[test]
+    fn test_add_rule_at_end() {
+        let op = AddRule::new(".title".into(), vec!["font-weight: bold".into()], None);
+        let result = run(&op, SHEET);
+        assert!(result.ends_with("}\n\n.title {\n  font-weight: bold;\n}\n"));
+    }
+
+    #[test]
+    fn test_add_rule_inside_media() {
+        let op = AddRule::new(
+            ".title".into(),
+            vec!["display: none".into()],
+            Some("(max-width:600px)".into()),
+        );
+        let result = run(&op, SHEET);
+        assert!(result.contains("    padding: 0;\n  }\n\n  .title {\n    display: none;\n  }\n}\n"));
+    }
+
+    #[test]
+    fn test_add_rule_creates_media_block() {
+        let op = AddRule::new(
+            ".card".into(),
+            vec!["color: black".into()],
+            Some("print".into()),
+        );
+        let result = run(&op, SHEET);
+        assert!(result.ends_with("@media print {\n  .card {\n    color: black;\n  }\n}\n"));
+    }
+
+    #[test]
+    fn test_add_existing_rule_adds_missing_declarations() {
+        let op = AddRule::new(
+            ".card".into(),
+            vec!["color: blue".into(), "margin: 0".into()],
+            None,
+        );
+        let result = run(&op, SHEET);
+        assert!(result.starts_with(".card {\n  color: red;\n  padding: 4px;\n  margin: 0;\n}\n"));
+    }
+
+    // --- remove_rule tests ---
+
+    #[test]
+    fn test_remove_rule() {
+        let source = ".a {\n  color: red;\n}\n\n.b {\n  color: blue;\n}\n\n.c {}\n";
+        let result = run(&RemoveRule::new(".b".into(), None), source);
+        assert_eq!(result, ".a {\n  color: red;\n}\n\n.c {}\n");
+    }
+
+    #[test]
+    fn test_remove_selector_from_list() {
+        let source = ".a, .b > p {\n  color: red;\n}\n";
+        let result = run(&RemoveRule::new(".b>p".into(), None), source);
+        assert_eq!(result, ".a {\n  color: red;\n}\n");
+    }
+
+    #[test]
+    fn test_remove_rule_inside_media_only() {
+        let op = RemoveRule::new(".card".into(), Some("(max-width: 600px)".into()));
+        let result = run(&op, SHEET);
+        assert!(result.starts_with(".card {\n  color: red;"));
+        assert!(result.contains("@media (max-width: 600px) {\n}"));
+    }
+
+    // --- declaration tests ---
+
+    #[test]
+    fn test_set_declaration_updates_value() {
+        let op = SetDeclaration::new(".card".into(), "color".into(), "blue".into(), None);
+        let result = run(&op, SHEET);
+        assert!(result.starts_with(".card {\n  color: blue;\n"));
+        assert!(result.contains("    padding: 0;"));
+    }
+
+    #[test]
+    fn test_set_declaration_appends_in_media() {
+        let op = SetDeclaration::new(
+            ".card".into(),
+            "color".into(),
+            "green".into(),
+            Some("(max-width: 600px)".into()),
+        );
+        let result = run(&op, SHEET);
+        assert!(result.contains("    padding: 0;\n    color: green;\n  }"));
+    }
+
+    #[test]
+    fn test_set_declaration_single_line_without_semicolon() {
+        let source = ".a { color: red }\n";
+        let op = SetDeclaration::new(".a".into(), "margin".into(), "0".into(), None);
+        assert_eq!(run(&op, source), ".a { color: red; margin: 0; }\n");
+    }
+
+    #[test]
+    fn test_remove_declaration() {
+        let op = RemoveDeclaration::new(".card".into(), "padding".into(), None);
+        let result = run(&op, SHEET);
+        assert!(result.starts_with(".card {\n  color: red;\n}\n"));
+        assert!(result.contains("    padding: 0;"));
+    }
+
+    // --- rename_selector tests ---
+
+    #[test]
+    fn test_rename_class_everywhere() {
+        let source = ".card, .list .card:hover {\n  color: red;\n}\n\n@media print {\n  .card {}\n}\n.cardholder {}\n";
+        let result = run(&RenameSelector::new(".card".into(), ".tile".into()), source);
+        assert_eq!(
+            result,
+            ".tile, .list .tile:hover {\n  color: red;\n}\n\n@media print {\n  .tile {}\n}\n.cardholder {}\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_complex_selector() {
+        let source = "ul > li {\n  margin: 0;\n}\n";
+        let result = run(
+            &RenameSelector::new("ul>li".into(), "ol > li".into()),
+            source,
+        );
+        assert_eq!(result, "ol > li {\n  margin: 0;\n}\n");
+    }
+
+    // --- nested (SCSS/Less) tests ---
+
+    const NESTED: &str = ".card {\n  padding: 4px;\n  // hover state\n  &:hover {\n    color: red;\n  }\n  .title {\n    margin: 0;\n  }\n}\n";
+
+    #[test]
+    fn test_set_declaration_on_nested_rule() {
+        let op = SetDeclaration::new(".card:hover".into(), "color".into(), "blue".into(), None);
+        let result = run(&op, NESTED);
+        assert!(result.contains("  &:hover {\n    color: blue;\n  }"));
+    }
+
+    #[test]
+    fn test_add_declaration_to_nested_descendant() {
+        let op = SetDeclaration::new(".card .title".into(), "padding".into(), "0".into(), None);
+        let result = run(&op, NESTED);
+        assert!(result.contains("  .title {\n    margin: 0;\n    padding: 0;\n  }"));
+    }
+
+    #[test]
+    fn test_remove_nested_rule() {
+        let result = run(&RemoveRule::new(".card:hover".into(), None), NESTED);
+        assert_eq!(
+            result,
+            ".card {\n  padding: 4px;\n  // hover state\n  .title {\n    margin: 0;\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_scss_variables_do_not_block_edits() {
+        // `$brand` is an ERROR node under the CSS grammar; edits elsewhere
+        // must still go through as long as they add no new errors.
+        let source = ".card {\n  color: $brand;\n}\n\n.title {\n  margin: 0;\n}\n";
+        let tree = parse_css(source);
+        assert!(tree.root_node().has_error());
+        let ops = vec![crate::operations::Operation::SetDeclaration {
+            file: None,
+            template: None,
+            selector: ".title".into(),
+            property: "margin".into(),
+            value: "4px".into(),
+            media: None,
+        }];
+        let result = crate::execute_operations(
+            source,
+            &tree,
+            &ops,
+            &css(),
+            crate::validate::ParseCheck::NoNewErrors,
+            &StyleConfig::default(),
+        )
+        .unwrap();
+        assert!(result.content.contains("margin: 4px;"));
+        assert!(result.content.contains("color: $brand;"));
+    }
+
+    #[test]
+    fn test_clean_check_refuses_source_with_errors() {
+        // Plain CSS gets no tolerance: a file that already fails to parse
+        // is refused rather than edited.
+        let source = ".card {\n  color: $brand;\n}\n\n.title {\n  margin: 0;\n}\n";
+        let tree = parse_css(source);
+        let ops = vec![crate::operations::Operation::SetDeclaration {
+            file: None,
+            template: None,
+            selector: ".title".into(),
+            property: "margin".into(),
+            value: "4px".into(),
+            media: None,
+        }];
+        assert!(matches!(
+            crate::execute_operations(
+                source,
+                &tree,
+                &ops,
+                &css(),
+                crate::validate::ParseCheck::Clean,
+                &StyleConfig::default(),
+            ),
+            Err(OperationError::InvalidResult { .. })
+        ));
+    }
+
+    // --- set_custom_property tests ---
+
+    #[test]
+    fn test_custom_property_updates_root() {
+        let source = ":root {\n  --brand: red;\n}\n";
+        let result = run(
+            &SetCustomProperty::new("brand".into(), "#00f".into()),
+            source,
+        );
+        assert_eq!(result, ":root {\n  --brand: #00f;\n}\n");
+    }
+
+    #[test]
+    fn test_custom_property_creates_root_after_imports() {
+        let source = "@import url('base.css');\n\nbody {\n  margin: 0;\n}\n";
+        let result = run(
+            &SetCustomProperty::new("--gap".into(), "8px".into()),
+            source,
+        );
+        assert_eq!(
+            result,
+            "@import url('base.css');\n\n:root {\n  --gap: 8px;\n}\n\nbody {\n  margin: 0;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_css_tree() {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        let source = "const a = 1;";
+        let tree = parser.parse(source, None).unwrap();
+        let op = RemoveRule::new(".a".into(), None);
+        assert!(matches!(
+            op.compute_edits(source, &tree),
+            Err(OperationError::UnsupportedLanguage { .. })
+        ));
+    }
+}
@@ -0,0 +1,526 @@
+//! Per-file half of `rename_css_module_class`.
+//!
+//! Rewrites `styles.card`, `styles?.card`, `styles['card']` and
+//! `const { card } = styles` on the default or namespace binding of a CSS
+//! Modules import, and `composes: card` references in stylesheets. Uses of
+//! the binding that could hide a reference (`fn(styles)`, `{ ...styles }`)
+//! are reported as warnings. Finding the importers and renaming the
+//! selector in the stylesheet is left to the caller, which knows about the
+//! file system.
+
+use crate::edit::TextEdit;
+use crate::operations::node_utils::{find_child_by_kind, named_children, node_text};
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// Renames a class on the bindings imported from a CSS module.
+pub struct RenameModuleClassUsages {
+    /// Module specifiers, as written in this file, that resolve to the
+    /// stylesheet (e.g. `./Card.module.css`).
+    pub module_sources: Vec<String>,
+    /// Class name without the leading dot.
+    pub from: String,
+    pub to: String,
+}
+
+impl RenameModuleClassUsages {
+    pub fn new(module_sources: Vec<String>, from: String, to: String) -> Self {
+        Self {
+            module_sources,
+            from: from.trim_start_matches('.').to_string(),
+            to: to.trim_start_matches('.').to_string(),
+        }
+    }
+}
+
+impl Executable for RenameModuleClassUsages {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let root = tree.root_node();
+        let mut warnings = Vec::new();
+        let bindings = self.module_bindings(&root, source, &mut warnings);
+        if bindings.is_empty() {
+            return Ok((vec![], warnings));
+        }
+
+        let mut edits = Vec::new();
+        collect_usages(&root, source, &bindings, self, &mut edits, &mut warnings);
+        Ok((edits, warnings))
+    }
+}
+
+impl RenameModuleClassUsages {
+    /// Local names bound to the stylesheet by default or namespace imports.
+    fn module_bindings(
+        &self,
+        root: &Node,
+        source: &str,
+        warnings: &mut Vec<String>,
+    ) -> Vec<String> {
+        let mut bindings = Vec::new();
+        let mut cursor = root.walk();
+        for stmt in root.children(&mut cursor) {
+            if stmt.kind() != "import_statement" {
+                continue;
+            }
+            let Some(module) = stmt
+                .child_by_field_name("source")
+                .map(|s| unquote(node_text(&s, source)))
+            else {
+                continue;
+            };
+            if !self.module_sources.iter().any(|m| m == module) {
+                continue;
+            }
+            let mut stmt_cursor = stmt.walk();
+            for clause in stmt.named_children(&mut stmt_cursor) {
+                if clause.kind() != "import_clause" {
+                    continue;
+                }
+                let mut clause_cursor = clause.walk();
+                for part in clause.named_children(&mut clause_cursor) {
+                    match part.kind() {
+                        "identifier" => bindings.push(node_text(&part, source).to_string()),
+                        "namespace_import" => {
+                            let mut ns_cursor = part.walk();
+                            let name = part
+                                .named_children(&mut ns_cursor)
+                                .find(|c| c.kind() == "identifier");
+                            if let Some(name) = name {
+                                bindings.push(node_text(&name, source).to_string());
+                            }
+                        }
+                        "named_imports"
+                            if node_text(&part, source).contains(self.from.as_str()) =>
+                        {
+                            warnings.push(format!(
+                                "Named import from '{}' may refer to '{}'; update it manually",
+                                module, self.from
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        bindings
+    }
+}
+
+/// Renames a class in `composes:` declarations of a stylesheet.
+pub struct RenameComposedClass {
+    /// `None`: the stylesheet that defines the class, where it is composed
+    /// without a `from` clause. `Some`: another stylesheet, where it is
+    /// composed `from` one of these specifiers.
+    pub module_sources: Option<Vec<String>>,
+    /// Class name without the leading dot.
+    pub from: String,
+    pub to: String,
+}
+
+impl RenameComposedClass {
+    pub fn new(module_sources: Option<Vec<String>>, from: String, to: String) -> Self {
+        Self {
+            module_sources,
+            from: from.trim_start_matches('.').to_string(),
+            to: to.trim_start_matches('.').to_string(),
+        }
+    }
+
+    /// Whether a `composes:` declaration's classes come from our module.
+    fn composes_from_module(&self, origin: Option<Node>, source: &str) -> bool {
+        match (&self.module_sources, origin) {
+            (None, None) => true,
+            (Some(sources), Some(origin)) if origin.kind() == "string_value" => {
+                let specifier = unquote(node_text(&origin, source));
+                sources.iter().any(|s| s == specifier)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Executable for RenameComposedClass {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let mut declarations = Vec::new();
+        collect_composes(&tree.root_node(), source, &mut declarations);
+
+        let mut edits = Vec::new();
+        for declaration in declarations {
+            let values: Vec<Node> = named_children(&declaration)
+                .into_iter()
+                .filter(|c| c.kind() != "property_name")
+                .collect();
+            let from_at = values
+                .iter()
+                .position(|v| v.kind() == "plain_value" && node_text(v, source) == "from");
+            let (classes, origin) = match from_at {
+                Some(i) => (&values[..i], values.get(i + 1).copied()),
+                None => (&values[..], None),
+            };
+            if !self.composes_from_module(origin, source) {
+                continue;
+            }
+            for class in classes {
+                if class.kind() == "plain_value" && node_text(class, source) == self.from {
+                    edits.push(TextEdit {
+                        start: class.start_byte(),
+                        end: class.end_byte(),
+                        replacement: self.to.clone(),
+                        label: format!("rename composed class {} -> {}", self.from, self.to),
+                        priority: 0,
+                    });
+                }
+            }
+        }
+        Ok(edits)
+    }
+}
+
+// --- Helper functions ---
+
+fn collect_composes<'a>(node: &Node<'a>, source: &str, out: &mut Vec<Node<'a>>) {
+    if node.kind() == "declaration" {
+        if find_child_by_kind(node, "property_name")
+            .is_some_and(|p| node_text(&p, source) == "composes")
+        {
+            out.push(*node);
+        }
+        return;
+    }
+    for child in named_children(node) {
+        collect_composes(&child, source, out);
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn collect_usages(
+    node: &Node,
+    source: &str,
+    bindings: &[String],
+    op: &RenameModuleClassUsages,
+    edits: &mut Vec<TextEdit>,
+    warnings: &mut Vec<String>,
+) {
+    let on_binding = |n: &Node| {
+        n.child_by_field_name("object").is_some_and(|o| {
+            o.kind() == "identifier" && bindings.iter().any(|b| b == node_text(&o, source))
+        })
+    };
+    let label = format!("rename module class {} -> {}", op.from, op.to);
+
+    match node.kind() {
+        "member_expression" if on_binding(node) => {
+            let object = node.child_by_field_name("object").unwrap();
+            if let Some(property) = node.child_by_field_name("property") {
+                if node_text(&property, source) == op.from {
+                    if is_identifier(&op.to) {
+                        edits.push(TextEdit {
+                            start: property.start_byte(),
+                            end: property.end_byte(),
+                            replacement: op.to.clone(),
+                            label,
+                            priority: 0,
+                        });
+                    } else {
+                        // `styles.card` -> `styles['card-item']`
+                        let optional =
+                            source[object.end_byte()..property.start_byte()].contains("?.");
+                        edits.push(TextEdit {
+                            start: object.end_byte(),
+                            end: property.end_byte(),
+                            replacement: format!(
+                                "{}['{}']",
+                                if optional { "?." } else { "" },
+                                op.to
+                            ),
+                            label,
+                            priority: 0,
+                        });
+                    }
+                }
+            }
+        }
+        "subscript_expression" if on_binding(node) => {
+            if let Some(index) = node.child_by_field_name("index") {
+                match index.kind() {
+                    "string" | "template_string"
+                        if unquote(node_text(&index, source)) == op.from =>
+                    {
+                        edits.push(TextEdit {
+                            start: index.start_byte() + 1,
+                            end: index.end_byte() - 1,
+                            replacement: op.to.clone(),
+                            label,
+                            priority: 0,
+                        });
+                    }
+                    "string" | "template_string" | "number" => {}
+                    _ => {
+                        let line = index.start_position().row + 1;
+                        warnings.push(format!(
+                            "Line {}: computed class access `{}` not renamed",
+                            line,
+                            node_text(node, source)
+                        ));
+                    }
+                }
+            }
+        }
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value");
+            let pattern = node.child_by_field_name("name");
+            if let (Some(value), Some(pattern)) = (value, pattern) {
+                if value.kind() == "identifier"
+                    && pattern.kind() == "object_pattern"
+                    && bindings.iter().any(|b| b == node_text(&value, source))
+                {
+                    rename_in_pattern(&pattern, source, op, &label, edits, warnings);
+                }
+            }
+        }
+        "identifier" | "shorthand_property_identifier"
+            if bindings.iter().any(|b| b == node_text(node, source)) && !is_followed(node) =>
+        {
+            warnings.push(format!(
+                "Line {}: `{}` is used as a value; references to '{}' through it are not renamed",
+                node.start_position().row + 1,
+                node_text(node, source),
+                op.from
+            ));
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_usages(&child, source, bindings, op, edits, warnings);
+    }
+}
+
+/// Whether a use of the binding is one `collect_usages` rewrites or that
+/// cannot hide a class reference: property access, destructuring, or the
+/// import itself.
+fn is_followed(node: &Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return true;
+    };
+    match parent.kind() {
+        "member_expression" | "subscript_expression" => parent
+            .child_by_field_name("object")
+            .is_some_and(|o| o.id() == node.id()),
+        "variable_declarator" => {
+            parent
+                .child_by_field_name("value")
+                .is_some_and(|v| v.id() == node.id())
+                && parent
+                    .child_by_field_name("name")
+                    .is_some_and(|n| n.kind() == "object_pattern")
+        }
+        "import_clause" | "namespace_import" => true,
+        _ => false,
+    }
+}
+
+/// `const { card } = styles` -> `const { tile: card } = styles`; the local
+/// name is kept so the rest of the file is untouched.
+fn rename_in_pattern(
+    pattern: &Node,
+    source: &str,
+    op: &RenameModuleClassUsages,
+    label: &str,
+    edits: &mut Vec<TextEdit>,
+    warnings: &mut Vec<String>,
+) {
+    let key = if is_identifier(&op.to) {
+        op.to.clone()
+    } else {
+        format!("'{}'", op.to)
+    };
+    let mut push = |start: usize, end: usize, replacement: String| {
+        edits.push(TextEdit {
+            start,
+            end,
+            replacement,
+            label: label.to_string(),
+            priority: 0,
+        });
+    };
+    let mut cursor = pattern.walk();
+    for entry in pattern.named_children(&mut cursor) {
+        let shorthand = match entry.kind() {
+            "shorthand_property_identifier_pattern" => Some(entry),
+            "object_assignment_pattern" => entry
+                .child_by_field_name("left")
+                .filter(|l| l.kind() == "shorthand_property_identifier_pattern"),
+            _ => None,
+        };
+        if let Some(name) = shorthand {
+            if node_text(&name, source) == op.from {
+                push(name.start_byte(), name.start_byte(), format!("{key}: "));
+            }
+            continue;
+        }
+        match entry.kind() {
+            "pair_pattern" => {
+                let Some(prop) = entry.child_by_field_name("key") else {
+                    continue;
+                };
+                match prop.kind() {
+                    "property_identifier" if node_text(&prop, source) == op.from => {
+                        push(prop.start_byte(), prop.end_byte(), key.clone());
+                    }
+                    "string" if unquote(node_text(&prop, source)) == op.from => {
+                        push(prop.start_byte() + 1, prop.end_byte() - 1, op.to.clone());
+                    }
+                    "computed_property_name" => warnings.push(format!(
+                        "Line {}: computed key `{}` not renamed",
+                        prop.start_position().row + 1,
+                        node_text(&prop, source)
+                    )),
+                    _ => {}
+                }
+            }
+            "rest_pattern" => warnings.push(format!(
+                "Line {}: `{}` collects the remaining classes; references to '{}' through it are not renamed",
+                entry.start_position().row + 1,
+                node_text(&entry, source),
+                op.from
+            )),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_tsx(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        let lang = tree_sitter_typescript::LANGUAGE_TSX.into();
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &RenameModuleClassUsages, source: &str) -> (String, Vec<String>) {
+        let tree = parse_tsx(source);
+        let (edits, warnings) = op.compute_edits_with_warnings(source, &tree).unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(!parse_tsx(&result).root_node().has_error(), "{}", result);
+        (result, warnings)
+    }
+
+    fn op(from: &str, to: &str) -> RenameModuleClassUsages {
+        RenameModuleClassUsages::new(vec!["./Card.module.css".into()], from.into(), to.into())
+    }
+
+    #[test]
+    fn test_renames_dot_and_bracket_access() {
+        let source = "import styles from './Card.module.css';\n\nexport const Card = () => (\n  <div className={styles.card}>\n    <p className={styles['card']} />\n    <p className={other.card} />\n  </div>\n);\n";
+        let (result, warnings) = run(&op("card", "tile"), source);
+        assert!(result.contains("className={styles.tile}"));
+        assert!(result.contains("className={styles['tile']}"));
+        assert!(result.contains("other.card"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_dashed_name_uses_bracket_access() {
+        let source = "import * as css from \"./Card.module.css\";\nconst a = css.card;\nconst b = css?.card;\n";
+        let (result, _) = run(&op(".card", ".card-item"), source);
+        assert!(result.contains("const a = css['card-item'];"));
+        assert!(result.contains("const b = css?.['card-item'];"));
+    }
+
+    #[test]
+    fn test_ignores_other_modules_and_warns_on_computed_access() {
+        let source = "import styles from './Other.module.css';\nimport card from './Card.module.css';\nconst a = styles.card;\nconst b = card[name];\n";
+        let (result, warnings) = run(&op("card", "tile"), source);
+        assert!(result.contains("styles.card"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("card[name]"));
+    }
+
+    #[test]
+    fn test_renames_destructured_classes() {
+        let source = "import styles from './Card.module.css';\nconst { card, title } = styles;\nconst { card: c, 'card': d } = styles;\nconst { card = 'x' } = styles;\nconst { card: other } = props;\n";
+        let (result, warnings) = run(&op("card", "tile"), source);
+        assert_eq!(
+            result,
+            "import styles from './Card.module.css';\nconst { tile: card, title } = styles;\nconst { tile: c, 'tile': d } = styles;\nconst { tile: card = 'x' } = styles;\nconst { card: other } = props;\n"
+        );
+        assert!(warnings.is_empty());
+
+        let (result, _) = run(&op("card", "card-item"), source);
+        assert!(result.contains("const { 'card-item': card, title } = styles;"));
+        assert!(result.contains("const { 'card-item': c, 'card-item': d } = styles;"));
+    }
+
+    #[test]
+    fn test_warns_on_uses_it_cannot_follow() {
+        let source = "import styles from './Card.module.css';\nconst { title, ...rest } = styles;\nconst all = { ...styles };\nconst keys = Object.keys(styles);\nexport { styles };\n";
+        let (result, warnings) = run(&op("card", "tile"), source);
+        assert_eq!(result, source);
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert!(warnings[0].contains("Line 2: `...rest`"));
+        assert!(warnings[1].contains("Line 3: `styles` is used as a value"));
+        assert!(warnings[2].contains("Line 4:"));
+        assert!(warnings[3].contains("Line 5:"));
+    }
+
+    fn run_css(op: &RenameComposedClass, source: &str) -> String {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_css::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let edits = op.compute_edits(source, &tree).unwrap();
+        EditSet::new(edits, source.len()).unwrap().apply(source)
+    }
+
+    #[test]
+    fn test_renames_local_composes() {
+        let source = ".card {\n  padding: 4px;\n}\n\n.title {\n  composes: base card;\n}\n\n.link {\n  composes: card from './Other.module.css';\n}\n";
+        let op = RenameComposedClass::new(None, ".card".into(), "tile".into());
+        assert_eq!(
+            run_css(&op, source),
+            ".card {\n  padding: 4px;\n}\n\n.title {\n  composes: base tile;\n}\n\n.link {\n  composes: card from './Other.module.css';\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_renames_composes_from_module() {
+        let source = ".a {\n  composes: card from './Card.module.css';\n}\n\n.b {\n  composes: card;\n  composes: card from global;\n  composes: card from \"./Other.module.css\";\n}\n";
+        let op = RenameComposedClass::new(
+            Some(vec!["./Card.module.css".into()]),
+            "card".into(),
+            "tile".into(),
+        );
+        assert_eq!(
+            run_css(&op, source),
+            source.replacen("composes: card from './Card", "composes: tile from './Card", 1)
+        );
+    }
+}
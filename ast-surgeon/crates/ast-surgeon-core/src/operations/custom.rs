@@ -0,0 +1,516 @@
+//! Declarative operations defined by a project rather than compiled in.
+//!
+//! A definition is a tree-sitter query, a rewrite template per capture and
+//! optional guards:
+//!
+//! ```json
+//! {
+//!   "name": "console_to_logger",
+//!   "query": "(call_expression function: (member_expression object: (identifier) @obj property: (property_identifier) @method) arguments: (arguments) @args) @call",
+//!   "guards": [{ "capture": "obj", "equals": "console" }, { "capture": "method", "one_of": ["log", "warn"] }],
+//!   "rewrite": { "call": "${logger}.{{method}}{{args}}" },
+//!   "params": { "logger": { "default": "logger" } }
+//! }
+//! ```
+//!
+//! Templates insert capture text with `{{capture}}` and operation
+//! arguments with `${param}`. Guards may use `${param}` as well. A match
+//! nested inside another (`console.log(console.log(x))`) is rewritten
+//! first, and the outer template sees its rewritten text.
+
+use crate::edit::TextEdit;
+use crate::operations::{Executable, OperationError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor, QueryMatch, Tree};
+
+/// A project-defined operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomOperationDef {
+    /// The `op` name callers use.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Tree-sitter query selecting the nodes to rewrite. Query predicates
+    /// (`#eq?`, `#match?`) are honored too.
+    pub query: String,
+    /// Capture name -> replacement template for that capture's node.
+    pub rewrite: BTreeMap<String, String>,
+    #[serde(default)]
+    pub guards: Vec<Guard>,
+    #[serde(default)]
+    pub params: BTreeMap<String, CustomParam>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomParam {
+    #[serde(default)]
+    pub description: String,
+    /// Used when the caller omits the parameter; required otherwise.
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// A condition on one capture; a match is rewritten only when all of its
+/// guards hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Guard {
+    pub capture: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_equals: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starts_with: Option<String>,
+    /// Node kind the capture must have as an ancestor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inside: Option<String>,
+    /// Node kind the capture must not have as an ancestor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_inside: Option<String>,
+}
+
+pub struct CustomOperation {
+    def: CustomOperationDef,
+    params: BTreeMap<String, String>,
+}
+
+impl CustomOperation {
+    /// Bind a definition to the arguments of one call. Strings are used
+    /// as-is, other JSON values in their JSON form.
+    pub fn new(def: CustomOperationDef, args: &Map<String, Value>) -> Result<Self, OperationError> {
+        if let Some(unknown) = args.keys().find(|k| !def.params.contains_key(*k)) {
+            return Err(invalid(format!(
+                "{} has no parameter '{unknown}'",
+                def.name
+            )));
+        }
+        let mut params = BTreeMap::new();
+        for (name, param) in &def.params {
+            let value = match args.get(name) {
+                Some(Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => param
+                    .default
+                    .clone()
+                    .ok_or_else(|| invalid(format!("{} requires parameter '{name}'", def.name)))?,
+            };
+            params.insert(name.clone(), value);
+        }
+        Ok(Self { def, params })
+    }
+}
+
+impl Executable for CustomOperation {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let query = Query::new(&tree.language(), &self.def.query)
+            .map_err(|e| invalid(format!("{}: invalid query: {e}", self.def.name)))?;
+        let capture_names = query.capture_names();
+        let known = |capture: &str| capture_names.contains(&capture);
+        if let Some(capture) = self
+            .def
+            .rewrite
+            .keys()
+            .chain(self.def.guards.iter().map(|g| &g.capture))
+            .find(|c| !known(c))
+        {
+            return Err(invalid(format!(
+                "{}: query has no capture @{capture}",
+                self.def.name
+            )));
+        }
+
+        // Rewrite innermost-first, so that in `console.log(console.log(x))`
+        // the outer replacement is rendered from the already rewritten
+        // inner call and takes over its edit
+        let mut targets = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        while let Some(m) = matches.next() {
+            let captures = match_captures(m, capture_names, source);
+            if !self.guards_hold(&captures)? {
+                continue;
+            }
+            for (capture, template) in &self.def.rewrite {
+                if let Some(&(start, end, _)) = captures.get(capture.as_str()) {
+                    targets.push((start, end, capture, template, captures.clone()));
+                }
+            }
+        }
+        targets.sort_by_key(|(start, end, ..)| (end - start, *start));
+
+        let mut edits: Vec<TextEdit> = Vec::new();
+        for (start, end, capture, template, captures) in targets {
+            let inside = |e: &TextEdit| start <= e.start && e.end <= end;
+            let overlaps = |e: &TextEdit| e.start < end && start < e.end;
+            // Same node from another match, or a match straddling one
+            // that was already rewritten
+            if edits
+                .iter()
+                .any(|e| (e.start == start && e.end == end) || (overlaps(e) && !inside(e)))
+            {
+                continue;
+            }
+            let replacement = self.render(template, &captures, &edits)?;
+            edits.retain(|e| !inside(e));
+            edits.push(TextEdit {
+                start,
+                end,
+                replacement,
+                label: format!("{}: rewrite @{capture}", self.def.name),
+                priority: 0,
+            });
+        }
+        edits.sort_by_key(|e| e.start);
+
+        if edits.is_empty() {
+            return Err(OperationError::TargetNotFound {
+                description: format!("no matches for {}", self.def.name),
+            });
+        }
+        Ok(edits)
+    }
+}
+
+impl CustomOperation {
+    fn guards_hold(&self, captures: &Captures) -> Result<bool, OperationError> {
+        let expand = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|v| self.substitute_params(v))
+                .transpose()
+        };
+        for guard in &self.def.guards {
+            let Some((_, _, node)) = captures.get(&guard.capture) else {
+                return Ok(false);
+            };
+            let text = captures.text(&guard.capture);
+            if expand(&guard.equals)?.is_some_and(|v| text != v)
+                || expand(&guard.not_equals)?.is_some_and(|v| text == v)
+                || expand(&guard.starts_with)?.is_some_and(|v| !text.starts_with(&v))
+                || guard
+                    .inside
+                    .as_deref()
+                    .is_some_and(|k| !has_ancestor(*node, k))
+                || guard
+                    .not_inside
+                    .as_deref()
+                    .is_some_and(|k| has_ancestor(*node, k))
+            {
+                return Ok(false);
+            }
+            if !guard.one_of.is_empty() {
+                let options = guard
+                    .one_of
+                    .iter()
+                    .map(|v| self.substitute_params(v))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !options.contains(&text) {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Fill in a template. Capture text includes the `rewritten` edits
+    /// that fall inside the capture. `${param}` is only looked for in the
+    /// template itself: captured code is inserted verbatim, even when it
+    /// holds template literals.
+    fn render(
+        &self,
+        template: &str,
+        captures: &Captures,
+        rewritten: &[TextEdit],
+    ) -> Result<String, OperationError> {
+        let mut result = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let end = rest[start..]
+                .find("}}")
+                .ok_or_else(|| invalid(format!("{}: unclosed {{{{ in template", self.def.name)))?;
+            result.push_str(&self.substitute_params(&rest[..start])?);
+            let name = rest[start + 2..start + end].trim();
+            result.push_str(&captures.rewritten_text(name, rewritten));
+            rest = &rest[start + end + 2..];
+        }
+        result.push_str(&self.substitute_params(rest)?);
+        Ok(result)
+    }
+
+    fn substitute_params(&self, text: &str) -> Result<String, OperationError> {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| invalid(format!("{}: unclosed ${{ in template", self.def.name)))?;
+            let name = &rest[start + 2..start + end];
+            let value = self.params.get(name).ok_or_else(|| {
+                invalid(format!(
+                    "{}: unknown parameter '${{{name}}}'",
+                    self.def.name
+                ))
+            })?;
+            result.push_str(&rest[..start]);
+            result.push_str(value);
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+}
+
+// --- Helper functions ---
+
+/// Captures of one match by name: byte range and first node. A quantified
+/// capture spans from its first to its last node.
+#[derive(Clone)]
+struct Captures<'tree, 'src> {
+    source: &'src str,
+    by_name: BTreeMap<&'src str, (usize, usize, Node<'tree>)>,
+}
+
+impl<'tree, 'src> Captures<'tree, 'src> {
+    fn get(&self, name: &str) -> Option<&(usize, usize, Node<'tree>)> {
+        self.by_name.get(name)
+    }
+
+    /// Source text of a capture; empty when it did not participate.
+    fn text(&self, name: &str) -> String {
+        self.rewritten_text(name, &[])
+    }
+
+    /// Text of a capture with the edits inside it applied.
+    fn rewritten_text(&self, name: &str, edits: &[TextEdit]) -> String {
+        let Some(&(start, end, _)) = self.get(name) else {
+            return String::new();
+        };
+        let mut inner: Vec<&TextEdit> = edits
+            .iter()
+            .filter(|e| start <= e.start && e.end <= end)
+            .collect();
+        inner.sort_by_key(|e| e.start);
+        let mut text = String::new();
+        let mut at = start;
+        for edit in inner {
+            text.push_str(&self.source[at..edit.start]);
+            text.push_str(&edit.replacement);
+            at = edit.end;
+        }
+        text.push_str(&self.source[at..end]);
+        text
+    }
+}
+
+fn match_captures<'tree, 'src>(
+    m: &QueryMatch<'_, 'tree>,
+    capture_names: &[&'src str],
+    source: &'src str,
+) -> Captures<'tree, 'src> {
+    let mut by_name: BTreeMap<&str, (usize, usize, Node)> = BTreeMap::new();
+    for capture in m.captures {
+        let name = capture_names[capture.index as usize];
+        let node = capture.node;
+        by_name
+            .entry(name)
+            .and_modify(|(start, end, _)| {
+                *start = (*start).min(node.start_byte());
+                *end = (*end).max(node.end_byte());
+            })
+            .or_insert((node.start_byte(), node.end_byte(), node));
+    }
+    Captures { source, by_name }
+}
+
+fn has_ancestor(node: Node, kind: &str) -> bool {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if n.kind() == kind {
+            return true;
+        }
+        current = n.parent();
+    }
+    false
+}
+
+fn invalid(message: String) -> OperationError {
+    OperationError::InvalidParams { message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use serde_json::json;
+
+    fn parse(source: &str) -> Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(
+        def: serde_json::Value,
+        args: serde_json::Value,
+        source: &str,
+    ) -> Result<String, OperationError> {
+        let def: CustomOperationDef = serde_json::from_value(def).unwrap();
+        let op = CustomOperation::new(def, args.as_object().unwrap())?;
+        let edits = op.compute_edits(source, &parse(source))?;
+        Ok(EditSet::new(edits, source.len()).unwrap().apply(source))
+    }
+
+    fn console_to_logger() -> serde_json::Value {
+        json!({
+            "name": "console_to_logger",
+            "query": "(call_expression function: (member_expression object: (identifier) @obj property: (property_identifier) @method) arguments: (arguments) @args) @call",
+            "guards": [
+                {"capture": "obj", "equals": "console"},
+                {"capture": "method", "one_of": ["log", "warn"]},
+                {"capture": "call", "not_inside": "catch_clause"}
+            ],
+            "rewrite": {"call": "${logger}.{{method}}{{args}}"},
+            "params": {"logger": {"default": "logger"}}
+        })
+    }
+
+    #[test]
+    fn test_rewrites_guarded_matches() {
+        let source = "console.log('a', 1);\nconsole.error('b');\nfoo.log('c');\ntry { x(); } catch (e) { console.warn(e); }\nconsole.warn('d');\n";
+        let result = run(console_to_logger(), json!({"logger": "log"}), source).unwrap();
+        assert_eq!(
+            result,
+            "log.log('a', 1);\nconsole.error('b');\nfoo.log('c');\ntry { x(); } catch (e) { console.warn(e); }\nlog.warn('d');\n"
+        );
+    }
+
+    #[test]
+    fn test_reports_missing_matches_and_bad_definitions() {
+        let no_match = run(console_to_logger(), json!({}), "foo();\n");
+        assert!(matches!(
+            no_match,
+            Err(OperationError::TargetNotFound { .. })
+        ));
+
+        let unknown_param = run(console_to_logger(), json!({"level": "info"}), "foo();\n");
+        assert!(matches!(
+            unknown_param,
+            Err(OperationError::InvalidParams { .. })
+        ));
+
+        let mut bad_capture = console_to_logger();
+        bad_capture["rewrite"] = json!({"callee": "x"});
+        let result = run(bad_capture, json!({}), "console.log(1);\n");
+        assert!(matches!(result, Err(OperationError::InvalidParams { .. })));
+    }
+
+    #[test]
+    fn test_nested_matches_rewrite_innermost_first() {
+        let source = "console.log(console.log(x), console.warn(console.log(y)));\n";
+        let result = run(console_to_logger(), json!({}), source).unwrap();
+        assert_eq!(
+            result,
+            "logger.log(logger.log(x), logger.warn(logger.log(y)));\n"
+        );
+    }
+
+    /// `fn(...)` calls renamed to `renamed(...)` under a single guard.
+    fn rename_call(guard: serde_json::Value) -> serde_json::Value {
+        json!({
+            "name": "rename_call",
+            "query": "(call_expression function: (identifier) @fn arguments: (arguments) @args)",
+            "guards": [guard],
+            "rewrite": {"fn": "renamed"},
+            "params": {"target": {"default": "track"}}
+        })
+    }
+
+    #[test]
+    fn test_each_guard_kind() {
+        let source = "track(1);\ntrackPage(2);\nlog(3);\nfunction f() {\n  track(4);\n}\n";
+        let cases = [
+            (
+                json!({"capture": "fn", "equals": "${target}"}),
+                "renamed(1);\ntrackPage(2);\nlog(3);\nfunction f() {\n  renamed(4);\n}\n",
+            ),
+            (
+                json!({"capture": "fn", "not_equals": "track"}),
+                "track(1);\nrenamed(2);\nrenamed(3);\nfunction f() {\n  track(4);\n}\n",
+            ),
+            (
+                json!({"capture": "fn", "one_of": ["log", "trackPage"]}),
+                "track(1);\nrenamed(2);\nrenamed(3);\nfunction f() {\n  track(4);\n}\n",
+            ),
+            (
+                json!({"capture": "fn", "starts_with": "${target}"}),
+                "renamed(1);\nrenamed(2);\nlog(3);\nfunction f() {\n  renamed(4);\n}\n",
+            ),
+            (
+                json!({"capture": "fn", "inside": "function_declaration"}),
+                "track(1);\ntrackPage(2);\nlog(3);\nfunction f() {\n  renamed(4);\n}\n",
+            ),
+            (
+                json!({"capture": "fn", "not_inside": "function_declaration"}),
+                "renamed(1);\nrenamed(2);\nrenamed(3);\nfunction f() {\n  track(4);\n}\n",
+            ),
+        ];
+        for (guard, expected) in cases {
+            let result = run(rename_call(guard.clone()), json!({}), source).unwrap();
+            assert_eq!(result, expected, "guard {guard}");
+        }
+    }
+
+    #[test]
+    fn test_missing_params_are_reported() {
+        let mut required = console_to_logger();
+        required["params"] = json!({"logger": {}});
+        let result = run(required, json!({}), "console.log(1);\n");
+        assert!(
+            matches!(&result, Err(OperationError::InvalidParams { message }) if message.contains("requires parameter 'logger'")),
+            "{result:?}"
+        );
+
+        let mut undeclared = console_to_logger();
+        undeclared["rewrite"] = json!({"call": "${sink}.{{method}}{{args}}"});
+        let result = run(undeclared, json!({}), "console.log(1);\n");
+        assert!(
+            matches!(&result, Err(OperationError::InvalidParams { message }) if message.contains("unknown parameter '${sink}'")),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_captured_template_literals_are_not_params() {
+        let source = "console.log(`cfg ${logger}`);\nconsole.warn(`${id} missing`);\n";
+        let result = run(console_to_logger(), json!({"logger": "log"}), source).unwrap();
+        assert_eq!(
+            result,
+            "log.log(`cfg ${logger}`);\nlog.warn(`${id} missing`);\n"
+        );
+    }
+
+    #[test]
+    fn test_capture_text_substitution() {
+        // Captures may repeat or be spaced inside the braces, and an
+        // optional capture that did not participate renders as empty
+        let def = json!({
+            "name": "to_assert",
+            "query": "(call_expression function: (identifier) @fn arguments: (arguments (_) @first (_)? @second)) @call",
+            "guards": [{"capture": "fn", "equals": "check"}],
+            "rewrite": {"call": "assert({{first}}, '{{ first }}') /* {{second}} */"}
+        });
+        let source = "check(a > 1);\ncheck(b, msg);\n";
+        let result = run(def, json!({}), source).unwrap();
+        assert_eq!(
+            result,
+            "assert(a > 1, 'a > 1') /*  */;\nassert(b, 'b') /* msg */;\n"
+        );
+    }
+}
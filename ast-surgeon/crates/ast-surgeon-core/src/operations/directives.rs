@@ -0,0 +1,438 @@
+//! Module directive operations: `add_directive` and `remove_directive`.
+//!
+//! Directives (`'use client'`, `'use server'`) must come before anything
+//! else in a module except comments and a shebang. This module also holds
+//! [`check_directives`], which flags React Server Components mistakes:
+//! client-only hooks and event handlers in files without `'use client'`,
+//! and server-only imports in client files.
+
+use crate::edit::TextEdit;
+use crate::format::StyleConfig;
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use serde::Serialize;
+use tree_sitter::{Node, Tree};
+
+/// Directives these operations manage.
+pub const DIRECTIVES: &[&str] = &["use client", "use server"];
+
+/// Hooks that only run in client components.
+const CLIENT_HOOKS: &[&str] = &[
+    "useState",
+    "useEffect",
+    "useLayoutEffect",
+    "useInsertionEffect",
+    "useReducer",
+    "useRef",
+    "useContext",
+    "useTransition",
+    "useDeferredValue",
+    "useSyncExternalStore",
+    "useImperativeHandle",
+    "useOptimistic",
+    "useActionState",
+    "useFormStatus",
+    "useRouter",
+    "usePathname",
+    "useSearchParams",
+    "useParams",
+];
+
+/// Modules that must never end up in a client bundle.
+const SERVER_ONLY_MODULES: &[&str] = &[
+    "server-only",
+    "next/headers",
+    "fs",
+    "fs/promises",
+    "path",
+    "child_process",
+    "os",
+];
+
+/// The add_directive operation.
+///
+/// Adds `'use client'` or `'use server'` at the top of the file, after a
+/// shebang and before imports. A no-op when the directive is already there.
+pub struct AddDirective {
+    pub directive: String,
+}
+
+impl AddDirective {
+    pub fn new(directive: String) -> Self {
+        Self { directive }
+    }
+}
+
+impl Executable for AddDirective {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let style = style.resolve(source);
+        let directive = check_directive(&self.directive)?;
+        let present = file_directives(tree, source);
+        if present.iter().any(|(d, _)| d == directive) {
+            return Ok((vec![], vec![])); // Already there -- no-op
+        }
+        if let Some((other, _)) = present
+            .iter()
+            .find(|(d, _)| DIRECTIVES.contains(&d.as_str()))
+        {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "File already has '{}'; remove it before adding '{}'",
+                    other, directive
+                ),
+            });
+        }
+
+        let at = if source.starts_with("#!") {
+            source.find('\n').map_or(source.len(), |i| i + 1)
+        } else {
+            0
+        };
+        let quote = style.quote;
+        let semi = style.semi();
+        let mut text = format!("{}{}{}{}\n", quote, directive, quote, semi);
+        // Keep a blank line between the directive and the code below
+        if !source[at..].is_empty() && !source[at..].starts_with('\n') {
+            text.push('\n');
+        }
+        let edit = TextEdit {
+            start: at,
+            end: at,
+            replacement: text,
+            label: format!("add '{}'", directive),
+            priority: 0,
+        };
+        Ok((vec![edit], vec![]))
+    }
+}
+
+/// The remove_directive operation.
+pub struct RemoveDirective {
+    pub directive: String,
+}
+
+impl RemoveDirective {
+    pub fn new(directive: String) -> Self {
+        Self { directive }
+    }
+}
+
+impl Executable for RemoveDirective {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let directive = check_directive(&self.directive)?;
+        let directives = file_directives(tree, source);
+        let (_, statement) = directives
+            .iter()
+            .find(|(d, _)| d == directive)
+            .ok_or_else(|| OperationError::TargetNotFound {
+                description: format!("No '{}' directive in file", directive),
+            })?;
+
+        let start = statement.start_byte();
+        let mut end = source[statement.end_byte()..]
+            .find('\n')
+            .map_or(source.len(), |i| statement.end_byte() + i + 1);
+        // Drop the blank line that separated the prologue from the code
+        let is_last = directives
+            .last()
+            .is_some_and(|(_, n)| n.id() == statement.id());
+        if is_last && source[end..].starts_with('\n') {
+            end += 1;
+        }
+        Ok(vec![TextEdit {
+            start,
+            end,
+            replacement: String::new(),
+            label: format!("remove '{}'", directive),
+            priority: 0,
+        }])
+    }
+}
+
+/// A directive problem found by [`check_directives`].
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectiveIssue {
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based column.
+    pub column: usize,
+    pub rule: &'static str,
+    pub message: String,
+}
+
+/// Directives in the file prologue, with their statements.
+pub fn file_directives<'a>(tree: &'a Tree, source: &str) -> Vec<(String, Node<'a>)> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let mut directives = Vec::new();
+    for node in root.named_children(&mut cursor) {
+        match node.kind() {
+            "hash_bang_line" | "comment" => continue,
+            "expression_statement" => {
+                let Some(string) = node.named_child(0).filter(|s| s.kind() == "string") else {
+                    break;
+                };
+                if node.named_child_count() != 1 {
+                    break;
+                }
+                let text = node_text(&string, source);
+                directives.push((text[1..text.len() - 1].to_string(), node));
+            }
+            _ => break,
+        }
+    }
+    directives
+}
+
+/// Flag client-only hooks and event handlers in files without
+/// `'use client'`, and server-only imports in files with it.
+///
+/// Custom hooks (functions named `useX`) may call client hooks freely;
+/// they take on the directive of the component that uses them.
+pub fn check_directives(source: &str, tree: &Tree) -> Vec<DirectiveIssue> {
+    let present = file_directives(tree, source);
+    let has = |d: &str| present.iter().any(|(p, _)| p == d);
+    let mut issues = Vec::new();
+
+    if has("use client") {
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor) {
+            let Some(module) = node
+                .child_by_field_name("source")
+                .filter(|_| node.kind() == "import_statement")
+                .filter(|_| !node_text(&node, source).starts_with("import type"))
+            else {
+                continue;
+            };
+            let name = node_text(&module, source).trim_matches(|c| c == '\'' || c == '"');
+            if SERVER_ONLY_MODULES.contains(&name) || name.starts_with("node:") {
+                issues.push(issue(
+                    &node,
+                    "server-import-in-client",
+                    format!("'{}' is server-only but this file has 'use client'", name),
+                ));
+            }
+        }
+    } else {
+        let mut seen = Vec::new();
+        collect_client_only(&tree.root_node(), source, &mut seen, &mut issues);
+    }
+    issues
+}
+
+// --- Helper functions ---
+
+fn check_directive(directive: &str) -> Result<&str, OperationError> {
+    let directive = directive
+        .trim()
+        .trim_end_matches(';')
+        .trim_matches(|c| c == '\'' || c == '"');
+    DIRECTIVES
+        .iter()
+        .find(|d| **d == directive)
+        .copied()
+        .ok_or_else(|| OperationError::InvalidParams {
+            message: format!(
+                "Unknown directive '{}' (expected 'use client' or 'use server')",
+                directive
+            ),
+        })
+}
+
+/// Report the first use of each client-only hook and of event handlers.
+fn collect_client_only(
+    node: &Node,
+    source: &str,
+    seen: &mut Vec<String>,
+    issues: &mut Vec<DirectiveIssue>,
+) {
+    if is_custom_hook(node, source) {
+        return;
+    }
+    match node.kind() {
+        "call_expression" => {
+            let hook = node
+                .child_by_field_name("function")
+                .and_then(|f| match f.kind() {
+                    "identifier" => Some(node_text(&f, source)),
+                    "member_expression" => f
+                        .child_by_field_name("property")
+                        .map(|p| node_text(&p, source)),
+                    _ => None,
+                });
+            if let Some(hook) = hook.filter(|h| CLIENT_HOOKS.contains(h)) {
+                if !seen.iter().any(|s| s == hook) {
+                    seen.push(hook.to_string());
+                    issues.push(issue(
+                        node,
+                        "client-hook-without-directive",
+                        format!("{} only works in client components; add 'use client'", hook),
+                    ));
+                }
+            }
+        }
+        "jsx_attribute" => {
+            let handler = node
+                .named_child(0)
+                .map(|n| node_text(&n, source))
+                .filter(|name| {
+                    name.strip_prefix("on")
+                        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+                });
+            let is_expression = node
+                .named_child(1)
+                .is_some_and(|v| v.kind() == "jsx_expression");
+            if let (Some(handler), true) = (handler, is_expression) {
+                if !seen.iter().any(|s| s == "on*") {
+                    seen.push("on*".to_string());
+                    issues.push(issue(
+                        node,
+                        "event-handler-without-directive",
+                        format!(
+                            "Event handler {} needs a client component; add 'use client'",
+                            handler
+                        ),
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_client_only(&child, source, seen, issues);
+    }
+}
+
+/// `function useX() {}` or `const useX = () => {}`.
+fn is_custom_hook(node: &Node, source: &str) -> bool {
+    let name = match node.kind() {
+        "function_declaration" => node.child_by_field_name("name"),
+        "variable_declarator"
+            if node
+                .child_by_field_name("value")
+                .is_some_and(|v| matches!(v.kind(), "arrow_function" | "function_expression")) =>
+        {
+            node.child_by_field_name("name")
+        }
+        _ => None,
+    };
+    name.is_some_and(|n| {
+        node_text(&n, source)
+            .strip_prefix("use")
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+    })
+}
+
+fn issue(node: &Node, rule: &'static str, message: String) -> DirectiveIssue {
+    DirectiveIssue {
+        line: node.start_position().row + 1,
+        column: node.start_position().column + 1,
+        rule,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use crate::operations::imports::AddImport;
+    use tree_sitter::Parser;
+
+    fn parse_tsx(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TSX.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_tsx(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        EditSet::new(edits, source.len()).unwrap().apply(source)
+    }
+
+    #[test]
+    fn test_add_directive_above_imports_and_after_shebang() {
+        let source = "import { useState } from 'react';\n\nexport function Counter() {}\n";
+        let op = AddDirective::new("use client".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "'use client';\n\nimport { useState } from 'react';\n\nexport function Counter() {}\n"
+        );
+        assert_eq!(run(&op, &result), result);
+
+        let source = "#!/usr/bin/env node\nimport fs from \"fs\"\n";
+        let op = AddDirective::new("'use server'".into());
+        assert_eq!(
+            run(&op, source),
+            "#!/usr/bin/env node\n\"use server\"\n\nimport fs from \"fs\"\n"
+        );
+    }
+
+    #[test]
+    fn test_add_conflicting_directive_fails() {
+        let source = "'use server'\n\nexport async function save() {}\n";
+        let tree = parse_tsx(source);
+        let op = AddDirective::new("use client".into());
+        assert!(matches!(
+            op.compute_edits(source, &tree),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_directive() {
+        let source = "// Counter\n'use client'\n\nimport { useState } from 'react'\n";
+        let op = RemoveDirective::new("use client".into());
+        assert_eq!(
+            run(&op, source),
+            "// Counter\nimport { useState } from 'react'\n"
+        );
+    }
+
+    #[test]
+    fn test_imports_go_below_directives() {
+        let source = "'use client'\n\nexport function Counter() {}\n";
+        let op = AddImport::new("react".into(), vec!["useState".into()], None, false);
+        assert_eq!(
+            run(&op, source),
+            "'use client'\nimport { useState } from 'react'\n\nexport function Counter() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_check_directives() {
+        let source = "import { useState } from 'react'\n\nexport function useToggle() {\n  return useState(false)\n}\n\nexport default function Page() {\n  const [open, setOpen] = React.useState(false)\n  useEffect(() => {}, [])\n  return <button onClick={() => setOpen(!open)} onKeyDown={f}>x</button>\n}\n";
+        let issues = check_directives(source, &parse_tsx(source));
+        let rules: Vec<(&str, usize)> = issues.iter().map(|i| (i.rule, i.line)).collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("client-hook-without-directive", 8),
+                ("client-hook-without-directive", 9),
+                ("event-handler-without-directive", 10),
+            ]
+        );
+
+        let source = "'use client'\nimport { cookies } from 'next/headers'\nimport { readFile } from 'node:fs/promises'\nimport { useState } from 'react'\n";
+        let issues = check_directives(source, &parse_tsx(source));
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.rule == "server-import-in-client"));
+    }
+}
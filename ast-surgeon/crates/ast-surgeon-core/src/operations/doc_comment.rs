@@ -0,0 +1,413 @@
+//! JSDoc / TSDoc comment generation and syncing.
+//!
+//! `upsert_doc_comment` writes the `/** */` block above a function, class
+//! or interface. Descriptive text and unrelated tags (`@example`,
+//! `@throws`, ...) are kept; `@param` tags follow the current parameter
+//! list and `@returns` is added or dropped depending on whether the
+//! function returns a value.
+
+use crate::edit::TextEdit;
+use crate::format;
+use crate::operations::node_utils::node_text;
+use crate::operations::signature::{
+    collect_param_nodes, find_formal_parameters, find_function_by_name,
+};
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// The upsert_doc_comment operation.
+pub struct UpsertDocComment {
+    pub target: String,
+    /// Replaces the descriptive text; `None` keeps the existing one.
+    pub description: Option<String>,
+}
+
+/// A parsed doc comment: description lines, then tags (each tag keeps its
+/// continuation lines).
+#[derive(Debug, Default)]
+struct DocComment {
+    description: Vec<String>,
+    tags: Vec<Vec<String>>,
+}
+
+impl UpsertDocComment {
+    pub fn new(target: String, description: Option<String>) -> Self {
+        Self {
+            target,
+            description,
+        }
+    }
+}
+
+impl Executable for UpsertDocComment {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let root = tree.root_node();
+        let function = find_function_by_name(&root, source, &self.target);
+        let declaration = match function {
+            Some(f) => f,
+            None => find_type_declaration(&root, source, &self.target).ok_or_else(|| {
+                OperationError::TargetNotFound {
+                    description: format!("function, class or interface '{}'", self.target),
+                }
+            })?,
+        };
+        let anchor = anchor_of(&declaration);
+        let existing = format::find_attached_comments(source, &anchor)
+            .leading
+            .into_iter()
+            .rfind(|c| c.text.starts_with("/**"));
+
+        let mut doc = existing
+            .as_ref()
+            .map(|c| parse_doc(&c.text))
+            .unwrap_or_default();
+        if let Some(description) = &self.description {
+            doc.description = description
+                .trim()
+                .lines()
+                .map(|l| l.trim_end().to_string())
+                .collect();
+        }
+        if let Some(function) = function {
+            let params = param_names(&function, source);
+            sync_tags(&mut doc, &params, returns_value(&function, source));
+        }
+        if doc.description.is_empty() && doc.tags.is_empty() {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "'{}' has nothing to document; pass a description",
+                    self.target
+                ),
+            });
+        }
+
+        let prefix = format::extract_line_prefix(source, anchor.start_byte());
+        let single_line = existing.as_ref().is_none_or(|c| !c.text.contains('\n'));
+        let rendered = render(&doc, &prefix, single_line);
+        let label = format!("doc comment for {}", self.target);
+        Ok(vec![match existing {
+            Some(comment) if comment.text == rendered => return Ok(vec![]),
+            Some(comment) => TextEdit {
+                start: comment.start,
+                end: comment.end,
+                replacement: rendered,
+                label,
+                priority: 0,
+            },
+            None => TextEdit {
+                start: anchor.start_byte(),
+                end: anchor.start_byte(),
+                replacement: format!("{}\n{}", rendered, prefix),
+                label,
+                priority: 0,
+            },
+        }])
+    }
+}
+
+// --- Helper functions ---
+
+fn find_type_declaration<'a>(node: &Node<'a>, source: &str, name: &str) -> Option<Node<'a>> {
+    if matches!(
+        node.kind(),
+        "class_declaration" | "abstract_class_declaration" | "interface_declaration"
+    ) && node
+        .child_by_field_name("name")
+        .is_some_and(|n| node_text(&n, source) == name)
+    {
+        return Some(*node);
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .find_map(|child| find_type_declaration(&child, source, name));
+    found
+}
+
+/// The statement a doc comment sits above: `export const f = () => {}`
+/// for an arrow function, the declaration itself otherwise.
+fn anchor_of<'a>(declaration: &Node<'a>) -> Node<'a> {
+    let mut anchor = *declaration;
+    while let Some(parent) = anchor.parent() {
+        match parent.kind() {
+            "variable_declarator"
+            | "lexical_declaration"
+            | "variable_declaration"
+            | "export_statement" => anchor = parent,
+            _ => break,
+        }
+    }
+    anchor
+}
+
+fn param_names(function: &Node, source: &str) -> Vec<String> {
+    if let Some(parameter) = function.child_by_field_name("parameter") {
+        return vec![node_text(&parameter, source).to_string()];
+    }
+    let Some(parameters) = find_formal_parameters(function) else {
+        return vec![];
+    };
+    collect_param_nodes(&parameters)
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let mut pattern = param
+                .child_by_field_name("pattern")
+                .or_else(|| param.child_by_field_name("left"))
+                .unwrap_or(*param);
+            if pattern.kind() == "rest_pattern" {
+                pattern = pattern.named_child(0).unwrap_or(pattern);
+            }
+            match pattern.kind() {
+                "identifier" => node_text(&pattern, source).to_string(),
+                // Destructured: JSDoc's own convention
+                _ => format!("param{}", i),
+            }
+        })
+        .collect()
+}
+
+/// Whether the function hands back a value worth an `@returns` tag.
+fn returns_value(function: &Node, source: &str) -> bool {
+    if let Some(return_type) = function.child_by_field_name("return_type") {
+        let text = node_text(&return_type, source)
+            .trim_start_matches(':')
+            .trim();
+        return !matches!(
+            text,
+            "void" | "Promise<void>" | "never" | "undefined" | "Promise<undefined>"
+        );
+    }
+    if function.kind() == "method_definition"
+        && (function
+            .child_by_field_name("name")
+            .is_some_and(|n| node_text(&n, source) == "constructor")
+            || function.child(0).is_some_and(|c| c.kind() == "set"))
+    {
+        return false;
+    }
+    match function.child_by_field_name("body") {
+        Some(body) if body.kind() == "statement_block" => has_value_return(&body),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn has_value_return(node: &Node) -> bool {
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .any(|child| match child.kind() {
+            "return_statement" => child.named_child_count() > 0,
+            "function_declaration"
+            | "function_expression"
+            | "arrow_function"
+            | "generator_function_declaration"
+            | "method_definition"
+            | "class_declaration"
+            | "class" => false,
+            _ => has_value_return(&child),
+        });
+    found
+}
+
+fn parse_doc(comment: &str) -> DocComment {
+    let inner = comment.trim_start_matches("/**").trim_end_matches("*/");
+    let lines: Vec<String> = inner
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ')
+                .unwrap_or(line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let mut doc = DocComment::default();
+    for line in lines {
+        if line.starts_with('@') {
+            doc.tags.push(vec![line]);
+        } else if let Some(tag) = doc.tags.last_mut() {
+            tag.push(line);
+        } else {
+            doc.description.push(line);
+        }
+    }
+    trim_blank(&mut doc.description);
+    for tag in &mut doc.tags {
+        trim_blank(tag);
+    }
+    doc
+}
+
+fn trim_blank(lines: &mut Vec<String>) {
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    while lines.first().is_some_and(|l| l.is_empty()) {
+        lines.remove(0);
+    }
+}
+
+/// `@param` / `@returns` tag name, and for `@param` the documented name
+/// (`{Type}` skipped, `[name=default]` unwrapped).
+fn tag_info(tag: &[String]) -> (&str, Option<String>) {
+    let first = tag.first().map(String::as_str).unwrap_or("");
+    let (kind, rest) = first.split_once(char::is_whitespace).unwrap_or((first, ""));
+    if !matches!(kind, "@param" | "@arg" | "@argument") {
+        return (kind, None);
+    }
+    let mut rest = rest.trim_start();
+    if rest.starts_with('{') {
+        let mut depth = 0;
+        let close = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        });
+        rest = close.map_or("", |i| rest[i + 1..].trim_start());
+    }
+    let token = rest.split_whitespace().next().unwrap_or("");
+    let name = token
+        .trim_start_matches('[')
+        .split(['=', ']'])
+        .next()
+        .unwrap_or("");
+    ("@param", Some(name.to_string()))
+}
+
+/// Rebuild the `@param` tags from `params` (existing text kept per name,
+/// nested `name.prop` entries kept with their parent) and add or drop
+/// `@returns`. Other tags keep their order.
+fn sync_tags(doc: &mut DocComment, params: &[String], returns: bool) {
+    let existing = std::mem::take(&mut doc.tags);
+    let param_tags: Vec<(String, &Vec<String>)> = existing
+        .iter()
+        .filter_map(|t| match tag_info(t) {
+            ("@param", Some(name)) => Some((name, t)),
+            _ => None,
+        })
+        .collect();
+    let mut synced = Vec::new();
+    for param in params {
+        match param_tags.iter().find(|(name, _)| name == param) {
+            Some((_, tag)) => synced.push((*tag).clone()),
+            None => synced.push(vec![format!("@param {}", param)]),
+        }
+        let nested = format!("{}.", param);
+        synced.extend(
+            param_tags
+                .iter()
+                .filter(|(name, _)| name.starts_with(&nested))
+                .map(|(_, tag)| (*tag).clone()),
+        );
+    }
+
+    let mut tags = Vec::new();
+    let mut params_at = None;
+    let mut has_returns = false;
+    for tag in existing.iter() {
+        match tag_info(tag).0 {
+            "@param" => {
+                params_at.get_or_insert(tags.len());
+            }
+            "@returns" | "@return" => {
+                params_at.get_or_insert(tags.len());
+                if returns && !has_returns {
+                    tags.push(tag.clone());
+                    has_returns = true;
+                }
+            }
+            _ => tags.push(tag.clone()),
+        }
+    }
+    if returns && !has_returns {
+        synced.push(vec!["@returns".to_string()]);
+    }
+    let at = params_at.unwrap_or(0);
+    tags.splice(at..at, synced);
+    doc.tags = tags;
+}
+
+fn render(doc: &DocComment, prefix: &str, single_line: bool) -> String {
+    if single_line && doc.tags.is_empty() && doc.description.len() == 1 {
+        return format!("/** {} */", doc.description[0]);
+    }
+    let mut lines = vec!["/**".to_string()];
+    let star = |line: &str| {
+        if line.is_empty() {
+            format!("{} *", prefix)
+        } else {
+            format!("{} * {}", prefix, line)
+        }
+    };
+    lines.extend(doc.description.iter().map(|l| star(l)));
+    if !doc.description.is_empty() && !doc.tags.is_empty() {
+        lines.push(star(""));
+    }
+    lines.extend(doc.tags.iter().flatten().map(|l| star(l)));
+    lines.push(format!("{} */", prefix));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn apply(op: &UpsertDocComment, source: &str) -> Result<String, OperationError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let edits = op.compute_edits(source, &tree)?;
+        Ok(EditSet::new(edits, source.len())?.apply(source))
+    }
+
+    #[test]
+    fn test_creates_doc_comment() {
+        let source = "export async function fetchUser(id: string, { cache }: Options = {}): Promise<User> {\n  return load(id);\n}\n";
+        let op = UpsertDocComment::new("fetchUser".into(), Some("Load a user by id.".into()));
+        assert_eq!(
+            apply(&op, source).unwrap(),
+            "/**\n * Load a user by id.\n *\n * @param id\n * @param param1\n * @returns\n */\nexport async function fetchUser(id: string, { cache }: Options = {}): Promise<User> {\n  return load(id);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_syncs_params_and_keeps_text() {
+        let source = "class Cart {\n  /**\n   * Add an item.\n   *\n   * @param {string} sku - the product\n   * @param legacy - unused\n   * @returns the new total\n   * @example cart.add('a', 2)\n   */\n  add(sku: string, qty = 1): void {\n    this.items.push([sku, qty]);\n  }\n}\n";
+        let op = UpsertDocComment::new("add".into(), None);
+        assert_eq!(
+            apply(&op, source).unwrap(),
+            "class Cart {\n  /**\n   * Add an item.\n   *\n   * @param {string} sku - the product\n   * @param qty\n   * @example cart.add('a', 2)\n   */\n  add(sku: string, qty = 1): void {\n    this.items.push([sku, qty]);\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_arrow_function_and_interface() {
+        let source = "// helpers\n\n/** Doubles. */\nexport const double = (n) => n * 2;\n\ninterface Props {}\n";
+        let op = UpsertDocComment::new("double".into(), None);
+        let result = apply(&op, source).unwrap();
+        assert!(result.contains(
+            "/**\n * Doubles.\n *\n * @param n\n * @returns\n */\nexport const double = (n) => n * 2;\n"
+        ));
+
+        let op = UpsertDocComment::new("Props".into(), Some("Card props.".into()));
+        assert!(apply(&op, source)
+            .unwrap()
+            .ends_with("/** Card props. */\ninterface Props {}\n"));
+        let op = UpsertDocComment::new("Props".into(), None);
+        assert!(matches!(
+            apply(&op, source),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+}
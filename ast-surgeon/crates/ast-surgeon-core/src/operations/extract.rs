@@ -4,6 +4,7 @@
 //! into a named `const` or `let` variable declaration.
 
 use crate::edit::TextEdit;
+use crate::format::StyleConfig;
 use crate::operations::{Executable, OperationError};
 use tree_sitter::{Node, Tree};
 
@@ -22,6 +23,7 @@ impl VarKind {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self, OperationError> {
         match s.to_lowercase().as_str() {
             "const" => Ok(Self::Const),
@@ -65,11 +67,17 @@ impl ExtractToVariable {
 }
 
 impl Executable for ExtractToVariable {
-    fn compute_edits(
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
         &self,
         source: &str,
         tree: &Tree,
-    ) -> Result<Vec<TextEdit>, OperationError> {
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
         // Find the expression in the source text
         let expr_byte_start = source
             .find(&self.expression)
@@ -109,12 +117,13 @@ impl Executable for ExtractToVariable {
             None => String::new(),
         };
         let declaration = format!(
-            "{}{} {}{} = {};\n",
+            "{}{} {}{} = {}{}\n",
             indent,
             self.var_kind.keyword(),
             self.variable_name,
             type_suffix,
-            self.expression
+            self.expression,
+            style.resolve(source).semi()
         );
 
         let mut edits = Vec::new();
@@ -137,7 +146,7 @@ impl Executable for ExtractToVariable {
             priority: 0,
         });
 
-        Ok(edits)
+        Ok((edits, vec![]))
     }
 }
 
@@ -209,6 +218,27 @@ mod tests {
         assert!(result.contains("console.log(sum)"));
     }
 
+    #[test]
+    fn test_extract_follows_statement_style() {
+        let source = "function foo() {\n  console.log(1 + 2)\n}\n";
+        let tree = parse_ts(source);
+        let op = ExtractToVariable::new(
+            "1 + 2".to_string(),
+            "sum".to_string(),
+            VarKind::Const,
+            None,
+        );
+        let edits = op.compute_edits(source, &tree).unwrap();
+        assert!(apply(source, edits).contains("  const sum = 1 + 2\n"));
+
+        let style = StyleConfig {
+            semicolons: Some(true),
+            ..StyleConfig::default()
+        };
+        let (edits, _) = op.compute_edits_with_style(source, &tree, &style).unwrap();
+        assert!(apply(source, edits).contains("  const sum = 1 + 2;\n"));
+    }
+
     #[test]
     fn test_extract_with_type_annotation() {
         let source = "function foo() {\n  return getData();\n}\n";
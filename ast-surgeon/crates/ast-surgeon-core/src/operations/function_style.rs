@@ -0,0 +1,535 @@
+//! `convert_function_style` operation.
+//!
+//! Converts `function foo() {}` into `const foo = () => {}` and back,
+//! keeping `export`/`export default`, `async`, generics and the return
+//! type. Conversions that would change behaviour are refused: arrows
+//! are not hoisted and do not bind their own `this`/`arguments`.
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig, StyleProfile};
+use crate::operations::node_utils::{has_child_kind, node_text};
+use crate::operations::signature::find_function_by_name;
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// Target style for `convert_function_style`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FunctionStyle {
+    /// `const foo = () => {}`
+    Arrow,
+    /// `function foo() {}`
+    Declaration,
+}
+
+impl FunctionStyle {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, OperationError> {
+        match s.to_lowercase().as_str() {
+            "arrow" => Ok(Self::Arrow),
+            "declaration" | "function" => Ok(Self::Declaration),
+            other => Err(OperationError::InvalidParams {
+                message: format!(
+                    "Invalid style '{}', expected 'arrow' or 'declaration'",
+                    other
+                ),
+            }),
+        }
+    }
+}
+
+/// The convert_function_style operation.
+pub struct ConvertFunctionStyle {
+    pub function_name: String,
+    pub to: FunctionStyle,
+}
+
+impl ConvertFunctionStyle {
+    pub fn new(function_name: String, to: FunctionStyle) -> Self {
+        Self { function_name, to }
+    }
+
+    fn label(&self) -> String {
+        let style = match self.to {
+            FunctionStyle::Arrow => "arrow function",
+            FunctionStyle::Declaration => "function declaration",
+        };
+        format!("convert '{}' to {}", self.function_name, style)
+    }
+
+    fn refuse(&self, reason: &str) -> OperationError {
+        OperationError::InvalidParams {
+            message: format!("Cannot convert '{}': {}", self.function_name, reason),
+        }
+    }
+
+    fn to_arrow(
+        &self,
+        source: &str,
+        profile: &StyleProfile,
+        func: &Node,
+    ) -> Result<Vec<TextEdit>, OperationError> {
+        match func.kind() {
+            "function_declaration" => {}
+            "arrow_function" => return Ok(vec![]), // Already an arrow -- no-op
+            "generator_function_declaration" | "generator_function" => {
+                return Err(self.refuse("generators cannot be arrow functions"))
+            }
+            _ => return Err(self.refuse("only function declarations can become arrow functions")),
+        }
+        if has_overloads(source, func, &self.function_name) {
+            return Err(self.refuse("it has overload signatures"));
+        }
+        if let Some(body) = func.child_by_field_name("body") {
+            if let Some(what) = find_own_binding_use(&body, source) {
+                return Err(self.refuse(&format!("its body uses `{}`", what)));
+            }
+        }
+        if let Some(line) = find_use_before_definition(source, func, &self.function_name) {
+            return Err(self.refuse(&format!(
+                "it is used on line {} before its definition and would no longer be hoisted",
+                line
+            )));
+        }
+
+        let semi = profile.semi();
+        let is_async = has_child_kind(func, "async");
+        let type_params = func
+            .child_by_field_name("type_parameters")
+            .map(|tp| arrow_type_parameters(&tp, source))
+            .unwrap_or_default();
+        let params = func
+            .child_by_field_name("parameters")
+            .map(|p| node_text(&p, source))
+            .unwrap_or("()");
+        let return_type = func
+            .child_by_field_name("return_type")
+            .map(|r| node_text(&r, source))
+            .unwrap_or("");
+        let body = func
+            .child_by_field_name("body")
+            .map(|b| node_text(&b, source))
+            .unwrap_or("{}");
+
+        let arrow = format!(
+            "const {} = {}{}{}{} => {}{}",
+            self.function_name,
+            if is_async { "async " } else { "" },
+            type_params,
+            params,
+            return_type,
+            body,
+            semi
+        );
+
+        // `export default const` is not valid: declare, then export the binding
+        let parent = func.parent().filter(|p| p.kind() == "export_statement");
+        if let Some(export) = parent.filter(|p| has_child_kind(p, "default")) {
+            let prefix = format::extract_line_prefix(source, export.start_byte());
+            return Ok(vec![TextEdit {
+                start: export.start_byte(),
+                end: export.end_byte(),
+                replacement: format!(
+                    "{}\n{}export default {}{}",
+                    arrow, prefix, self.function_name, semi
+                ),
+                label: self.label(),
+                priority: 0,
+            }]);
+        }
+
+        Ok(vec![TextEdit {
+            start: func.start_byte(),
+            end: func.end_byte(),
+            replacement: arrow,
+            label: self.label(),
+            priority: 0,
+        }])
+    }
+
+    fn to_declaration(
+        &self,
+        source: &str,
+        profile: &StyleProfile,
+        func: &Node,
+    ) -> Result<Vec<TextEdit>, OperationError> {
+        match func.kind() {
+            "arrow_function" | "function_expression" => {}
+            "function_declaration" => return Ok(vec![]), // Already a declaration -- no-op
+            _ => return Err(self.refuse("only `const` arrow functions can become declarations")),
+        }
+
+        let declarator = func
+            .parent()
+            .filter(|p| p.kind() == "variable_declarator")
+            .ok_or_else(|| self.refuse("it is not assigned to a variable"))?;
+        let declaration = declarator
+            .parent()
+            .filter(|p| p.kind() == "lexical_declaration" || p.kind() == "variable_declaration")
+            .ok_or_else(|| self.refuse("it is not part of a variable declaration"))?;
+        if declaration.named_child_count() > 1 {
+            return Err(self.refuse("its declaration declares several variables"));
+        }
+        if declarator.child_by_field_name("type").is_some() {
+            return Err(
+                self.refuse("the variable has a type annotation that a declaration cannot express")
+            );
+        }
+        if func.kind() == "arrow_function" {
+            if let Some(body) = func.child_by_field_name("body") {
+                if let Some(what) = find_own_binding_use(&body, source) {
+                    return Err(self.refuse(&format!(
+                        "its body uses `{}` from the enclosing scope",
+                        what
+                    )));
+                }
+            }
+        }
+
+        let is_async = has_child_kind(func, "async");
+        let type_params = func
+            .child_by_field_name("type_parameters")
+            .map(|tp| node_text(&tp, source))
+            .unwrap_or("");
+        let params = match func.child_by_field_name("parameters") {
+            Some(p) => node_text(&p, source).to_string(),
+            None => func
+                .child_by_field_name("parameter")
+                .map(|p| format!("({})", node_text(&p, source)))
+                .unwrap_or_else(|| "()".to_string()),
+        };
+        let return_type = func
+            .child_by_field_name("return_type")
+            .map(|r| node_text(&r, source))
+            .unwrap_or("");
+        let body_node = func
+            .child_by_field_name("body")
+            .ok_or_else(|| self.refuse("it has no body"))?;
+
+        let prefix = format::extract_line_prefix(source, declaration.start_byte());
+        let body = if body_node.kind() == "statement_block" {
+            node_text(&body_node, source).to_string()
+        } else {
+            expression_body_to_block(source, profile, &body_node, &prefix)
+        };
+
+        Ok(vec![TextEdit {
+            start: declaration.start_byte(),
+            end: declaration.end_byte(),
+            replacement: format!(
+                "{}function {}{}{}{} {}",
+                if is_async { "async " } else { "" },
+                self.function_name,
+                type_params,
+                params,
+                return_type,
+                body
+            ),
+            label: self.label(),
+            priority: 0,
+        }])
+    }
+}
+
+impl Executable for ConvertFunctionStyle {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = tree.root_node();
+        let func = find_function_by_name(&root, source, &self.function_name).ok_or_else(|| {
+            OperationError::TargetNotFound {
+                description: format!("Function '{}' not found", self.function_name),
+            }
+        })?;
+
+        let edits = match self.to {
+            FunctionStyle::Arrow => self.to_arrow(source, &profile, &func)?,
+            FunctionStyle::Declaration => self.to_declaration(source, &profile, &func)?,
+        };
+        Ok((edits, vec![]))
+    }
+}
+
+// --- Helper functions ---
+
+/// Functions that bind their own `this` and `arguments`.
+fn is_binding_scope(kind: &str) -> bool {
+    matches!(
+        kind,
+        "function_declaration"
+            | "function_expression"
+            | "generator_function_declaration"
+            | "generator_function"
+            | "method_definition"
+            | "class_body"
+    )
+}
+
+fn is_function_scope(kind: &str) -> bool {
+    is_binding_scope(kind) || kind == "arrow_function"
+}
+
+/// Find a use of `this` or `arguments` that refers to the function
+/// owning `body` (i.e. not inside a nested non-arrow function).
+pub(crate) fn find_own_binding_use(body: &Node, source: &str) -> Option<&'static str> {
+    let mut cursor = body.walk();
+    if !cursor.goto_first_child() {
+        return None;
+    }
+    loop {
+        let child = cursor.node();
+        if child.kind() == "this" {
+            return Some("this");
+        }
+        if child.kind() == "identifier" && node_text(&child, source) == "arguments" {
+            return Some("arguments");
+        }
+        if !is_binding_scope(child.kind()) {
+            if let Some(found) = find_own_binding_use(&child, source) {
+                return Some(found);
+            }
+        }
+        if !cursor.goto_next_sibling() {
+            return None;
+        }
+    }
+}
+
+/// Nearest enclosing function of `node`, by byte range.
+fn enclosing_function(node: &Node) -> Option<(usize, usize)> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if is_function_scope(n.kind()) {
+            return Some((n.start_byte(), n.end_byte()));
+        }
+        current = n.parent();
+    }
+    None
+}
+
+/// Line (1-based) of the first reference to `name` that runs before the
+/// declaration -- i.e. one that appears earlier in the same function scope.
+fn find_use_before_definition(source: &str, func: &Node, name: &str) -> Option<usize> {
+    let scope = enclosing_function(func);
+    let mut root = *func;
+    while let Some(p) = root.parent() {
+        root = p;
+    }
+
+    let mut stack = vec![root];
+    let mut first: Option<Node> = None;
+    while let Some(node) = stack.pop() {
+        if node.start_byte() >= func.start_byte() {
+            continue;
+        }
+        if node.kind() == "identifier"
+            && node_text(&node, source) == name
+            && enclosing_function(&node) == scope
+            && first.is_none_or(|f| node.start_byte() < f.start_byte())
+        {
+            first = Some(node);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    first.map(|n| n.start_position().row + 1)
+}
+
+/// Whether `func` is the implementation of an overloaded function.
+fn has_overloads(source: &str, func: &Node, name: &str) -> bool {
+    let statement = match func.parent() {
+        Some(p) if p.kind() == "export_statement" => p,
+        _ => *func,
+    };
+    let mut prev = statement.prev_named_sibling();
+    while let Some(p) = prev {
+        let sig = if p.kind() == "export_statement" {
+            p.child_by_field_name("declaration")
+        } else {
+            Some(p)
+        };
+        match sig {
+            Some(s) if s.kind() == "function_signature" => {
+                if s.child_by_field_name("name")
+                    .is_some_and(|n| node_text(&n, source) == name)
+                {
+                    return true;
+                }
+            }
+            _ => return false,
+        }
+        prev = p.prev_named_sibling();
+    }
+    false
+}
+
+/// `<T>` is read as a JSX tag in `.tsx` arrows; write it as `<T,>`.
+fn arrow_type_parameters(type_params: &Node, source: &str) -> String {
+    let text = node_text(type_params, source);
+    let params: Vec<Node> = {
+        let mut cursor = type_params.walk();
+        type_params
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() == "type_parameter")
+            .collect()
+    };
+    let single_bare = params.len() == 1
+        && params[0].child_by_field_name("constraint").is_none()
+        && !has_child_kind(type_params, ",");
+    if single_bare {
+        format!("<{},>", node_text(&params[0], source))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Turn an arrow's expression body into `{ return expr; }`.
+fn expression_body_to_block(
+    source: &str,
+    profile: &StyleProfile,
+    body: &Node,
+    prefix: &str,
+) -> String {
+    let inner = format::indent_deeper(prefix, &profile.indent);
+    let unit = &inner[prefix.len()..];
+
+    // `() => ({ ... })` needs the parentheses only as an arrow body
+    let expr = match body.kind() {
+        "parenthesized_expression" if body.named_child(0).is_some_and(|c| c.kind() == "object") => {
+            body.named_child(0).unwrap()
+        }
+        _ => *body,
+    };
+    let text = node_text(&expr, source).replace('\n', &format!("\n{}", unit));
+    let semi = profile.semi();
+    format!("{{\n{}return {}{}\n{}}}", inner, text, semi, prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse(source: &str, tsx: bool) -> Tree {
+        let mut parser = Parser::new();
+        let lang = if tsx {
+            tree_sitter_typescript::LANGUAGE_TSX.into()
+        } else {
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
+        };
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn convert(source: &str, name: &str, to: FunctionStyle) -> Result<String, OperationError> {
+        let tree = parse(source, true);
+        let op = ConvertFunctionStyle::new(name.into(), to);
+        let edits = op.compute_edits(source, &tree)?;
+        if edits.is_empty() {
+            return Ok(source.to_string());
+        }
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(
+            !parse(&result, true).root_node().has_error(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        Ok(result)
+    }
+
+    #[test]
+    fn test_declaration_to_arrow_keeps_signature() {
+        let source =
+            "export async function load<T>(id: string): Promise<T> {\n  return fetch(id);\n}\n";
+        let result = convert(source, "load", FunctionStyle::Arrow).unwrap();
+        assert_eq!(
+            result,
+            "export const load = async <T,>(id: string): Promise<T> => {\n  return fetch(id);\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_export_default_declaration_to_arrow() {
+        let source = "export default function App() {\n  return <div />;\n}\n";
+        let result = convert(source, "App", FunctionStyle::Arrow).unwrap();
+        assert_eq!(
+            result,
+            "const App = () => {\n  return <div />;\n};\nexport default App;\n"
+        );
+    }
+
+    #[test]
+    fn test_arrow_refused_on_use_before_definition() {
+        let source = "init();\n\nfunction init() {\n  setup();\n}\n";
+        let err = convert(source, "init", FunctionStyle::Arrow).unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{}", err);
+    }
+
+    #[test]
+    fn test_arrow_allowed_when_earlier_use_is_deferred() {
+        let source = "function main() {\n  helper();\n}\n\nfunction helper() {\n  return 1;\n}\n";
+        let result = convert(source, "helper", FunctionStyle::Arrow).unwrap();
+        assert!(result.contains("const helper = () => {"));
+    }
+
+    #[test]
+    fn test_arrow_refused_on_this_and_arguments() {
+        let source =
+            "function a() {\n  return this.x;\n}\nfunction b() {\n  return arguments.length;\n}\n";
+        assert!(convert(source, "a", FunctionStyle::Arrow).is_err());
+        assert!(convert(source, "b", FunctionStyle::Arrow).is_err());
+    }
+
+    #[test]
+    fn test_arrow_allows_this_in_nested_function() {
+        let source = "function a() {\n  return function () {\n    return this;\n  };\n}\n";
+        assert!(convert(source, "a", FunctionStyle::Arrow).is_ok());
+    }
+
+    #[test]
+    fn test_arrow_to_declaration_with_block_body() {
+        let source = "export const Button = <T,>({ label }: Props<T>): JSX.Element => {\n  return <b>{label}</b>;\n};\n";
+        let result = convert(source, "Button", FunctionStyle::Declaration).unwrap();
+        assert_eq!(
+            result,
+            "export function Button<T,>({ label }: Props<T>): JSX.Element {\n  return <b>{label}</b>;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_arrow_to_declaration_with_expression_body() {
+        let source = "const toPoint = async x => ({ x, y: 0 });\n";
+        let result = convert(source, "toPoint", FunctionStyle::Declaration).unwrap();
+        assert_eq!(
+            result,
+            "async function toPoint(x) {\n  return { x, y: 0 };\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_arrow_to_declaration_refuses_this() {
+        let source = "const f = () => this.value;\n";
+        assert!(convert(source, "f", FunctionStyle::Declaration).is_err());
+    }
+
+    #[test]
+    fn test_arrow_to_declaration_refuses_typed_variable() {
+        let source = "const App: FC<Props> = () => null;\n";
+        assert!(convert(source, "App", FunctionStyle::Declaration).is_err());
+    }
+
+    #[test]
+    fn test_already_in_target_style_is_noop() {
+        let source = "const f = () => 1;\n";
+        assert_eq!(convert(source, "f", FunctionStyle::Arrow).unwrap(), source);
+    }
+}
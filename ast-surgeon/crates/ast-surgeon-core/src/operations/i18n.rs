@@ -0,0 +1,488 @@
+//! Per-file half of `extract_i18n`.
+//!
+//! Replaces hard-coded JSX text and chosen string attributes with
+//! `t('namespace.key')` calls, adds `const { t } = useTranslation()` to each
+//! component that needs it and imports the hook. The extracted messages
+//! are returned so the caller can write them into a locale file.
+
+use crate::edit::{EditSet, TextEdit};
+use crate::format::{self, StyleConfig, StyleProfile};
+use crate::operations::imports::AddImport;
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use std::collections::BTreeMap;
+use tree_sitter::{Node, Tree};
+
+/// The hook that provides the translation function.
+const HOOK: &str = "useTranslation";
+
+/// Attributes extracted when none are given.
+pub const DEFAULT_ATTRIBUTES: &[&str] = &["placeholder", "title", "alt", "aria-label", "label"];
+
+/// Extracts user-visible strings into translation keys.
+pub struct ExtractI18n {
+    /// Key prefix, e.g. `home` for `t('home.welcomeBack')`.
+    pub namespace: String,
+    /// String attributes to extract besides JSX text.
+    pub attributes: Vec<String>,
+    /// Name of the translation function (`t`).
+    pub function: String,
+    /// Module the hook is imported from.
+    pub import_from: String,
+    /// Only extract inside this component.
+    pub component: Option<String>,
+    /// Keys already in the locale file under `namespace`, with their text.
+    pub existing: BTreeMap<String, String>,
+}
+
+/// Result of [`ExtractI18n::extract`].
+pub struct Extraction {
+    pub edits: Vec<TextEdit>,
+    /// New `(key, text)` pairs, keys relative to the namespace.
+    pub messages: Vec<(String, String)>,
+    pub warnings: Vec<String>,
+}
+
+/// A string to replace with a `t()` call.
+struct Candidate<'a> {
+    start: usize,
+    end: usize,
+    text: String,
+    component: Node<'a>,
+}
+
+impl ExtractI18n {
+    pub fn new(
+        namespace: String,
+        attributes: Vec<String>,
+        function: String,
+        import_from: String,
+        component: Option<String>,
+    ) -> Self {
+        Self {
+            namespace,
+            attributes,
+            function,
+            import_from,
+            component,
+            existing: BTreeMap::new(),
+        }
+    }
+
+    /// Reuse or avoid the keys already in the locale file.
+    pub fn with_existing(mut self, existing: BTreeMap<String, String>) -> Self {
+        self.existing = existing;
+        self
+    }
+
+    /// Compute the edits together with the messages they refer to; new
+    /// code follows `style`.
+    pub fn extract(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<Extraction, OperationError> {
+        if self.function.is_empty() || self.function.contains(|c: char| !is_identifier_char(c)) {
+            return Err(OperationError::InvalidParams {
+                message: format!("'{}' is not a valid function name", self.function),
+            });
+        }
+        let mut warnings = Vec::new();
+        let mut candidates = Vec::new();
+        self.collect(&tree.root_node(), source, &mut candidates, &mut warnings);
+        if candidates.is_empty() {
+            return Ok(Extraction {
+                edits: vec![],
+                messages: vec![],
+                warnings,
+            });
+        }
+
+        // Assign keys: same text, same key; never clobber an existing key
+        let profile = style.resolve(source);
+        let quote = profile.quote;
+        let mut keys: BTreeMap<String, String> = self.existing.clone();
+        let mut messages = Vec::new();
+        let mut replacements = Vec::new();
+        for candidate in &candidates {
+            let key = match keys.iter().find(|(_, text)| **text == candidate.text) {
+                Some((key, _)) => key.clone(),
+                None => {
+                    let base = key_for(&candidate.text);
+                    let mut key = base.clone();
+                    let mut n = 2;
+                    while keys.contains_key(&key) {
+                        key = format!("{}{}", base, n);
+                        n += 1;
+                    }
+                    keys.insert(key.clone(), candidate.text.clone());
+                    messages.push((key.clone(), candidate.text.clone()));
+                    key
+                }
+            };
+            let full_key = if self.namespace.is_empty() {
+                key
+            } else {
+                format!("{}.{}", self.namespace, key)
+            };
+            replacements.push(TextEdit {
+                start: candidate.start,
+                end: candidate.end,
+                replacement: format!("{{{}({}{}{})}}", self.function, quote, full_key, quote),
+                label: format!("extract '{}'", full_key),
+                priority: 0,
+            });
+        }
+
+        // One hook call per component
+        let mut components: Vec<Node> = Vec::new();
+        for candidate in &candidates {
+            if !components
+                .iter()
+                .any(|c| c.id() == candidate.component.id())
+            {
+                components.push(candidate.component);
+            }
+        }
+        let mut edits = Vec::new();
+        let mut hook_added = false;
+        for component in components {
+            let Some(body) = component.child_by_field_name("body") else {
+                continue;
+            };
+            let (inside, outside): (Vec<TextEdit>, Vec<TextEdit>) = replacements
+                .into_iter()
+                .partition(|e| e.start >= body.start_byte() && e.end <= body.end_byte());
+            replacements = outside;
+            if node_text(&body, source).contains(&format!("{}(", HOOK)) {
+                edits.extend(inside);
+                continue;
+            }
+            hook_added = true;
+            edits.extend(self.hook_edits(source, &profile, &component, &body, inside)?);
+        }
+
+        if hook_added {
+            edits.extend(
+                AddImport::new(
+                    self.import_from.clone(),
+                    vec![HOOK.to_string()],
+                    None,
+                    false,
+                )
+                .compute_edits_with_style(source, tree, style)?
+                .0,
+            );
+        }
+        Ok(Extraction {
+            edits,
+            messages,
+            warnings,
+        })
+    }
+
+    fn collect<'a>(
+        &self,
+        node: &Node<'a>,
+        source: &str,
+        candidates: &mut Vec<Candidate<'a>>,
+        warnings: &mut Vec<String>,
+    ) {
+        let found = match node.kind() {
+            "jsx_text" => {
+                let raw = node_text(node, source);
+                let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+                let start = node.start_byte() + (raw.len() - raw.trim_start().len());
+                let end = node.start_byte() + raw.trim_end().len();
+                Some((start, end, text))
+            }
+            "jsx_attribute" => node
+                .named_child(0)
+                .filter(|name| self.attributes.iter().any(|a| a == node_text(name, source)))
+                .and_then(|_| node.named_child(1))
+                .filter(|value| value.kind() == "string")
+                .map(|value| {
+                    let text = node_text(&value, source);
+                    (
+                        value.start_byte(),
+                        value.end_byte(),
+                        text[1..text.len() - 1].to_string(),
+                    )
+                }),
+            _ => None,
+        };
+
+        if let Some((start, end, text)) =
+            found.filter(|(_, _, t)| t.chars().any(char::is_alphabetic))
+        {
+            match component_of(node, source) {
+                Some((component, name)) if self.component.as_ref().is_none_or(|c| *c == name) => {
+                    candidates.push(Candidate {
+                        start,
+                        end,
+                        text,
+                        component,
+                    });
+                }
+                Some(_) => {}
+                None if self.component.is_none() => warnings.push(format!(
+                    "'{}' is not inside a function component; left unchanged",
+                    text
+                )),
+                None => {}
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect(&child, source, candidates, warnings);
+        }
+    }
+
+    /// Declare the translation function at the top of the component,
+    /// turning an expression-bodied arrow into a block when needed.
+    fn hook_edits(
+        &self,
+        source: &str,
+        profile: &StyleProfile,
+        component: &Node,
+        body: &Node,
+        inside: Vec<TextEdit>,
+    ) -> Result<Vec<TextEdit>, OperationError> {
+        let semi = profile.semi();
+        let binding = if self.function == "t" {
+            "{ t }".to_string()
+        } else {
+            format!("{{ t: {} }}", self.function)
+        };
+        let declaration = format!("const {} = {}(){}", binding, HOOK, semi);
+        let label = format!("add {}()", HOOK);
+        let outer = format::extract_line_prefix(source, component.start_byte());
+
+        if body.kind() == "statement_block" {
+            let inner = body
+                .named_child(0)
+                .map(|first| format::extract_line_prefix(source, first.start_byte()))
+                .filter(|prefix| !prefix.is_empty())
+                .unwrap_or_else(|| format::indent_deeper(&outer, &profile.indent));
+            let mut edits = inside;
+            edits.push(TextEdit {
+                start: body.start_byte() + 1,
+                end: body.start_byte() + 1,
+                replacement: format!("\n{}{}", inner, declaration),
+                label,
+                priority: 0,
+            });
+            return Ok(edits);
+        }
+
+        // `() => <p>Hi</p>` -> `() => { const { t } = ...; return <p>...</p> }`
+        let start = body.start_byte();
+        let relative = inside
+            .into_iter()
+            .map(|e| TextEdit {
+                start: e.start - start,
+                end: e.end - start,
+                ..e
+            })
+            .collect();
+        let expression =
+            EditSet::new(relative, body.end_byte() - start)?.apply(node_text(body, source));
+        let inner = format::indent_deeper(&outer, &profile.indent);
+        let unit = &inner[outer.len()..];
+        Ok(vec![TextEdit {
+            start,
+            end: body.end_byte(),
+            replacement: format!(
+                "{{\n{}{}\n{}return {}{}\n{}}}",
+                inner,
+                declaration,
+                inner,
+                format::indent_code(&expression, unit),
+                semi,
+                outer
+            ),
+            label,
+            priority: 0,
+        }])
+    }
+}
+
+impl Executable for ExtractI18n {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.extract(source, tree, &StyleConfig::default())
+            .map(|e| e.edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.extract(source, tree, style)
+            .map(|e| (e.edits, e.warnings))
+    }
+}
+
+// --- Helper functions ---
+
+/// `Welcome back, friend!` -> `welcomeBackFriend`.
+fn key_for(text: &str) -> String {
+    let mut key = String::new();
+    for (i, word) in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(4)
+        .enumerate()
+    {
+        let word = word.to_lowercase();
+        let mut chars = word.chars();
+        if i > 0 {
+            key.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        }
+        key.extend(chars);
+    }
+    if key.is_empty() || key.starts_with(|c: char| c.is_numeric()) {
+        key.insert_str(0, "text");
+    }
+    key
+}
+
+/// The nearest enclosing function component and its name.
+fn component_of<'a>(node: &Node<'a>, source: &str) -> Option<(Node<'a>, String)> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if matches!(
+            n.kind(),
+            "function_declaration" | "function_expression" | "arrow_function"
+        ) {
+            if let Some(name) = component_name(&n, source) {
+                return Some((n, name));
+            }
+        }
+        current = n.parent();
+    }
+    None
+}
+
+/// `function Card()`, `const Card = () =>`, `const Card = memo(() =>` or
+/// `export default function ()`.
+fn component_name(function: &Node, source: &str) -> Option<String> {
+    let is_component = |name: &str| name.starts_with(|c: char| c.is_ascii_uppercase());
+    if let Some(name) = function.child_by_field_name("name") {
+        let name = node_text(&name, source);
+        return is_component(name).then(|| name.to_string());
+    }
+    let mut parent = function.parent();
+    while let Some(p) = parent {
+        match p.kind() {
+            "arguments" | "call_expression" | "parenthesized_expression" => parent = p.parent(),
+            "variable_declarator" => {
+                let name = node_text(&p.child_by_field_name("name")?, source);
+                return is_component(name).then(|| name.to_string());
+            }
+            "export_statement" => return Some("default".to_string()),
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn parse_tsx(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TSX.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn extractor() -> ExtractI18n {
+        ExtractI18n::new(
+            "home".into(),
+            DEFAULT_ATTRIBUTES.iter().map(|a| a.to_string()).collect(),
+            "t".into(),
+            "react-i18next".into(),
+            None,
+        )
+    }
+
+    fn run(op: &ExtractI18n, source: &str) -> (String, Vec<(String, String)>) {
+        let tree = parse_tsx(source);
+        let extraction = op.extract(source, &tree, &StyleConfig::default()).unwrap();
+        let result = EditSet::new(extraction.edits, source.len())
+            .unwrap()
+            .apply(source);
+        assert!(!parse_tsx(&result).root_node().has_error(), "{}", result);
+        (result, extraction.messages)
+    }
+
+    #[test]
+    fn test_extract_text_and_attributes() {
+        let source = "import { useState } from 'react';\n\nexport function Home() {\n  const [q, setQ] = useState('');\n  return (\n    <main>\n      <h1>Welcome back!</h1>\n      <input placeholder=\"Search products\" value={q} />\n      <p>Welcome back!</p>\n      <span>{q}</span>\n    </main>\n  );\n}\n";
+        let (result, messages) = run(&extractor(), source);
+        assert_eq!(
+            result,
+            "import { useState } from 'react';\nimport { useTranslation } from 'react-i18next';\n\nexport function Home() {\n  const { t } = useTranslation();\n  const [q, setQ] = useState('');\n  return (\n    <main>\n      <h1>{t('home.welcomeBack')}</h1>\n      <input placeholder={t('home.searchProducts')} value={q} />\n      <p>{t('home.welcomeBack')}</p>\n      <span>{q}</span>\n    </main>\n  );\n}\n"
+        );
+        assert_eq!(
+            messages,
+            vec![
+                ("welcomeBack".to_string(), "Welcome back!".to_string()),
+                ("searchProducts".to_string(), "Search products".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expression_arrow_gets_block_body() {
+        let source = "const Empty = () => (\n  <p>\n    Nothing   here\n  </p>\n)\n";
+        let (result, _) = run(&extractor(), source);
+        assert_eq!(
+            result,
+            "import { useTranslation } from 'react-i18next'\nconst Empty = () => {\n  const { t } = useTranslation()\n  return (\n    <p>\n      {t('home.nothingHere')}\n    </p>\n  )\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_existing_keys_are_reused_or_avoided() {
+        let source = "export default function () {\n  return <p>Save <b>Cancel</b></p>\n}\n";
+        let existing = BTreeMap::from([
+            ("save".to_string(), "Save changes".to_string()),
+            ("dismiss".to_string(), "Cancel".to_string()),
+        ]);
+        let (result, messages) = run(&extractor().with_existing(existing), source);
+        assert!(result.contains("<p>{t('home.save2')} <b>{t('home.dismiss')}</b></p>"));
+        assert_eq!(messages, vec![("save2".to_string(), "Save".to_string())]);
+    }
+
+    #[test]
+    fn test_text_outside_components_is_reported() {
+        let source = "function helper() {\n  return <p>Hi</p>\n}\n";
+        let tree = parse_tsx(source);
+        let extraction = extractor()
+            .extract(source, &tree, &StyleConfig::default())
+            .unwrap();
+        assert!(extraction.edits.is_empty());
+        assert_eq!(extraction.warnings.len(), 1);
+    }
+}
@@ -1,6 +1,8 @@
-//! `add_import` and `remove_import` operations.
+//! `add_import`, `remove_import` and `fix_type_imports` operations.
 
 use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig, StyleProfile, TrailingCommas};
+use crate::operations::node_utils::{declares_name, declares_type_name, find_child_by_kind};
 use crate::operations::{Executable, OperationError};
 use tree_sitter::{Node, Tree};
 
@@ -32,11 +34,18 @@ impl AddImport {
 }
 
 impl Executable for AddImport {
-    fn compute_edits(
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
         &self,
         source: &str,
         tree: &Tree,
-    ) -> Result<Vec<TextEdit>, OperationError> {
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
         if self.specifiers.is_empty() && self.default_import.is_none() {
             return Err(OperationError::InvalidParams {
                 message: "add_import requires at least one specifier or a default import"
@@ -47,11 +56,11 @@ impl Executable for AddImport {
         let root = tree.root_node();
 
         // Find existing import from the same source module
-        if let Some(existing) = find_import_from_source(&root, source, &self.source_module) {
-            self.merge_into_existing(source, &existing)
-        } else {
-            self.insert_new_import(source, tree)
-        }
+        let edits = match find_import_from_source(&root, source, &self.source_module) {
+            Some(existing) => self.merge_into_existing(source, &existing, &profile)?,
+            None => self.insert_new_import(source, tree, &profile)?,
+        };
+        Ok((edits, vec![]))
     }
 }
 
@@ -61,6 +70,7 @@ impl AddImport {
         &self,
         source: &str,
         import_node: &Node,
+        profile: &StyleProfile,
     ) -> Result<Vec<TextEdit>, OperationError> {
         // Find existing specifiers
         let existing_specifiers = extract_existing_specifiers(import_node, source);
@@ -94,22 +104,12 @@ impl AddImport {
                 .map(|s| s.to_string())
                 .chain(new_specifiers.iter().map(|s| s.to_string()))
                 .collect();
-            let quote = detect_quote_style(source);
-            let semi = if detect_semicolons(source) { ";" } else { "" };
-            let type_keyword = if self.type_only { "type " } else { "" };
-
-            let mut parts = Vec::new();
-            if let Some(ref default) = self.default_import {
-                parts.push(default.clone());
-            }
-            if !all_specifiers.is_empty() {
-                let specs = all_specifiers.join(", ");
-                parts.push(format!("{{ {} }}", specs));
-            }
-            let import_clause = parts.join(", ");
-            let new_import = format!(
-                "import {}{} from {}{}{}{}",
-                type_keyword, import_clause, quote, self.source_module, quote, semi
+            let new_import = render_import(
+                self.type_only,
+                self.default_import.as_deref(),
+                &all_specifiers,
+                &self.source_module,
+                profile,
             );
             edits.push(TextEdit {
                 start: import_node.start_byte(),
@@ -140,41 +140,71 @@ impl AddImport {
                     }
                 });
             if let Some(named_imports) = named_imports {
-                // Find the closing brace position
                 let close_brace = named_imports.end_byte() - 1; // position of '}'
-                let before_brace = &source[named_imports.start_byte()..close_brace];
-
-                // Detect if there's a trailing comma
-                let trimmed = before_brace.trim_end();
-                let has_trailing_comma = trimmed.ends_with(',');
-
-                // Build the insertion text
-                let _separator = if has_trailing_comma { " " } else { ", " };
-                let new_text = new_specifiers
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-
-                let insertion = if has_trailing_comma {
-                    format!("{}{}", new_text, if has_trailing_comma { ", " } else { "" })
-                } else {
-                    format!(", {}", new_text)
+                let last_specifier = {
+                    let mut c = named_imports.walk();
+                    let last = named_imports
+                        .named_children(&mut c)
+                        .filter(|n| n.kind() == "import_specifier")
+                        .last();
+                    last
+                };
+                let names: Vec<&str> = new_specifiers.iter().map(|s| s.as_str()).collect();
+
+                let (position, insertion) = match last_specifier {
+                    Some(last) => {
+                        let between = &source[last.end_byte()..close_brace];
+                        let multiline = source[named_imports.start_byte()..close_brace]
+                            .contains('\n');
+                        if multiline && between.contains(',') {
+                            // One specifier per line, trailing comma kept
+                            let prefix = crate::format::extract_line_prefix(source, last.start_byte());
+                            let comma = last.end_byte() + between.find(',').unwrap() + 1;
+                            let lines: Vec<String> =
+                                names.iter().map(|n| format!("\n{}{},", prefix, n)).collect();
+                            (comma, lines.concat())
+                        } else if between.contains(',') {
+                            let comma = last.end_byte() + between.find(',').unwrap() + 1;
+                            (comma, format!(" {},", names.join(", ")))
+                        } else {
+                            (last.end_byte(), format!(", {}", names.join(", ")))
+                        }
+                    }
+                    // `import {} from 'x'`
+                    None => (close_brace, format!(" {} ", names.join(", "))),
                 };
 
-                // Insert before the closing brace
                 edits.push(TextEdit {
-                    start: close_brace,
-                    end: close_brace,
+                    start: position,
+                    end: position,
                     replacement: insertion,
                     label: format!("add specifiers to import from '{}'", self.source_module),
                     priority: 0,
                 });
+            } else if has_namespace_import(import_node) {
+                // `import * as X` cannot take named specifiers: add a second import
+                edits.push(TextEdit {
+                    start: import_node.end_byte(),
+                    end: import_node.end_byte(),
+                    replacement: format!("\n{}", self.format_full_import(profile, &[])),
+                    label: format!("add import from '{}'", self.source_module),
+                    priority: 0,
+                });
             } else {
                 // No named_imports block exists (maybe only default import).
                 // We need to add { specifiers } after the existing import clause.
-                // Replace the entire import statement.
-                let new_import = self.format_full_import(source, &existing_specifiers);
+                // Replace the entire import statement, keeping the default.
+                let default = self
+                    .default_import
+                    .clone()
+                    .or_else(|| existing_default_import(import_node, source));
+                let new_import = AddImport::new(
+                    self.source_module.clone(),
+                    self.specifiers.clone(),
+                    default,
+                    self.type_only,
+                )
+                .format_full_import(profile, &existing_specifiers);
                 edits.push(TextEdit {
                     start: import_node.start_byte(),
                     end: import_node.end_byte(),
@@ -193,9 +223,10 @@ impl AddImport {
         &self,
         source: &str,
         tree: &Tree,
+        profile: &StyleProfile,
     ) -> Result<Vec<TextEdit>, OperationError> {
         let insertion_point = find_import_insertion_point(source, tree);
-        let import_text = self.format_full_import(source, &[]);
+        let import_text = self.format_full_import(profile, &[]);
 
         // Add newline handling
         let needs_leading_newline =
@@ -203,10 +234,19 @@ impl AddImport {
         let needs_trailing_newline = insertion_point < source.len()
             && source.as_bytes().get(insertion_point) != Some(&b'\n');
 
+        // Match the indentation of the surrounding code (e.g. an indented
+        // `<script>` block in a component)
+        let prefix = tree
+            .root_node()
+            .named_child(0)
+            .map(|first| crate::format::extract_line_prefix(source, first.start_byte()))
+            .unwrap_or_default();
+
         let mut text = String::new();
         if needs_leading_newline {
             text.push('\n');
         }
+        text.push_str(&prefix);
         text.push_str(&import_text);
         text.push('\n');
         if needs_trailing_newline && insertion_point == 0 {
@@ -223,28 +263,13 @@ impl AddImport {
     }
 
     /// Format a complete import statement matching the file's conventions.
-    fn format_full_import(&self, source: &str, _existing_specifiers: &[&str]) -> String {
-        let quote = detect_quote_style(source);
-        let semi = if detect_semicolons(source) { ";" } else { "" };
-        let type_keyword = if self.type_only { "type " } else { "" };
-
-        let mut parts = Vec::new();
-
-        // Default import
-        if let Some(ref default) = self.default_import {
-            parts.push(default.clone());
-        }
-
-        // Named imports
-        if !self.specifiers.is_empty() {
-            let specs = self.specifiers.join(", ");
-            parts.push(format!("{{ {} }}", specs));
-        }
-
-        let import_clause = parts.join(", ");
-        format!(
-            "import {}{} from {}{}{}{}",
-            type_keyword, import_clause, quote, self.source_module, quote, semi
+    fn format_full_import(&self, profile: &StyleProfile, _existing_specifiers: &[&str]) -> String {
+        render_import(
+            self.type_only,
+            self.default_import.as_deref(),
+            &self.specifiers,
+            &self.source_module,
+            profile,
         )
     }
 }
@@ -265,11 +290,18 @@ impl RemoveImport {
 }
 
 impl Executable for RemoveImport {
-    fn compute_edits(
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
         &self,
         source: &str,
         tree: &Tree,
-    ) -> Result<Vec<TextEdit>, OperationError> {
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
         let root = tree.root_node();
 
         let import_node = find_import_from_source(&root, source, &self.source_module)
@@ -285,13 +317,16 @@ impl Executable for RemoveImport {
             } else {
                 end
             };
-            return Ok(vec![TextEdit {
-                start: import_node.start_byte(),
-                end: end_with_newline,
-                replacement: String::new(),
-                label: format!("remove import from '{}'", self.source_module),
-                priority: 0,
-            }]);
+            return Ok((
+                vec![TextEdit {
+                    start: import_node.start_byte(),
+                    end: end_with_newline,
+                    replacement: String::new(),
+                    label: format!("remove import from '{}'", self.source_module),
+                    priority: 0,
+                }],
+                vec![],
+            ));
         }
 
         // Remove specific specifiers
@@ -309,40 +344,321 @@ impl Executable for RemoveImport {
             } else {
                 end
             };
-            return Ok(vec![TextEdit {
-                start: import_node.start_byte(),
-                end: end_with_newline,
-                replacement: String::new(),
-                label: format!("remove import from '{}'", self.source_module),
-                priority: 0,
-            }]);
+            return Ok((
+                vec![TextEdit {
+                    start: import_node.start_byte(),
+                    end: end_with_newline,
+                    replacement: String::new(),
+                    label: format!("remove import from '{}'", self.source_module),
+                    priority: 0,
+                }],
+                vec![],
+            ));
         }
 
         // Rewrite import with remaining specifiers
-        let quote = detect_quote_style(source);
-        let semi = if detect_semicolons(source) { ";" } else { "" };
-        let specs = remaining
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
-        let new_import = format!(
-            "import {{ {} }} from {}{}{}{}",
-            specs, quote, self.source_module, quote, semi
-        );
+        let specs: Vec<String> = remaining.iter().map(|s| s.to_string()).collect();
+        let new_import = render_import(false, None, &specs, &self.source_module, &profile);
 
-        Ok(vec![TextEdit {
-            start: import_node.start_byte(),
-            end: import_node.end_byte(),
-            replacement: new_import,
-            label: format!("remove specifiers from import '{}'", self.source_module),
-            priority: 0,
-        }])
+        Ok((
+            vec![TextEdit {
+                start: import_node.start_byte(),
+                end: import_node.end_byte(),
+                replacement: new_import,
+                label: format!("remove specifiers from import '{}'", self.source_module),
+                priority: 0,
+            }],
+            vec![],
+        ))
+    }
+}
+
+/// The fix_type_imports operation.
+///
+/// For `verbatimModuleSyntax`: imported bindings referenced only in type
+/// positions (annotations, `implements`, generic arguments, `typeof` in a
+/// type) are made type-only, either as inline `type` specifiers or split
+/// into a separate `import type` statement. Unused bindings are left alone.
+pub struct FixTypeImports {
+    /// "separate" or "inline".
+    pub style: String,
+}
+
+/// One binding of an import statement being rewritten.
+struct ImportBinding {
+    /// Specifier text without a leading `type`, e.g. `a as b`, `* as ns`.
+    text: String,
+    kind: BindingKind,
+    type_only: bool,
+}
+
+#[derive(PartialEq)]
+enum BindingKind {
+    Default,
+    Namespace,
+    Named,
+}
+
+impl FixTypeImports {
+    pub fn new(style: String) -> Self {
+        Self { style }
+    }
+}
+
+impl Executable for FixTypeImports {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let inline = match self.style.as_str() {
+            "inline" => true,
+            "separate" => false,
+            other => {
+                return Err(OperationError::InvalidParams {
+                    message: format!("style must be 'separate' or 'inline', got '{}'", other),
+                })
+            }
+        };
+
+        let root = tree.root_node();
+        let mut edits = Vec::new();
+        let mut cursor = root.walk();
+        for import in root.named_children(&mut cursor) {
+            if import.kind() != "import_statement" || is_type_only_import(&import) {
+                continue;
+            }
+            let Some(bindings) = import_bindings(&import, &root, source) else {
+                continue;
+            };
+            let converted: Vec<&str> = bindings
+                .iter()
+                .filter(|b| b.type_only)
+                .map(|b| b.text.as_str())
+                .collect();
+            if converted.is_empty() {
+                continue;
+            }
+
+            let Some(module) = import.child_by_field_name("source") else {
+                continue;
+            };
+            let module = &source[module.start_byte()..module.end_byte()];
+            let semi = if source[..import.end_byte()].ends_with(';') {
+                ";"
+            } else {
+                ""
+            };
+            let statements = split_type_import(&bindings, inline)
+                .into_iter()
+                .map(|(type_only, parts)| {
+                    format!(
+                        "import {}{} from {}{}",
+                        if type_only { "type " } else { "" },
+                        parts,
+                        module,
+                        semi
+                    )
+                })
+                .collect::<Vec<_>>();
+            edits.push(TextEdit {
+                start: import.start_byte(),
+                end: import.end_byte(),
+                replacement: statements.join("\n"),
+                label: format!(
+                    "type-only import of {} from {}",
+                    converted.join(", "),
+                    module
+                ),
+                priority: 0,
+            });
+        }
+        Ok(edits)
     }
 }
 
 // --- Helper functions ---
 
+/// `import type ...` (as opposed to inline `type` specifiers).
+fn is_type_only_import(import_node: &Node) -> bool {
+    let mut cursor = import_node.walk();
+    let found = import_node
+        .children(&mut cursor)
+        .take_while(|c| c.kind() != "import_clause")
+        .any(|c| c.kind() == "type");
+    found
+}
+
+/// The bindings of an import with their usage classification; `None` for
+/// side-effect imports.
+fn import_bindings(import_node: &Node, root: &Node, source: &str) -> Option<Vec<ImportBinding>> {
+    let clause = find_child_by_kind(import_node, "import_clause")?;
+    let text = |n: &Node| source[n.start_byte()..n.end_byte()].to_string();
+    let mut bindings = Vec::new();
+    let mut cursor = clause.walk();
+    for child in clause.named_children(&mut cursor) {
+        match child.kind() {
+            "identifier" => bindings.push(ImportBinding {
+                type_only: used_only_as_type(root, source, &text(&child)),
+                text: text(&child),
+                kind: BindingKind::Default,
+            }),
+            "namespace_import" => {
+                let local = child.named_child(0).map(|n| text(&n)).unwrap_or_default();
+                bindings.push(ImportBinding {
+                    type_only: used_only_as_type(root, source, &local),
+                    text: text(&child),
+                    kind: BindingKind::Namespace,
+                });
+            }
+            "named_imports" => {
+                let mut inner = child.walk();
+                for specifier in child.named_children(&mut inner) {
+                    if specifier.kind() != "import_specifier" {
+                        continue;
+                    }
+                    let Some(name) = specifier.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let local = specifier.child_by_field_name("alias").unwrap_or(name);
+                    let inline_type = specifier.child(0).is_some_and(|c| c.kind() == "type");
+                    bindings.push(ImportBinding {
+                        text: source[name.start_byte()..specifier.end_byte()].to_string(),
+                        kind: BindingKind::Named,
+                        type_only: inline_type || used_only_as_type(root, source, &text(&local)),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(bindings)
+}
+
+/// Whether `name` is referenced at least once, and only in type positions.
+fn used_only_as_type(root: &Node, source: &str, name: &str) -> bool {
+    let (mut type_uses, mut value_uses) = (0, 0);
+    count_uses(root, source, name, (false, false), &mut type_uses, &mut value_uses);
+    type_uses > 0 && value_uses == 0
+}
+
+/// Count references to the imported `name`. `shadowed` is (value, type):
+/// whether an inner scope has rebound the name in that namespace, so that
+/// references to it there are not uses of the import.
+fn count_uses(
+    node: &Node,
+    source: &str,
+    name: &str,
+    shadowed: (bool, bool),
+    type_uses: &mut usize,
+    value_uses: &mut usize,
+) {
+    let shadowed = (
+        shadowed.0 || declares_name(node, source, name),
+        shadowed.1 || declares_type_name(node, source, name),
+    );
+    match node.kind() {
+        "import_statement" => return,
+        "type_identifier"
+            if !shadowed.1 && &source[node.start_byte()..node.end_byte()] == name =>
+        {
+            *type_uses += 1;
+        }
+        "identifier" | "shorthand_property_identifier"
+            if &source[node.start_byte()..node.end_byte()] == name =>
+        {
+            // `typeof x` in a type and the `NS` of `NS.Type` are type positions
+            let in_type = node
+                .parent()
+                .is_some_and(|p| matches!(p.kind(), "type_query" | "nested_type_identifier"));
+            // These resolve in the value namespace, so a local `x` hides them too
+            if !shadowed.0 {
+                if in_type {
+                    *type_uses += 1;
+                } else {
+                    *value_uses += 1;
+                }
+            }
+        }
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        count_uses(&child, source, name, shadowed, type_uses, value_uses);
+    }
+}
+
+/// The import clauses an import splits into, each with whether it is
+/// `import type`. A type-only default can't share a statement with named
+/// bindings, so it always gets its own.
+fn split_type_import(bindings: &[ImportBinding], inline: bool) -> Vec<(bool, String)> {
+    let clause = |default: Option<&str>, rest: Option<String>| match (default, rest) {
+        (Some(d), Some(r)) => format!("{}, {}", d, r),
+        (Some(d), None) => d.to_string(),
+        (None, Some(r)) => r,
+        (None, None) => String::new(),
+    };
+    let named =
+        |items: Vec<String>| (!items.is_empty()).then(|| format!("{{ {} }}", items.join(", ")));
+    let default = bindings.iter().find(|b| b.kind == BindingKind::Default);
+    let namespace = bindings.iter().find(|b| b.kind == BindingKind::Namespace);
+    let specifiers: Vec<&ImportBinding> = bindings
+        .iter()
+        .filter(|b| b.kind == BindingKind::Named)
+        .collect();
+
+    let mut statements = Vec::new();
+    if let Some(d) = default.filter(|d| d.type_only) {
+        statements.push((true, d.text.clone()));
+    }
+    let value_default = default.filter(|d| !d.type_only).map(|d| d.text.as_str());
+    let all_named_types = specifiers.iter().all(|s| s.type_only);
+
+    if let Some(ns) = namespace {
+        statements.push((
+            ns.type_only && value_default.is_none(),
+            clause(value_default, Some(ns.text.clone())),
+        ));
+    } else if specifiers.is_empty() {
+        if let Some(d) = value_default {
+            statements.push((false, d.to_string()));
+        }
+    } else if all_named_types && value_default.is_none() {
+        statements.push((
+            true,
+            clause(
+                None,
+                named(specifiers.iter().map(|s| s.text.clone()).collect()),
+            ),
+        ));
+    } else if inline {
+        let items = specifiers
+            .iter()
+            .map(|s| {
+                if s.type_only {
+                    format!("type {}", s.text)
+                } else {
+                    s.text.clone()
+                }
+            })
+            .collect();
+        statements.push((false, clause(value_default, named(items))));
+    } else {
+        let (types, values): (Vec<&&ImportBinding>, Vec<&&ImportBinding>) =
+            specifiers.iter().partition(|s| s.type_only);
+        statements.push((
+            false,
+            clause(
+                value_default,
+                named(values.iter().map(|s| s.text.clone()).collect()),
+            ),
+        ));
+        if !types.is_empty() {
+            statements.push((
+                true,
+                clause(None, named(types.iter().map(|s| s.text.clone()).collect())),
+            ));
+        }
+    }
+    statements
+}
+
 /// Find an import_statement node that imports from the given source module.
 fn find_import_from_source<'a>(
     root: &'a Node<'a>,
@@ -420,21 +736,27 @@ fn has_default_import(import_node: &Node, _source: &str) -> bool {
     false
 }
 
-/// Find a direct child node by its kind.
-fn find_child_by_kind<'a>(node: &'a Node<'a>, kind: &str) -> Option<Node<'a>> {
-    let mut cursor = node.walk();
-    if !cursor.goto_first_child() {
-        return None;
-    }
-    loop {
-        if cursor.node().kind() == kind {
-            return Some(cursor.node());
-        }
-        if !cursor.goto_next_sibling() {
-            break;
-        }
-    }
-    None
+/// Name of the default import, e.g. `React` in `import React from 'react'`.
+fn existing_default_import(import_node: &Node, source: &str) -> Option<String> {
+    let clause = find_child_by_kind(import_node, "import_clause")?;
+    let mut cursor = clause.walk();
+    let default = clause
+        .children(&mut cursor)
+        .find(|c| c.kind() == "identifier")
+        .map(|c| source[c.start_byte()..c.end_byte()].to_string());
+    default
+}
+
+/// Check if an import statement has a namespace import (`* as X`).
+fn has_namespace_import(import_node: &Node) -> bool {
+    let Some(clause) = find_child_by_kind(import_node, "import_clause") else {
+        return false;
+    };
+    let mut cursor = clause.walk();
+    let found = clause
+        .children(&mut cursor)
+        .any(|c| c.kind() == "namespace_import");
+    found
 }
 
 /// Find the byte offset where new imports should be inserted.
@@ -460,30 +782,81 @@ fn find_import_insertion_point(source: &str, tree: &Tree) -> usize {
         }
     }
 
-    last_import_end.unwrap_or_else(|| {
-        if source.starts_with("#!") {
-            source.find('\n').map(|i| i + 1).unwrap_or(0)
-        } else {
-            0
-        }
-    })
+    last_import_end.unwrap_or_else(|| prologue_end(source, tree))
 }
 
-/// Detect quote style from existing imports.
-fn detect_quote_style(source: &str) -> char {
-    let single = source.matches("from '").count();
-    let double = source.matches("from \"").count();
-    if single >= double { '\'' } else { '"' }
+/// End of the file prologue: the shebang line and any directives
+/// (`'use client'`, `'use strict'`). Nothing may be inserted before it.
+pub(crate) fn prologue_end(source: &str, tree: &Tree) -> usize {
+    let line_end = |end: usize| source[end..].find('\n').map_or(end, |i| end + i + 1);
+    let mut prologue = if source.starts_with("#!") {
+        line_end(0)
+    } else {
+        0
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for node in root.named_children(&mut cursor) {
+        match node.kind() {
+            "hash_bang_line" | "comment" => continue,
+            "expression_statement"
+                if node.named_child_count() == 1
+                    && node.named_child(0).is_some_and(|s| s.kind() == "string") =>
+            {
+                prologue = line_end(node.end_byte());
+            }
+            _ => break,
+        }
+    }
+    prologue
 }
 
-/// Detect whether the file uses semicolons.
-fn detect_semicolons(source: &str) -> bool {
-    let with_semi = source
-        .lines()
-        .take(30)
-        .filter(|l| l.trim_end().ends_with(';'))
-        .count();
-    with_semi > 0
+/// `import [type] [Default, ]{ a, b } from 'module';` in the file's style.
+/// Named specifiers go on their own lines when the statement would be
+/// wider than the print width.
+fn render_import(
+    type_only: bool,
+    default: Option<&str>,
+    specifiers: &[String],
+    module: &str,
+    style: &StyleProfile,
+) -> String {
+    let type_keyword = if type_only { "type " } else { "" };
+    let from = format!("from {0}{1}{0}{2}", style.quote, module, style.semi());
+    let default_part = match (default, specifiers.is_empty()) {
+        (Some(default), true) => default.to_string(),
+        (Some(default), false) => format!("{}, ", default),
+        (None, _) => String::new(),
+    };
+    if specifiers.is_empty() {
+        return format!("import {}{} {}", type_keyword, default_part, from);
+    }
+    let line = format!(
+        "import {}{}{{ {} }} {}",
+        type_keyword,
+        default_part,
+        specifiers.join(", "),
+        from
+    );
+    if line.len() <= style.print_width || specifiers.len() < 2 {
+        return line;
+    }
+    let unit = format::indent_deeper("", &style.indent);
+    let comma = if style.trailing_commas == TrailingCommas::None {
+        ""
+    } else {
+        ","
+    };
+    format!(
+        "import {}{}{{\n{}{}{}\n}} {}",
+        type_keyword,
+        default_part,
+        unit,
+        specifiers.join(&format!(",\n{}", unit)),
+        comma,
+        from
+    )
 }
 
 #[cfg(test)]
@@ -585,6 +958,55 @@ mod tests {
         assert!(edits.is_empty(), "Should be no-op for existing specifier");
     }
 
+    #[test]
+    fn test_add_specifier_keeps_brace_spacing() {
+        let op = AddImport::new("react".to_string(), vec!["useMemo".to_string()], None, false);
+
+        let source = "import { useState } from 'react';\n";
+        let result = apply(source, op.compute_edits(source, &parse_ts(source)).unwrap());
+        assert_eq!(result, "import { useState, useMemo } from 'react';\n");
+
+        let source = "import {\n  useState,\n  useEffect,\n} from 'react';\n";
+        let result = apply(source, op.compute_edits(source, &parse_ts(source)).unwrap());
+        assert_eq!(
+            result,
+            "import {\n  useState,\n  useEffect,\n  useMemo,\n} from 'react';\n"
+        );
+    }
+
+    #[test]
+    fn test_add_import_keeps_existing_default() {
+        let source = "import React from 'react';\n";
+        let tree = parse_ts(source);
+        let op = AddImport::new(
+            "react".to_string(),
+            vec!["useState".to_string()],
+            None,
+            false,
+        );
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = apply(source, edits);
+        assert_eq!(result, "import React, { useState } from 'react';\n");
+    }
+
+    #[test]
+    fn test_add_import_next_to_namespace_import() {
+        let source = "import * as React from 'react';\n";
+        let tree = parse_ts(source);
+        let op = AddImport::new(
+            "react".to_string(),
+            vec!["useState".to_string()],
+            None,
+            false,
+        );
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = apply(source, edits);
+        assert_eq!(
+            result,
+            "import * as React from 'react';\nimport { useState } from 'react';\n"
+        );
+    }
+
     #[test]
     fn test_add_import_matches_double_quotes() {
         let source = "import { useState } from \"react\";\n";
@@ -652,6 +1074,35 @@ mod tests {
         assert!(result.contains("useCallback"));
     }
 
+    #[test]
+    fn test_add_import_follows_configured_style() {
+        let source = "const foo = 1\n";
+        let op = AddImport::new(
+            "react".to_string(),
+            vec!["useState".to_string(), "useEffect".to_string()],
+            None,
+            false,
+        );
+        let config = StyleConfig {
+            quote: Some('"'),
+            semicolons: Some(true),
+            trailing_commas: Some(TrailingCommas::Es5),
+            print_width: Some(30),
+            ..Default::default()
+        };
+        let (edits, _) = op
+            .compute_edits_with_style(source, &parse_ts(source), &config)
+            .unwrap();
+        assert_eq!(
+            apply(source, edits),
+            "import {\n  useState,\n  useEffect,\n} from \"react\";\nconst foo = 1\n"
+        );
+
+        // Without configuration the file's own style is kept
+        let result = apply(source, op.compute_edits(source, &parse_ts(source)).unwrap());
+        assert!(result.starts_with("import { useState, useEffect } from 'react'\n"));
+    }
+
     // --- remove_import tests ---
 
     #[test]
@@ -720,4 +1171,48 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_fix_type_imports_separate() {
+        let source = "import { useState, type FC, ReactNode, Ref as R } from 'react';\nimport * as z from 'zod';\nimport Store, { Action } from './store';\n\nexport const App: FC<{ children: ReactNode; r: R<Store> }> = () => {\n  const [a] = useState<Action>();\n  return a;\n};\ntype Schema = z.infer<typeof schema>;\n";
+        let tree = parse_tsx(source);
+        let op = FixTypeImports::new("separate".to_string());
+        let result = apply(source, op.compute_edits(source, &tree).unwrap());
+        assert_eq!(
+            result,
+            "import { useState } from 'react';\nimport type { FC, ReactNode, Ref as R } from 'react';\nimport type * as z from 'zod';\nimport type Store from './store';\nimport type { Action } from './store';\n\nexport const App: FC<{ children: ReactNode; r: R<Store> }> = () => {\n  const [a] = useState<Action>();\n  return a;\n};\ntype Schema = z.infer<typeof schema>;\n"
+        );
+        assert!(!parse_tsx(&result).root_node().has_error());
+    }
+
+    #[test]
+    fn test_fix_type_imports_inline_keeps_value_uses() {
+        let source = "import Button, { ButtonProps, variants } from './Button'\nimport { Config } from './config'\n\nexport class Themed implements ButtonProps {\n  size = variants.sm\n}\nexport const el = <Button />\nconst c = Config\n";
+        let tree = parse_tsx(source);
+        let op = FixTypeImports::new("inline".to_string());
+        let result = apply(source, op.compute_edits(source, &tree).unwrap());
+        assert!(result.starts_with(
+            "import Button, { type ButtonProps, variants } from './Button'\nimport { Config } from './config'\n"
+        ));
+
+        let op = FixTypeImports::new("merged".to_string());
+        assert!(matches!(
+            op.compute_edits(source, &tree),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fix_type_imports_resolves_shadowing() {
+        // The local `Item` is not a value use of the import, and the `Model`
+        // type parameter and local `Row` alias are not type uses of theirs
+        let source = "import { Item, Model, Row } from './types';\n\nexport function first(items: Item[]) {\n  const Item = items[0];\n  return Item;\n}\n\nexport function wrap<Model>(m: Model): Model {\n  type Row = { m: Model };\n  const row: Row = { m };\n  return row.m;\n}\n";
+        let tree = parse_ts(source);
+        let op = FixTypeImports::new("separate".to_string());
+        let result = apply(source, op.compute_edits(source, &tree).unwrap());
+        assert_eq!(
+            result,
+            "import { Model, Row } from './types';\nimport type { Item } from './types';\n\nexport function first(items: Item[]) {\n  const Item = items[0];\n  return Item;\n}\n\nexport function wrap<Model>(m: Model): Model {\n  type Row = { m: Model };\n  const row: Row = { m };\n  return row.m;\n}\n"
+        );
+    }
 }
@@ -0,0 +1,682 @@
+//! JSON / JSONC operations: `json_set`, `json_remove`, `json_append` and
+//! `add_dependency`.
+//!
+//! Values are addressed by JSON pointer (RFC 6901), e.g.
+//! `/compilerOptions/paths` or `/plugins/0`. Only the addressed value is
+//! rewritten; comments, key order and formatting elsewhere are kept.
+//! New values are rendered with the file's indentation.
+
+use crate::edit::TextEdit;
+use crate::format::{self, IndentStyle, StyleConfig};
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use serde_json::Value;
+use tree_sitter::{Node, Tree};
+
+/// The json_set operation.
+///
+/// Replaces the value at `pointer`, or adds it when missing. Missing
+/// intermediate objects are created; `-` as the last token of an array
+/// path appends.
+pub struct JsonSet {
+    pub pointer: String,
+    pub value: Value,
+}
+
+impl JsonSet {
+    pub fn new(pointer: String, value: Value) -> Self {
+        Self { pointer, value }
+    }
+}
+
+impl Executable for JsonSet {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = document_value(tree)?;
+        let tokens = parse_pointer(&self.pointer)?;
+        let label = format!("set {}", self.pointer);
+        let edits = set_value(source, &profile.indent, root, &tokens, &self.value, &label)?;
+        Ok((edits, vec![]))
+    }
+}
+
+/// The json_remove operation.
+///
+/// Removes an object member or array element, together with its comma
+/// and the comments directly above it.
+pub struct JsonRemove {
+    pub pointer: String,
+}
+
+impl JsonRemove {
+    pub fn new(pointer: String) -> Self {
+        Self { pointer }
+    }
+}
+
+impl Executable for JsonRemove {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let root = document_value(tree)?;
+        let tokens = parse_pointer(&self.pointer)?;
+        let Some((last, parents)) = tokens.split_last() else {
+            return Err(OperationError::InvalidParams {
+                message: "Cannot remove the document root".to_string(),
+            });
+        };
+        let container = resolve(root, parents, source).ok_or_else(|| not_found(&self.pointer))?;
+        let member = member_of(&container, last, source).ok_or_else(|| not_found(&self.pointer))?;
+        Ok(remove_member(
+            source,
+            &container,
+            &member,
+            format!("remove {}", self.pointer),
+        ))
+    }
+}
+
+/// The json_append operation.
+///
+/// Appends a value to the array at `pointer`; a missing array is created.
+/// A trailing `-` token (`/files/-`) names the same array.
+pub struct JsonAppend {
+    pub pointer: String,
+    pub value: Value,
+}
+
+impl JsonAppend {
+    pub fn new(pointer: String, value: Value) -> Self {
+        Self { pointer, value }
+    }
+}
+
+impl Executable for JsonAppend {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = document_value(tree)?;
+        let mut tokens = parse_pointer(&self.pointer)?;
+        if tokens.last().is_some_and(|t| t == "-") {
+            tokens.pop();
+        }
+        let label = format!("append to {}", self.pointer);
+        let indent = &profile.indent;
+        let edits = match resolve(root, &tokens, source) {
+            Some(array) if array.kind() == "array" => {
+                let prefix = member_prefix(source, indent, &array);
+                let text = render(&self.value, indent, &prefix);
+                insert_member(source, indent, &array, None, &text, &label)
+            }
+            Some(other) => {
+                return Err(OperationError::InvalidParams {
+                    message: format!("{} is a {}, not an array", self.pointer, other.kind()),
+                })
+            }
+            None => set_value(
+                source,
+                indent,
+                root,
+                &tokens,
+                &Value::Array(vec![self.value.clone()]),
+                &label,
+            )?,
+        };
+        Ok((edits, vec![]))
+    }
+}
+
+/// The add_dependency operation (package.json).
+///
+/// Adds or updates `name` in `dependencies` (or `devDependencies`),
+/// inserting new entries in alphabetical position.
+pub struct AddDependency {
+    pub name: String,
+    pub version: String,
+    pub dev: bool,
+}
+
+impl AddDependency {
+    pub fn new(name: String, version: String, dev: bool) -> Self {
+        Self { name, version, dev }
+    }
+
+    fn section(&self, dev: bool) -> &'static str {
+        if dev {
+            "devDependencies"
+        } else {
+            "dependencies"
+        }
+    }
+}
+
+impl Executable for AddDependency {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let indent = &style.resolve(source).indent;
+        let root = document_value(tree)?;
+        if root.kind() != "object" {
+            return Err(OperationError::InvalidParams {
+                message: "package.json must contain an object".to_string(),
+            });
+        }
+        let section = self.section(self.dev);
+        let label = format!("add {} to {}", self.name, section);
+        let version = Value::String(self.version.clone());
+
+        let mut warnings = Vec::new();
+        let other = self.section(!self.dev);
+        if resolve(root, &[other.to_string()], source)
+            .is_some_and(|deps| member_of(&deps, &self.name, source).is_some())
+        {
+            warnings.push(format!("{} is also listed in {}", self.name, other));
+        }
+
+        let Some(deps) = resolve(root, &[section.to_string()], source) else {
+            let edits = set_value(
+                source,
+                indent,
+                root,
+                &[section.to_string()],
+                &serde_json::json!({ &self.name: version }),
+                &label,
+            )?;
+            return Ok((edits, warnings));
+        };
+        if deps.kind() != "object" {
+            return Err(OperationError::InvalidParams {
+                message: format!("{} is not an object", section),
+            });
+        }
+        if let Some(existing) = resolve(deps, std::slice::from_ref(&self.name), source) {
+            let edits = replace_value(source, indent, &existing, &version, &label);
+            return Ok((edits, warnings));
+        }
+
+        // Before the first entry that sorts after the new one
+        let before = members(&deps).into_iter().find(|pair| {
+            pair.child_by_field_name("key")
+                .is_some_and(|k| key_text(&k, source) > self.name.as_str())
+        });
+        let text = format!(
+            "{}: {}",
+            serde_json::to_string(&self.name).unwrap_or_default(),
+            render(&version, indent, "")
+        );
+        Ok((
+            insert_member(source, indent, &deps, before.as_ref(), &text, &label),
+            warnings,
+        ))
+    }
+}
+
+// --- Helper functions ---
+
+fn not_found(pointer: &str) -> OperationError {
+    OperationError::TargetNotFound {
+        description: format!("Nothing at JSON pointer '{}'", pointer),
+    }
+}
+
+/// The top-level value of a JSON document.
+fn document_value(tree: &Tree) -> Result<Node<'_>, OperationError> {
+    let root = tree.root_node();
+    if root.kind() != "document" {
+        return Err(OperationError::UnsupportedLanguage {
+            language: format!("{} (JSON operations need a JSON file)", root.kind()),
+        });
+    }
+    let mut cursor = root.walk();
+    let value = root
+        .named_children(&mut cursor)
+        .find(|n| n.kind() != "comment");
+    value.ok_or_else(|| OperationError::TargetNotFound {
+        description: "Empty JSON document".to_string(),
+    })
+}
+
+/// Split a JSON pointer into unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, OperationError> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(OperationError::InvalidParams {
+            message: format!("JSON pointer '{}' must start with '/'", pointer),
+        });
+    };
+    Ok(rest
+        .split('/')
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Follow `tokens` from `node`; `None` if any step is missing.
+fn resolve<'a>(node: Node<'a>, tokens: &[String], source: &str) -> Option<Node<'a>> {
+    tokens.iter().try_fold(node, |current, token| {
+        let member = member_of(&current, token, source)?;
+        if member.kind() == "pair" {
+            member.child_by_field_name("value")
+        } else {
+            Some(member)
+        }
+    })
+}
+
+/// The pair (objects) or element (arrays) addressed by `token`.
+/// `resolve` unwraps pairs to their value.
+fn member_of<'a>(container: &Node<'a>, token: &str, source: &str) -> Option<Node<'a>> {
+    match container.kind() {
+        "object" => members(container).into_iter().find(|pair| {
+            pair.child_by_field_name("key")
+                .is_some_and(|k| key_text(&k, source) == token)
+        }),
+        "array" => {
+            let index: usize = token.parse().ok()?;
+            members(container).into_iter().nth(index)
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn members<'a>(container: &Node<'a>) -> Vec<Node<'a>> {
+    let mut cursor = container.walk();
+    container
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() != "comment" && !n.is_error())
+        .collect()
+}
+
+/// The comma after `member`. JSONC trailing commas (`[1, 2,]`) parse as
+/// ERROR nodes holding the comma.
+fn comma_after<'a>(source: &str, member: &Node<'a>) -> Option<Node<'a>> {
+    member
+        .next_sibling()
+        .filter(|n| n.kind() == "," || (n.is_error() && node_text(n, source).trim() == ","))
+}
+
+fn key_text<'s>(key: &Node, source: &'s str) -> &'s str {
+    node_text(key, source).trim_matches('"')
+}
+
+/// Set `tokens` below `node` to `value`, creating what is missing.
+fn set_value(
+    source: &str,
+    indent: &IndentStyle,
+    node: Node,
+    tokens: &[String],
+    value: &Value,
+    label: &str,
+) -> Result<Vec<TextEdit>, OperationError> {
+    let Some((first, rest)) = tokens.split_first() else {
+        return Ok(replace_value(source, indent, &node, value, label));
+    };
+
+    if let Some(member) = member_of(&node, first, source) {
+        let child = match member.kind() {
+            "pair" => member.child_by_field_name("value").unwrap_or(member),
+            _ => member,
+        };
+        if !rest.is_empty() && !matches!(child.kind(), "object" | "array") {
+            return Err(OperationError::InvalidParams {
+                message: format!("'{}' is a {}, not a container", first, child.kind()),
+            });
+        }
+        return set_value(source, indent, child, rest, value, label);
+    }
+
+    // Missing: build the rest of the path as nested objects
+    let nested = rest.iter().rev().fold(value.clone(), |inner, key| {
+        let mut map = serde_json::Map::new();
+        map.insert(key.clone(), inner);
+        Value::Object(map)
+    });
+    let prefix = member_prefix(source, indent, &node);
+    let rendered = render(&nested, indent, &prefix);
+    match node.kind() {
+        "object" => {
+            let text = format!(
+                "{}: {}",
+                serde_json::to_string(first).unwrap_or_default(),
+                rendered
+            );
+            Ok(insert_member(source, indent, &node, None, &text, label))
+        }
+        "array" if first == "-" || first.parse() == Ok(members(&node).len()) => {
+            Ok(insert_member(source, indent, &node, None, &rendered, label))
+        }
+        "array" => Err(OperationError::TargetNotFound {
+            description: format!("Array index '{}' out of range", first),
+        }),
+        other => Err(OperationError::InvalidParams {
+            message: format!("Cannot add '{}' to a {}", first, other),
+        }),
+    }
+}
+
+fn replace_value(
+    source: &str,
+    indent: &IndentStyle,
+    node: &Node,
+    value: &Value,
+    label: &str,
+) -> Vec<TextEdit> {
+    let prefix = format::extract_line_prefix(source, node.start_byte());
+    let text = render(value, indent, &prefix);
+    if text == node_text(node, source) {
+        return vec![]; // Already set -- no-op
+    }
+    vec![TextEdit {
+        start: node.start_byte(),
+        end: node.end_byte(),
+        replacement: text,
+        label: label.to_string(),
+        priority: 0,
+    }]
+}
+
+/// Render a value with the `indent` unit; continuation lines get `prefix`
+/// in front.
+fn render(value: &Value, indent: &IndentStyle, prefix: &str) -> String {
+    let unit = format::indent_deeper("", indent);
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    serde::Serialize::serialize(value, &mut serializer).expect("serializing a JSON value");
+    let text = String::from_utf8(out).unwrap_or_default();
+    text.replace('\n', &format!("\n{}", prefix))
+}
+
+/// Line prefix for members of `container`.
+pub(crate) fn member_prefix(source: &str, indent: &IndentStyle, container: &Node) -> String {
+    match members(container).first() {
+        Some(first) if is_multiline(source, container) => {
+            format::extract_line_prefix(source, first.start_byte())
+        }
+        _ => format::indent_deeper(
+            &format::extract_line_prefix(source, container.start_byte()),
+            indent,
+        ),
+    }
+}
+
+/// Whether only whitespace precedes `offset` on its line.
+fn starts_line(source: &str, offset: usize) -> bool {
+    source[..offset]
+        .rsplit('\n')
+        .next()
+        .is_some_and(|before| before.trim().is_empty())
+}
+
+fn is_multiline(source: &str, node: &Node) -> bool {
+    node_text(node, source).contains('\n')
+}
+
+/// Insert `text` as a member of `container`, before `before` or at the end.
+pub(crate) fn insert_member(
+    source: &str,
+    indent: &IndentStyle,
+    container: &Node,
+    before: Option<&Node>,
+    text: &str,
+    label: &str,
+) -> Vec<TextEdit> {
+    let edit = |start: usize, replacement: String, priority: i32| TextEdit {
+        start,
+        end: start,
+        replacement,
+        label: label.to_string(),
+        priority,
+    };
+    let existing = members(container);
+    let multiline = is_multiline(source, container);
+    let prefix = member_prefix(source, indent, container);
+
+    if let Some(before) = before {
+        if multiline {
+            let line_start = source[..before.start_byte()]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+            return vec![edit(line_start, format!("{}{},\n", prefix, text), 0)];
+        }
+        return vec![edit(before.start_byte(), format!("{}, ", text), 0)];
+    }
+
+    let Some(last) = existing.last() else {
+        // Empty container: `{}` -> `{ "a": 1 }`, laid out like the file
+        let open = container.start_byte() + 1;
+        let close = container.end_byte() - 1;
+        let replacement = if source.contains('\n') {
+            format!(
+                "\n{}{}\n{}",
+                prefix,
+                text,
+                format::extract_line_prefix(source, container.start_byte())
+            )
+        } else {
+            format!(" {} ", text)
+        };
+        return vec![TextEdit {
+            start: open,
+            end: close,
+            replacement,
+            label: label.to_string(),
+            priority: 0,
+        }];
+    };
+
+    let close = container.end_byte() - 1;
+    let trailing_comma = comma_after(source, last);
+    let after_last = trailing_comma.map_or(last.end_byte(), |c| c.end_byte());
+    if !multiline || !source[after_last..close].contains('\n') {
+        if trailing_comma.is_some() {
+            return vec![edit(after_last, format!(" {},", text), 0)];
+        }
+        return vec![edit(after_last, format!(", {}", text), 0)];
+    }
+
+    // Next line after the last member, past any comment trailing it
+    let line_end = after_last + source[after_last..].find('\n').unwrap_or(0);
+    if trailing_comma.is_some() {
+        return vec![edit(line_end, format!("\n{}{},", prefix, text), 0)];
+    }
+    vec![
+        edit(last.end_byte(), ",".to_string(), 0),
+        edit(line_end, format!("\n{}{}", prefix, text), 1),
+    ]
+}
+
+/// Remove a pair or element with its separator.
+pub(crate) fn remove_member(
+    source: &str,
+    container: &Node,
+    member: &Node,
+    label: String,
+) -> Vec<TextEdit> {
+    let existing = members(container);
+    let index = existing
+        .iter()
+        .position(|m| m.id() == member.id())
+        .unwrap_or(0);
+    let is_last = index + 1 == existing.len();
+    let trailing_comma = comma_after(source, member);
+    let edit = |start: usize, end: usize| TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+        label: label.clone(),
+        priority: 0,
+    };
+
+    if existing.len() == 1 {
+        return vec![edit(container.start_byte() + 1, container.end_byte() - 1)];
+    }
+
+    if !is_multiline(source, container) || !starts_line(source, member.start_byte()) {
+        return if is_last {
+            vec![edit(existing[index - 1].end_byte(), member.end_byte())]
+        } else {
+            vec![edit(member.start_byte(), existing[index + 1].start_byte())]
+        };
+    }
+
+    // Whole lines, including comments directly above and on the same line
+    let comments = format::find_attached_comments(source, member);
+    let first = comments
+        .leading
+        .iter()
+        .rev()
+        .take_while(|c| starts_line(source, c.start))
+        .last()
+        .map_or(member.start_byte(), |c| c.start);
+    let start = source[..first].rfind('\n').map_or(0, |i| i + 1);
+    let after = trailing_comma.map_or(member.end_byte(), |c| c.end_byte());
+    let end = source[after..]
+        .find('\n')
+        .map_or(source.len(), |i| after + i + 1);
+
+    let mut edits = vec![edit(start, end.min(container.end_byte() - 1))];
+    if is_last && trailing_comma.is_none() {
+        // The previous member's comma now dangles
+        if let Some(comma) = comma_after(source, &existing[index - 1]) {
+            edits.push(edit(comma.start_byte(), comma.end_byte()));
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use serde_json::json;
+    use tree_sitter::Parser;
+
+    fn parse_json(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_json(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(!parse_json(&result).root_node().has_error(), "{}", result);
+        result
+    }
+
+    const TSCONFIG: &str = "{\n  // Shared settings\n  \"compilerOptions\": {\n    \"strict\": true, // keep\n    \"target\": \"ES2020\"\n  },\n  \"include\": [\"src\"]\n}\n";
+
+    #[test]
+    fn test_set_existing_value_keeps_comments() {
+        let op = JsonSet::new("/compilerOptions/target".into(), json!("ES2022"));
+        assert_eq!(
+            run(&op, TSCONFIG),
+            TSCONFIG.replace("\"ES2020\"", "\"ES2022\"")
+        );
+    }
+
+    #[test]
+    fn test_set_missing_path_creates_objects() {
+        let op = JsonSet::new("/compilerOptions/paths/@~1*".into(), json!(["./src/*"]));
+        let result = run(&op, TSCONFIG);
+        assert!(result.contains(
+            "    \"target\": \"ES2020\",\n    \"paths\": {\n      \"@/*\": [\n        \"./src/*\"\n      ]\n    }\n  },"
+        ));
+        assert!(result.contains("\"strict\": true, // keep\n"));
+    }
+
+    #[test]
+    fn test_set_after_trailing_comment() {
+        let op = JsonSet::new("/compilerOptions/noEmit".into(), json!(true));
+        let source = "{\n  \"compilerOptions\": {\n    \"strict\": true // keep\n  }\n}\n";
+        assert_eq!(
+            run(&op, source),
+            "{\n  \"compilerOptions\": {\n    \"strict\": true, // keep\n    \"noEmit\": true\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_remove_member_and_comma() {
+        let op = JsonRemove::new("/compilerOptions/target".into());
+        assert_eq!(
+            run(&op, TSCONFIG),
+            "{\n  // Shared settings\n  \"compilerOptions\": {\n    \"strict\": true // keep\n  },\n  \"include\": [\"src\"]\n}\n"
+        );
+        let op = JsonRemove::new("/compilerOptions".into());
+        assert_eq!(run(&op, TSCONFIG), "{\n  \"include\": [\"src\"]\n}\n");
+    }
+
+    #[test]
+    fn test_append_inline_array() {
+        let op = JsonAppend::new("/include".into(), json!("tests"));
+        assert!(run(&op, TSCONFIG).contains("\"include\": [\"src\", \"tests\"]"));
+        let op = JsonAppend::new("/exclude".into(), json!("dist"));
+        assert!(run(&op, TSCONFIG)
+            .contains("  \"include\": [\"src\"],\n  \"exclude\": [\n    \"dist\"\n  ]\n}"));
+    }
+
+    #[test]
+    fn test_append_with_dash_token() {
+        let source = "{\n  \"files\": [\"a.ts\"]\n}\n";
+        let op = JsonAppend::new("/files/-".into(), json!("b.ts"));
+        assert_eq!(
+            run(&op, source),
+            "{\n  \"files\": [\"a.ts\", \"b.ts\"]\n}\n"
+        );
+        let op = JsonAppend::new("/include/-".into(), json!("src"));
+        assert!(run(&op, source).contains("\"include\": [\n    \"src\"\n  ]"));
+    }
+
+    #[test]
+    fn test_add_dependency_sorted() {
+        let source = "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"react\": \"^18.2.0\",\n    \"zod\": \"^3.0.0\"\n  }\n}\n";
+        let op = AddDependency::new("axios".into(), "^1.6.0".into(), false);
+        assert!(run(&op, source).contains("{\n    \"axios\": \"^1.6.0\",\n    \"react\""));
+        let op = AddDependency::new("swr".into(), "^2.0.0".into(), false);
+        assert!(run(&op, source)
+            .contains("\"react\": \"^18.2.0\",\n    \"swr\": \"^2.0.0\",\n    \"zod\""));
+        let op = AddDependency::new("react".into(), "^19.0.0".into(), false);
+        assert!(run(&op, source).contains("\"react\": \"^19.0.0\""));
+        let op = AddDependency::new("vitest".into(), "^1.0.0".into(), true);
+        assert!(run(&op, source)
+            .ends_with("  },\n  \"devDependencies\": {\n    \"vitest\": \"^1.0.0\"\n  }\n}\n"));
+    }
+}
@@ -0,0 +1,476 @@
+//! Per-file half of `migrate_to_typescript`.
+//!
+//! Runs on JavaScript source parsed with the TypeScript (or TSX) grammar:
+//! top-level `require` calls and `module.exports` / `exports.x`
+//! assignments become imports and exports as in `cjs_to_esm`, and
+//! parameters of named functions and methods get annotations taken from
+//! JSDoc `@param {Type}` tags (by position for destructured parameters,
+//! with `opts.field` tags forming an object type), inferred from a literal
+//! default, or `any`. Renaming the file and updating importers is left to
+//! the caller.
+
+use crate::edit::TextEdit;
+use crate::format::StyleConfig;
+use crate::operations::cjs_to_esm::convert_module;
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// The migrate_to_typescript operation (source rewrite only).
+#[derive(Default)]
+pub struct MigrateToTypescript;
+
+/// A JSDoc `@param` tag.
+struct ParamTag {
+    name: String,
+    ts_type: String,
+    optional: bool,
+    /// Span of `{Type} ` in the comment, removed once the type moves into
+    /// the signature.
+    type_start: usize,
+    type_end: usize,
+}
+
+impl MigrateToTypescript {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Executable for MigrateToTypescript {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let root = tree.root_node();
+        let (mut edits, mut warnings) = convert_module(source, tree, style);
+
+        let mut untyped = 0;
+        annotate_functions(&root, source, &mut edits, &mut untyped);
+        if untyped > 0 {
+            warnings.push(format!(
+                "{} parameter(s) typed as `any`; narrow them when you can",
+                untyped
+            ));
+        }
+        Ok((edits, warnings))
+    }
+}
+
+// --- Helper functions ---
+
+fn annotate_functions(node: &Node, source: &str, edits: &mut Vec<TextEdit>, untyped: &mut usize) {
+    if is_named_function(node) {
+        let tags = jsdoc_of(node)
+            .map(|comment| param_tags(&comment, source))
+            .unwrap_or_default();
+        let mut used_tags = Vec::new();
+
+        if let Some(parameter) = node.child_by_field_name("parameter") {
+            // `x => ...`: parenthesize to make room for the annotation
+            let name = node_text(&parameter, source);
+            let (ts_type, _) = param_type(name, None, &tags, &mut used_tags, untyped);
+            edits.push(TextEdit {
+                start: parameter.start_byte(),
+                end: parameter.end_byte(),
+                replacement: format!("({}: {})", name, ts_type),
+                label: format!("annotate {}", name),
+                priority: 0,
+            });
+        } else if let Some(parameters) = node.child_by_field_name("parameters") {
+            // `@param {Object} opts` documents a destructured parameter by
+            // position, since it has no name of its own
+            let mut top_level_tags = tags.iter().filter(|t| !t.name.contains('.'));
+            let mut cursor = parameters.walk();
+            for parameter in parameters.named_children(&mut cursor) {
+                if parameter.kind() == "comment" {
+                    continue;
+                }
+                let positional_tag = top_level_tags.next();
+                if parameter.kind() != "required_parameter"
+                    || parameter.child_by_field_name("type").is_some()
+                {
+                    continue;
+                }
+                let Some(pattern) = parameter.child_by_field_name("pattern") else {
+                    continue;
+                };
+                let default = parameter.child_by_field_name("value");
+                let (name, rest) = match pattern.kind() {
+                    "rest_pattern" => (
+                        pattern.named_child(0).map_or("", |n| node_text(&n, source)),
+                        true,
+                    ),
+                    "identifier" => (node_text(&pattern, source), false),
+                    "object_pattern" | "array_pattern" => {
+                        (positional_tag.map_or("", |t| t.name.as_str()), false)
+                    }
+                    _ => ("", false),
+                };
+                let (mut ts_type, optional) =
+                    param_type(name, default.as_ref(), &tags, &mut used_tags, untyped);
+                if rest && !ts_type.ends_with("[]") {
+                    ts_type = format!("{}[]", wrap_union(&ts_type));
+                }
+                let marker = if optional && default.is_none() && !rest {
+                    "?"
+                } else {
+                    ""
+                };
+                edits.push(TextEdit {
+                    start: pattern.end_byte(),
+                    end: pattern.end_byte(),
+                    replacement: format!("{}: {}", marker, ts_type),
+                    label: format!("annotate {}", name),
+                    priority: 0,
+                });
+            }
+        }
+
+        for tag in tags.iter().filter(|t| used_tags.contains(&t.name)) {
+            edits.push(TextEdit {
+                start: tag.type_start,
+                end: tag.type_end,
+                replacement: String::new(),
+                label: format!("move @param {} type into signature", tag.name),
+                priority: 0,
+            });
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        annotate_functions(&child, source, edits, untyped);
+    }
+}
+
+/// Type for a parameter and whether JSDoc marks it optional.
+fn param_type(
+    name: &str,
+    default: Option<&Node>,
+    tags: &[ParamTag],
+    used_tags: &mut Vec<String>,
+    untyped: &mut usize,
+) -> (String, bool) {
+    if let Some(tag) = tags.iter().find(|t| !name.is_empty() && t.name == name) {
+        used_tags.push(tag.name.clone());
+        // `@param {number} opts.size` tags describe the object's shape
+        let prefix = format!("{}.", name);
+        let fields: Vec<&ParamTag> = tags
+            .iter()
+            .filter(|t| {
+                t.name
+                    .strip_prefix(&prefix)
+                    .is_some_and(|field| !field.contains('.'))
+            })
+            .collect();
+        if fields.is_empty() {
+            return (tag.ts_type.clone(), tag.optional);
+        }
+        let members: Vec<String> = fields
+            .iter()
+            .map(|field| {
+                used_tags.push(field.name.clone());
+                format!(
+                    "{}{}: {}",
+                    &field.name[prefix.len()..],
+                    if field.optional { "?" } else { "" },
+                    field.ts_type
+                )
+            })
+            .collect();
+        return (format!("{{ {} }}", members.join("; ")), tag.optional);
+    }
+    let inferred = default.and_then(|d| match d.kind() {
+        "number" => Some("number"),
+        "string" | "template_string" => Some("string"),
+        "true" | "false" => Some("boolean"),
+        _ => None,
+    });
+    match inferred {
+        Some(t) => (t.to_string(), false),
+        None => {
+            *untyped += 1;
+            ("any".to_string(), false)
+        }
+    }
+}
+
+/// Functions whose signature is the API: declarations, methods, and
+/// function values bound to a name. Inline callbacks keep their
+/// contextual types.
+fn is_named_function(node: &Node) -> bool {
+    match node.kind() {
+        "function_declaration" | "generator_function_declaration" | "method_definition" => true,
+        "function_expression" | "arrow_function" => node.parent().is_some_and(|p| {
+            matches!(
+                p.kind(),
+                "variable_declarator"
+                    | "pair"
+                    | "assignment_expression"
+                    | "export_statement"
+                    | "public_field_definition"
+            )
+        }),
+        _ => false,
+    }
+}
+
+/// The `/** ... */` block directly above a function's statement.
+fn jsdoc_of<'a>(function: &Node<'a>) -> Option<Node<'a>> {
+    let mut anchor = *function;
+    while let Some(parent) = anchor.parent() {
+        match parent.kind() {
+            "variable_declarator"
+            | "lexical_declaration"
+            | "variable_declaration"
+            | "export_statement"
+            | "assignment_expression"
+            | "expression_statement" => anchor = parent,
+            _ => break,
+        }
+    }
+    anchor.prev_named_sibling().filter(|c| {
+        c.kind() == "comment" && c.end_position().row + 1 >= anchor.start_position().row
+    })
+}
+
+fn param_tags(comment: &Node, source: &str) -> Vec<ParamTag> {
+    let text = node_text(comment, source);
+    if !text.starts_with("/**") {
+        return vec![];
+    }
+    let base = comment.start_byte();
+    let mut tags = Vec::new();
+    for (at, _) in text.match_indices("@param") {
+        let after = at + "@param".len();
+        let rest = &text[after..];
+        let Some(open) = rest.find('{').filter(|i| rest[..*i].trim().is_empty()) else {
+            continue;
+        };
+        let mut depth = 0;
+        let Some(close) = rest[open..].char_indices().find_map(|(i, c)| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(open + i)
+        }) else {
+            continue;
+        };
+        let jsdoc_type = rest[open + 1..close].trim();
+        let after_type = &rest[close + 1..];
+        let gap = after_type.len() - after_type.trim_start().len();
+        let token = after_type.split_whitespace().next().unwrap_or("");
+        let bracketed = token.starts_with('[');
+        let name = token
+            .trim_start_matches('[')
+            .split(['=', ']'])
+            .next()
+            .unwrap_or("")
+            .to_string();
+        if name.is_empty() {
+            continue;
+        }
+        let (ts_type, optional_type) = convert_jsdoc_type(jsdoc_type);
+        tags.push(ParamTag {
+            name,
+            ts_type,
+            optional: bracketed || optional_type,
+            type_start: base + after + open,
+            type_end: base + after + close + 1 + gap,
+        });
+    }
+    tags
+}
+
+/// JSDoc type expression to TypeScript, plus whether it is optional (`T=`).
+fn convert_jsdoc_type(jsdoc: &str) -> (String, bool) {
+    let optional = jsdoc.ends_with('=');
+    let jsdoc = jsdoc.trim_end_matches('=').trim();
+    let (jsdoc, variadic) = match jsdoc.strip_prefix("...") {
+        Some(inner) => (inner, true),
+        None => (jsdoc, false),
+    };
+    let mut ts_type = match jsdoc {
+        "*" | "?" | "" => "any".to_string(),
+        "Object" | "object" => "Record<string, any>".to_string(),
+        "function" | "Function" => "(...args: any[]) => any".to_string(),
+        "Array" => "any[]".to_string(),
+        "String" => "string".to_string(),
+        "Number" => "number".to_string(),
+        "Boolean" => "boolean".to_string(),
+        other => {
+            let other = other.replace(".<", "<");
+            match (other.strip_prefix('?'), other.strip_prefix('!')) {
+                (Some(nullable), _) => format!("{} | null", nullable),
+                (_, Some(non_null)) => non_null.to_string(),
+                _ => other,
+            }
+        }
+    };
+    if variadic {
+        ts_type = format!("{}[]", wrap_union(&ts_type));
+    }
+    (ts_type, optional)
+}
+
+fn wrap_union(ts_type: &str) -> String {
+    if ts_type.contains('|') || ts_type.contains("=>") {
+        format!("({})", ts_type)
+    } else {
+        ts_type.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(source: &str) -> (String, Vec<String>) {
+        let tree = parse_ts(source);
+        let (edits, warnings) = MigrateToTypescript::new()
+            .compute_edits_with_warnings(source, &tree)
+            .unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(!parse_ts(&result).root_node().has_error(), "{}", result);
+        (result, warnings)
+    }
+
+    #[test]
+    fn test_requires_become_imports() {
+        let source = "const path = require('path');\nconst { readFile, writeFile: write } = require('fs');\nrequire('./polyfills');\n\nfunction load() {\n  return require('./data.json');\n}\n";
+        let (result, warnings) = run(source);
+        assert!(result.starts_with(
+            "import path from 'path';\nimport { readFile, writeFile as write } from 'fs';\nimport './polyfills';\n"
+        ));
+        assert!(result.contains("return require('./data.json');"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0],
+            "line 6: `require('./data.json')` is not a top-level declaration and was left in place"
+        );
+    }
+
+    #[test]
+    fn test_jsdoc_params_become_annotations() {
+        let source = "/**\n * Format a price.\n * @param {number} amount - in cents\n * @param {string=} currency\n * @param {Array.<string>} [tags]\n */\nexport function format(amount, currency, tags) {\n  return amount;\n}\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "/**\n * Format a price.\n * @param amount - in cents\n * @param currency\n * @param [tags]\n */\nexport function format(amount: number, currency?: string, tags?: Array<string>) {\n  return amount;\n}\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_inferred_and_any_annotations() {
+        let source = "const scale = (value, factor = 2, label = 'x', { round }, ...rest) => value;\nconst double = x => x * 2;\nclass Cart {\n  add(item, qty = 1) {\n    return [item].map(i => i);\n  }\n}\n";
+        let (result, warnings) = run(source);
+        assert!(result.contains(
+            "const scale = (value: any, factor: number = 2, label: string = 'x', { round }: any, ...rest: any[]) => value;"
+        ));
+        assert!(result.contains("const double = (x: any) => x * 2;"));
+        assert!(result.contains("  add(item: any, qty: number = 1) {"));
+        assert!(result.contains("[item].map(i => i)"));
+        assert_eq!(
+            warnings,
+            vec!["5 parameter(s) typed as `any`; narrow them when you can"]
+        );
+    }
+
+    #[test]
+    fn test_require_conversion_alone() {
+        let source = "const { join } = require('path');\nconst config = require('./config').default;\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "import { join } from 'path';\nimport config from './config';\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_module_exports_become_exports() {
+        let source = "function add(a, b) {\n  return a + b;\n}\n\nmodule.exports = { add };\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "function add(a: any, b: any) {\n  return a + b;\n}\n\nexport { add };\nexport default { add };\n"
+        );
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_any_annotation_alone() {
+        let source = "function log(message) {\n  console.log(message);\n}\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "function log(message: any) {\n  console.log(message);\n}\n"
+        );
+        assert_eq!(
+            warnings,
+            vec!["1 parameter(s) typed as `any`; narrow them when you can"]
+        );
+    }
+
+    #[test]
+    fn test_literal_default_annotation_alone() {
+        let source = "function pad(text = '', width = 8, trim = false, fill = `-`) {}\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "function pad(text: string = '', width: number = 8, trim: boolean = false, fill: string = `-`) {}\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_jsdoc_conversion_alone() {
+        let source = "/**\n * @param {?string} name\n * @param {...number} values\n */\nfunction sum(name, ...values) {}\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "/**\n * @param name\n * @param values\n */\nfunction sum(name: string | null, ...values: number[]) {}\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_jsdoc_object_param_with_destructuring() {
+        let source = "/**\n * @param {Object} opts\n * @param {number} opts.size\n * @param {string} [opts.color]\n * @param {boolean} loud\n */\nfunction paint({ size, color }, loud) {}\n\n/** @param {Object} options */\nfunction open({ path }) {}\n";
+        let (result, warnings) = run(source);
+        assert_eq!(
+            result,
+            "/**\n * @param opts\n * @param opts.size\n * @param [opts.color]\n * @param loud\n */\nfunction paint({ size, color }: { size: number; color?: string }, loud: boolean) {}\n\n/** @param options */\nfunction open({ path }: Record<string, any>) {}\n"
+        );
+        assert!(warnings.is_empty());
+    }
+}
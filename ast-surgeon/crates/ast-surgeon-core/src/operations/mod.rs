@@ -1,14 +1,34 @@
 //! Operation vocabulary and execution trait.
 
+pub mod class_component;
+pub mod class_members;
+pub mod cjs_to_esm;
+pub mod config;
+pub mod css;
+pub mod css_modules;
+pub mod custom;
+pub mod directives;
+pub mod doc_comment;
 pub mod extract;
+pub mod function_style;
+pub mod i18n;
 pub mod imports;
+pub mod json;
 pub mod make_async;
+pub mod migrate_typescript;
+pub(crate) mod node_utils;
+pub mod promise_to_async;
 pub mod rename_symbol;
 pub mod signature;
+pub mod svelte;
+pub mod test_cases;
+pub mod types;
 pub mod update_paths;
+pub mod vue;
 pub mod wrap;
 
 use crate::edit::TextEdit;
+use crate::format::StyleConfig;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tree_sitter::Tree;
@@ -44,6 +64,11 @@ pub enum OperationError {
 
     #[error("Invalid operation parameters: {message}")]
     InvalidParams { message: String },
+
+    /// The operation found constructs it cannot convert without changing
+    /// behaviour. Nothing is edited; each warning names one blocker.
+    #[error("Operation blocked: {}", warnings.join("; "))]
+    Blocked { warnings: Vec<String> },
 }
 
 /// A source location for error reporting.
@@ -190,6 +215,407 @@ pub enum Operation {
         #[serde(default)]
         type_annotation: Option<String>,
     },
+    AddTypeMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Interface or object type alias to extend.
+        type_name: String,
+        /// Member source text, e.g. "email?: string" or "greet(name: string): void".
+        member: String,
+    },
+    RemoveTypeMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Interface or object type alias to modify.
+        type_name: String,
+        /// Name of the member to remove.
+        member_name: String,
+    },
+    SetMemberOptional {
+        #[serde(default)]
+        file: Option<String>,
+        /// Interface or object type alias to modify.
+        type_name: String,
+        /// Name of the property or method signature.
+        member_name: String,
+        /// true adds `?`, false removes it. Default: true.
+        #[serde(default = "default_true")]
+        optional: bool,
+    },
+    AddUnionMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Type alias to extend.
+        type_name: String,
+        /// Type to add, e.g. "'error'" or "null".
+        member: String,
+    },
+    AddEnumMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Enum to extend.
+        enum_name: String,
+        /// New member name.
+        member_name: String,
+        /// Optional initializer expression, e.g. "'blue'" or "4".
+        #[serde(default)]
+        value: Option<String>,
+    },
+    ExtractInlineType {
+        #[serde(default)]
+        file: Option<String>,
+        /// Parameter or variable whose inline object type is extracted.
+        target: String,
+        /// Only look at the parameters of this function.
+        #[serde(default)]
+        function_name: Option<String>,
+        /// Name for the new exported interface.
+        type_name: String,
+    },
+    AddClassMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Class to modify.
+        class_name: String,
+        /// "property", "method", "getter", or "parameter_property".
+        member_kind: String,
+        /// Member source text, e.g. "private count = 0" or "reset() { ... }".
+        code: String,
+        /// Existing member to place the new one next to. Default: by kind.
+        #[serde(default)]
+        anchor: Option<String>,
+        /// "before" or "after" the anchor. Default: "after".
+        #[serde(default = "default_placement")]
+        placement: String,
+    },
+    RemoveClassMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Class to modify.
+        class_name: String,
+        /// Field, method, accessor or parameter property to remove.
+        member_name: String,
+    },
+    RenameClassMember {
+        #[serde(default)]
+        file: Option<String>,
+        /// Class to modify.
+        class_name: String,
+        /// Current member name.
+        from: String,
+        /// New member name.
+        to: String,
+    },
+    ConvertFunctionStyle {
+        #[serde(default)]
+        file: Option<String>,
+        /// Function declaration or `const` arrow function to convert.
+        function_name: String,
+        /// "arrow" or "declaration".
+        to: String,
+    },
+    PromiseToAsync {
+        #[serde(default)]
+        file: Option<String>,
+        /// Function whose `.then()`/`.catch()` chains are converted.
+        function_name: String,
+    },
+    ClassToFunctionComponent {
+        #[serde(default)]
+        file: Option<String>,
+        /// React class component to convert.
+        component_name: String,
+        /// Wrap methods in `useCallback` instead of plain functions. Default: false.
+        #[serde(default)]
+        use_callback: bool,
+    },
+    AddRule {
+        #[serde(default)]
+        file: Option<String>,
+        /// In script files: variable holding the `css`/`styled` template to edit.
+        #[serde(default)]
+        template: Option<String>,
+        /// Selector list, e.g. ".card" or ".list > li".
+        selector: String,
+        /// Declarations, e.g. ["color: red", "padding: 4px"].
+        #[serde(default)]
+        declarations: Vec<String>,
+        /// Query of the `@media` block to add to, e.g. "(max-width: 600px)".
+        #[serde(default)]
+        media: Option<String>,
+    },
+    RemoveRule {
+        #[serde(default)]
+        file: Option<String>,
+        /// In script files: variable holding the `css`/`styled` template to edit.
+        #[serde(default)]
+        template: Option<String>,
+        /// Whole selector list, or one item of it.
+        selector: String,
+        /// Only look inside this `@media` block. None = top level.
+        #[serde(default)]
+        media: Option<String>,
+    },
+    SetDeclaration {
+        #[serde(default)]
+        file: Option<String>,
+        /// In script files: variable holding the `css`/`styled` template to edit.
+        #[serde(default)]
+        template: Option<String>,
+        /// Rule to modify.
+        selector: String,
+        /// Property name, e.g. "color".
+        property: String,
+        /// New value, e.g. "red !important".
+        value: String,
+        /// Only look inside this `@media` block. None = top level.
+        #[serde(default)]
+        media: Option<String>,
+    },
+    RemoveDeclaration {
+        #[serde(default)]
+        file: Option<String>,
+        /// In script files: variable holding the `css`/`styled` template to edit.
+        #[serde(default)]
+        template: Option<String>,
+        /// Rule to modify.
+        selector: String,
+        /// Property to remove.
+        property: String,
+        /// Only look inside this `@media` block. None = top level.
+        #[serde(default)]
+        media: Option<String>,
+    },
+    RenameSelector {
+        #[serde(default)]
+        file: Option<String>,
+        /// In script files: variable holding the `css`/`styled` template to edit.
+        #[serde(default)]
+        template: Option<String>,
+        /// Selector to replace, e.g. ".card".
+        from: String,
+        /// Replacement selector.
+        to: String,
+    },
+    SetCustomProperty {
+        #[serde(default)]
+        file: Option<String>,
+        /// In script files: variable holding the `css`/`styled` template to edit.
+        #[serde(default)]
+        template: Option<String>,
+        /// Property name, with or without the leading `--`.
+        name: String,
+        /// Value, e.g. "#0af".
+        value: String,
+    },
+    VueAddToSetup {
+        #[serde(default)]
+        file: Option<String>,
+        /// Code for the `<script setup>` block; imports go after existing imports.
+        code: String,
+    },
+    VueWrapInRef {
+        #[serde(default)]
+        file: Option<String>,
+        /// Top-level `<script setup>` variable to turn into a `ref()`.
+        variable_name: String,
+    },
+    SvelteAddReactive {
+        #[serde(default)]
+        file: Option<String>,
+        /// Name of the derived value.
+        name: String,
+        /// Expression it is derived from, e.g. "count * 2".
+        expression: String,
+        /// Force Svelte 5 runes (`$derived`) or Svelte 4 (`$:`); detected when omitted.
+        #[serde(default)]
+        runes: Option<bool>,
+    },
+    SvelteAddStore {
+        #[serde(default)]
+        file: Option<String>,
+        name: String,
+        /// Initial value expression, e.g. "0" or "[]".
+        initial_value: String,
+        /// "writable" (default) or "readable".
+        #[serde(default = "default_store_kind")]
+        kind: String,
+    },
+    JsonSet {
+        #[serde(default)]
+        file: Option<String>,
+        /// JSON pointer, e.g. "/compilerOptions/strict".
+        pointer: String,
+        value: serde_json::Value,
+    },
+    JsonRemove {
+        #[serde(default)]
+        file: Option<String>,
+        pointer: String,
+    },
+    JsonAppend {
+        #[serde(default)]
+        file: Option<String>,
+        /// JSON pointer of the array; created when missing.
+        pointer: String,
+        value: serde_json::Value,
+    },
+    AddDependency {
+        #[serde(default)]
+        file: Option<String>,
+        name: String,
+        version: String,
+        /// Add to devDependencies instead of dependencies.
+        #[serde(default)]
+        dev: bool,
+    },
+    ConfigSet {
+        #[serde(default)]
+        file: Option<String>,
+        /// Dotted property path, e.g. "server.port".
+        path: String,
+        /// JavaScript expression, e.g. "5173" or "{ host: true }".
+        value: String,
+    },
+    ConfigRemove {
+        #[serde(default)]
+        file: Option<String>,
+        path: String,
+    },
+    ConfigAppend {
+        #[serde(default)]
+        file: Option<String>,
+        /// Dotted path of the array, e.g. "plugins"; created when missing.
+        path: String,
+        value: String,
+        /// Module to import the called factory from, e.g. "@vitejs/plugin-react".
+        #[serde(default)]
+        import_from: Option<String>,
+        /// Import the factory as a named import instead of the default.
+        #[serde(default)]
+        named_import: bool,
+    },
+    AddDirective {
+        #[serde(default)]
+        file: Option<String>,
+        /// "use client" or "use server".
+        directive: String,
+    },
+    RemoveDirective {
+        #[serde(default)]
+        file: Option<String>,
+        directive: String,
+    },
+    CjsToEsm {
+        #[serde(default)]
+        file: Option<String>,
+    },
+    AddTestCase {
+        #[serde(default)]
+        file: Option<String>,
+        /// Enclosing describes as `Outer > Inner`; empty for top level.
+        /// Missing describes are created.
+        #[serde(default)]
+        describe_path: String,
+        name: String,
+        /// Test body; re-indented to fit.
+        body: String,
+        /// "it" or "test".
+        #[serde(default = "default_test_kind")]
+        kind: String,
+    },
+    SkipTest {
+        #[serde(default)]
+        file: Option<String>,
+        /// `Outer > Inner > title`, as in Jest / Vitest failure names.
+        test_path: String,
+        /// false removes the skip.
+        #[serde(default = "default_true")]
+        enabled: bool,
+    },
+    OnlyTest {
+        #[serde(default)]
+        file: Option<String>,
+        test_path: String,
+        #[serde(default = "default_true")]
+        enabled: bool,
+    },
+    RemoveTestCase {
+        #[serde(default)]
+        file: Option<String>,
+        test_path: String,
+    },
+    UpsertDocComment {
+        #[serde(default)]
+        file: Option<String>,
+        /// Function, method, class or interface name.
+        target: String,
+        /// Replaces the comment's descriptive text; omit to keep it.
+        #[serde(default)]
+        description: Option<String>,
+    },
+    FixTypeImports {
+        #[serde(default)]
+        file: Option<String>,
+        /// "separate" (`import type { A }` statements) or "inline"
+        /// (`import { type A, b }`).
+        #[serde(default = "default_type_import_style")]
+        style: String,
+    },
+    /// A project-defined operation; callers use the definition's name as
+    /// `op` and the host fills in the definition.
+    Custom {
+        #[serde(default)]
+        file: Option<String>,
+        definition: custom::CustomOperationDef,
+        #[serde(default)]
+        params: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+impl Operation {
+    /// Whether this is a stylesheet operation (CSS, SCSS, Less, or a
+    /// `css`/`styled` template embedded in a script).
+    pub fn is_css(&self) -> bool {
+        matches!(
+            self,
+            Operation::AddRule { .. }
+                | Operation::RemoveRule { .. }
+                | Operation::SetDeclaration { .. }
+                | Operation::RemoveDeclaration { .. }
+                | Operation::RenameSelector { .. }
+                | Operation::SetCustomProperty { .. }
+        )
+    }
+
+    /// The embedded template a CSS operation addresses, if given.
+    pub fn css_template(&self) -> Option<&str> {
+        match self {
+            Operation::AddRule { template, .. }
+            | Operation::RemoveRule { template, .. }
+            | Operation::SetDeclaration { template, .. }
+            | Operation::RemoveDeclaration { template, .. }
+            | Operation::RenameSelector { template, .. }
+            | Operation::SetCustomProperty { template, .. } => template.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_type_import_style() -> String {
+    "separate".to_string()
+}
+
+fn default_test_kind() -> String {
+    "it".to_string()
+}
+
+fn default_store_kind() -> String {
+    "writable".to_string()
 }
 
 fn default_var_kind() -> String {
@@ -204,6 +630,10 @@ fn default_position() -> String {
     "last".to_string()
 }
 
+fn default_placement() -> String {
+    "after".to_string()
+}
+
 /// Trait for computing text edits from a parse tree.
 ///
 /// Each operation implements this to produce edits.
@@ -214,4 +644,27 @@ pub trait Executable {
         source: &str,
         tree: &Tree,
     ) -> Result<Vec<TextEdit>, OperationError>;
+
+    /// Compute edits together with non-blocking warnings about them
+    /// (e.g. conversions whose result should be reviewed).
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        Ok((self.compute_edits(source, tree)?, vec![]))
+    }
+
+    /// Compute edits and warnings with the project's formatter settings
+    /// for this file. Operations that generate code override this and
+    /// infer whatever `style` leaves unset from the source; the plain
+    /// methods above infer everything.
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        _style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+    }
 }
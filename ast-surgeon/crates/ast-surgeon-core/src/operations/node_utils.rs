@@ -0,0 +1,153 @@
+//! Tree-sitter node helpers shared by the operations.
+
+use tree_sitter::Node;
+
+/// The source text a node spans.
+pub(crate) fn node_text<'s>(node: &Node, source: &'s str) -> &'s str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+/// The named children of a node.
+pub(crate) fn named_children<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    let mut cursor = node.walk();
+    let children = node.named_children(&mut cursor).collect();
+    children
+}
+
+/// Whether any direct child, named or anonymous, has the given kind.
+pub(crate) fn has_child_kind(node: &Node, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|c| c.kind() == kind);
+    found
+}
+
+/// The first direct child of the given kind.
+pub(crate) fn find_child_by_kind<'a>(node: &Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    if !cursor.goto_first_child() {
+        return None;
+    }
+    loop {
+        if cursor.node().kind() == kind {
+            return Some(cursor.node());
+        }
+        if !cursor.goto_next_sibling() {
+            return None;
+        }
+    }
+}
+
+/// Whether the scope `node` opens binds its own value `name`, shadowing
+/// any outer binding.
+pub(crate) fn declares_name(node: &Node, source: &str, name: &str) -> bool {
+    match node.kind() {
+        "statement_block" => block_declares(node, source, name),
+        "function_expression"
+        | "function_declaration"
+        | "generator_function"
+        | "generator_function_declaration"
+        | "arrow_function"
+        | "method_definition" => {
+            let params = node
+                .child_by_field_name("parameters")
+                .or_else(|| node.child_by_field_name("parameter"));
+            let body = node
+                .child_by_field_name("body")
+                .filter(|b| b.kind() == "statement_block");
+            params.is_some_and(|p| binds_name(&p, source, name))
+                || body.is_some_and(|b| block_declares(&b, source, name))
+        }
+        "catch_clause" => node
+            .child_by_field_name("parameter")
+            .is_some_and(|p| binds_name(&p, source, name)),
+        "for_statement" => node
+            .child_by_field_name("initializer")
+            .is_some_and(|i| declaration_binds(&i, source, name)),
+        "for_in_statement" => {
+            let declares = node
+                .child_by_field_name("kind")
+                .is_some_and(|k| matches!(k.kind(), "let" | "const" | "var"));
+            declares
+                && node
+                    .child_by_field_name("left")
+                    .is_some_and(|l| binds_name(&l, source, name))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a block's own statements declare `name`.
+fn block_declares(block: &Node, source: &str, name: &str) -> bool {
+    named_children(block).iter().any(|statement| match statement.kind() {
+        "lexical_declaration" | "variable_declaration" => {
+            declaration_binds(statement, source, name)
+        }
+        "function_declaration" | "generator_function_declaration" | "class_declaration" => {
+            statement
+                .child_by_field_name("name")
+                .is_some_and(|n| node_text(&n, source) == name)
+        }
+        _ => false,
+    })
+}
+
+fn declaration_binds(declaration: &Node, source: &str, name: &str) -> bool {
+    matches!(declaration.kind(), "lexical_declaration" | "variable_declaration")
+        && named_children(declaration)
+            .iter()
+            .filter(|d| d.kind() == "variable_declarator")
+            .any(|d| {
+                d.child_by_field_name("name")
+                    .is_some_and(|n| binds_name(&n, source, name))
+            })
+}
+
+/// Whether a binding pattern (parameter list, destructuring, ...)
+/// introduces `name`.
+fn binds_name(pattern: &Node, source: &str, name: &str) -> bool {
+    let field_binds = |field: &str| {
+        pattern
+            .child_by_field_name(field)
+            .is_some_and(|p| binds_name(&p, source, name))
+    };
+    match pattern.kind() {
+        "identifier" | "shorthand_property_identifier_pattern" => {
+            node_text(pattern, source) == name
+        }
+        "required_parameter" | "optional_parameter" => field_binds("pattern"),
+        "assignment_pattern" | "object_assignment_pattern" => field_binds("left"),
+        "pair_pattern" => field_binds("value"),
+        "formal_parameters" | "object_pattern" | "array_pattern" | "rest_pattern" => {
+            named_children(pattern)
+                .iter()
+                .any(|p| binds_name(p, source, name))
+        }
+        _ => false,
+    }
+}
+
+/// Whether the scope `node` opens declares its own type `name`: a type
+/// parameter, or a type, interface, class or enum declared in a block.
+pub(crate) fn declares_type_name(node: &Node, source: &str, name: &str) -> bool {
+    if node.kind() == "statement_block" {
+        return named_children(node).iter().any(|statement| {
+            matches!(
+                statement.kind(),
+                "type_alias_declaration"
+                    | "interface_declaration"
+                    | "class_declaration"
+                    | "abstract_class_declaration"
+                    | "enum_declaration"
+            ) && statement
+                .child_by_field_name("name")
+                .is_some_and(|n| node_text(&n, source) == name)
+        });
+    }
+    node.child_by_field_name("type_parameters")
+        .is_some_and(|params| {
+            named_children(&params).iter().any(|p| {
+                p.child_by_field_name("name")
+                    .is_some_and(|n| node_text(&n, source) == name)
+            })
+        })
+}
@@ -0,0 +1,908 @@
+//! `promise_to_async` operation.
+//!
+//! Flattens linear `.then()` chains inside a function into `await`
+//! statements, turns a trailing `.catch()` into `try`/`catch` and makes
+//! the function `async`. Chains that branch (two-argument `.then`,
+//! conditional returns in a handler, `.catch` in the middle, `.finally`)
+//! are refused rather than approximated. A chain used as a bare
+//! statement is only converted when it is the last statement of the
+//! function, since awaiting it would otherwise delay the code after it.
+
+use std::collections::HashSet;
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig};
+use crate::operations::function_style::find_own_binding_use;
+use crate::operations::make_async::MakeAsync;
+use crate::operations::node_utils::node_text;
+use crate::operations::signature::{collect_param_nodes, find_function_by_name};
+use crate::operations::wrap::{render_wrapped, WrapKind};
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// The promise_to_async operation.
+pub struct PromiseToAsync {
+    pub function_name: String,
+}
+
+impl PromiseToAsync {
+    pub fn new(function_name: String) -> Self {
+        Self { function_name }
+    }
+
+    fn refuse(&self, node: &Node, reason: &str) -> OperationError {
+        OperationError::InvalidParams {
+            message: format!(
+                "Cannot convert promise chain in '{}' (line {}): {}",
+                self.function_name,
+                node.start_position().row + 1,
+                reason
+            ),
+        }
+    }
+}
+
+impl Executable for PromiseToAsync {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let style = style.resolve(source);
+        let root = tree.root_node();
+        let func = find_function_by_name(&root, source, &self.function_name).ok_or_else(|| {
+            OperationError::TargetNotFound {
+                description: format!("Function '{}' not found", self.function_name),
+            }
+        })?;
+        let body =
+            func.child_by_field_name("body")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Function '{}' has no body", self.function_name),
+                })?;
+
+        let ctx = Context {
+            source,
+            semi: style.semi(),
+            unit: format::indent_deeper("", &style.indent),
+        };
+        let label = format!(
+            "convert promise chain in '{}' to async/await",
+            self.function_name
+        );
+        let mut edits = Vec::new();
+        let mut warnings = Vec::new();
+
+        if body.kind() != "statement_block" {
+            // `const load = () => fetch(x).then(...)`: expression body
+            let Some((base, links)) = unroll_chain(&body, source) else {
+                return Err(OperationError::TargetNotFound {
+                    description: format!("No promise chain found in '{}'", self.function_name),
+                });
+            };
+            let mut names = function_param_names(&func, source);
+            let prefix = format::extract_line_prefix(source, func.start_byte());
+            let inner = format!("{}{}", prefix, ctx.unit);
+            let lines = self.convert_chain(&ctx, base, &links, Sink::Return, &mut names, &inner)?;
+            edits.push(TextEdit {
+                start: body.start_byte(),
+                end: body.end_byte(),
+                replacement: format!("{{\n{}\n{}}}", lines, prefix),
+                label: label.clone(),
+                priority: 0,
+            });
+        } else {
+            let mut statements = Vec::new();
+            collect_chain_statements(&body, source, &mut statements);
+            if statements.is_empty() {
+                return Err(OperationError::TargetNotFound {
+                    description: format!("No promise chain found in '{}'", self.function_name),
+                });
+            }
+
+            for (statement, chain) in statements {
+                let block = statement
+                    .parent()
+                    .filter(|p| p.kind() == "statement_block")
+                    .ok_or_else(|| {
+                        self.refuse(&statement, "the statement is not inside a block")
+                    })?;
+                let mut names = block_declared_names(&block, source, &statement);
+                if block.id() == body.id() {
+                    names.extend(function_param_names(&func, source));
+                }
+                let (base, links) = unroll_chain(&chain, source).expect("collected chains unroll");
+                let sink = match statement.kind() {
+                    "return_statement" => Sink::Return,
+                    "lexical_declaration" | "variable_declaration" => {
+                        let declarator = statement.named_child(0).unwrap();
+                        let pattern = declarator.child_by_field_name("name").unwrap();
+                        let typed = declarator
+                            .child_by_field_name("type")
+                            .map(|t| {
+                                format!("{}{}", node_text(&pattern, source), node_text(&t, source))
+                            })
+                            .unwrap_or_else(|| node_text(&pattern, source).to_string());
+                        let keyword = statement
+                            .child(0)
+                            .map(|k| node_text(&k, source))
+                            .unwrap_or("const");
+                        Sink::Declare(format!("{} {}", keyword, typed))
+                    }
+                    _ => {
+                        // Fire-and-forget: nothing waited on the chain before
+                        if block.id() != body.id() || statement.next_named_sibling().is_some() {
+                            return Err(self.refuse(
+                                &statement,
+                                "the chain is not awaited or returned; awaiting it would delay the code after it",
+                            ));
+                        }
+                        warnings.push(format!(
+                            "The promise chain at line {} was not awaited or returned; '{}' now waits for it before resolving",
+                            statement.start_position().row + 1,
+                            self.function_name
+                        ));
+                        Sink::Statement
+                    }
+                };
+
+                let introduced_before = names.clone();
+                let prefix = format::extract_line_prefix(source, statement.start_byte());
+                let lines = self.convert_chain(&ctx, base, &links, sink, &mut names, &prefix)?;
+
+                // Code after the chain must not pick up the new bindings
+                let introduced: HashSet<&String> = names.difference(&introduced_before).collect();
+                let mut next = statement.next_named_sibling();
+                while let Some(n) = next {
+                    let mut used = Vec::new();
+                    collect_identifier_names(&n, source, &mut used);
+                    if let Some(clash) = used.iter().find(|u| introduced.contains(u)) {
+                        return Err(self.refuse(
+                            &statement,
+                            &format!("'{}' would shadow a binding used after the chain", clash),
+                        ));
+                    }
+                    next = n.next_named_sibling();
+                }
+
+                edits.push(TextEdit {
+                    start: statement.start_byte(),
+                    end: statement.end_byte(),
+                    replacement: lines.trim_start().to_string(),
+                    label: label.clone(),
+                    priority: 0,
+                });
+            }
+        }
+
+        edits.extend(MakeAsync::new(self.function_name.clone()).compute_edits(source, tree)?);
+        Ok((edits, warnings))
+    }
+}
+
+impl PromiseToAsync {
+    /// Produce the flattened statements for one chain, each line prefixed
+    /// with `indent`.
+    fn convert_chain(
+        &self,
+        ctx: &Context,
+        base: Node,
+        links: &[Node],
+        sink: Sink,
+        names: &mut HashSet<String>,
+        indent: &str,
+    ) -> Result<String, OperationError> {
+        let source = ctx.source;
+        let mut thens = Vec::new();
+        let mut catch = None;
+        for (i, link) in links.iter().enumerate() {
+            let method = link_method(link, source);
+            let args = link_args(link);
+            match method {
+                "then" => {
+                    if catch.is_some() {
+                        return Err(
+                            self.refuse(link, "`.then` after `.catch` cannot be linearized")
+                        );
+                    }
+                    if args.len() != 1 {
+                        return Err(self.refuse(link, "`.then` must have exactly one handler"));
+                    }
+                    thens.push(self.parse_handler(&args[0], source)?);
+                }
+                "catch" => {
+                    if i != links.len() - 1 {
+                        return Err(
+                            self.refuse(link, "only a trailing `.catch` can become try/catch")
+                        );
+                    }
+                    if args.len() != 1 {
+                        return Err(self.refuse(link, "`.catch` must have exactly one handler"));
+                    }
+                    catch = Some(self.parse_handler(&args[0], source)?);
+                }
+                _ => {
+                    return Err(self.refuse(link, &format!("`.{}` is not supported", method)));
+                }
+            }
+        }
+
+        // Handlers may not reuse or capture each other's bindings
+        for handler in thens.iter().chain(catch.iter()) {
+            for declared in &handler.declared {
+                if names.contains(declared) {
+                    return Err(self.refuse(
+                        &handler.node,
+                        &format!("'{}' is already declared in this scope", declared),
+                    ));
+                }
+            }
+            names.extend(handler.declared.iter().cloned());
+        }
+        let mut introduced: Vec<&String> = Vec::new();
+        for handler in &thens {
+            if let Some(clash) = handler
+                .free_names
+                .iter()
+                .find(|u| introduced.contains(u) && !handler.declared.contains(u))
+            {
+                return Err(self.refuse(
+                    &handler.node,
+                    &format!(
+                        "'{}' would be captured by an earlier handler's binding",
+                        clash
+                    ),
+                ));
+            }
+            introduced.extend(handler.declared.iter());
+        }
+
+        let in_try = catch.is_some();
+        let semi = ctx.semi;
+        let mut out: Vec<String> = Vec::new();
+        let mut value = Some(Expr::from_node(&base, source));
+
+        for handler in &thens {
+            match &handler.kind {
+                HandlerKind::Inline {
+                    param,
+                    statements,
+                    result,
+                } => {
+                    match (param, &value) {
+                        (Some(p), Some(v)) => {
+                            out.push(format!("const {} = {}{}", p, v.awaited(), semi))
+                        }
+                        (Some(p), None) => out.push(format!("const {} = undefined{}", p, semi)),
+                        (None, Some(v)) => out.push(format!("{}{}", v.awaited(), semi)),
+                        (None, None) => {}
+                    }
+                    out.extend(statements.iter().cloned());
+                    value = result.clone();
+                }
+                HandlerKind::Reference(callee) => {
+                    let name = fresh_name(names, "result");
+                    match &value {
+                        Some(v) => out.push(format!("const {} = {}{}", name, v.awaited(), semi)),
+                        None => out.push(format!("const {} = undefined{}", name, semi)),
+                    }
+                    value = Some(Expr {
+                        text: format!("{}({})", callee, name),
+                        simple: true,
+                        call: true,
+                    });
+                }
+            }
+        }
+
+        match (&sink, &value) {
+            (Sink::Return, Some(v)) if in_try => {
+                out.push(format!("return {}{}", v.awaited(), semi))
+            }
+            (Sink::Return, Some(v)) => out.push(format!("return {}{}", v.text, semi)),
+            (Sink::Return, None) => {}
+            (Sink::Statement, Some(v)) if v.call => out.push(format!("{}{}", v.awaited(), semi)),
+            (Sink::Statement, Some(v)) if !v.simple => out.push(format!("{}{}", v.text, semi)),
+            (Sink::Statement, _) => {}
+            (Sink::Declare(_), _) if in_try => {
+                return Err(self.refuse(
+                    &base,
+                    "a `.catch` on an assigned chain would need the variable declared outside try",
+                ))
+            }
+            (Sink::Declare(target), Some(v)) => {
+                out.push(format!("{} = {}{}", target, v.awaited(), semi))
+            }
+            (Sink::Declare(_), None) => {
+                return Err(self.refuse(&base, "the chain does not produce a value to assign"))
+            }
+        }
+
+        let Some(handler) = catch else {
+            return Ok(indent_lines(&out, indent));
+        };
+
+        let HandlerKind::Inline {
+            param,
+            statements,
+            result,
+        } = &handler.kind
+        else {
+            // `.catch(handleError)`
+            let HandlerKind::Reference(callee) = &handler.kind else {
+                unreachable!()
+            };
+            let catch_param = fresh_name(names, "error");
+            let call = format!("{}({})", callee, catch_param);
+            let stmt = match sink {
+                Sink::Return => format!("return {}{}", call, semi),
+                _ => format!("{}{}", call, semi),
+            };
+            return Ok(self.render_try(indent, &ctx.unit, &out, &[stmt], catch_param));
+        };
+
+        let catch_param = param.clone().unwrap_or_else(|| fresh_name(names, "error"));
+        let mut handler_out = statements.clone();
+        if let Some(v) = result {
+            match sink {
+                Sink::Return => handler_out.push(format!("return {}{}", v.text, semi)),
+                _ if !v.simple || v.call => handler_out.push(format!("{}{}", v.text, semi)),
+                _ => {}
+            }
+        }
+        Ok(self.render_try(indent, &ctx.unit, &out, &handler_out, catch_param))
+    }
+
+    fn render_try(
+        &self,
+        indent: &str,
+        unit: &str,
+        body: &[String],
+        handler: &[String],
+        catch_param: String,
+    ) -> String {
+        let inner = format!("{}{}", indent, unit);
+        let handler = if handler.is_empty() {
+            None
+        } else {
+            Some(indent_lines(handler, &inner))
+        };
+        render_wrapped(
+            &WrapKind::TryCatch { catch_param },
+            indent,
+            unit,
+            &indent_lines(body, &inner),
+            handler.as_deref(),
+        )
+    }
+
+    fn parse_handler<'a>(
+        &self,
+        handler: &Node<'a>,
+        source: &str,
+    ) -> Result<Handler<'a>, OperationError> {
+        match handler.kind() {
+            "identifier" | "member_expression" => {
+                return Ok(Handler {
+                    node: *handler,
+                    kind: HandlerKind::Reference(node_text(handler, source).to_string()),
+                    declared: vec![],
+                    free_names: vec![],
+                })
+            }
+            "arrow_function" | "function_expression" => {}
+            _ => {
+                return Err(self.refuse(
+                    handler,
+                    "handler must be a function or a function reference",
+                ))
+            }
+        }
+        if handler.kind() == "function_expression" {
+            if let Some(body) = handler.child_by_field_name("body") {
+                if let Some(what) = find_own_binding_use(&body, source) {
+                    return Err(self.refuse(handler, &format!("handler uses `{}`", what)));
+                }
+            }
+        }
+
+        let mut declared = Vec::new();
+        let param = match handler.child_by_field_name("parameter") {
+            Some(p) => Some(p),
+            None => {
+                let params = handler
+                    .child_by_field_name("parameters")
+                    .map(|p| collect_param_nodes(&p))
+                    .unwrap_or_default();
+                if params.len() > 1 {
+                    return Err(self.refuse(handler, "handler takes more than one parameter"));
+                }
+                params.first().copied()
+            }
+        };
+        if let Some(p) = param {
+            if p.child_by_field_name("value").is_some() {
+                return Err(self.refuse(handler, "handler parameter has a default value"));
+            }
+            let pattern = p.child_by_field_name("pattern").unwrap_or(p);
+            collect_binding_names(&pattern, source, &mut declared);
+        }
+
+        let body = handler
+            .child_by_field_name("body")
+            .ok_or_else(|| self.refuse(handler, "handler has no body"))?;
+        let mut free_names = Vec::new();
+        collect_identifier_names(&body, source, &mut free_names);
+
+        let (statements, result) = if body.kind() == "statement_block" {
+            let children: Vec<Node> = {
+                let mut cursor = body.walk();
+                body.named_children(&mut cursor).collect()
+            };
+            let mut statements = Vec::new();
+            let mut result = None;
+            for (i, stmt) in children.iter().enumerate() {
+                let is_last = i == children.len() - 1;
+                if is_last && stmt.kind() == "return_statement" {
+                    result = stmt.named_child(0).map(|e| Expr::from_node(&e, source));
+                    continue;
+                }
+                if contains_return(stmt) {
+                    return Err(self.refuse(stmt, "handler returns from a branch"));
+                }
+                if matches!(stmt.kind(), "lexical_declaration" | "variable_declaration") {
+                    let mut cursor = stmt.walk();
+                    for declarator in stmt.named_children(&mut cursor) {
+                        if let Some(name) = declarator.child_by_field_name("name") {
+                            collect_binding_names(&name, source, &mut declared);
+                        }
+                    }
+                }
+                statements.push(normalized_text(stmt, source));
+            }
+            (statements, result)
+        } else {
+            (vec![], Some(Expr::from_node(&body, source)))
+        };
+
+        Ok(Handler {
+            node: *handler,
+            kind: HandlerKind::Inline {
+                param: param.map(|p| normalized_text(&p, source)),
+                statements,
+                result,
+            },
+            declared,
+            free_names,
+        })
+    }
+}
+
+// --- Helper functions ---
+
+struct Context<'s> {
+    source: &'s str,
+    semi: &'static str,
+    unit: String,
+}
+
+/// Where the value of the chain goes.
+enum Sink {
+    /// `return chain`
+    Return,
+    /// `chain;`
+    Statement,
+    /// `const x = chain` -- holds `const x`
+    Declare(String),
+}
+
+struct Handler<'a> {
+    node: Node<'a>,
+    kind: HandlerKind,
+    /// Names the handler would introduce into the enclosing block.
+    declared: Vec<String>,
+    /// Identifiers referenced by the handler body.
+    free_names: Vec<String>,
+}
+
+enum HandlerKind {
+    /// `(x) => { ...; return y }`
+    Inline {
+        param: Option<String>,
+        statements: Vec<String>,
+        result: Option<Expr>,
+    },
+    /// `.then(parse)`
+    Reference(String),
+}
+
+/// An expression as normalized source text.
+#[derive(Clone)]
+struct Expr {
+    text: String,
+    /// Binds tighter than `await`, so needs no parentheses.
+    simple: bool,
+    call: bool,
+}
+
+impl Expr {
+    fn from_node(node: &Node, source: &str) -> Self {
+        let simple = matches!(
+            node.kind(),
+            "call_expression"
+                | "member_expression"
+                | "subscript_expression"
+                | "identifier"
+                | "parenthesized_expression"
+                | "new_expression"
+                | "await_expression"
+                | "string"
+                | "template_string"
+                | "number"
+                | "true"
+                | "false"
+                | "null"
+                | "undefined"
+                | "object"
+                | "array"
+                | "this"
+        );
+        Self {
+            text: normalized_text(node, source),
+            simple,
+            call: matches!(
+                node.kind(),
+                "call_expression" | "new_expression" | "await_expression"
+            ),
+        }
+    }
+
+    fn awaited(&self) -> String {
+        if self.text.starts_with("await ") {
+            self.text.clone()
+        } else if self.simple {
+            format!("await {}", self.text)
+        } else {
+            format!("await ({})", self.text)
+        }
+    }
+}
+
+/// Node text with continuation lines re-based to column 0.
+fn normalized_text(node: &Node, source: &str) -> String {
+    let prefix = format::extract_line_prefix(source, node.start_byte());
+    node_text(node, source)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line
+            } else {
+                line.strip_prefix(prefix.as_str())
+                    .unwrap_or(line.trim_start())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent_lines(statements: &[String], indent: &str) -> String {
+    statements
+        .iter()
+        .map(|s| format!("{}{}", indent, format::indent_code(s, indent)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fresh_name(names: &mut HashSet<String>, base: &str) -> String {
+    let mut candidate = base.to_string();
+    let mut n = 2;
+    while names.contains(&candidate) {
+        candidate = format!("{}{}", base, n);
+        n += 1;
+    }
+    names.insert(candidate.clone());
+    candidate
+}
+
+fn link_method<'s>(call: &Node, source: &'s str) -> &'s str {
+    call.child_by_field_name("function")
+        .and_then(|f| f.child_by_field_name("property"))
+        .map(|p| node_text(&p, source))
+        .unwrap_or("")
+}
+
+fn link_args<'a>(call: &Node<'a>) -> Vec<Node<'a>> {
+    let Some(args) = call.child_by_field_name("arguments") else {
+        return vec![];
+    };
+    let mut cursor = args.walk();
+    let nodes = args
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() != "comment")
+        .collect();
+    nodes
+}
+
+/// Split `base.then(a).then(b).catch(c)` into `base` and the call
+/// nodes of each link, innermost first.
+fn unroll_chain<'a>(node: &Node<'a>, source: &str) -> Option<(Node<'a>, Vec<Node<'a>>)> {
+    let mut links = Vec::new();
+    let mut current = *node;
+    while current.kind() == "call_expression" {
+        let Some(callee) = current.child_by_field_name("function") else {
+            break;
+        };
+        if callee.kind() != "member_expression" {
+            break;
+        }
+        let method = callee
+            .child_by_field_name("property")
+            .map(|p| node_text(&p, source));
+        if !matches!(method, Some("then" | "catch" | "finally")) {
+            break;
+        }
+        links.push(current);
+        current = callee.child_by_field_name("object")?;
+    }
+    if links.is_empty() {
+        return None;
+    }
+    links.reverse();
+    Some((current, links))
+}
+
+/// Statements (owned by this function, not nested ones) whose value is a
+/// promise chain, paired with the chain expression.
+fn collect_chain_statements<'a>(
+    node: &Node<'a>,
+    source: &str,
+    out: &mut Vec<(Node<'a>, Node<'a>)>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let chain = match child.kind() {
+            "return_statement" | "expression_statement" => child.named_child(0),
+            "lexical_declaration" | "variable_declaration" if child.named_child_count() == 1 => {
+                child
+                    .named_child(0)
+                    .and_then(|d| d.child_by_field_name("value"))
+            }
+            _ => None,
+        };
+        if let Some(chain) = chain.filter(|c| unroll_chain(c, source).is_some()) {
+            out.push((child, chain));
+            continue;
+        }
+        if !is_function(child.kind()) {
+            collect_chain_statements(&child, source, out);
+        }
+    }
+}
+
+fn is_function(kind: &str) -> bool {
+    matches!(
+        kind,
+        "function_declaration"
+            | "function_expression"
+            | "arrow_function"
+            | "generator_function_declaration"
+            | "generator_function"
+            | "method_definition"
+            | "class_declaration"
+            | "class"
+    )
+}
+
+fn contains_return(node: &Node) -> bool {
+    if node.kind() == "return_statement" {
+        return true;
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .any(|c| !is_function(c.kind()) && contains_return(&c));
+    found
+}
+
+/// Names bound by a parameter or declarator pattern.
+fn collect_binding_names(pattern: &Node, source: &str, out: &mut Vec<String>) {
+    match pattern.kind() {
+        "identifier" | "shorthand_property_identifier_pattern" => {
+            out.push(node_text(pattern, source).to_string());
+        }
+        "pair_pattern" => {
+            if let Some(value) = pattern.child_by_field_name("value") {
+                collect_binding_names(&value, source, out);
+            }
+        }
+        "type_annotation" => {}
+        _ => {
+            let mut cursor = pattern.walk();
+            for child in pattern.named_children(&mut cursor) {
+                collect_binding_names(&child, source, out);
+            }
+        }
+    }
+}
+
+fn collect_identifier_names(node: &Node, source: &str, out: &mut Vec<String>) {
+    if matches!(node.kind(), "identifier" | "shorthand_property_identifier") {
+        out.push(node_text(node, source).to_string());
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_identifier_names(&child, source, out);
+    }
+}
+
+fn function_param_names(func: &Node, source: &str) -> HashSet<String> {
+    let mut names = Vec::new();
+    if let Some(p) = func.child_by_field_name("parameter") {
+        collect_binding_names(&p, source, &mut names);
+    }
+    if let Some(params) = func.child_by_field_name("parameters") {
+        for p in collect_param_nodes(&params) {
+            let pattern = p.child_by_field_name("pattern").unwrap_or(p);
+            collect_binding_names(&pattern, source, &mut names);
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// Names declared directly in `block`, other than by `skip`.
+fn block_declared_names(block: &Node, source: &str, skip: &Node) -> HashSet<String> {
+    let mut names = Vec::new();
+    let mut cursor = block.walk();
+    for stmt in block.named_children(&mut cursor) {
+        if stmt.id() == skip.id() {
+            continue;
+        }
+        match stmt.kind() {
+            "lexical_declaration" | "variable_declaration" => {
+                let mut inner = stmt.walk();
+                for declarator in stmt.named_children(&mut inner) {
+                    if let Some(name) = declarator.child_by_field_name("name") {
+                        collect_binding_names(&name, source, &mut names);
+                    }
+                }
+            }
+            "function_declaration" | "class_declaration" => {
+                if let Some(name) = stmt.child_by_field_name("name") {
+                    names.push(node_text(&name, source).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    names.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        let lang = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn convert(source: &str, name: &str) -> Result<String, OperationError> {
+        let tree = parse_ts(source);
+        let edits = PromiseToAsync::new(name.into()).compute_edits(source, &tree)?;
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(
+            !parse_ts(&result).root_node().has_error(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        Ok(result)
+    }
+
+    #[test]
+    fn test_flatten_then_chain() {
+        let source = "function loadUser(id: string): Promise<User> {\n  return fetch(`/users/${id}`)\n    .then((res) => res.json())\n    .then((data) => {\n      log(data);\n      return toUser(data);\n    });\n}\n";
+        let result = convert(source, "loadUser").unwrap();
+        assert_eq!(
+            result,
+            "async function loadUser(id: string): Promise<User> {\n  const res = await fetch(`/users/${id}`);\n  const data = await res.json();\n  log(data);\n  return toUser(data);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_trailing_catch_becomes_try_catch() {
+        let source = "function save(item) {\n  api.post(item)\n    .then(() => notify('saved'))\n    .catch((err) => {\n      console.error(err);\n    });\n}\n";
+        let result = convert(source, "save").unwrap();
+        assert_eq!(
+            result,
+            "async function save(item) {\n  try {\n    await api.post(item);\n    await notify('saved');\n  } catch (err) {\n    console.error(err);\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_trailing_fire_and_forget_chain_warns() {
+        let source = "function save(item) {\n  api.post(item).then(() => notify('saved'));\n}\n";
+        let tree = parse_ts(source);
+        let (_, warnings) = PromiseToAsync::new("save".into())
+            .compute_edits_with_style(source, &tree, &StyleConfig::default())
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("not awaited"));
+    }
+
+    #[test]
+    fn test_refuses_fire_and_forget_chain_before_other_code() {
+        let source = "function save(item) {\n  api.post(item).then(() => notify('saved'));\n  closeDialog();\n}\n";
+        let err = convert(source, "save").unwrap_err().to_string();
+        assert!(err.contains("not awaited or returned"), "{}", err);
+    }
+
+    #[test]
+    fn test_catch_value_is_returned() {
+        let source = "const getName = (id) => fetch(id).then(r => r.text()).catch(() => null);\n";
+        let result = convert(source, "getName").unwrap();
+        assert_eq!(
+            result,
+            "const getName = async (id) => {\n  try {\n    const r = await fetch(id);\n    return await r.text();\n  } catch (error) {\n    return null;\n  }\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_function_reference_handler_gets_named_result() {
+        let source = "function run() {\n  const parsed = read().then(parse);\n  use(parsed);\n}\n";
+        let result = convert(source, "run").unwrap();
+        assert!(result
+            .contains("  const result = await read();\n  const parsed = await parse(result);\n"));
+    }
+
+    #[test]
+    fn test_refuses_two_argument_then() {
+        let source = "function f() {\n  return g().then(a => a, e => null);\n}\n";
+        assert!(convert(source, "f").is_err());
+    }
+
+    #[test]
+    fn test_refuses_branching_handler() {
+        let source = "function f() {\n  return g().then(a => {\n    if (a) {\n      return 1;\n    }\n    return 2;\n  });\n}\n";
+        assert!(convert(source, "f").is_err());
+    }
+
+    #[test]
+    fn test_refuses_catch_in_middle() {
+        let source = "function f() {\n  return g().catch(() => 0).then(a => a + 1);\n}\n";
+        assert!(convert(source, "f").is_err());
+    }
+
+    #[test]
+    fn test_refuses_conflicting_binding() {
+        let source = "function f(res) {\n  return g().then(res => res.ok);\n}\n";
+        assert!(convert(source, "f").is_err());
+    }
+
+    #[test]
+    fn test_no_chain_errors() {
+        let source = "function f() {\n  return 1;\n}\n";
+        assert!(matches!(
+            convert(source, "f"),
+            Err(OperationError::TargetNotFound { .. })
+        ));
+    }
+}
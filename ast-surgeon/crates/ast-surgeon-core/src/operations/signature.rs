@@ -19,6 +19,7 @@ pub enum ParamPosition {
 }
 
 impl ParamPosition {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self, OperationError> {
         match s.to_lowercase().as_str() {
             "first" => Ok(Self::First),
@@ -272,8 +273,8 @@ impl Executable for RemoveParameter {
 // --- Helper functions ---
 
 /// Find a function/arrow-function/method node by its name.
-fn find_function_by_name<'a>(
-    root: &'a Node<'a>,
+pub(crate) fn find_function_by_name<'a>(
+    root: &Node<'a>,
     source: &str,
     name: &str,
 ) -> Option<Node<'a>> {
@@ -353,7 +354,7 @@ fn find_function_recursive<'a>(
 }
 
 /// Find the formal_parameters node inside a function/arrow node.
-fn find_formal_parameters<'a>(func_node: &'a Node<'a>) -> Option<Node<'a>> {
+pub(crate) fn find_formal_parameters<'a>(func_node: &Node<'a>) -> Option<Node<'a>> {
     // function_declaration/function_expression: has "parameters" field
     if let Some(params) = func_node.child_by_field_name("parameters") {
         return Some(params);
@@ -378,7 +379,7 @@ fn find_formal_parameters<'a>(func_node: &'a Node<'a>) -> Option<Node<'a>> {
 
 /// Collect actual parameter nodes from a formal_parameters node
 /// (excluding punctuation like `(`, `)`, `,`).
-fn collect_param_nodes<'a>(params_node: &'a Node<'a>) -> Vec<Node<'a>> {
+pub(crate) fn collect_param_nodes<'a>(params_node: &Node<'a>) -> Vec<Node<'a>> {
     let mut params = Vec::new();
     let mut cursor = params_node.walk();
     if cursor.goto_first_child() {
@@ -0,0 +1,322 @@
+//! Svelte instance-script operations: `svelte_add_reactive` and
+//! `svelte_add_store`.
+//!
+//! Both run on the content of the `<script>` block; splitting the component
+//! is done by the caller (see `ast_surgeon_lang::svelte`).
+
+use crate::edit::TextEdit;
+use crate::format::StyleConfig;
+use crate::operations::imports::AddImport;
+use crate::operations::node_utils::node_text;
+use crate::operations::vue::AddToSetup;
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// Rune calls that mark a component as Svelte 5 runes mode.
+const RUNES: &[&str] = &[
+    "$state(",
+    "$state.",
+    "$derived(",
+    "$derived.",
+    "$effect(",
+    "$effect.",
+    "$props(",
+    "$bindable(",
+    "$inspect(",
+];
+
+/// The svelte_add_reactive operation.
+///
+/// Adds a derived value: `$: name = expression` in Svelte 4 components,
+/// `let name = $derived(expression)` in runes mode. The mode is detected
+/// from the script unless `runes` is given.
+pub struct AddReactive {
+    pub name: String,
+    pub expression: String,
+    pub runes: Option<bool>,
+}
+
+impl AddReactive {
+    pub fn new(name: String, expression: String, runes: Option<bool>) -> Self {
+        Self {
+            name,
+            expression,
+            runes,
+        }
+    }
+}
+
+impl Executable for AddReactive {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        check_identifier(&self.name)?;
+        let expression = self.expression.trim().trim_end_matches(';');
+        if expression.is_empty() {
+            return Err(OperationError::InvalidParams {
+                message: "expression must be non-empty".to_string(),
+            });
+        }
+        if declares(&tree.root_node(), source, &self.name) {
+            return Err(OperationError::InvalidParams {
+                message: format!("'{}' is already declared in the script", self.name),
+            });
+        }
+
+        let semi = style.resolve(source).semi();
+        let statement = if self.runes.unwrap_or_else(|| uses_runes(source)) {
+            format!("let {} = $derived({}){}", self.name, expression, semi)
+        } else {
+            format!("$: {} = {}{}", self.name, expression, semi)
+        };
+        let label = format!("add reactive {}", self.name);
+        let edits = AddToSetup::new(statement)
+            .compute_edits_with_style(source, tree, style)?
+            .0
+            .into_iter()
+            .map(|e| TextEdit {
+                label: label.clone(),
+                ..e
+            })
+            .collect();
+        Ok((edits, vec![]))
+    }
+}
+
+/// The svelte_add_store operation.
+///
+/// Declares `const name = writable(initial)` (or `readable`) and imports
+/// the store factory from `svelte/store`.
+pub struct AddStore {
+    pub name: String,
+    pub initial_value: String,
+    pub kind: String,
+}
+
+impl AddStore {
+    pub fn new(name: String, initial_value: String, kind: String) -> Self {
+        Self {
+            name,
+            initial_value,
+            kind,
+        }
+    }
+}
+
+impl Executable for AddStore {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        check_identifier(&self.name)?;
+        if !matches!(self.kind.as_str(), "writable" | "readable") {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "Unknown store kind '{}' (expected writable or readable)",
+                    self.kind
+                ),
+            });
+        }
+        if declares(&tree.root_node(), source, &self.name) {
+            return Err(OperationError::InvalidParams {
+                message: format!("'{}' is already declared in the script", self.name),
+            });
+        }
+
+        let semi = style.resolve(source).semi();
+        let statement = format!(
+            "const {} = {}({}){}",
+            self.name,
+            self.kind,
+            self.initial_value.trim(),
+            semi
+        );
+        let label = format!("add store {}", self.name);
+
+        let mut edits = AddImport::new(
+            "svelte/store".to_string(),
+            vec![self.kind.clone()],
+            None,
+            false,
+        )
+        .compute_edits_with_style(source, tree, style)?
+        .0;
+        // A new import line lands after the newline that ends the last
+        // import; when the declaration follows that import too, move it to
+        // the same point so it comes after the new line.
+        let import_at = edits.iter().find(|e| e.start == e.end).map(|e| e.start);
+        edits.extend(
+            AddToSetup::new(statement)
+                .compute_edits_with_style(source, tree, style)?
+                .0
+                .into_iter()
+                .map(|e| match import_at {
+                    Some(at) if e.start == e.end && source.get(e.start..at) == Some("\n") => {
+                        TextEdit {
+                            start: at,
+                            end: at,
+                            replacement: format!("{}\n", &e.replacement[1..]),
+                            label: label.clone(),
+                            priority: 1,
+                        }
+                    }
+                    _ => TextEdit {
+                        label: label.clone(),
+                        ..e
+                    },
+                }),
+        );
+
+        let mut warnings = Vec::new();
+        if uses_runes(source) {
+            warnings.push(format!(
+                "Component uses runes; consider `let {} = $state(...)` instead of a store",
+                self.name
+            ));
+        }
+        Ok((edits, warnings))
+    }
+}
+
+/// Whether a script uses Svelte 5 runes.
+pub fn uses_runes(source: &str) -> bool {
+    RUNES.iter().any(|r| source.contains(r))
+}
+
+// --- Helper functions ---
+
+fn check_identifier(name: &str) -> Result<(), OperationError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if valid {
+        Ok(())
+    } else {
+        Err(OperationError::InvalidParams {
+            message: format!("'{}' is not a valid identifier", name),
+        })
+    }
+}
+
+/// Whether `name` is a top-level variable or a `$: name = ...` target.
+fn declares(root: &Node, source: &str, name: &str) -> bool {
+    let mut cursor = root.walk();
+    let result = root.named_children(&mut cursor).any(|statement| {
+        let statement = if statement.kind() == "export_statement" {
+            match statement.child_by_field_name("declaration") {
+                Some(d) => d,
+                None => return false,
+            }
+        } else {
+            statement
+        };
+        match statement.kind() {
+            "lexical_declaration" | "variable_declaration" => {
+                let mut decl_cursor = statement.walk();
+                let found = statement.named_children(&mut decl_cursor).any(|d| {
+                    d.kind() == "variable_declarator"
+                        && d.child_by_field_name("name")
+                            .is_some_and(|n| node_text(&n, source) == name)
+                });
+                found
+            }
+            "labeled_statement" => statement
+                .child_by_field_name("body")
+                .and_then(|b| b.named_child(0))
+                .filter(|e| e.kind() == "assignment_expression")
+                .and_then(|e| e.child_by_field_name("left"))
+                .is_some_and(|l| node_text(&l, source) == name),
+            _ => false,
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_ts(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(!parse_ts(&result).root_node().has_error(), "{}", result);
+        result
+    }
+
+    #[test]
+    fn test_add_reactive_svelte4() {
+        let source = "let count = 0;\n";
+        let op = AddReactive::new("doubled".into(), "count * 2".into(), None);
+        assert_eq!(
+            run(&op, source),
+            "let count = 0;\n$: doubled = count * 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_add_reactive_runes() {
+        let source = "let count = $state(0)\n";
+        let op = AddReactive::new("doubled".into(), "count * 2".into(), None);
+        assert_eq!(
+            run(&op, source),
+            "let count = $state(0)\nlet doubled = $derived(count * 2)\n"
+        );
+    }
+
+    #[test]
+    fn test_add_reactive_rejects_existing_name() {
+        let source = "let count = 0\n$: doubled = count * 2\n";
+        let tree = parse_ts(source);
+        let op = AddReactive::new("doubled".into(), "count * 3".into(), Some(false));
+        assert!(matches!(
+            op.compute_edits(source, &tree),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+
+    #[test]
+    fn test_add_store_imports_factory() {
+        let source = "import Button from './Button.svelte'\n";
+        let op = AddStore::new("count".into(), "0".into(), "writable".into());
+        assert_eq!(
+            run(&op, source),
+            "import Button from './Button.svelte'\nimport { writable } from 'svelte/store'\n\nconst count = writable(0)\n"
+        );
+    }
+}
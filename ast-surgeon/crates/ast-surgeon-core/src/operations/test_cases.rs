@@ -0,0 +1,617 @@
+//! Jest / Vitest test-file operations.
+//!
+//! Tests are addressed by their title path, `Outer > Inner > title`, the
+//! same `ancestorTitles > title` form the Jest and Vitest reporters use.
+//! `describe`, `it` and `test` are recognized with their `.skip` / `.only`
+//! modifiers and the `xit` / `fit` style aliases.
+
+use crate::edit::TextEdit;
+use crate::format::{self, IndentStyle, StyleConfig};
+use crate::operations::class_members::remove_member_lines;
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, Location, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// Add a test to a `describe` block, creating missing describes.
+pub struct AddTestCase {
+    pub describe_path: Vec<String>,
+    pub name: String,
+    pub body: String,
+    /// "it" or "test".
+    pub kind: String,
+}
+
+/// Which modifier [`ToggleTestModifier`] sets or clears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestModifier {
+    Skip,
+    Only,
+}
+
+/// Add or remove `.skip` / `.only` on a test or describe.
+pub struct ToggleTestModifier {
+    pub path: Vec<String>,
+    pub modifier: TestModifier,
+    pub enabled: bool,
+}
+
+/// Remove a test or describe block.
+pub struct RemoveTestCase {
+    pub path: Vec<String>,
+}
+
+/// A `describe` / `it` / `test` call statement.
+struct TestCall<'a> {
+    statement: Node<'a>,
+    callee: Node<'a>,
+    /// "describe", "it" or "test".
+    base: &'static str,
+    /// "skip", "only", "todo", ... from `it.skip` or `xit`.
+    modifier: Option<String>,
+    title: String,
+    /// The callback's statement block.
+    body: Option<Node<'a>>,
+}
+
+/// Split `A > B > title` into its titles.
+pub fn parse_test_path(path: &str) -> Vec<String> {
+    path.split(" > ")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl AddTestCase {
+    pub fn new(describe_path: Vec<String>, name: String, body: String, kind: String) -> Self {
+        Self {
+            describe_path,
+            name,
+            body,
+            kind,
+        }
+    }
+}
+
+impl Executable for AddTestCase {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        if !matches!(self.kind.as_str(), "it" | "test") {
+            return Err(OperationError::InvalidParams {
+                message: format!("kind must be 'it' or 'test', got '{}'", self.kind),
+            });
+        }
+
+        // Walk down as far as the describes exist
+        let mut block = tree.root_node();
+        let mut found = 0;
+        for title in &self.describe_path {
+            let describes: Vec<TestCall> = test_calls(&block, source)
+                .into_iter()
+                .filter(|c| c.base == "describe" && &c.title == title && c.body.is_some())
+                .collect();
+            match describes.len() {
+                0 => break,
+                1 => block = describes[0].body.expect("filtered on body"),
+                _ => return Err(ambiguous(&self.describe_path[..=found], &describes, source)),
+            }
+            found += 1;
+        }
+        if found == self.describe_path.len()
+            && test_calls(&block, source)
+                .iter()
+                .any(|c| c.base != "describe" && c.title == self.name)
+        {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "a test named '{}' already exists in {}",
+                    self.name,
+                    describe_label(&self.describe_path)
+                ),
+            });
+        }
+
+        let style = profile.indent.clone();
+        let quote = profile.quote;
+        let semi = profile.semi();
+        let (outer_prefix, prefix) = match block.parent() {
+            Some(_) => {
+                let outer =
+                    format::extract_line_prefix(source, enclosing_statement(&block).start_byte());
+                let inner = format::indent_deeper(&outer, &style);
+                (outer, inner)
+            }
+            None => (String::new(), String::new()),
+        };
+
+        let async_marker = if self.body.contains("await ") {
+            "async "
+        } else {
+            ""
+        };
+        let mut lines = vec![];
+        let missing = &self.describe_path[found..];
+        for (depth, title) in missing.iter().enumerate() {
+            lines.push(format!(
+                "{}describe({}, () => {{",
+                deeper(&prefix, &style, depth),
+                quoted(title, quote)
+            ));
+        }
+        let test_prefix = deeper(&prefix, &style, missing.len());
+        lines.push(format!(
+            "{}{}({}, {}() => {{",
+            test_prefix,
+            self.kind,
+            quoted(&self.name, quote),
+            async_marker
+        ));
+        let body_prefix = format::indent_deeper(&test_prefix, &style);
+        for line in dedent(&self.body).lines() {
+            lines.push(if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", body_prefix, line)
+            });
+        }
+        lines.push(format!("{}}}){}", test_prefix, semi));
+        for depth in (0..missing.len()).rev() {
+            lines.push(format!("{}}}){}", deeper(&prefix, &style, depth), semi));
+        }
+        let rendered = lines.join("\n");
+
+        let label = format!(
+            "add {} '{}' to {}",
+            self.kind,
+            self.name,
+            describe_label(&self.describe_path)
+        );
+        let has_statements = block
+            .named_children(&mut block.walk())
+            .any(|c| c.kind() != "comment");
+        if block.parent().is_none() {
+            let lead = match (has_statements, source.is_empty() || source.ends_with('\n')) {
+                (true, true) => "\n",
+                (true, false) => "\n\n",
+                (false, true) => "",
+                (false, false) => "\n",
+            };
+            let edit = TextEdit {
+                start: source.len(),
+                end: source.len(),
+                replacement: format!("{}{}\n", lead, rendered),
+                label,
+                priority: 0,
+            };
+            return Ok((vec![edit], vec![]));
+        }
+
+        let close = block.end_byte() - 1;
+        let inner = &source[block.start_byte() + 1..close];
+        let close_line_start = source[..close].rfind('\n').map_or(0, |i| i + 1);
+        let edit = if inner.trim().is_empty() {
+            TextEdit {
+                start: block.start_byte() + 1,
+                end: close,
+                replacement: format!("\n{}\n{}", rendered, outer_prefix),
+                label,
+                priority: 0,
+            }
+        } else if source[close_line_start..close].trim().is_empty() && close_line_start > 0 {
+            TextEdit {
+                start: close_line_start,
+                end: close_line_start,
+                replacement: format!("\n{}\n", rendered),
+                label,
+                priority: 0,
+            }
+        } else {
+            TextEdit {
+                start: close,
+                end: close,
+                replacement: format!("\n\n{}\n{}", rendered, outer_prefix),
+                label,
+                priority: 0,
+            }
+        };
+        Ok((vec![edit], vec![]))
+    }
+}
+
+impl ToggleTestModifier {
+    pub fn new(path: Vec<String>, modifier: TestModifier, enabled: bool) -> Self {
+        Self {
+            path,
+            modifier,
+            enabled,
+        }
+    }
+}
+
+impl Executable for ToggleTestModifier {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let call = find_test(&tree.root_node(), &self.path, source)?;
+        let wanted = match self.modifier {
+            TestModifier::Skip => "skip",
+            TestModifier::Only => "only",
+        };
+        let current = call.modifier.as_deref();
+        if let Some(other) = current.filter(|m| !matches!(*m, "skip" | "only")) {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "'{}' uses {}.{}; toggle it by hand",
+                    self.path.join(" > "),
+                    call.base,
+                    other
+                ),
+            });
+        }
+        let callee = match (self.enabled, current) {
+            (true, Some(m)) if m == wanted => return Ok(vec![]),
+            (true, _) => format!("{}.{}", call.base, wanted),
+            (false, Some(m)) if m == wanted => call.base.to_string(),
+            (false, _) => return Ok(vec![]),
+        };
+        Ok(vec![TextEdit {
+            start: call.callee.start_byte(),
+            end: call.callee.end_byte(),
+            replacement: callee.clone(),
+            label: format!("{} -> {}", node_text(&call.callee, source), callee),
+            priority: 0,
+        }])
+    }
+}
+
+impl RemoveTestCase {
+    pub fn new(path: Vec<String>) -> Self {
+        Self { path }
+    }
+}
+
+impl Executable for RemoveTestCase {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let call = find_test(&tree.root_node(), &self.path, source)?;
+        Ok(vec![remove_member_lines(
+            source,
+            &call.statement,
+            format!("remove {} '{}'", call.base, self.path.join(" > ")),
+        )])
+    }
+}
+
+// --- Helper functions ---
+
+/// Resolve a title path to its call; intermediate titles must be describes.
+fn find_test<'a>(
+    root: &Node<'a>,
+    path: &[String],
+    source: &str,
+) -> Result<TestCall<'a>, OperationError> {
+    let Some((last, describes)) = path.split_last() else {
+        return Err(OperationError::InvalidParams {
+            message: "test path is empty".to_string(),
+        });
+    };
+    let mut block = *root;
+    for (depth, title) in describes.iter().enumerate() {
+        let mut matches: Vec<TestCall> = test_calls(&block, source)
+            .into_iter()
+            .filter(|c| c.base == "describe" && &c.title == title && c.body.is_some())
+            .collect();
+        block = match matches.len() {
+            0 => {
+                return Err(OperationError::TargetNotFound {
+                    description: format!("describe '{}'", path[..=depth].join(" > ")),
+                })
+            }
+            1 => matches.remove(0).body.expect("filtered on body"),
+            _ => return Err(ambiguous(&path[..=depth], &matches, source)),
+        };
+    }
+    let mut matches: Vec<TestCall> = test_calls(&block, source)
+        .into_iter()
+        .filter(|c| &c.title == last)
+        .collect();
+    match matches.len() {
+        0 => Err(OperationError::TargetNotFound {
+            description: format!("test '{}'", path.join(" > ")),
+        }),
+        1 => Ok(matches.remove(0)),
+        _ => Err(ambiguous(path, &matches, source)),
+    }
+}
+
+/// The test calls directly inside a block (not nested deeper).
+fn test_calls<'a>(block: &Node<'a>, source: &str) -> Vec<TestCall<'a>> {
+    let mut calls = Vec::new();
+    let mut cursor = block.walk();
+    for statement in block.named_children(&mut cursor) {
+        if statement.kind() != "expression_statement" {
+            continue;
+        }
+        let Some(call) = statement
+            .named_child(0)
+            .filter(|c| c.kind() == "call_expression")
+        else {
+            continue;
+        };
+        let (Some(callee), Some(arguments)) = (
+            call.child_by_field_name("function"),
+            call.child_by_field_name("arguments"),
+        ) else {
+            continue;
+        };
+        let Some((base, modifier)) = classify_callee(&callee, source) else {
+            continue;
+        };
+        let Some(title) = arguments.named_child(0).and_then(|t| title_of(&t, source)) else {
+            continue;
+        };
+        let body = arguments
+            .named_child(1)
+            .filter(|f| {
+                matches!(
+                    f.kind(),
+                    "arrow_function" | "function_expression" | "function"
+                )
+            })
+            .and_then(|f| f.child_by_field_name("body"))
+            .filter(|b| b.kind() == "statement_block");
+        calls.push(TestCall {
+            statement,
+            callee,
+            base,
+            modifier,
+            title,
+            body,
+        });
+    }
+    calls
+}
+
+/// `it` -> ("it", None), `it.skip` -> ("it", "skip"), `xit` -> ("it", "skip").
+fn classify_callee(callee: &Node, source: &str) -> Option<(&'static str, Option<String>)> {
+    let base_of = |name: &str| match name {
+        "describe" => Some("describe"),
+        "it" => Some("it"),
+        "test" => Some("test"),
+        _ => None,
+    };
+    match callee.kind() {
+        "identifier" => match node_text(callee, source) {
+            "xdescribe" => Some(("describe", Some("skip".to_string()))),
+            "xit" => Some(("it", Some("skip".to_string()))),
+            "xtest" => Some(("test", Some("skip".to_string()))),
+            "fdescribe" => Some(("describe", Some("only".to_string()))),
+            "fit" => Some(("it", Some("only".to_string()))),
+            name => base_of(name).map(|b| (b, None)),
+        },
+        "member_expression" => {
+            let object = callee.child_by_field_name("object")?;
+            let property = callee.child_by_field_name("property")?;
+            if object.kind() != "identifier" {
+                return None;
+            }
+            let base = base_of(node_text(&object, source))?;
+            Some((base, Some(node_text(&property, source).to_string())))
+        }
+        _ => None,
+    }
+}
+
+/// A title from a string or substitution-free template literal.
+fn title_of(node: &Node, source: &str) -> Option<String> {
+    let text = node_text(node, source);
+    match node.kind() {
+        "string" => Some(unescape(&text[1..text.len() - 1])),
+        "template_string" if node.named_child_count() == 0 => {
+            Some(unescape(&text[1..text.len() - 1]))
+        }
+        _ => None,
+    }
+}
+
+fn unescape(inner: &str) -> String {
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn quoted(text: &str, quote: char) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace(quote, &format!("\\{}", quote));
+    format!("{}{}{}", quote, escaped, quote)
+}
+
+/// Strip the common indentation and surrounding blank lines of a body.
+fn dedent(body: &str) -> String {
+    let lines: Vec<&str> = body.trim_matches('\n').lines().collect();
+    let common = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|l| l.get(common..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn deeper(prefix: &str, style: &IndentStyle, levels: usize) -> String {
+    (0..levels).fold(prefix.to_string(), |p, _| format::indent_deeper(&p, style))
+}
+
+/// The statement holding a describe callback's block.
+fn enclosing_statement<'a>(block: &Node<'a>) -> Node<'a> {
+    let mut current = *block;
+    while let Some(parent) = current.parent() {
+        current = parent;
+        if current.kind() == "expression_statement" {
+            break;
+        }
+    }
+    current
+}
+
+fn describe_label(path: &[String]) -> String {
+    if path.is_empty() {
+        "the top level".to_string()
+    } else {
+        format!("'{}'", path.join(" > "))
+    }
+}
+
+fn ambiguous(path: &[String], calls: &[TestCall], source: &str) -> OperationError {
+    OperationError::AmbiguousMatch {
+        description: format!("'{}'", path.join(" > ")),
+        count: calls.len(),
+        locations: calls
+            .iter()
+            .map(|c| Location {
+                line: c.statement.start_position().row + 1,
+                column: c.statement.start_position().column + 1,
+                context: node_text(&c.statement, source)
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .to_string(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    const SUITE: &str = "import { describe, it, expect } from 'vitest';\n\ndescribe('formatDate', () => {\n  it('formats ISO dates', () => {\n    expect(formatDate('2024-01-01')).toBe('Jan 1');\n  });\n\n  describe('invalid input', () => {\n    it('throws', () => {\n      expect(() => formatDate('x')).toThrow();\n    });\n  });\n});\n";
+
+    fn apply(op: &dyn Executable, source: &str) -> Result<String, OperationError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let edits = op.compute_edits(source, &tree)?;
+        let result = EditSet::new(edits, source.len())?.apply(source);
+        assert!(!parser.parse(&result, None).unwrap().root_node().has_error());
+        Ok(result)
+    }
+
+    #[test]
+    fn test_add_test_case_to_nested_describe() {
+        let op = AddTestCase::new(
+            parse_test_path("formatDate > invalid input"),
+            "returns null for empty strings".into(),
+            "  const result = await formatDate('')\n  expect(result).toBeNull();\n".into(),
+            "it".into(),
+        );
+        let result = apply(&op, SUITE).unwrap();
+        assert!(result.contains(
+            "      expect(() => formatDate('x')).toThrow();\n    });\n\n    it('returns null for empty strings', async () => {\n      const result = await formatDate('')\n      expect(result).toBeNull();\n    });\n  });\n});\n"
+        ));
+
+        let duplicate = AddTestCase::new(
+            parse_test_path("formatDate > invalid input"),
+            "throws".into(),
+            "".into(),
+            "it".into(),
+        );
+        assert!(matches!(
+            apply(&duplicate, SUITE),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+
+    #[test]
+    fn test_add_test_case_creates_missing_describes() {
+        let op = AddTestCase::new(
+            parse_test_path("formatDate > time zones > UTC"),
+            "keeps the offset".into(),
+            "expect(1).toBe(1);".into(),
+            "test".into(),
+        );
+        let result = apply(&op, SUITE).unwrap();
+        assert!(result.ends_with(
+            "  });\n\n  describe('time zones', () => {\n    describe('UTC', () => {\n      test('keeps the offset', () => {\n        expect(1).toBe(1);\n      });\n    });\n  });\n});\n"
+        ));
+
+        let top = AddTestCase::new(
+            vec![],
+            "smoke".into(),
+            "expect(true).toBe(true);".into(),
+            "it".into(),
+        );
+        let result = apply(&top, SUITE).unwrap();
+        assert!(result.ends_with("});\n\nit('smoke', () => {\n  expect(true).toBe(true);\n});\n"));
+    }
+
+    #[test]
+    fn test_toggle_skip_and_only() {
+        let skip = ToggleTestModifier::new(
+            parse_test_path("formatDate > invalid input > throws"),
+            TestModifier::Skip,
+            true,
+        );
+        let skipped = apply(&skip, SUITE).unwrap();
+        assert!(skipped.contains("    it.skip('throws', () => {"));
+
+        let unskip = ToggleTestModifier::new(
+            parse_test_path("formatDate > invalid input > throws"),
+            TestModifier::Skip,
+            false,
+        );
+        assert_eq!(apply(&unskip, &skipped).unwrap(), SUITE);
+        let xit = SUITE.replace("it('throws'", "xit('throws'");
+        assert_eq!(apply(&unskip, &xit).unwrap(), SUITE);
+
+        let only = ToggleTestModifier::new(parse_test_path("formatDate"), TestModifier::Only, true);
+        assert!(apply(&only, SUITE)
+            .unwrap()
+            .contains("describe.only('formatDate', () => {"));
+
+        let missing = ToggleTestModifier::new(
+            parse_test_path("formatDate > nope"),
+            TestModifier::Skip,
+            true,
+        );
+        assert!(matches!(
+            apply(&missing, SUITE),
+            Err(OperationError::TargetNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_test_case() {
+        let op = RemoveTestCase::new(parse_test_path("formatDate > formats ISO dates"));
+        let result = apply(&op, SUITE).unwrap();
+        assert_eq!(
+            result,
+            "import { describe, it, expect } from 'vitest';\n\ndescribe('formatDate', () => {\n  describe('invalid input', () => {\n    it('throws', () => {\n      expect(() => formatDate('x')).toThrow();\n    });\n  });\n});\n"
+        );
+    }
+}
@@ -0,0 +1,1132 @@
+//! Type-level operations on interfaces, type aliases and enums.
+//!
+//! `add_type_member`, `remove_type_member`, `set_member_optional`,
+//! `add_union_member`, `add_enum_member` and `extract_inline_type`.
+//! Member separators (`;`, `,` or bare newlines) follow whatever the
+//! target body already uses, and untouched comments are left in place.
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig, StyleProfile};
+use crate::operations::node_utils::{find_child_by_kind, node_text};
+use crate::operations::signature::{
+    collect_param_nodes, find_formal_parameters, find_function_by_name,
+};
+use crate::operations::{Executable, Location, OperationError};
+use tree_sitter::{Node, Tree};
+
+/// The add_type_member operation.
+///
+/// Appends a member (e.g. `email?: string`) to an interface or to the
+/// object type of a type alias. No-op if a member with the same name exists.
+pub struct AddTypeMember {
+    pub type_name: String,
+    pub member: String,
+}
+
+impl AddTypeMember {
+    pub fn new(type_name: String, member: String) -> Self {
+        Self { type_name, member }
+    }
+}
+
+impl Executable for AddTypeMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let member = self.member.trim().trim_end_matches([';', ',']).trim_end();
+        let new_name =
+            member_name_from_text(member).ok_or_else(|| OperationError::InvalidParams {
+                message: format!("Could not determine member name from '{}'", self.member),
+            })?;
+
+        let body = find_type_body(tree.root_node(), source, &self.type_name)?;
+        let members = body_members(&body);
+
+        if members
+            .iter()
+            .any(|m| member_name(m, source) == Some(new_name))
+        {
+            return Ok((vec![], vec![])); // Already declared -- no-op
+        }
+
+        let separator = body_separator(&profile, &body, &members);
+        Ok((
+            append_member(
+                source,
+                &profile,
+                &body,
+                &members,
+                separator,
+                member,
+                format!("add member '{}' to '{}'", new_name, self.type_name),
+            ),
+            vec![],
+        ))
+    }
+}
+
+/// The remove_type_member operation.
+pub struct RemoveTypeMember {
+    pub type_name: String,
+    pub member_name: String,
+}
+
+impl RemoveTypeMember {
+    pub fn new(type_name: String, member_name: String) -> Self {
+        Self {
+            type_name,
+            member_name,
+        }
+    }
+}
+
+impl Executable for RemoveTypeMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let body = find_type_body(tree.root_node(), source, &self.type_name)?;
+        let members = body_members(&body);
+
+        let idx = members
+            .iter()
+            .position(|m| member_name(m, source) == Some(self.member_name.as_str()))
+            .ok_or_else(|| OperationError::TargetNotFound {
+                description: format!(
+                    "Member '{}' not found in '{}'",
+                    self.member_name, self.type_name
+                ),
+            })?;
+
+        Ok(vec![remove_member(
+            source,
+            &members,
+            idx,
+            format!(
+                "remove member '{}' from '{}'",
+                self.member_name, self.type_name
+            ),
+        )])
+    }
+}
+
+/// The set_member_optional operation.
+///
+/// Adds or removes the `?` marker on a property or method signature.
+pub struct SetMemberOptional {
+    pub type_name: String,
+    pub member_name: String,
+    pub optional: bool,
+}
+
+impl SetMemberOptional {
+    pub fn new(type_name: String, member_name: String, optional: bool) -> Self {
+        Self {
+            type_name,
+            member_name,
+            optional,
+        }
+    }
+}
+
+impl Executable for SetMemberOptional {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let body = find_type_body(tree.root_node(), source, &self.type_name)?;
+        let member = body_members(&body)
+            .into_iter()
+            .find(|m| member_name(m, source) == Some(self.member_name.as_str()))
+            .ok_or_else(|| OperationError::TargetNotFound {
+                description: format!(
+                    "Member '{}' not found in '{}'",
+                    self.member_name, self.type_name
+                ),
+            })?;
+
+        if !matches!(member.kind(), "property_signature" | "method_signature") {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "Member '{}' is a {} and cannot be made optional",
+                    self.member_name,
+                    member.kind()
+                ),
+            });
+        }
+
+        let marker = find_child_by_kind(&member, "?");
+        match (marker, self.optional) {
+            (None, true) => {
+                let name = member.child_by_field_name("name").ok_or_else(|| {
+                    OperationError::TargetNotFound {
+                        description: format!("Name of member '{}' not found", self.member_name),
+                    }
+                })?;
+                Ok(vec![TextEdit {
+                    start: name.end_byte(),
+                    end: name.end_byte(),
+                    replacement: "?".to_string(),
+                    label: format!("make '{}.{}' optional", self.type_name, self.member_name),
+                    priority: 0,
+                }])
+            }
+            (Some(q), false) => Ok(vec![TextEdit {
+                start: q.start_byte(),
+                end: q.end_byte(),
+                replacement: String::new(),
+                label: format!("make '{}.{}' required", self.type_name, self.member_name),
+                priority: 0,
+            }]),
+            _ => Ok(vec![]), // Already in the requested state -- no-op
+        }
+    }
+}
+
+/// The add_union_member operation.
+///
+/// Appends a constituent to a type alias union (`type Status = 'a' | 'b'`).
+/// A non-union alias becomes a union. Multi-line unions keep their layout.
+pub struct AddUnionMember {
+    pub type_name: String,
+    pub member: String,
+}
+
+impl AddUnionMember {
+    pub fn new(type_name: String, member: String) -> Self {
+        Self { type_name, member }
+    }
+}
+
+impl Executable for AddUnionMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        let member = self.member.trim();
+        if member.is_empty() {
+            return Err(OperationError::InvalidParams {
+                message: "add_union_member requires a non-empty 'member'".to_string(),
+            });
+        }
+
+        let alias = find_declaration(
+            tree.root_node(),
+            source,
+            &self.type_name,
+            &["type_alias_declaration"],
+        )
+        .ok_or_else(|| OperationError::TargetNotFound {
+            description: format!("Type alias '{}' not found", self.type_name),
+        })?;
+        let value =
+            alias
+                .child_by_field_name("value")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Type alias '{}' has no value", self.type_name),
+                })?;
+
+        let mut constituents = Vec::new();
+        flatten_union(&value, &mut constituents);
+        if constituents.iter().any(|c| node_text(c, source) == member) {
+            return Ok(vec![]); // Already in the union -- no-op
+        }
+
+        let label = format!("add '{}' to union '{}'", member, self.type_name);
+        let value_text = node_text(&value, source);
+
+        if value.kind() == "union_type" && value_text.contains('\n') {
+            let last = constituents.last().copied().unwrap_or(value);
+            let prefix = format::extract_line_prefix(source, last.start_byte());
+            let line_start = source[..last.start_byte()]
+                .rfind('\n')
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            let replacement = if source[line_start..last.start_byte()].trim() == "|" {
+                format!("\n{}| {}", prefix, member)
+            } else {
+                format!(" |\n{}{}", prefix, member)
+            };
+            return Ok(vec![TextEdit {
+                start: value.end_byte(),
+                end: value.end_byte(),
+                replacement,
+                label,
+                priority: 0,
+            }]);
+        }
+
+        let mut edits = Vec::new();
+        if matches!(
+            value.kind(),
+            "function_type" | "constructor_type" | "conditional_type"
+        ) {
+            edits.push(TextEdit {
+                start: value.start_byte(),
+                end: value.start_byte(),
+                replacement: "(".to_string(),
+                label: format!("parenthesize '{}' before widening", self.type_name),
+                priority: 0,
+            });
+            edits.push(TextEdit {
+                start: value.end_byte(),
+                end: value.end_byte(),
+                replacement: format!(") | {}", member),
+                label,
+                priority: 0,
+            });
+        } else {
+            edits.push(TextEdit {
+                start: value.end_byte(),
+                end: value.end_byte(),
+                replacement: format!(" | {}", member),
+                label,
+                priority: 0,
+            });
+        }
+        Ok(edits)
+    }
+}
+
+/// The add_enum_member operation.
+pub struct AddEnumMember {
+    pub enum_name: String,
+    pub member_name: String,
+    pub value: Option<String>,
+}
+
+impl AddEnumMember {
+    pub fn new(enum_name: String, member_name: String, value: Option<String>) -> Self {
+        Self {
+            enum_name,
+            member_name,
+            value,
+        }
+    }
+}
+
+impl Executable for AddEnumMember {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let decl = find_declaration(
+            tree.root_node(),
+            source,
+            &self.enum_name,
+            &["enum_declaration"],
+        )
+        .ok_or_else(|| OperationError::TargetNotFound {
+            description: format!("Enum '{}' not found", self.enum_name),
+        })?;
+        let body =
+            decl.child_by_field_name("body")
+                .ok_or_else(|| OperationError::TargetNotFound {
+                    description: format!("Enum '{}' has no body", self.enum_name),
+                })?;
+        let members = body_members(&body);
+
+        if members
+            .iter()
+            .any(|m| member_name(m, source) == Some(self.member_name.as_str()))
+        {
+            return Ok((vec![], vec![])); // Already declared -- no-op
+        }
+
+        let text = match &self.value {
+            Some(value) => format!("{} = {}", self.member_name, value),
+            None => self.member_name.clone(),
+        };
+        Ok((
+            append_member(
+                source,
+                &profile,
+                &body,
+                &members,
+                ",",
+                &text,
+                format!(
+                    "add member '{}' to enum '{}'",
+                    self.member_name, self.enum_name
+                ),
+            ),
+            vec![],
+        ))
+    }
+}
+
+/// The extract_inline_type operation.
+///
+/// Lifts an inline object type from a parameter or variable annotation into
+/// a named `export interface`, inserted above the enclosing top-level statement.
+pub struct ExtractInlineType {
+    /// Parameter or variable whose annotation holds the object type.
+    pub target: String,
+    /// Restrict the search to the parameters of this function.
+    pub function_name: Option<String>,
+    /// Name for the new interface.
+    pub type_name: String,
+}
+
+impl ExtractInlineType {
+    pub fn new(target: String, function_name: Option<String>, type_name: String) -> Self {
+        Self {
+            target,
+            function_name,
+            type_name,
+        }
+    }
+}
+
+impl Executable for ExtractInlineType {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let profile = style.resolve(source);
+        let root = tree.root_node();
+
+        if find_declaration(
+            root,
+            source,
+            &self.type_name,
+            &["interface_declaration", "type_alias_declaration"],
+        )
+        .is_some()
+        {
+            return Err(OperationError::InvalidParams {
+                message: format!("A type named '{}' already exists", self.type_name),
+            });
+        }
+
+        let object_type = self.find_object_type(&root, source)?;
+
+        let statement =
+            top_level_statement(object_type).ok_or_else(|| OperationError::TargetNotFound {
+                description: format!("No top-level statement contains '{}'", self.target),
+            })?;
+        let comments = format::find_attached_comments(source, &statement);
+        let anchor = comments
+            .leading
+            .first()
+            .map(|c| c.start)
+            .unwrap_or(statement.start_byte());
+        let line_start = source[..anchor].rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        let interface = format_interface(source, &profile, &object_type, &self.type_name);
+
+        Ok((
+            vec![
+                TextEdit {
+                    start: line_start,
+                    end: line_start,
+                    replacement: format!("{}\n\n", interface),
+                    label: format!("declare interface '{}'", self.type_name),
+                    priority: 0,
+                },
+                TextEdit {
+                    start: object_type.start_byte(),
+                    end: object_type.end_byte(),
+                    replacement: self.type_name.clone(),
+                    label: format!(
+                        "replace inline type of '{}' with '{}'",
+                        self.target, self.type_name
+                    ),
+                    priority: 0,
+                },
+            ],
+            vec![],
+        ))
+    }
+}
+
+impl ExtractInlineType {
+    fn find_object_type<'a>(
+        &self,
+        root: &'a Node<'a>,
+        source: &str,
+    ) -> Result<Node<'a>, OperationError> {
+        let mut candidates = Vec::new();
+
+        if let Some(ref function_name) = self.function_name {
+            let func = find_function_by_name(root, source, function_name).ok_or_else(|| {
+                OperationError::TargetNotFound {
+                    description: format!("Function '{}' not found", function_name),
+                }
+            })?;
+            if let Some(params) = find_formal_parameters(&func) {
+                for param in collect_param_nodes(&params) {
+                    if let Some(obj) =
+                        annotated_object_type(&param, "pattern", source, &self.target)
+                    {
+                        candidates.push(obj);
+                    }
+                }
+            }
+        } else {
+            collect_annotated_object_types(*root, source, &self.target, &mut candidates);
+        }
+
+        match candidates.len() {
+            0 => Err(OperationError::TargetNotFound {
+                description: format!("No inline object type found for '{}'", self.target),
+            }),
+            1 => Ok(candidates[0]),
+            count => Err(OperationError::AmbiguousMatch {
+                description: format!(
+                    "inline object type for '{}' (pass 'function_name' to disambiguate)",
+                    self.target
+                ),
+                count,
+                locations: candidates
+                    .iter()
+                    .map(|c| Location {
+                        line: c.start_position().row + 1,
+                        column: c.start_position().column + 1,
+                        context: node_text(c, source)
+                            .lines()
+                            .next()
+                            .unwrap_or("")
+                            .to_string(),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+// --- Helper functions ---
+
+/// Find a declaration of one of `kinds` whose `name` field equals `name`.
+fn find_declaration<'a>(
+    node: Node<'a>,
+    source: &str,
+    name: &str,
+    kinds: &[&str],
+) -> Option<Node<'a>> {
+    if kinds.contains(&node.kind()) {
+        if let Some(name_node) = node.child_by_field_name("name") {
+            if node_text(&name_node, source) == name {
+                return Some(node);
+            }
+        }
+    }
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            if let Some(found) = find_declaration(cursor.node(), source, name, kinds) {
+                return Some(found);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+    None
+}
+
+/// Find the member container of an interface or object-typed type alias.
+fn find_type_body<'a>(
+    root: Node<'a>,
+    source: &str,
+    name: &str,
+) -> Result<Node<'a>, OperationError> {
+    let decl = find_declaration(
+        root,
+        source,
+        name,
+        &["interface_declaration", "type_alias_declaration"],
+    )
+    .ok_or_else(|| OperationError::TargetNotFound {
+        description: format!("Interface or type alias '{}' not found", name),
+    })?;
+
+    let body = if decl.kind() == "interface_declaration" {
+        decl.child_by_field_name("body")
+    } else {
+        decl.child_by_field_name("value")
+    };
+
+    match body {
+        Some(b) if matches!(b.kind(), "interface_body" | "object_type") => Ok(b),
+        _ => Err(OperationError::InvalidParams {
+            message: format!("Type alias '{}' is not an object type", name),
+        }),
+    }
+}
+
+/// Member nodes of an interface body, object type or enum body (comments excluded).
+fn body_members<'a>(body: &Node<'a>) -> Vec<Node<'a>> {
+    let mut members = Vec::new();
+    let mut cursor = body.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            if child.is_named() && child.kind() != "comment" {
+                members.push(child);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+    members
+}
+
+/// The declared name of a member, if it has one.
+fn member_name<'s>(member: &Node, source: &'s str) -> Option<&'s str> {
+    match member.kind() {
+        "property_identifier" => Some(node_text(member, source)),
+        _ => member
+            .child_by_field_name("name")
+            .map(|n| node_text(&n, source)),
+    }
+}
+
+/// Derive the member name from source text like `readonly email?: string`.
+fn member_name_from_text(text: &str) -> Option<&str> {
+    let text = text.trim_start();
+    let text = text.strip_prefix("readonly ").unwrap_or(text).trim_start();
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(text.len());
+    if end == 0 {
+        None
+    } else {
+        Some(&text[..end])
+    }
+}
+
+/// The `;` or `,` token directly following a member, if any.
+fn separator_after<'a>(member: &Node<'a>) -> Option<Node<'a>> {
+    member
+        .next_sibling()
+        .filter(|s| matches!(s.kind(), ";" | ","))
+}
+
+/// Decide which separator new members of this body should use.
+///
+/// Existing members win; an empty body falls back to the file's
+/// semicolon style.
+fn body_separator(profile: &StyleProfile, body: &Node, members: &[Node]) -> &'static str {
+    if body.kind() == "enum_body" {
+        return ",";
+    }
+    for m in members {
+        if let Some(sep) = separator_after(m) {
+            return if sep.kind() == "," { "," } else { ";" };
+        }
+    }
+    if members.len() > 1 {
+        return ""; // newline-separated members
+    }
+    profile.semi()
+}
+
+/// Compute the edits that append `text` as the last member of `body`.
+fn append_member(
+    source: &str,
+    profile: &StyleProfile,
+    body: &Node,
+    members: &[Node],
+    separator: &str,
+    text: &str,
+    label: String,
+) -> Vec<TextEdit> {
+    let close = body.end_byte() - 1; // position of '}'
+    let body_prefix = format::extract_line_prefix(source, body.start_byte());
+    let member_indent = format::indent_deeper(&body_prefix, &profile.indent);
+
+    let last = match members.last() {
+        Some(last) => *last,
+        None => {
+            let replacement = if body.kind() == "object_type" {
+                let sep = if separator == "," { "" } else { separator };
+                format!(" {}{} ", text, sep)
+            } else {
+                format!("\n{}{}{}\n{}", member_indent, text, separator, body_prefix)
+            };
+            return vec![TextEdit {
+                start: body.start_byte() + 1,
+                end: close,
+                replacement,
+                label,
+                priority: 0,
+            }];
+        }
+    };
+
+    let last_sep = separator_after(&last);
+    let anchor = last_sep.map(|s| s.end_byte()).unwrap_or(last.end_byte());
+    let own_line = body.end_position().row > last.end_position().row;
+
+    if own_line {
+        // Insert on a new line after the last member (and its trailing comment)
+        let line_end = source[anchor..close]
+            .find('\n')
+            .map(|i| anchor + i)
+            .unwrap_or(close);
+        let indent = format::extract_line_prefix(source, last.start_byte());
+        let mut edits = Vec::new();
+        let new_sep = match last_sep {
+            Some(sep) => node_text(&sep, source).to_string(),
+            None => {
+                if separator == "," {
+                    edits.push(TextEdit {
+                        start: last.end_byte(),
+                        end: last.end_byte(),
+                        replacement: ",".to_string(),
+                        label: format!("{} (separator)", label),
+                        priority: 0,
+                    });
+                }
+                String::new()
+            }
+        };
+        edits.push(TextEdit {
+            start: line_end,
+            end: line_end,
+            replacement: format!("\n{}{}{}", indent, text, new_sep),
+            label,
+            priority: 0,
+        });
+        return edits;
+    }
+
+    // Single-line body: `{ a: string; b: number }`
+    let replacement = match last_sep {
+        Some(sep) => format!(" {}{}", text, node_text(&sep, source)),
+        None => {
+            let sep = if separator.is_empty() { "," } else { separator };
+            format!("{} {}", sep, text)
+        }
+    };
+    vec![TextEdit {
+        start: anchor,
+        end: anchor,
+        replacement,
+        label,
+        priority: 0,
+    }]
+}
+
+/// Compute the edit that removes `members[idx]` with its separator and comments.
+fn remove_member(source: &str, members: &[Node], idx: usize, label: String) -> TextEdit {
+    let member = members[idx];
+    let sep = separator_after(&member);
+    let comments = format::find_attached_comments(source, &member);
+
+    let start = comments
+        .leading
+        .first()
+        .map(|c| c.start)
+        .unwrap_or(member.start_byte());
+    let end = [
+        Some(member.end_byte()),
+        sep.map(|s| s.end_byte()),
+        comments.trailing.as_ref().map(|c| c.end),
+    ]
+    .into_iter()
+    .flatten()
+    .max()
+    .unwrap_or(member.end_byte());
+
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let rest_of_line = source[end..].find('\n').map(|i| end + i);
+    let starts_line = source[line_start..start].trim().is_empty();
+    let ends_line = rest_of_line.is_some_and(|nl| source[end..nl].trim().is_empty());
+
+    if starts_line && ends_line {
+        return TextEdit {
+            start: line_start,
+            end: rest_of_line.unwrap() + 1,
+            replacement: String::new(),
+            label,
+            priority: 0,
+        };
+    }
+
+    let (start, end) = if let Some(sep) = sep {
+        // Remove the member, its separator and the space after it
+        let after = source[sep.end_byte()..]
+            .find(|c: char| c != ' ' && c != '\t')
+            .map(|i| sep.end_byte() + i)
+            .unwrap_or(sep.end_byte());
+        (member.start_byte(), after)
+    } else if idx > 0 {
+        // Last member without separator: remove the preceding separator instead
+        let prev = members[idx - 1];
+        let prev_end = separator_after(&prev)
+            .map(|s| s.start_byte())
+            .unwrap_or(prev.end_byte());
+        (prev_end, member.end_byte())
+    } else {
+        (member.start_byte(), member.end_byte())
+    };
+
+    TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+        label,
+        priority: 0,
+    }
+}
+
+/// Flatten a (left-nested) union type into its constituents.
+fn flatten_union<'a>(node: &Node<'a>, out: &mut Vec<Node<'a>>) {
+    if node.kind() != "union_type" {
+        out.push(*node);
+        return;
+    }
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            if child.is_named() && child.kind() != "comment" {
+                flatten_union(&child, out);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// If `decl` names `target` and is annotated with an inline object type, return it.
+fn annotated_object_type<'a>(
+    decl: &Node<'a>,
+    name_field: &str,
+    source: &str,
+    target: &str,
+) -> Option<Node<'a>> {
+    let name = decl.child_by_field_name(name_field)?;
+    if node_text(&name, source) != target {
+        return None;
+    }
+    let annotation = decl.child_by_field_name("type")?;
+    let ty = find_child_by_kind(&annotation, "object_type")?;
+    Some(ty)
+}
+
+fn collect_annotated_object_types<'a>(
+    node: Node<'a>,
+    source: &str,
+    target: &str,
+    out: &mut Vec<Node<'a>>,
+) {
+    let found = match node.kind() {
+        "variable_declarator" => annotated_object_type(&node, "name", source, target),
+        "required_parameter" | "optional_parameter" => {
+            annotated_object_type(&node, "pattern", source, target)
+        }
+        _ => None,
+    };
+    if let Some(obj) = found {
+        out.push(obj);
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            collect_annotated_object_types(cursor.node(), source, target, out);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Walk up to the statement that is a direct child of the program root.
+fn top_level_statement(node: Node) -> Option<Node> {
+    let mut current = node;
+    loop {
+        let parent = current.parent()?;
+        if parent.kind() == "program" {
+            return Some(current);
+        }
+        current = parent;
+    }
+}
+
+/// Render an object type as a multi-line `export interface`.
+fn format_interface(
+    source: &str,
+    profile: &StyleProfile,
+    object_type: &Node,
+    name: &str,
+) -> String {
+    let indent = format::indent_deeper("", &profile.indent);
+    let separator = profile.semi();
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut last_member_row = None;
+    let mut cursor = object_type.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            if child.kind() == "comment" {
+                let text = node_text(&child, source);
+                match (last_member_row, lines.last_mut()) {
+                    (Some(row), Some(line)) if row == child.start_position().row => {
+                        line.push(' ');
+                        line.push_str(text);
+                    }
+                    _ => lines.push(format!("{}{}", indent, text)),
+                }
+            } else if child.is_named() {
+                lines.push(format!(
+                    "{}{}{}",
+                    indent,
+                    node_text(&child, source),
+                    separator
+                ));
+                last_member_row = Some(child.end_position().row);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    if lines.is_empty() {
+        format!("export interface {} {{}}", name)
+    } else {
+        format!("export interface {} {{\n{}\n}}", name, lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        let lang = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn apply(source: &str, edits: Vec<TextEdit>) -> String {
+        if edits.is_empty() {
+            return source.to_string();
+        }
+        let edit_set = EditSet::new(edits, source.len()).unwrap();
+        edit_set.apply(source)
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_ts(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = apply(source, edits);
+        assert!(
+            !parse_ts(&result).root_node().has_error(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        result
+    }
+
+    // --- add_type_member tests ---
+
+    #[test]
+    fn test_add_member_to_interface_with_semicolons() {
+        let source = "export interface User {\n  id: number; // the id\n  name: string;\n}\n";
+        let op = AddTypeMember::new("User".into(), "email?: string".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "export interface User {\n  id: number; // the id\n  name: string;\n  email?: string;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_member_keeps_comma_separators() {
+        let source = "interface Props {\n  a: string,\n  b: number\n}\n";
+        let op = AddTypeMember::new("Props".into(), "c: boolean".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "interface Props {\n  a: string,\n  b: number,\n  c: boolean\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_member_to_inline_type_alias() {
+        let source = "type Point = { x: number, y: number };\n";
+        let op = AddTypeMember::new("Point".into(), "z: number".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "type Point = { x: number, y: number, z: number };\n"
+        );
+    }
+
+    #[test]
+    fn test_add_member_to_empty_interface() {
+        let source = "const a = 1;\ninterface Empty {}\n";
+        let op = AddTypeMember::new("Empty".into(), "id: string".into());
+        let result = run(&op, source);
+        assert!(result.contains("interface Empty {\n  id: string;\n}"));
+    }
+
+    #[test]
+    fn test_add_existing_member_is_noop() {
+        let source = "interface User {\n  id: number;\n}\n";
+        let tree = parse_ts(source);
+        let op = AddTypeMember::new("User".into(), "id: string".into());
+        assert!(op.compute_edits(source, &tree).unwrap().is_empty());
+    }
+
+    // --- remove_type_member tests ---
+
+    #[test]
+    fn test_remove_member_with_comments() {
+        let source =
+            "interface User {\n  /** Primary key */\n  id: number; // the id\n  name: string;\n}\n";
+        let op = RemoveTypeMember::new("User".into(), "id".into());
+        let result = run(&op, source);
+        assert_eq!(result, "interface User {\n  name: string;\n}\n");
+    }
+
+    #[test]
+    fn test_remove_last_member_inline() {
+        let source = "type Point = { x: number, y: number };\n";
+        let op = RemoveTypeMember::new("Point".into(), "y".into());
+        let result = run(&op, source);
+        assert_eq!(result, "type Point = { x: number };\n");
+    }
+
+    #[test]
+    fn test_remove_missing_member_errors() {
+        let source = "interface User {\n  id: number;\n}\n";
+        let tree = parse_ts(source);
+        let op = RemoveTypeMember::new("User".into(), "email".into());
+        assert!(op.compute_edits(source, &tree).is_err());
+    }
+
+    // --- set_member_optional tests ---
+
+    #[test]
+    fn test_set_member_optional_and_back() {
+        let source = "interface User {\n  id: number;\n  name?: string;\n}\n";
+        let result = run(
+            &SetMemberOptional::new("User".into(), "id".into(), true),
+            source,
+        );
+        assert!(result.contains("id?: number;"));
+        let result = run(
+            &SetMemberOptional::new("User".into(), "name".into(), false),
+            source,
+        );
+        assert!(result.contains("  name: string;"));
+    }
+
+    // --- add_union_member tests ---
+
+    #[test]
+    fn test_add_union_member_inline() {
+        let source = "type Status = 'idle' | 'loading';\n";
+        let op = AddUnionMember::new("Status".into(), "'error'".into());
+        let result = run(&op, source);
+        assert_eq!(result, "type Status = 'idle' | 'loading' | 'error';\n");
+    }
+
+    #[test]
+    fn test_add_union_member_multiline_leading_pipe() {
+        let source = "type Status =\n  | 'idle'\n  | 'loading';\n";
+        let op = AddUnionMember::new("Status".into(), "'error'".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "type Status =\n  | 'idle'\n  | 'loading'\n  | 'error';\n"
+        );
+    }
+
+    #[test]
+    fn test_add_union_member_parenthesizes_function_type() {
+        let source = "type Handler = () => void;\n";
+        let op = AddUnionMember::new("Handler".into(), "null".into());
+        let result = run(&op, source);
+        assert_eq!(result, "type Handler = (() => void) | null;\n");
+    }
+
+    #[test]
+    fn test_add_existing_union_member_is_noop() {
+        let source = "type Status = 'idle' | 'loading';\n";
+        let tree = parse_ts(source);
+        let op = AddUnionMember::new("Status".into(), "'idle'".into());
+        assert!(op.compute_edits(source, &tree).unwrap().is_empty());
+    }
+
+    // --- add_enum_member tests ---
+
+    #[test]
+    fn test_add_enum_member_multiline() {
+        let source = "enum Color {\n  Red = 'red',\n  Green = 'green'\n}\n";
+        let op = AddEnumMember::new("Color".into(), "Blue".into(), Some("'blue'".into()));
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "enum Color {\n  Red = 'red',\n  Green = 'green',\n  Blue = 'blue'\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_enum_member_inline_trailing_comma() {
+        let source = "enum Dir { Up, Down, }\n";
+        let op = AddEnumMember::new("Dir".into(), "Left".into(), None);
+        let result = run(&op, source);
+        assert_eq!(result, "enum Dir { Up, Down, Left, }\n");
+    }
+
+    // --- extract_inline_type tests ---
+
+    #[test]
+    fn test_extract_inline_param_type() {
+        let source = "import { x } from './x';\n\n// Draws a box\nexport function draw(opts: { width: number; height: number /* px */ }) {\n  return opts;\n}\n";
+        let op = ExtractInlineType::new("opts".into(), Some("draw".into()), "DrawOptions".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "import { x } from './x';\n\nexport interface DrawOptions {\n  width: number;\n  height: number; /* px */\n}\n\n// Draws a box\nexport function draw(opts: DrawOptions) {\n  return opts;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_inline_variable_type() {
+        let source = "const config: { port: number } = { port: 80 };\n";
+        let op = ExtractInlineType::new("config".into(), None, "Config".into());
+        let result = run(&op, source);
+        assert!(result.starts_with("export interface Config {\n  port: number;\n}\n\n"));
+        assert!(result.contains("const config: Config = { port: 80 };"));
+    }
+
+    #[test]
+    fn test_extract_inline_type_ambiguous() {
+        let source = "function a(opts: { x: number }) {}\nfunction b(opts: { y: number }) {}\n";
+        let tree = parse_ts(source);
+        let op = ExtractInlineType::new("opts".into(), None, "Opts".into());
+        match op.compute_edits(source, &tree) {
+            Err(OperationError::AmbiguousMatch { count, .. }) => assert_eq!(count, 2),
+            other => panic!("Expected AmbiguousMatch, got {:?}", other.map(|e| e.len())),
+        }
+    }
+}
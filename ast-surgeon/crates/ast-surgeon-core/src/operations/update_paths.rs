@@ -18,6 +18,7 @@ pub enum MatchMode {
 }
 
 impl MatchMode {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self, OperationError> {
         match s.to_lowercase().as_str() {
             "exact" => Ok(Self::Exact),
@@ -0,0 +1,575 @@
+//! Vue `<script setup>` operations: `vue_add_to_setup` and `vue_wrap_in_ref`.
+//!
+//! Both run on the content of the script block. Splitting the SFC and
+//! mapping offsets back into the `.vue` file is done by the caller
+//! (see `ast_surgeon_lang::vue`).
+
+use crate::edit::TextEdit;
+use crate::format::{self, StyleConfig};
+use crate::operations::imports::AddImport;
+use crate::operations::node_utils::node_text;
+use crate::operations::{Executable, OperationError};
+use tree_sitter::{Node, Parser, Tree};
+
+/// The vue_add_to_setup operation.
+///
+/// Appends code to the setup script. Named and default imports are merged
+/// into an existing import from the same module (as `add_import` does);
+/// other import statements go after the existing imports and anything
+/// else goes at the end. Code that is already present verbatim is not
+/// added twice.
+pub struct AddToSetup {
+    pub code: String,
+}
+
+impl AddToSetup {
+    pub fn new(code: String) -> Self {
+        Self { code }
+    }
+}
+
+impl Executable for AddToSetup {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let code = self.code.trim();
+        if code.is_empty() {
+            return Err(OperationError::InvalidParams {
+                message: "code must be non-empty".to_string(),
+            });
+        }
+        if source.contains(code) {
+            return Ok((vec![], vec![])); // Already there -- no-op
+        }
+
+        // Split the snippet into mergeable imports and the rest
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree.language())
+            .expect("language version mismatch");
+        let mut edits = Vec::new();
+        let mut imports = Vec::new();
+        let mut rest = code.to_string();
+        if let Some(snippet) = parser
+            .parse(code, None)
+            .filter(|t| !t.root_node().has_error())
+        {
+            let snippet_root = snippet.root_node();
+            let mut cursor = snippet_root.walk();
+            let statements: Vec<Node> = snippet_root.named_children(&mut cursor).collect();
+            let mut kept = String::new();
+            let mut pos = 0;
+            for statement in statements.iter().filter(|s| s.kind() == "import_statement") {
+                let text = node_text(statement, code);
+                match mergeable_import(statement, code) {
+                    Some(import) => {
+                        edits.extend(import.compute_edits_with_style(source, tree, style)?.0)
+                    }
+                    None if source.contains(text) => {}
+                    None => imports.push(text.to_string()),
+                }
+                kept.push_str(&code[pos..statement.start_byte()]);
+                pos = statement.end_byte();
+            }
+            kept.push_str(&code[pos..]);
+            rest = kept.trim().to_string();
+        }
+
+        for import in imports {
+            edits.extend(append_to_setup(source, tree, &import, true));
+        }
+        if !rest.is_empty() && !source.contains(&rest) {
+            edits.extend(append_to_setup(source, tree, &rest, false));
+        }
+        Ok((edits, vec![]))
+    }
+}
+
+/// An `add_import` equivalent of a snippet import with a default and/or
+/// named specifiers; `None` for namespace and side-effect imports.
+fn mergeable_import(statement: &Node, source: &str) -> Option<AddImport> {
+    let module = statement.child_by_field_name("source")?;
+    let module = node_text(&module, source);
+    let module = module.get(1..module.len() - 1)?.to_string();
+    let clause = {
+        let mut cursor = statement.walk();
+        let found = statement
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "import_clause");
+        found
+    }?;
+    let type_only = {
+        let mut cursor = statement.walk();
+        let found = statement.children(&mut cursor).any(|c| c.kind() == "type");
+        found
+    };
+
+    let mut default_import = None;
+    let mut specifiers = Vec::new();
+    let mut cursor = clause.walk();
+    for part in clause.named_children(&mut cursor) {
+        match part.kind() {
+            "identifier" => default_import = Some(node_text(&part, source).to_string()),
+            "named_imports" => {
+                let mut inner = part.walk();
+                specifiers.extend(
+                    part.named_children(&mut inner)
+                        .filter(|s| s.kind() == "import_specifier")
+                        .map(|s| node_text(&s, source).to_string()),
+                );
+            }
+            _ => return None,
+        }
+    }
+    if default_import.is_none() && specifiers.is_empty() {
+        return None;
+    }
+    Some(AddImport::new(
+        module,
+        specifiers,
+        default_import,
+        type_only,
+    ))
+}
+
+/// Insert `code` after the last import (`after_imports`) or at the end
+/// of the script.
+fn append_to_setup(source: &str, tree: &Tree, code: &str, after_imports: bool) -> Vec<TextEdit> {
+    let root = tree.root_node();
+    let statements: Vec<Node> = {
+        let mut cursor = root.walk();
+        root.named_children(&mut cursor).collect()
+    };
+    let label = "add to <script setup>".to_string();
+
+    let Some(last) = statements.last() else {
+        return vec![TextEdit {
+            start: 0,
+            end: source.len(),
+            replacement: format!("{}\n", code),
+            label,
+            priority: 1,
+        }];
+    };
+
+    let prefix = format::extract_line_prefix(source, statements[0].start_byte());
+    let text = format::indent_code(code, &prefix);
+
+    let anchor = if after_imports {
+        statements
+            .iter()
+            .rev()
+            .find(|s| s.kind() == "import_statement")
+    } else {
+        None
+    };
+    let anchor = anchor.unwrap_or(last);
+
+    // Keep single-line statements together; separate blocks with a blank line
+    let multiline = code.contains('\n') || node_text(anchor, source).contains('\n');
+    let same_kind = after_imports == (anchor.kind() == "import_statement");
+    let separator = if multiline || !same_kind {
+        "\n\n"
+    } else {
+        "\n"
+    };
+
+    // After an import added to the same spot by add_import
+    vec![TextEdit {
+        start: anchor.end_byte(),
+        end: anchor.end_byte(),
+        replacement: format!("{}{}{}", separator, prefix, text),
+        label,
+        priority: 1,
+    }]
+}
+
+/// The vue_wrap_in_ref operation.
+///
+/// Turns a top-level `let count = 0` into `const count = ref(0)` and
+/// rewrites every script use of `count` to `count.value`. Template uses
+/// need no change since refs are unwrapped there. Adds the `ref` import.
+pub struct WrapInRef {
+    pub variable_name: String,
+}
+
+impl WrapInRef {
+    pub fn new(variable_name: String) -> Self {
+        Self { variable_name }
+    }
+}
+
+impl Executable for WrapInRef {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        source: &str,
+        tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let root = tree.root_node();
+        let name = self.variable_name.as_str();
+        let (declaration, declarator) =
+            find_top_level_declarator(&root, source, name).ok_or_else(|| {
+                OperationError::TargetNotFound {
+                    description: format!("No top-level variable '{}' in the setup script", name),
+                }
+            })?;
+
+        let value = declarator.child_by_field_name("value");
+        if value.is_some_and(|v| is_reactive_call(&v, source)) {
+            return Ok((vec![], vec![])); // Already a ref -- no-op
+        }
+
+        let label = format!("wrap {} in ref()", name);
+        let mut edits = Vec::new();
+
+        // `let` -> `const` when it is the only declarator
+        let declarators = {
+            let mut cursor = declaration.walk();
+            declaration
+                .named_children(&mut cursor)
+                .filter(|c| c.kind() == "variable_declarator")
+                .count()
+        };
+        if let Some(keyword) = declaration.child(0) {
+            if declarators == 1 && matches!(keyword.kind(), "let" | "var") {
+                edits.push(TextEdit {
+                    start: keyword.start_byte(),
+                    end: keyword.end_byte(),
+                    replacement: "const".to_string(),
+                    label: label.clone(),
+                    priority: 0,
+                });
+            }
+        }
+
+        // `count: number = 0` -> `count = ref<number>(0)`
+        let name_node = declarator.child_by_field_name("name").unwrap();
+        let type_arg = declarator.child_by_field_name("type").map(|t| {
+            node_text(&t, source)
+                .trim_start_matches(':')
+                .trim()
+                .to_string()
+        });
+        let call = format!(
+            "ref{}({})",
+            type_arg.map(|t| format!("<{}>", t)).unwrap_or_default(),
+            value.map(|v| node_text(&v, source)).unwrap_or("")
+        );
+        edits.push(TextEdit {
+            start: name_node.end_byte(),
+            end: declarator.end_byte(),
+            replacement: format!(" = {}", call),
+            label: label.clone(),
+            priority: 0,
+        });
+
+        // Every other use becomes `.value`
+        let mut uses = Vec::new();
+        collect_uses(&root, source, name, &name_node, &mut uses);
+        for (node, shorthand) in uses {
+            let replacement = if shorthand {
+                format!("{}: {}.value", name, name)
+            } else {
+                format!("{}.value", name)
+            };
+            edits.push(TextEdit {
+                start: node.start_byte(),
+                end: node.end_byte(),
+                replacement,
+                label: label.clone(),
+                priority: 0,
+            });
+        }
+
+        edits.extend(
+            AddImport::new("vue".to_string(), vec!["ref".to_string()], None, false)
+                .compute_edits_with_style(source, tree, style)?
+                .0,
+        );
+        Ok((edits, vec![]))
+    }
+}
+
+// --- Helper functions ---
+
+fn find_top_level_declarator<'a>(
+    root: &Node<'a>,
+    source: &str,
+    name: &str,
+) -> Option<(Node<'a>, Node<'a>)> {
+    let mut cursor = root.walk();
+    for stmt in root.named_children(&mut cursor) {
+        let declaration = match stmt.kind() {
+            "lexical_declaration" | "variable_declaration" => stmt,
+            "export_statement" => match stmt.child_by_field_name("declaration") {
+                Some(d) if matches!(d.kind(), "lexical_declaration" | "variable_declaration") => d,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        let mut decl_cursor = declaration.walk();
+        let found = declaration
+            .named_children(&mut decl_cursor)
+            .filter(|c| c.kind() == "variable_declarator")
+            .find(|d| {
+                d.child_by_field_name("name")
+                    .is_some_and(|n| n.kind() == "identifier" && node_text(&n, source) == name)
+            });
+        if let Some(declarator) = found {
+            return Some((declaration, declarator));
+        }
+    }
+    None
+}
+
+fn is_reactive_call(value: &Node, source: &str) -> bool {
+    value.kind() == "call_expression"
+        && value.child_by_field_name("function").is_some_and(|f| {
+            matches!(
+                node_text(&f, source),
+                "ref" | "shallowRef" | "computed" | "reactive" | "toRef"
+            )
+        })
+}
+
+/// Identifier uses of `name` that refer to the top-level binding, paired
+/// with whether they are object shorthand (`{ count }`).
+fn collect_uses<'a>(
+    node: &Node<'a>,
+    source: &str,
+    name: &str,
+    declared: &Node,
+    out: &mut Vec<(Node<'a>, bool)>,
+) {
+    let kind = node.kind();
+    if matches!(kind, "identifier" | "shorthand_property_identifier")
+        && node_text(node, source) == name
+        && node.id() != declared.id()
+        && !is_import_binding(node)
+        && !is_shadowed(node, source, name)
+    {
+        out.push((*node, kind == "shorthand_property_identifier"));
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if !matches!(child.kind(), "string" | "template_string" | "comment") {
+            collect_uses(&child, source, name, declared, out);
+        } else if child.kind() == "template_string" {
+            // Only the substitutions are code
+            let mut sub_cursor = child.walk();
+            for sub in child.named_children(&mut sub_cursor) {
+                collect_uses(&sub, source, name, declared, out);
+            }
+        }
+    }
+}
+
+fn is_import_binding(node: &Node) -> bool {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if n.kind() == "import_statement" {
+            return true;
+        }
+        current = n.parent();
+    }
+    false
+}
+
+/// Whether a scope between `node` and the program root declares `name`.
+fn is_shadowed(node: &Node, source: &str, name: &str) -> bool {
+    let mut current = node.parent();
+    while let Some(scope) = current {
+        if scope.kind() == "program" {
+            return false;
+        }
+        let declares = match scope.kind() {
+            "function_declaration"
+            | "function_expression"
+            | "arrow_function"
+            | "method_definition" => scope
+                .child_by_field_name("parameters")
+                .or_else(|| scope.child_by_field_name("parameter"))
+                .is_some_and(|p| pattern_binds(&p, source, name)),
+            "statement_block" | "for_statement" | "for_in_statement" | "catch_clause" => {
+                let mut cursor = scope.walk();
+                let children: Vec<Node> = scope.named_children(&mut cursor).collect();
+                children.iter().any(|c| match c.kind() {
+                    "lexical_declaration" | "variable_declaration" => {
+                        let mut decl_cursor = c.walk();
+                        let bound = c
+                            .named_children(&mut decl_cursor)
+                            .filter_map(|d| d.child_by_field_name("name"))
+                            .any(|n| pattern_binds(&n, source, name));
+                        bound
+                    }
+                    "function_declaration" | "class_declaration" => c
+                        .child_by_field_name("name")
+                        .is_some_and(|n| node_text(&n, source) == name),
+                    _ => false,
+                }) || scope
+                    .child_by_field_name("left")
+                    .or_else(|| scope.child_by_field_name("parameter"))
+                    .is_some_and(|p| pattern_binds(&p, source, name))
+            }
+            _ => false,
+        };
+        if declares {
+            return true;
+        }
+        current = scope.parent();
+    }
+    false
+}
+
+/// Whether a parameter list or binding pattern introduces `name`.
+fn pattern_binds(pattern: &Node, source: &str, name: &str) -> bool {
+    if matches!(
+        pattern.kind(),
+        "identifier" | "shorthand_property_identifier_pattern"
+    ) {
+        return node_text(pattern, source) == name;
+    }
+    let mut cursor = pattern.walk();
+    let children: Vec<Node> = pattern.named_children(&mut cursor).collect();
+    children.iter().any(|c| {
+        // Skip type annotations and default values
+        if matches!(c.kind(), "type_annotation") {
+            return false;
+        }
+        if pattern.kind() == "assignment_pattern"
+            && pattern.child_by_field_name("right").map(|r| r.id()) == Some(c.id())
+        {
+            return false;
+        }
+        if pattern.kind() == "pair_pattern"
+            && pattern.child_by_field_name("key").map(|k| k.id()) == Some(c.id())
+        {
+            return false;
+        }
+        pattern_binds(c, source, name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edit::EditSet;
+    use tree_sitter::Parser;
+
+    fn parse_ts(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        let lang = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        parser.set_language(&lang).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn run(op: &dyn Executable, source: &str) -> String {
+        let tree = parse_ts(source);
+        let edits = op.compute_edits(source, &tree).unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(
+            !parse_ts(&result).root_node().has_error(),
+            "Result has syntax errors:\n{}",
+            result
+        );
+        result
+    }
+
+    #[test]
+    fn test_add_code_at_end() {
+        let source = "import { ref } from 'vue'\n\nconst a = ref(1)\n";
+        let result = run(&AddToSetup::new("const b = ref(2)".into()), source);
+        assert_eq!(
+            result,
+            "import { ref } from 'vue'\n\nconst a = ref(1)\nconst b = ref(2)\n"
+        );
+    }
+
+    #[test]
+    fn test_added_imports_follow_style() {
+        let source = "let count = 0\n";
+        let style = StyleConfig {
+            quote: Some('"'),
+            semicolons: Some(true),
+            ..StyleConfig::default()
+        };
+        let tree = parse_ts(source);
+        let (edits, _) = WrapInRef::new("count".into())
+            .compute_edits_with_style(source, &tree, &style)
+            .unwrap();
+        let result = EditSet::new(edits, source.len()).unwrap().apply(source);
+        assert!(result.starts_with("import { ref } from \"vue\";\n"));
+
+        let (edits, _) = AddToSetup::new("import { computed } from 'vue'".into())
+            .compute_edits_with_style(&result, &parse_ts(&result), &style)
+            .unwrap();
+        let result = EditSet::new(edits, result.len()).unwrap().apply(&result);
+        assert!(result.starts_with("import { ref, computed } from \"vue\";\n"));
+    }
+
+    #[test]
+    fn test_add_import_after_imports_and_is_idempotent() {
+        let source = "import { ref } from 'vue'\n\nconst a = ref(1)\n";
+        let op = AddToSetup::new("import Child from './Child.vue'".into());
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "import { ref } from 'vue'\nimport Child from './Child.vue'\n\nconst a = ref(1)\n"
+        );
+        assert_eq!(run(&op, &result), result);
+    }
+
+    #[test]
+    fn test_add_merges_imports_into_existing() {
+        let source = "import { ref } from 'vue'\n\nconst a = ref(1)\n";
+        let op = AddToSetup::new(
+            "import { ref, computed } from 'vue'\nimport * as api from './api'\n\nconst b = computed(() => a.value * 2)".into(),
+        );
+        let result = run(&op, source);
+        assert_eq!(
+            result,
+            "import { ref, computed } from 'vue'\nimport * as api from './api'\n\nconst a = ref(1)\nconst b = computed(() => a.value * 2)\n"
+        );
+        assert_eq!(run(&op, &result), result);
+    }
+
+    #[test]
+    fn test_wrap_in_ref_rewrites_uses() {
+        let source = "import { computed } from 'vue';\n\nlet count: number = 0;\nconst double = computed(() => count * 2);\nfunction inc() {\n  count++;\n  emit('change', { count });\n}\nfunction reset(count: number) {\n  return count;\n}\n";
+        let result = run(&WrapInRef::new("count".into()), source);
+        assert_eq!(
+            result,
+            "import { computed, ref } from 'vue';\n\nconst count = ref<number>(0);\nconst double = computed(() => count.value * 2);\nfunction inc() {\n  count.value++;\n  emit('change', { count: count.value });\n}\nfunction reset(count: number) {\n  return count;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_wrap_in_ref_is_noop_on_ref() {
+        let source = "import { ref } from 'vue'\nconst count = ref(0)\n";
+        assert_eq!(run(&WrapInRef::new("count".into()), source), source);
+    }
+
+    #[test]
+    fn test_wrap_in_ref_missing_variable() {
+        let source = "function f() { let count = 0; }\n";
+        let tree = parse_ts(source);
+        assert!(matches!(
+            WrapInRef::new("count".into()).compute_edits(source, &tree),
+            Err(OperationError::TargetNotFound { .. })
+        ));
+    }
+}
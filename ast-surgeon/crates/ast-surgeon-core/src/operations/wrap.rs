@@ -4,7 +4,7 @@
 //! (if, try-catch, for, plain block).
 
 use crate::edit::TextEdit;
-use crate::format;
+use crate::format::{self, StyleConfig};
 use crate::operations::{Executable, OperationError};
 use tree_sitter::Tree;
 
@@ -39,11 +39,17 @@ impl WrapInBlock {
 }
 
 impl Executable for WrapInBlock {
-    fn compute_edits(
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_style(
         &self,
         source: &str,
         _tree: &Tree,
-    ) -> Result<Vec<TextEdit>, OperationError> {
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
         if self.start_line == 0 || self.end_line == 0 || self.start_line > self.end_line {
             return Err(OperationError::InvalidParams {
                 message: format!(
@@ -72,7 +78,7 @@ impl Executable for WrapInBlock {
         // Detect indentation from the first line
         let first_line = lines[self.start_line - 1];
         let base_indent = extract_leading_whitespace(first_line);
-        let indent_style = format::infer_indent_style(source);
+        let indent_style = style.resolve(source).indent;
         let indent_unit = match indent_style {
             format::IndentStyle::Spaces(n) => " ".repeat(n as usize),
             format::IndentStyle::Tabs => "\t".to_string(),
@@ -88,41 +94,15 @@ impl Executable for WrapInBlock {
             .collect();
         let wrapped_body = wrapped_lines.join("\n");
 
-        // Build the replacement
-        let replacement = match &self.wrap_kind {
-            WrapKind::If { condition } => {
-                format!(
-                    "{}if ({}) {{\n{}\n{}}}",
-                    base_indent, condition, wrapped_body, base_indent
-                )
-            }
-            WrapKind::TryCatch { catch_param } => {
-                format!(
-                    "{}try {{\n{}\n{}}} catch ({}) {{\n{}{}\n{}}}",
-                    base_indent,
-                    wrapped_body,
-                    base_indent,
-                    catch_param,
-                    base_indent,
-                    indent_unit,
-                    base_indent
-                )
-            }
-            WrapKind::ForOf { item, iterable } => {
-                format!(
-                    "{}for (const {} of {}) {{\n{}\n{}}}",
-                    base_indent, item, iterable, wrapped_body, base_indent
-                )
-            }
-            WrapKind::Block => {
-                format!(
-                    "{}{{\n{}\n{}}}",
-                    base_indent, wrapped_body, base_indent
-                )
-            }
-        };
+        let replacement = render_wrapped(
+            &self.wrap_kind,
+            base_indent,
+            &indent_unit,
+            &wrapped_body,
+            None,
+        );
 
-        Ok(vec![TextEdit {
+        let edit = TextEdit {
             start: start_byte,
             end: end_byte,
             replacement,
@@ -131,7 +111,8 @@ impl Executable for WrapInBlock {
                 self.start_line, self.end_line, self.wrap_kind_name()
             ),
             priority: 0,
-        }])
+        };
+        Ok((vec![edit], vec![]))
     }
 }
 
@@ -146,6 +127,48 @@ impl WrapInBlock {
     }
 }
 
+/// Render `body` (already indented one level below `base_indent`) inside
+/// the block for `kind`. For `TryCatch`, `handler` is the catch body; an
+/// empty catch block is produced when it is `None`.
+pub(crate) fn render_wrapped(
+    kind: &WrapKind,
+    base_indent: &str,
+    indent_unit: &str,
+    wrapped_body: &str,
+    handler: Option<&str>,
+) -> String {
+    match kind {
+        WrapKind::If { condition } => {
+            format!(
+                "{}if ({}) {{\n{}\n{}}}",
+                base_indent, condition, wrapped_body, base_indent
+            )
+        }
+        WrapKind::TryCatch { catch_param } => {
+            let handler = match handler {
+                Some(h) => h.to_string(),
+                None => format!("{}{}", base_indent, indent_unit),
+            };
+            format!(
+                "{}try {{\n{}\n{}}} catch ({}) {{\n{}\n{}}}",
+                base_indent, wrapped_body, base_indent, catch_param, handler, base_indent
+            )
+        }
+        WrapKind::ForOf { item, iterable } => {
+            format!(
+                "{}for (const {} of {}) {{\n{}\n{}}}",
+                base_indent, item, iterable, wrapped_body, base_indent
+            )
+        }
+        WrapKind::Block => {
+            format!(
+                "{}{{\n{}\n{}}}",
+                base_indent, wrapped_body, base_indent
+            )
+        }
+    }
+}
+
 /// Get the byte offset of the start of a 1-indexed line.
 fn line_start_byte(source: &str, line: usize) -> usize {
     let mut current_line = 1;
@@ -1,6 +1,7 @@
 //! Post-edit validation: re-parse and check for syntax errors.
 
 use serde::Serialize;
+use std::borrow::Cow;
 use thiserror::Error;
 use tree_sitter::{Parser, Tree};
 
@@ -29,6 +30,63 @@ pub struct SyntaxError {
     pub node_kind: String,
 }
 
+/// How an edited file's re-parse is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseCheck {
+    /// The result must parse without errors.
+    Clean,
+    /// The result may keep the source's parse errors but must not add
+    /// any. For languages parsed with a stand-in grammar (Less under the
+    /// CSS grammar), where valid files can have ERROR nodes.
+    NoNewErrors,
+    /// `NoNewErrors` for SCSS, with `$variables` masked before every
+    /// parse (see [`mask_scss_variables`]) so declarations and values
+    /// using them get ordinary nodes.
+    ScssNoNewErrors,
+}
+
+impl ParseCheck {
+    /// The text the parser sees for `source`; same length, so byte
+    /// offsets in the tree apply to `source`.
+    pub fn parse_input<'a>(&self, source: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::ScssNoNewErrors => mask_scss_variables(source),
+            _ => Cow::Borrowed(source),
+        }
+    }
+
+    /// Parse `source` (best-effort) the way this check expects.
+    pub fn parse(
+        &self,
+        source: &str,
+        language: &tree_sitter::Language,
+    ) -> Result<Tree, ValidationError> {
+        parse_best_effort(&self.parse_input(source), language)
+    }
+}
+
+/// Replace the `$` of SCSS variables with `-`. The CSS grammar has no
+/// node for `$name` but reads `-name` as a plain value (or, before a
+/// colon, a property), so `$brand: #333;` and `color: $brand;` parse as
+/// declarations.
+pub fn mask_scss_variables(source: &str) -> Cow<'_, str> {
+    let bytes = source.as_bytes();
+    let is_variable = |i: usize| {
+        bytes[i] == b'$'
+            && bytes
+                .get(i + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_' || *b == b'-')
+    };
+    if !(0..bytes.len()).any(is_variable) {
+        return Cow::Borrowed(source);
+    }
+    let masked: String = source
+        .char_indices()
+        .map(|(i, c)| if is_variable(i) { '-' } else { c })
+        .collect();
+    Cow::Owned(masked)
+}
+
 /// Verify that `source` parses cleanly with the given language.
 ///
 /// Returns `Ok(tree)` if the parse tree has no ERROR or MISSING nodes,
@@ -0,0 +1,310 @@
+//! CSS, SCSS and Less language support.
+//!
+//! All three are parsed with the CSS grammar, which handles nested rules,
+//! the `&` nesting selector and `//` comments. SCSS `$variables` are
+//! masked as `-variables` before parsing (see
+//! [`ast_surgeon_core::validate::mask_scss_variables`]), so `$brand: #333;`
+//! and `color: $brand;` are ordinary declarations. Less `@variables` and
+//! SCSS interpolation have no nodes and show up as ERROR nodes, so edits
+//! to those files are only checked for not adding new errors. The
+//! indented `.sass` syntax is not supported.
+
+/// Query to find all rule sets, including nested ones.
+pub const RULE_SETS_QUERY: &str = r#"
+(rule_set
+  (selectors) @selectors
+  (block) @block
+) @rule
+"#;
+
+/// Query to find rule sets nested inside another rule (SCSS/Less nesting).
+pub const NESTED_RULE_SETS_QUERY: &str = r#"
+(rule_set
+  (block
+    (rule_set
+      (selectors) @selectors) @nested)
+) @parent
+"#;
+
+/// Query to find all declarations with their property name.
+pub const DECLARATIONS_QUERY: &str = r#"
+(declaration
+  (property_name) @property
+) @declaration
+"#;
+
+/// Query to find custom property declarations (`--name: value`).
+pub const CUSTOM_PROPERTIES_QUERY: &str = r#"
+(declaration
+  (property_name) @property
+  (#match? @property "^--")
+) @declaration
+"#;
+
+/// Query to find class names used in selectors.
+pub const CLASS_SELECTORS_QUERY: &str = r#"
+(class_selector
+  (class_name) @class
+) @selector
+"#;
+
+/// Query to find `@media` blocks.
+pub const MEDIA_QUERY: &str = r#"
+(media_statement
+  (block) @block
+) @media
+"#;
+
+/// Query to find `@import` statements.
+pub const IMPORTS_QUERY: &str = r#"
+(import_statement) @import
+"#;
+
+/// Query to find at-rules the CSS grammar has no specific node for, such
+/// as SCSS `@include`/`@extend` or Less guards.
+pub const AT_RULES_QUERY: &str = r#"
+[
+  (postcss_statement
+    (at_keyword) @keyword) @statement
+  (at_rule
+    (at_keyword) @keyword) @statement
+]
+"#;
+
+/// Query to find `//` line comments (SCSS/Less only).
+pub const LINE_COMMENTS_QUERY: &str = r#"
+(js_comment) @comment
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SupportedLanguage;
+    use ast_surgeon_core::format::StyleConfig;
+    use ast_surgeon_core::operations::Operation;
+    use streaming_iterator::StreamingIterator;
+    use tree_sitter::{Parser, Query, QueryCursor};
+
+    fn matches(query: &str, source: &str) -> usize {
+        let lang = tree_sitter_css::LANGUAGE.into();
+        let mut parser = Parser::new();
+        parser.set_language(&lang).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let query = Query::new(&lang, query).unwrap();
+        let mut cursor = QueryCursor::new();
+        let mut it = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        let mut count = 0;
+        while it.next().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_queries_compile() {
+        for query in [
+            RULE_SETS_QUERY,
+            NESTED_RULE_SETS_QUERY,
+            DECLARATIONS_QUERY,
+            CUSTOM_PROPERTIES_QUERY,
+            CLASS_SELECTORS_QUERY,
+            MEDIA_QUERY,
+            IMPORTS_QUERY,
+            AT_RULES_QUERY,
+            LINE_COMMENTS_QUERY,
+        ] {
+            matches(query, "");
+        }
+    }
+
+    #[test]
+    fn test_nested_rules_and_comments() {
+        let source = ".card {\n  // hover state\n  &:hover { color: red; }\n  .title { margin: 0; }\n  @include shadow(2px);\n}\n";
+        assert_eq!(matches(RULE_SETS_QUERY, source), 3);
+        assert_eq!(matches(NESTED_RULE_SETS_QUERY, source), 2);
+        assert_eq!(matches(LINE_COMMENTS_QUERY, source), 1);
+        assert_eq!(matches(AT_RULES_QUERY, source), 1);
+    }
+
+    const NESTED_SCSS: &str = "$brand: #333;\n\n.card {\n  color: $brand;\n  // hover state\n  &:hover {\n    color: red;\n  }\n  .title {\n    margin: 0;\n  }\n  @include shadow(2px);\n}\n";
+
+    fn run_scss(ops: Vec<Operation>) -> String {
+        crate::execute_operations(
+            NESTED_SCSS,
+            SupportedLanguage::Scss,
+            &ops,
+            &StyleConfig::default(),
+        )
+        .unwrap()
+        .content
+    }
+
+    #[test]
+    fn test_scss_set_declaration_keeps_nesting() {
+        let content = run_scss(vec![Operation::SetDeclaration {
+            file: None,
+            template: None,
+            selector: ".card".into(),
+            property: "padding".into(),
+            value: "4px".into(),
+            media: None,
+        }]);
+        assert_eq!(
+            content,
+            "$brand: #333;\n\n.card {\n  color: $brand;\n  padding: 4px;\n  // hover state\n  &:hover {\n    color: red;\n  }\n  .title {\n    margin: 0;\n  }\n  @include shadow(2px);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_scss_variable_values() {
+        let content = run_scss(vec![
+            Operation::RemoveDeclaration {
+                file: None,
+                template: None,
+                selector: ".card".into(),
+                property: "color".into(),
+                media: None,
+            },
+            Operation::SetDeclaration {
+                file: None,
+                template: None,
+                selector: ".card .title".into(),
+                property: "color".into(),
+                value: "$brand".into(),
+                media: None,
+            },
+            Operation::SetDeclaration {
+                file: None,
+                template: None,
+                selector: ".card:hover".into(),
+                property: "color".into(),
+                value: "darken($brand, 10%)".into(),
+                media: None,
+            },
+        ]);
+        assert_eq!(
+            content,
+            "$brand: #333;\n\n.card {\n  // hover state\n  &:hover {\n    color: darken($brand, 10%);\n  }\n  .title {\n    margin: 0;\n    color: $brand;\n  }\n  @include shadow(2px);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_scss_variable_before_nested_rule() {
+        let source = ".card {\n  $gap: 4px;\n  .title {\n    margin: $gap;\n  }\n}\n";
+        let ops = vec![
+            Operation::SetDeclaration {
+                file: None,
+                template: None,
+                selector: ".card".into(),
+                property: "$gap".into(),
+                value: "8px".into(),
+                media: None,
+            },
+            Operation::SetDeclaration {
+                file: None,
+                template: None,
+                selector: ".card".into(),
+                property: "padding".into(),
+                value: "$gap".into(),
+                media: None,
+            },
+        ];
+        let content = crate::execute_operations(
+            source,
+            SupportedLanguage::Scss,
+            &ops,
+            &StyleConfig::default(),
+        )
+        .unwrap()
+        .content;
+        assert_eq!(
+            content,
+            ".card {\n  $gap: 8px;\n  padding: $gap;\n  .title {\n    margin: $gap;\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_scss_rename_selector_in_nested_rules() {
+        let content = run_scss(vec![Operation::RenameSelector {
+            file: None,
+            template: None,
+            from: ".title".into(),
+            to: ".heading".into(),
+        }]);
+        assert_eq!(
+            content,
+            "$brand: #333;\n\n.card {\n  color: $brand;\n  // hover state\n  &:hover {\n    color: red;\n  }\n  .heading {\n    margin: 0;\n  }\n  @include shadow(2px);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_scss_add_rule_and_remove_nested_declaration() {
+        let content = run_scss(vec![
+            Operation::AddRule {
+                file: None,
+                template: None,
+                selector: ".footer".into(),
+                declarations: vec!["padding: 8px".into()],
+                media: None,
+            },
+            Operation::RemoveDeclaration {
+                file: None,
+                template: None,
+                selector: ".title".into(),
+                property: "margin".into(),
+                media: None,
+            },
+        ]);
+        assert_eq!(
+            content,
+            "$brand: #333;\n\n.card {\n  color: $brand;\n  // hover state\n  &:hover {\n    color: red;\n  }\n  .title {\n  }\n  @include shadow(2px);\n}\n\n.footer {\n  padding: 8px;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_scss_edit_that_adds_errors_is_refused() {
+        // Pre-existing `$variable` errors are tolerated; new ones are not.
+        let ops = vec![Operation::SetDeclaration {
+            file: None,
+            template: None,
+            selector: ".card".into(),
+            property: "padding".into(),
+            value: "4px; }".into(),
+            media: None,
+        }];
+        assert!(crate::execute_operations(
+            NESTED_SCSS,
+            SupportedLanguage::Scss,
+            &ops,
+            &StyleConfig::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_css_with_errors_is_refused() {
+        // Plain CSS keeps the strict check.
+        let ops = vec![Operation::SetDeclaration {
+            file: None,
+            template: None,
+            selector: ".card".into(),
+            property: "padding".into(),
+            value: "4px".into(),
+            media: None,
+        }];
+        assert!(crate::execute_operations(
+            NESTED_SCSS,
+            SupportedLanguage::Css,
+            &ops,
+            &StyleConfig::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_custom_properties() {
+        let source = ":root {\n  --brand: red;\n  color: blue;\n}\n";
+        assert_eq!(matches(CUSTOM_PROPERTIES_QUERY, source), 1);
+        assert_eq!(matches(DECLARATIONS_QUERY, source), 2);
+    }
+}
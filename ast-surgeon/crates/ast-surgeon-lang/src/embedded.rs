@@ -0,0 +1,602 @@
+//! Languages embedded in tagged template literals.
+//!
+//! styled-components / emotion (`styled.x`, `css`, `keyframes`,
+//! `createGlobalStyle`) and lit's `html` / `svg` templates are found in a
+//! script's tree. Their content is parsed with the matching
+//! grammar as a sub-document whose offsets map 1:1 back into the file:
+//! every `${...}` interpolation is masked with a placeholder of the same
+//! length, so CSS operations and queries can run inside the template
+//! while interpolations stay opaque and are never edited.
+
+use crate::{QueryCapture, SupportedLanguage};
+use ast_surgeon_core::edit::TextEdit;
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::{Executable, Operation, OperationError, OperationResult};
+use ast_surgeon_core::validate::ParseCheck;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Parser, Query, QueryCursor, QueryError, Tree};
+
+/// A tagged template literal with a known tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedTemplate {
+    /// Tag expression without type arguments, e.g. `styled.button` or `css`.
+    pub tag: String,
+    /// Variable the template is assigned to, e.g. `Button`.
+    pub binding: Option<String>,
+    /// Grammar for the content.
+    pub language: SupportedLanguage,
+    /// Byte range of the content between the backticks.
+    pub start: usize,
+    pub end: usize,
+    /// Byte ranges of the `${...}` interpolations.
+    pub placeholders: Vec<(usize, usize)>,
+}
+
+/// A parsed template: masked content plus its tree.
+pub struct SubDocument {
+    pub text: String,
+    pub tree: Tree,
+    /// Bytes added before the content (styled templates are wrapped in
+    /// `& { ... }` so bare declarations form a rule).
+    prefix_len: usize,
+    start: usize,
+    end: usize,
+    placeholders: Vec<(usize, usize)>,
+}
+
+impl EmbeddedTemplate {
+    /// Whether bare declarations need wrapping in a rule to parse.
+    fn wraps_declarations(&self) -> bool {
+        self.language == SupportedLanguage::Css
+            && !matches!(self.tag.as_str(), "createGlobalStyle" | "injectGlobal")
+    }
+
+    /// Parse the content with the template's grammar.
+    pub fn parse(&self, source: &str) -> Option<SubDocument> {
+        let (prefix, suffix) = if self.wraps_declarations() {
+            ("& {", "}")
+        } else {
+            ("", "")
+        };
+        let text = format!("{}{}{}", prefix, mask(source, self), suffix);
+
+        let mut parser = Parser::new();
+        parser.set_language(&self.language.ts_language()).ok()?;
+        let tree = parser.parse(&text, None)?;
+        Some(SubDocument {
+            text,
+            tree,
+            prefix_len: prefix.len(),
+            start: self.start,
+            end: self.end,
+            placeholders: self.placeholders.clone(),
+        })
+    }
+}
+
+impl SubDocument {
+    /// Map a sub-document offset to a file offset; `None` inside the
+    /// wrapping added around the content.
+    pub fn to_file_offset(&self, offset: usize) -> Option<usize> {
+        let offset = offset.checked_sub(self.prefix_len)? + self.start;
+        (offset <= self.end).then_some(offset)
+    }
+
+    /// Map an edit of the sub-document into the file. Insertions before
+    /// or after the `& { ... }` wrapper land at the start or end of the
+    /// template body, indented like it, and removing the whole wrapper
+    /// empties the body.
+    fn map_edit(&self, edit: TextEdit) -> Result<TextEdit, OperationError> {
+        let body_start = self.prefix_len;
+        let body_end = self.prefix_len + (self.end - self.start);
+        let body = &self.text[body_start..body_end];
+
+        let (start, end, replacement) = if edit.start >= body_start && edit.end <= body_end {
+            (
+                self.start + edit.start - body_start,
+                self.start + edit.end - body_start,
+                edit.replacement.clone(),
+            )
+        } else if edit.start == edit.end {
+            let block = indent_block(&edit.replacement, body_indent(body));
+            if body.trim().is_empty() {
+                (self.start, self.end, format!("\n{}\n", block))
+            } else if edit.start < body_start {
+                let first = body.len() - body.trim_start().len();
+                let at = self.start + body[..first].rfind('\n').map_or(0, |i| i + 1);
+                (at, at, format!("{}\n\n", block))
+            } else {
+                let at = self.start + body.trim_end().len();
+                (at, at, format!("\n\n{}", block))
+            }
+        } else if edit.start == 0 && edit.end == self.text.len() && edit.replacement.is_empty() {
+            (self.start, self.end, String::new())
+        } else {
+            return Err(OperationError::InvalidParams {
+                message: format!(
+                    "'{}' would edit the `& {{ }}` rule wrapped around the template",
+                    edit.label
+                ),
+            });
+        };
+
+        if !self.keeps_interpolations(start, end, &replacement) {
+            return Err(OperationError::InvalidParams {
+                message: format!("'{}' would change a ${{}} interpolation", edit.label),
+            });
+        }
+        Ok(TextEdit {
+            start,
+            end,
+            replacement,
+            ..edit
+        })
+    }
+
+    /// Whether an edit (in file offsets) leaves interpolations intact: it
+    /// may replace or delete a whole `${...}`, but not cut into one or
+    /// carry its placeholder text into the file.
+    fn keeps_interpolations(&self, start: usize, end: usize, replacement: &str) -> bool {
+        self.placeholders.iter().all(|&(p_start, p_end)| {
+            if start >= p_end || end <= p_start {
+                return true;
+            }
+            let at = self.prefix_len + p_start - self.start;
+            let masked = &self.text[at..at + (p_end - p_start)];
+            start <= p_start && end >= p_end && !replacement.contains(masked)
+        })
+    }
+}
+
+/// Find tagged templates with known tags in a script's tree.
+pub fn find_templates(source: &str, tree: &Tree) -> Vec<EmbeddedTemplate> {
+    let mut templates = Vec::new();
+    collect_templates(&tree.root_node(), source, &mut templates);
+    templates
+}
+
+/// Run a tree-sitter query inside every template of the given language.
+/// Captures are reported in file offsets.
+pub fn query_templates(
+    source: &str,
+    tree: &Tree,
+    language: SupportedLanguage,
+    query: &str,
+) -> Result<Vec<QueryCapture>, QueryError> {
+    let query = Query::new(&language.ts_language(), query)?;
+    let mut captures = Vec::new();
+    for template in find_templates(source, tree) {
+        if template.language != language {
+            continue;
+        }
+        let Some(document) = template.parse(source) else {
+            continue;
+        };
+        let mut cursor = QueryCursor::new();
+        let mut matches =
+            cursor.captures(&query, document.tree.root_node(), document.text.as_bytes());
+        while let Some((m, index)) = matches.next() {
+            let capture = m.captures[*index];
+            let (Some(start), Some(end)) = (
+                document.to_file_offset(capture.node.start_byte()),
+                document.to_file_offset(capture.node.end_byte()),
+            ) else {
+                continue;
+            };
+            captures.push(QueryCapture {
+                name: query.capture_names()[capture.index as usize].to_string(),
+                start,
+                end,
+                line: 0,
+                column: 0,
+                text: source[start..end].to_string(),
+            });
+        }
+    }
+    Ok(captures)
+}
+
+/// Apply operations to a script file. CSS operations run inside the CSS
+/// template named by their `template` field (or the only one in the
+/// file); everything else runs on the script itself.
+pub fn execute_operations(
+    source: &str,
+    tree: &Tree,
+    ops: &[Operation],
+    language: &tree_sitter::Language,
+    style: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    let templates = find_templates(source, tree);
+    let executables = ops
+        .iter()
+        .map(|op| {
+            let executable = ast_surgeon_core::operation_to_executable(op)?;
+            if !op.is_css() {
+                return Ok(executable);
+            }
+            let template = css_template_for(&templates, op.css_template())?;
+            let document = template
+                .parse(source)
+                .ok_or_else(|| OperationError::InvalidParams {
+                    message: format!("Could not parse the `{}` template", template.tag),
+                })?;
+            Ok(Box::new(Injected {
+                inner: executable,
+                document,
+            }) as Box<dyn Executable>)
+        })
+        .collect::<Result<Vec<_>, OperationError>>()?;
+    ast_surgeon_core::execute_executables(
+        source,
+        tree,
+        &executables,
+        language,
+        ParseCheck::Clean,
+        style,
+    )
+}
+
+// --- Helper functions ---
+
+/// Runs an operation on a template's sub-document and maps its edits
+/// back into the file.
+struct Injected {
+    inner: Box<dyn Executable>,
+    document: SubDocument,
+}
+
+impl Executable for Injected {
+    fn compute_edits(&self, source: &str, tree: &Tree) -> Result<Vec<TextEdit>, OperationError> {
+        self.compute_edits_with_warnings(source, tree)
+            .map(|(edits, _)| edits)
+    }
+
+    fn compute_edits_with_warnings(
+        &self,
+        source: &str,
+        tree: &Tree,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        self.compute_edits_with_style(source, tree, &StyleConfig::default())
+    }
+
+    fn compute_edits_with_style(
+        &self,
+        _source: &str,
+        _tree: &Tree,
+        style: &StyleConfig,
+    ) -> Result<(Vec<TextEdit>, Vec<String>), OperationError> {
+        let document = &self.document;
+        let (edits, warnings) =
+            self.inner
+                .compute_edits_with_style(&document.text, &document.tree, style)?;
+        let edits = edits
+            .into_iter()
+            .map(|edit| document.map_edit(edit))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((edits, warnings))
+    }
+}
+
+fn css_template_for<'a>(
+    templates: &'a [EmbeddedTemplate],
+    name: Option<&str>,
+) -> Result<&'a EmbeddedTemplate, OperationError> {
+    let candidates: Vec<&EmbeddedTemplate> = templates
+        .iter()
+        .filter(|t| t.language == SupportedLanguage::Css)
+        .filter(|t| name.is_none() || t.binding.as_deref() == name)
+        .collect();
+    match candidates.as_slice() {
+        [only] => Ok(only),
+        [] => Err(OperationError::TargetNotFound {
+            description: match name {
+                Some(name) => format!("No css/styled template assigned to '{}'", name),
+                None => "No css/styled template in file".to_string(),
+            },
+        }),
+        many => Err(OperationError::InvalidParams {
+            message: format!(
+                "{} css/styled templates in file; set 'template' to one of: {}",
+                many.len(),
+                many.iter()
+                    .filter_map(|t| t.binding.as_deref())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }),
+    }
+}
+
+fn collect_templates(node: &Node, source: &str, out: &mut Vec<EmbeddedTemplate>) {
+    if node.kind() == "call_expression" {
+        if let Some(template) = template_of(node, source) {
+            out.push(template);
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_templates(&child, source, out);
+    }
+}
+
+fn template_of(call: &Node, source: &str) -> Option<EmbeddedTemplate> {
+    let arguments = call.child_by_field_name("arguments")?;
+    if arguments.kind() != "template_string" {
+        return None;
+    }
+    let function = call.child_by_field_name("function")?;
+    let raw = &source[function.start_byte()..function.end_byte()];
+    // `styled.button<Props>` -> `styled.button`
+    let tag: String = raw
+        .split('<')
+        .next()
+        .unwrap_or(raw)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let language = language_for_tag(&tag)?;
+
+    let mut placeholders = Vec::new();
+    let mut cursor = arguments.walk();
+    for part in arguments.named_children(&mut cursor) {
+        if part.kind() == "template_substitution" {
+            placeholders.push((part.start_byte(), part.end_byte()));
+        }
+    }
+    Some(EmbeddedTemplate {
+        tag,
+        binding: binding_of(call, source),
+        language,
+        start: arguments.start_byte() + 1,
+        end: arguments.end_byte() - 1,
+        placeholders,
+    })
+}
+
+fn language_for_tag(tag: &str) -> Option<SupportedLanguage> {
+    let root = tag.split(['.', '(']).next().unwrap_or(tag);
+    match root {
+        "styled" | "css" | "keyframes" | "createGlobalStyle" | "injectGlobal" => {
+            Some(SupportedLanguage::Css)
+        }
+        "html" | "svg" => Some(SupportedLanguage::Html),
+        _ => None,
+    }
+}
+
+/// Name of the variable the template's value is assigned to.
+fn binding_of(call: &Node, source: &str) -> Option<String> {
+    let mut node = call.parent()?;
+    while !matches!(node.kind(), "variable_declarator" | "assignment_expression") {
+        // Allow `styled.div.attrs(...)` chains and `as` casts around the call
+        if !matches!(
+            node.kind(),
+            "call_expression" | "member_expression" | "as_expression" | "parenthesized_expression"
+        ) {
+            return None;
+        }
+        node = node.parent()?;
+    }
+    let target = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("left"))?;
+    Some(source[target.start_byte()..target.end_byte()].to_string())
+}
+
+/// Indentation of the first non-blank line of a template body.
+fn body_indent(body: &str) -> &str {
+    body.lines()
+        .find(|line| !line.trim().is_empty())
+        .map_or("", |line| &line[..line.len() - line.trim_start().len()])
+}
+
+/// `text` without surrounding newlines, each non-empty line indented.
+fn indent_block(text: &str, indent: &str) -> String {
+    text.trim_matches('\n')
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Template content with interpolations replaced by same-length
+/// placeholders: a comment when the interpolation is a statement of its
+/// own (`${mixin};`), an identifier-like run of `_` otherwise.
+fn mask(source: &str, template: &EmbeddedTemplate) -> String {
+    let mut text = source[template.start..template.end].to_string();
+    for &(start, end) in &template.placeholders {
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let alone = line_start >= template.start && source[line_start..start].trim().is_empty();
+        let end = if alone && source[end..].starts_with(';') {
+            end + 1
+        } else {
+            end
+        };
+        let len = end - start;
+        let placeholder = if alone && template.language == SupportedLanguage::Css && len >= 4 {
+            format!("/*{}*/", "_".repeat(len - 4))
+        } else {
+            "_".repeat(len)
+        };
+        let at = start - template.start;
+        text.replace_range(at..at + len, &placeholder);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLED: &str = "import styled, { css } from 'styled-components'\n\nconst base = css`\n  margin: 0;\n`\n\nexport const Button = styled.button`\n  ${base};\n  color: ${(p) => (p.primary ? 'white' : 'black')};\n  &:hover {\n    opacity: 0.8;\n  }\n`\n\nconst QUERY = gql`query { me { id } }`\n";
+
+    fn parse(source: &str) -> Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&SupportedLanguage::Tsx.ts_language())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_find_templates() {
+        let templates = find_templates(STYLED, &parse(STYLED));
+        let found: Vec<(&str, Option<&str>)> = templates
+            .iter()
+            .map(|t| (t.tag.as_str(), t.binding.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("css", Some("base")), ("styled.button", Some("Button"))]
+        );
+        assert_eq!(templates[1].placeholders.len(), 2);
+
+        let document = templates[1].parse(STYLED).unwrap();
+        assert!(!document.tree.root_node().has_error(), "{}", document.text);
+    }
+
+    #[test]
+    fn test_query_maps_to_file_offsets() {
+        let tree = parse(STYLED);
+        let captures = query_templates(
+            STYLED,
+            &tree,
+            SupportedLanguage::Css,
+            "(declaration (property_name) @property)",
+        )
+        .unwrap();
+        let properties: Vec<&str> = captures.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(properties, vec!["margin", "color", "opacity"]);
+        assert_eq!(&STYLED[captures[1].start..captures[1].end], "color");
+    }
+
+    #[test]
+    fn test_css_ops_inside_template() {
+        let tree = parse(STYLED);
+        let language = SupportedLanguage::Tsx.ts_language();
+        let ops = vec![
+            Operation::SetDeclaration {
+                file: None,
+                template: Some("Button".into()),
+                selector: "&:hover".into(),
+                property: "opacity".into(),
+                value: "0.6".into(),
+                media: None,
+            },
+            Operation::AddRule {
+                file: None,
+                template: Some("Button".into()),
+                selector: "&".into(),
+                declarations: vec!["cursor: pointer".into()],
+                media: None,
+            },
+        ];
+        let result =
+            execute_operations(STYLED, &tree, &ops, &language, &StyleConfig::default()).unwrap();
+        assert!(result.content.contains("    opacity: 0.6;\n"));
+        assert!(result
+            .content
+            .contains("'black')};\n  cursor: pointer;\n  &:hover {"));
+        assert!(result
+            .content
+            .contains("color: ${(p) => (p.primary ? 'white' : 'black')};"));
+    }
+
+    #[test]
+    fn test_interpolations_are_opaque() {
+        let tree = parse(STYLED);
+        let language = SupportedLanguage::Tsx.ts_language();
+        let ops = vec![Operation::SetDeclaration {
+            file: None,
+            template: Some("Button".into()),
+            selector: "&".into(),
+            property: "color".into(),
+            value: "red".into(),
+            media: None,
+        }];
+        let result =
+            execute_operations(STYLED, &tree, &ops, &language, &StyleConfig::default()).unwrap();
+        assert!(result.content.contains("  ${base};\n  color: red;\n"));
+
+        let template = &find_templates(STYLED, &tree)[1];
+        let document = template.parse(STYLED).unwrap();
+        let (p_start, p_end) = template.placeholders[1];
+        assert!(!document.keeps_interpolations(p_start + 2, p_end, ""));
+        let masked = "_".repeat(p_end - p_start);
+        assert!(!document.keeps_interpolations(p_start, p_end, &masked));
+        assert!(document.keeps_interpolations(p_start, p_end, "red"));
+    }
+
+    #[test]
+    fn test_top_level_rule_goes_into_template_body() {
+        let tree = parse(STYLED);
+        let language = SupportedLanguage::Tsx.ts_language();
+        let ops = vec![Operation::AddRule {
+            file: None,
+            template: Some("Button".into()),
+            selector: ".icon".into(),
+            declarations: vec!["width: 1em".into()],
+            media: None,
+        }];
+        let result =
+            execute_operations(STYLED, &tree, &ops, &language, &StyleConfig::default()).unwrap();
+        assert!(result
+            .content
+            .contains("    opacity: 0.8;\n  }\n\n  .icon {\n    width: 1em;\n  }\n`\n"));
+    }
+
+    #[test]
+    fn test_remove_wrapper_rule_empties_template() {
+        let tree = parse(STYLED);
+        let language = SupportedLanguage::Tsx.ts_language();
+        let ops = vec![Operation::RemoveRule {
+            file: None,
+            template: Some("base".into()),
+            selector: "&".into(),
+            media: None,
+        }];
+        let result =
+            execute_operations(STYLED, &tree, &ops, &language, &StyleConfig::default()).unwrap();
+        assert!(result.content.contains("const base = css``\n"));
+    }
+
+    #[test]
+    fn test_wrapper_selector_is_not_editable() {
+        let tree = parse(STYLED);
+        let language = SupportedLanguage::Tsx.ts_language();
+        let ops = vec![Operation::RenameSelector {
+            file: None,
+            template: Some("base".into()),
+            from: "&".into(),
+            to: ".base".into(),
+        }];
+        let Err(OperationError::InvalidParams { message }) =
+            execute_operations(STYLED, &tree, &ops, &language, &StyleConfig::default())
+        else {
+            panic!("expected an error");
+        };
+        assert!(message.contains("`& { }` rule"), "{}", message);
+    }
+
+    #[test]
+    fn test_ambiguous_template_needs_name() {
+        let tree = parse(STYLED);
+        let language = SupportedLanguage::Tsx.ts_language();
+        let ops = vec![Operation::SetCustomProperty {
+            file: None,
+            template: None,
+            name: "gap".into(),
+            value: "4px".into(),
+        }];
+        assert!(matches!(
+            execute_operations(STYLED, &tree, &ops, &language, &StyleConfig::default()),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+//! HTML language support.
+//!
+//! Provides tree-sitter query patterns for elements, attributes and the
+//! raw `<script>`/`<style>` blocks that embed other languages.
+
+/// Query to find all elements with their tag name.
+pub const ELEMENTS_QUERY: &str = r#"
+(element
+  [
+    (start_tag (tag_name) @tag)
+    (self_closing_tag (tag_name) @tag)
+  ]
+) @element
+"#;
+
+/// Query to find attributes and their values (quoted or not).
+pub const ATTRIBUTES_QUERY: &str = r#"
+(attribute
+  (attribute_name) @name
+  [
+    (attribute_value) @value
+    (quoted_attribute_value (attribute_value) @value)
+  ]?
+) @attribute
+"#;
+
+/// Query to find `class` attribute values.
+pub const CLASS_ATTRIBUTES_QUERY: &str = r#"
+(attribute
+  (attribute_name) @name
+  (quoted_attribute_value (attribute_value) @value)
+  (#eq? @name "class")
+) @attribute
+"#;
+
+/// Query to find inline `<script>` contents.
+pub const SCRIPT_BLOCKS_QUERY: &str = r#"
+(script_element
+  (start_tag) @start
+  (raw_text)? @content
+) @script
+"#;
+
+/// Query to find inline `<style>` contents.
+pub const STYLE_BLOCKS_QUERY: &str = r#"
+(style_element
+  (start_tag) @start
+  (raw_text)? @content
+) @style
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use streaming_iterator::StreamingIterator;
+    use tree_sitter::{Parser, Query, QueryCursor};
+
+    fn matches(query: &str, source: &str) -> usize {
+        let lang = tree_sitter_html::LANGUAGE.into();
+        let mut parser = Parser::new();
+        parser.set_language(&lang).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let query = Query::new(&lang, query).unwrap();
+        let mut cursor = QueryCursor::new();
+        let mut it = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        let mut count = 0;
+        while it.next().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_elements_and_attributes() {
+        let source =
+            "<div class=\"card\" id=main hidden>\n  <img src=\"a.png\" />\n  <p>Hi</p>\n</div>\n";
+        assert_eq!(matches(ELEMENTS_QUERY, source), 3);
+        assert_eq!(matches(ATTRIBUTES_QUERY, source), 4);
+        assert_eq!(matches(CLASS_ATTRIBUTES_QUERY, source), 1);
+    }
+
+    #[test]
+    fn test_script_and_style_blocks() {
+        let source = "<head>\n<style>.a { color: red; }</style>\n<script type=\"module\">import a from './a';</script>\n</head>\n";
+        assert_eq!(matches(SCRIPT_BLOCKS_QUERY, source), 1);
+        assert_eq!(matches(STYLE_BLOCKS_QUERY, source), 1);
+    }
+}
@@ -0,0 +1,86 @@
+//! JSON / JSONC language support.
+//!
+//! The grammar accepts `//` and `/* */` comments. JSONC trailing commas
+//! (common in `tsconfig.json`) parse as ERROR nodes holding the comma, so
+//! edits to JSON files are only checked for not adding new errors.
+
+/// Query to find object members with their key text and value.
+pub const PAIRS_QUERY: &str = r#"
+(pair
+  key: (string (string_content) @key)
+  value: (_) @value
+) @pair
+"#;
+
+/// Query to find array elements.
+pub const ARRAY_ELEMENTS_QUERY: &str = r#"
+(array (_) @element)
+"#;
+
+/// Query to find comments (JSONC).
+pub const COMMENTS_QUERY: &str = r#"
+(comment) @comment
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SupportedLanguage;
+    use ast_surgeon_core::format::StyleConfig;
+    use ast_surgeon_core::operations::Operation;
+    use serde_json::json;
+    use streaming_iterator::StreamingIterator;
+    use tree_sitter::{Parser, Query, QueryCursor};
+
+    fn matches(query: &str, source: &str) -> usize {
+        let lang = tree_sitter_json::LANGUAGE.into();
+        let mut parser = Parser::new();
+        parser.set_language(&lang).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        assert!(!tree.root_node().has_error());
+        let query = Query::new(&lang, query).unwrap();
+        let mut cursor = QueryCursor::new();
+        let mut it = cursor.matches(&query, tree.root_node(), source.as_bytes());
+        let mut count = 0;
+        while it.next().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_jsonc_pairs_and_comments() {
+        let source = "{\n  // Compiler settings\n  \"compilerOptions\": { \"strict\": true },\n  /* sources */\n  \"include\": [\"src\", \"tests\"]\n}\n";
+        assert_eq!(matches(PAIRS_QUERY, source), 3);
+        assert_eq!(matches(COMMENTS_QUERY, source), 2);
+        assert_eq!(matches(ARRAY_ELEMENTS_QUERY, source), 2);
+    }
+
+    #[test]
+    fn test_trailing_commas_in_tsconfig() {
+        let source = "{\n  \"compilerOptions\": {\n    \"strict\": true,\n  },\n  \"include\": [\"src\",],\n}\n";
+        let ops = vec![
+            Operation::JsonSet {
+                file: None,
+                pointer: "/compilerOptions/target".into(),
+                value: json!("ES2022"),
+            },
+            Operation::JsonAppend {
+                file: None,
+                pointer: "/include".into(),
+                value: json!("tests"),
+            },
+        ];
+        let result = crate::execute_operations(
+            source,
+            SupportedLanguage::Json,
+            &ops,
+            &StyleConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            result.content,
+            "{\n  \"compilerOptions\": {\n    \"strict\": true,\n    \"target\": \"ES2022\",\n  },\n  \"include\": [\"src\", \"tests\",],\n}\n"
+        );
+    }
+}
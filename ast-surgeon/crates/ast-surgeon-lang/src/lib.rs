@@ -4,17 +4,51 @@
 //! and provides language-specific query patterns and formatting rules.
 
 pub mod registry;
+pub mod style;
 
+#[cfg(any(feature = "css", feature = "scss", feature = "less"))]
+pub mod css;
+#[cfg(all(feature = "css", feature = "html"))]
+pub mod embedded;
+#[cfg(feature = "html")]
+pub mod html;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "html")]
+pub mod sfc;
+#[cfg(feature = "svelte")]
+pub mod svelte;
 #[cfg(feature = "typescript")]
 pub mod typescript;
+#[cfg(feature = "vue")]
+pub mod vue;
 
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::{Operation, OperationError, OperationResult};
+use ast_surgeon_core::validate::{ParseCheck, ValidationError};
 use thiserror::Error;
-use tree_sitter::Language;
+use tree_sitter::{Language, Tree};
 
 #[derive(Debug, Clone, Error)]
 pub enum LangError {
     #[error("Unsupported language: {0}")]
     Unsupported(String),
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("Parse error: {0}")]
+    Parse(String),
+}
+
+/// A query capture, in file byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCapture {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line and byte column of `start`.
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
 }
 
 /// Supported language identifiers.
@@ -25,10 +59,17 @@ pub enum SupportedLanguage {
     JavaScript,
     Jsx,
     Css,
+    Scss,
+    Less,
+    Html,
+    Vue,
+    Svelte,
+    Json,
 }
 
 impl SupportedLanguage {
     /// Parse a language string into a SupportedLanguage.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self, LangError> {
         match s.to_lowercase().as_str() {
             "typescript" | "ts" => Ok(Self::TypeScript),
@@ -36,6 +77,12 @@ impl SupportedLanguage {
             "javascript" | "js" => Ok(Self::JavaScript),
             "jsx" => Ok(Self::Jsx),
             "css" => Ok(Self::Css),
+            "scss" => Ok(Self::Scss),
+            "less" => Ok(Self::Less),
+            "html" => Ok(Self::Html),
+            "vue" => Ok(Self::Vue),
+            "svelte" => Ok(Self::Svelte),
+            "json" | "jsonc" => Ok(Self::Json),
             other => Err(LangError::Unsupported(other.to_string())),
         }
     }
@@ -48,10 +95,42 @@ impl SupportedLanguage {
             "js" | "mjs" | "cjs" => Ok(Self::JavaScript),
             "jsx" => Ok(Self::Jsx),
             "css" => Ok(Self::Css),
+            "scss" => Ok(Self::Scss),
+            "less" => Ok(Self::Less),
+            "html" | "htm" => Ok(Self::Html),
+            "vue" => Ok(Self::Vue),
+            "svelte" => Ok(Self::Svelte),
+            "json" | "jsonc" => Ok(Self::Json),
             other => Err(LangError::Unsupported(other.to_string())),
         }
     }
 
+    /// TypeScript or JavaScript, with or without JSX.
+    pub fn is_script(&self) -> bool {
+        matches!(
+            self,
+            Self::TypeScript | Self::Tsx | Self::JavaScript | Self::Jsx
+        )
+    }
+
+    /// How edits to a file in this language are checked. SCSS and Less
+    /// are parsed with the CSS grammar and JSONC with the JSON one, so
+    /// valid files can already have ERROR nodes (Less `@variables`, SCSS
+    /// interpolation, trailing commas); edits to them only must not add
+    /// errors. SCSS `$variables` are masked first.
+    pub fn parse_check(&self) -> ParseCheck {
+        match self {
+            Self::Scss => ParseCheck::ScssNoNewErrors,
+            Self::Less | Self::Json => ParseCheck::NoNewErrors,
+            _ => ParseCheck::Clean,
+        }
+    }
+
+    /// Parse `source` (best-effort) into the tree operations expect.
+    pub fn parse(&self, source: &str) -> Result<Tree, ValidationError> {
+        self.parse_check().parse(source, &self.ts_language())
+    }
+
     /// Get the tree-sitter Language for this language.
     pub fn ts_language(&self) -> Language {
         match self {
@@ -73,8 +152,133 @@ impl SupportedLanguage {
             }
             #[cfg(feature = "css")]
             Self::Css => tree_sitter_css::LANGUAGE.into(),
+            #[cfg(feature = "scss")]
+            Self::Scss => tree_sitter_css::LANGUAGE.into(),
+            #[cfg(feature = "less")]
+            Self::Less => tree_sitter_css::LANGUAGE.into(),
+            #[cfg(feature = "html")]
+            Self::Html => tree_sitter_html::LANGUAGE.into(),
+            // The component as a whole; blocks are parsed on their own (see `sfc`)
+            #[cfg(feature = "vue")]
+            Self::Vue => tree_sitter_html::LANGUAGE.into(),
+            #[cfg(feature = "svelte")]
+            Self::Svelte => tree_sitter_html::LANGUAGE.into(),
+            #[cfg(feature = "json")]
+            Self::Json => tree_sitter_json::LANGUAGE.into(),
             #[allow(unreachable_patterns)]
             _ => panic!("Grammar not compiled for {:?}", self),
         }
     }
 }
+
+/// Apply operations to the source of a file in `lang`. Vue and Svelte
+/// components are split into blocks first, and CSS operations on a script
+/// edit its `css` / `styled` templates. Generated code follows `style`,
+/// falling back to what the source itself uses.
+pub fn execute_operations(
+    source: &str,
+    lang: SupportedLanguage,
+    ops: &[Operation],
+    style: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    #[cfg(feature = "vue")]
+    if lang == SupportedLanguage::Vue {
+        return vue::execute_operations(source, ops, style);
+    }
+    #[cfg(feature = "svelte")]
+    if lang == SupportedLanguage::Svelte {
+        return svelte::execute_operations(source, ops, style);
+    }
+
+    let ts_language = lang.ts_language();
+    let tree = lang
+        .parse(source)
+        .map_err(|_| OperationError::SourceHasErrors { errors: vec![] })?;
+    #[cfg(all(feature = "css", feature = "html"))]
+    if lang.is_script() && ops.iter().any(Operation::is_css) {
+        return embedded::execute_operations(source, &tree, ops, &ts_language, style);
+    }
+    ast_surgeon_core::execute_operations(
+        source,
+        &tree,
+        ops,
+        &ts_language,
+        lang.parse_check(),
+        style,
+    )
+}
+
+/// Run a tree-sitter query on the source of a file in `lang`, returning
+/// its captures in document order. With `embedded`, the query runs in
+/// that language inside the file's tagged templates instead (CSS in
+/// `css` / `styled` templates, HTML in lit's `html`). Vue and Svelte
+/// components are queried block by block: their `<script>` blocks, or
+/// with `embedded` the blocks in that language (`css` for `<style>`,
+/// `html` for `<template>`).
+pub fn query_source(
+    source: &str,
+    lang: SupportedLanguage,
+    query: &str,
+    embedded: Option<SupportedLanguage>,
+) -> Result<Vec<QueryCapture>, LangError> {
+    let mut captures = query_captures(source, lang, query, embedded)?;
+    for capture in &mut captures {
+        let line_start = source[..capture.start].rfind('\n').map_or(0, |i| i + 1);
+        capture.line = source[..capture.start].matches('\n').count() + 1;
+        capture.column = capture.start - line_start + 1;
+    }
+    Ok(captures)
+}
+
+fn query_captures(
+    source: &str,
+    lang: SupportedLanguage,
+    query: &str,
+    embedded: Option<SupportedLanguage>,
+) -> Result<Vec<QueryCapture>, LangError> {
+    #[cfg(feature = "html")]
+    if matches!(lang, SupportedLanguage::Vue | SupportedLanguage::Svelte) {
+        return sfc::query_blocks(source, query, embedded);
+    }
+
+    let tree = lang
+        .parse(source)
+        .map_err(|e| LangError::Parse(e.to_string()))?;
+    match embedded {
+        None => {
+            let query = tree_sitter::Query::new(&lang.ts_language(), query)
+                .map_err(|e| LangError::InvalidQuery(e.to_string()))?;
+            Ok(query_tree(source, &tree, &query))
+        }
+        #[cfg(all(feature = "css", feature = "html"))]
+        Some(embedded) if lang.is_script() => {
+            embedded::query_templates(source, &tree, embedded, query)
+                .map_err(|e| LangError::InvalidQuery(e.to_string()))
+        }
+        Some(embedded) => Err(LangError::Unsupported(format!(
+            "{:?} templates in {:?} files",
+            embedded, lang
+        ))),
+    }
+}
+
+/// Captures of `query` in `tree`, in document order.
+pub(crate) fn query_tree(source: &str, tree: &Tree, query: &tree_sitter::Query) -> Vec<QueryCapture> {
+    use streaming_iterator::StreamingIterator;
+
+    let mut captures = Vec::new();
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut matches = cursor.captures(query, tree.root_node(), source.as_bytes());
+    while let Some((m, index)) = matches.next() {
+        let node = m.captures[*index].node;
+        captures.push(QueryCapture {
+            name: query.capture_names()[m.captures[*index].index as usize].to_string(),
+            start: node.start_byte(),
+            end: node.end_byte(),
+            line: 0,
+            column: 0,
+            text: source[node.start_byte()..node.end_byte()].to_string(),
+        });
+    }
+    captures
+}
@@ -72,9 +72,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_styles_markup_and_components() {
+        assert_eq!(
+            detect_language("Card.module.scss").unwrap(),
+            SupportedLanguage::Scss
+        );
+        assert_eq!(
+            detect_language("theme.less").unwrap(),
+            SupportedLanguage::Less
+        );
+        assert_eq!(
+            detect_language("public/index.html").unwrap(),
+            SupportedLanguage::Html
+        );
+        assert_eq!(
+            detect_language("src/App.vue").unwrap(),
+            SupportedLanguage::Vue
+        );
+        assert_eq!(
+            detect_language("src/routes/+page.svelte").unwrap(),
+            SupportedLanguage::Svelte
+        );
+    }
+
+    #[test]
+    fn test_detect_json() {
+        assert_eq!(
+            detect_language("package.json").unwrap(),
+            SupportedLanguage::Json
+        );
+        assert_eq!(
+            detect_language(".vscode/settings.jsonc").unwrap(),
+            SupportedLanguage::Json
+        );
+    }
+
     #[test]
     fn test_detect_unsupported() {
-        assert!(detect_language("data.json").is_err());
+        assert!(detect_language("config.yaml").is_err());
     }
 
     #[test]
@@ -89,4 +125,20 @@ mod tests {
         let parser = parser_for_language(SupportedLanguage::Tsx);
         drop(parser);
     }
+
+    #[test]
+    fn test_parser_for_scss_parses_nesting() {
+        let mut parser = parser_for_language(SupportedLanguage::Scss);
+        let tree = parser
+            .parse(".card {\n  // note\n  &:hover { color: red; }\n}\n", None)
+            .unwrap();
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn test_parser_for_html() {
+        let mut parser = parser_for_language(SupportedLanguage::Html);
+        let tree = parser.parse("<div class=\"a\"><p>Hi</p></div>", None).unwrap();
+        assert!(!tree.root_node().has_error());
+    }
 }
@@ -0,0 +1,303 @@
+//! Single-file component splitting (Vue, Svelte).
+//!
+//! A component file is parsed with the HTML grammar and its top-level
+//! `<template>`, `<script>` and `<style>` elements become blocks. Each
+//! block is a sub-document: operations run on its content with the
+//! block's own grammar and the edits are spliced back into the file.
+
+use crate::{LangError, QueryCapture, SupportedLanguage};
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::{Operation, OperationError, OperationResult};
+use tree_sitter::{Node, Parser};
+
+/// A top-level block of a component file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SfcBlock {
+    /// `template`, `script` or `style`.
+    pub tag: String,
+    /// Attributes of the opening tag; valueless attributes map to `None`.
+    pub attributes: Vec<(String, Option<String>)>,
+    /// Byte range of the block content in the file. A newline right after
+    /// the opening tag is not part of the content.
+    pub start: usize,
+    pub end: usize,
+    /// Byte range of the whole element, tags included.
+    pub element_start: usize,
+    pub element_end: usize,
+}
+
+impl SfcBlock {
+    pub fn content<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.start..self.end]
+    }
+
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    /// Grammar for the block content, from the tag and its `lang` attribute.
+    pub fn language(&self) -> Result<SupportedLanguage, OperationError> {
+        let lang = self.attribute("lang");
+        let language = match (self.tag.as_str(), lang) {
+            ("script", Some("ts")) => SupportedLanguage::TypeScript,
+            ("script", Some("tsx")) => SupportedLanguage::Tsx,
+            ("script", Some("jsx")) => SupportedLanguage::Jsx,
+            ("script", Some("js") | None) => SupportedLanguage::JavaScript,
+            ("style", Some("scss")) => SupportedLanguage::Scss,
+            ("style", Some("less")) => SupportedLanguage::Less,
+            ("style", Some("css") | None) => SupportedLanguage::Css,
+            ("template", Some("html") | None) => SupportedLanguage::Html,
+            (tag, lang) => {
+                return Err(OperationError::UnsupportedLanguage {
+                    language: format!("<{} lang=\"{}\">", tag, lang.unwrap_or_default()),
+                })
+            }
+        };
+        Ok(language)
+    }
+
+    /// 1-indexed line of the first content byte.
+    pub fn start_line(&self, source: &str) -> usize {
+        source[..self.start].matches('\n').count() + 1
+    }
+}
+
+/// Split a component file into its top-level blocks, in source order.
+pub fn split_blocks(source: &str) -> Vec<SfcBlock> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_html::LANGUAGE.into())
+        .expect("language version mismatch with tree-sitter");
+    let Some(tree) = parser.parse(source, None) else {
+        return vec![];
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .filter(|n| matches!(n.kind(), "element" | "script_element" | "style_element"))
+        .filter_map(|n| block_of(&n, source))
+        .collect()
+}
+
+/// Run operations on one block and splice the result into the file.
+/// Change locations are reported as lines of the whole file.
+pub fn execute_in_block(
+    source: &str,
+    block: &SfcBlock,
+    ops: &[Operation],
+    style: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    let lang = block.language()?;
+    let language = lang.ts_language();
+    let content = block.content(source);
+    let tree = lang
+        .parse(content)
+        .map_err(|_| OperationError::InvalidResult { errors: vec![] })?;
+    let mut result = ast_surgeon_core::execute_operations(
+        content,
+        &tree,
+        ops,
+        &language,
+        lang.parse_check(),
+        style,
+    )?;
+
+    let line_offset = block.start_line(source) - 1;
+    for change in &mut result.changes {
+        change.line += line_offset;
+    }
+    result.content = format!(
+        "{}{}{}",
+        &source[..block.start],
+        result.content,
+        &source[block.end..]
+    );
+    Ok(result)
+}
+
+/// Run a tree-sitter query on the `<script>` blocks of a component, or
+/// with `embedded` on its blocks in that language. Capture offsets are
+/// bytes of the whole file.
+pub fn query_blocks(
+    source: &str,
+    query: &str,
+    embedded: Option<SupportedLanguage>,
+) -> Result<Vec<QueryCapture>, LangError> {
+    let mut captures = Vec::new();
+    for block in split_blocks(source) {
+        let Ok(lang) = block.language() else {
+            continue;
+        };
+        let wanted = match embedded {
+            None => block.tag == "script",
+            Some(embedded) => lang == embedded,
+        };
+        if !wanted {
+            continue;
+        }
+        let content = block.content(source);
+        let tree = lang
+            .parse(content)
+            .map_err(|e| LangError::Parse(e.to_string()))?;
+        let query = tree_sitter::Query::new(&lang.ts_language(), query)
+            .map_err(|e| LangError::InvalidQuery(e.to_string()))?;
+        for mut capture in crate::query_tree(content, &tree, &query) {
+            capture.start += block.start;
+            capture.end += block.start;
+            captures.push(capture);
+        }
+    }
+    Ok(captures)
+}
+
+/// Whether `word` occurs in `text` as a whole identifier.
+pub fn contains_word(text: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    text.match_indices(word).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+// --- Helper functions ---
+
+fn block_of(element: &Node, source: &str) -> Option<SfcBlock> {
+    let mut cursor = element.walk();
+    let children: Vec<Node> = element.children(&mut cursor).collect();
+    let start_tag = children.iter().find(|c| c.kind() == "start_tag")?;
+    let end_tag = children.iter().rev().find(|c| c.kind() == "end_tag")?;
+
+    let mut tag = String::new();
+    let mut attributes = Vec::new();
+    let mut tag_cursor = start_tag.walk();
+    for part in start_tag.named_children(&mut tag_cursor) {
+        match part.kind() {
+            "tag_name" => tag = text(&part, source).to_lowercase(),
+            "attribute" => {
+                let mut attr_cursor = part.walk();
+                let mut name = String::new();
+                let mut value = None;
+                for piece in part.named_children(&mut attr_cursor) {
+                    match piece.kind() {
+                        "attribute_name" => name = text(&piece, source).to_string(),
+                        "attribute_value" => value = Some(text(&piece, source).to_string()),
+                        "quoted_attribute_value" => {
+                            let raw = text(&piece, source);
+                            value = Some(raw[1..raw.len().saturating_sub(1)].to_string());
+                        }
+                        _ => {}
+                    }
+                }
+                attributes.push((name, value));
+            }
+            _ => {}
+        }
+    }
+    if !matches!(tag.as_str(), "template" | "script" | "style") {
+        return None;
+    }
+
+    let mut start = start_tag.end_byte();
+    let end = end_tag.start_byte();
+    if source[start..end].starts_with("\r\n") {
+        start += 2;
+    } else if source[start..end].starts_with('\n') {
+        start += 1;
+    }
+    Some(SfcBlock {
+        tag,
+        attributes,
+        start,
+        end,
+        element_start: element.start_byte(),
+        element_end: element.end_byte(),
+    })
+}
+
+fn text<'s>(node: &Node, source: &'s str) -> &'s str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SFC: &str = "<template>\n  <button @click=\"count++\">{{ count }}</button>\n</template>\n\n<script setup lang=\"ts\">\nimport { ref } from 'vue'\nconst count = ref(0)\n</script>\n\n<style scoped>\n.a { color: red; }\n</style>\n";
+
+    #[test]
+    fn test_split_blocks() {
+        let blocks = split_blocks(SFC);
+        let tags: Vec<&str> = blocks.iter().map(|b| b.tag.as_str()).collect();
+        assert_eq!(tags, vec!["template", "script", "style"]);
+
+        let script = &blocks[1];
+        assert!(script.has_attribute("setup"));
+        assert_eq!(script.attribute("lang"), Some("ts"));
+        assert_eq!(
+            script.content(SFC),
+            "import { ref } from 'vue'\nconst count = ref(0)\n"
+        );
+        assert_eq!(script.start_line(SFC), 6);
+        assert_eq!(script.language().unwrap(), SupportedLanguage::TypeScript);
+        assert!(blocks[2].has_attribute("scoped"));
+        assert_eq!(blocks[2].language().unwrap(), SupportedLanguage::Css);
+    }
+
+    #[test]
+    fn test_execute_in_block_maps_lines() {
+        let blocks = split_blocks(SFC);
+        let ops = vec![Operation::RenameSymbol {
+            file: None,
+            from: "count".into(),
+            to: "clicks".into(),
+            scope: None,
+        }];
+        let result = execute_in_block(SFC, &blocks[1], &ops, &StyleConfig::default()).unwrap();
+        assert!(result.content.contains("const clicks = ref(0)\n</script>"));
+        // The template is a different block and stays as it was
+        assert!(result.content.contains("{{ count }}"));
+        assert_eq!(result.changes[0].line, 7);
+    }
+
+    #[test]
+    fn test_query_blocks() {
+        let captures = crate::query_source(
+            SFC,
+            SupportedLanguage::Vue,
+            "(import_statement source: (string) @source)",
+            None,
+        )
+        .unwrap();
+        assert_eq!(captures.len(), 1);
+        assert_eq!(captures[0].text, "'vue'");
+        assert_eq!((captures[0].line, captures[0].column), (6, 21));
+
+        let captures = crate::query_source(
+            SFC,
+            SupportedLanguage::Vue,
+            "(property_name) @property",
+            Some(SupportedLanguage::Css),
+        )
+        .unwrap();
+        assert_eq!(captures.len(), 1);
+        assert_eq!((captures[0].line, captures[0].text.as_str()), (11, "color"));
+    }
+
+    #[test]
+    fn test_unknown_lang_is_rejected() {
+        let blocks = split_blocks("<template lang=\"pug\">\ndiv\n</template>\n");
+        assert!(matches!(
+            blocks[0].language(),
+            Err(OperationError::UnsupportedLanguage { .. })
+        ));
+    }
+}
@@ -0,0 +1,662 @@
+//! Formatter settings from project configuration.
+//!
+//! Resolves the [`StyleConfig`] of a file from, in increasing precedence:
+//!
+//! 1. `.editorconfig` files (`indent_style`, `indent_size`, `end_of_line`,
+//!    `max_line_length`, `quote_type`), nearest last, up to `root = true`;
+//! 2. the nearest Prettier config (`.prettierrc`, `.prettierrc.json`,
+//!    `.prettierrc.yaml` / `.yml` or `package.json#prettier`, including
+//!    `overrides`). Only the options it sets are applied. JavaScript
+//!    configs, shared configs and files that do not parse cannot be read;
+//!    they are reported as warnings and ignored;
+//! 3. the nearest `biome.json` / `biome.jsonc` with a `formatter` or
+//!    `javascript.formatter` section, with Biome's defaults.
+//!
+//! Whatever none of them set is inferred from the source by
+//! [`StyleConfig::resolve`].
+
+use ast_surgeon_core::format::{IndentStyle, LineEnding, StyleConfig, TrailingCommas};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const PRETTIER_FILES: &[&str] = &[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+];
+
+/// Prettier configs that are code; their options are unknown.
+const PRETTIER_JS_FILES: &[&str] = &[
+    ".prettierrc.js",
+    ".prettierrc.cjs",
+    ".prettierrc.mjs",
+    "prettier.config.js",
+    "prettier.config.cjs",
+    "prettier.config.mjs",
+];
+
+const BIOME_FILES: &[&str] = &["biome.json", "biome.jsonc"];
+
+/// Formatter settings for the file at `path`, with a warning for each
+/// formatter config that was found but could not be read. Configuration
+/// is searched from the file's directory up to `root`, or without a root
+/// up to the first directory containing `.git`.
+pub fn resolve_style_config(path: &Path, root: Option<&Path>) -> (StyleConfig, Vec<String>) {
+    let dirs = search_dirs(path, root);
+    let mut warnings = Vec::new();
+    let mut config = editorconfig_style(path, &dirs);
+    if let Some(options) = prettier_options(path, &dirs, &mut warnings) {
+        apply_prettier(&mut config, &options);
+    }
+    if let Some(biome) = biome_formatter(&dirs) {
+        apply_biome(&mut config, &biome);
+    }
+    (config, warnings)
+}
+
+// --- Helper functions ---
+
+/// The file's directory and its ancestors, nearest first.
+fn search_dirs(path: &Path, root: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut current = path.parent();
+    while let Some(dir) = current {
+        dirs.push(dir.to_path_buf());
+        let at_root = match root {
+            Some(root) => dir == root,
+            None => dir.join(".git").exists(),
+        };
+        if at_root {
+            break;
+        }
+        current = dir.parent();
+    }
+    dirs
+}
+
+/// `/`-separated path of `path` relative to `dir`.
+fn relative_to(path: &Path, dir: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some(parts.join("/"))
+}
+
+fn editorconfig_style(path: &Path, dirs: &[PathBuf]) -> StyleConfig {
+    // Farthest file first so nearer ones override it
+    let mut files = Vec::new();
+    for dir in dirs {
+        let Ok(text) = std::fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let is_root = text
+            .lines()
+            .take_while(|l| !l.trim().starts_with('['))
+            .any(|l| parse_property(l).is_some_and(|(k, v)| k == "root" && v == "true"));
+        files.push((dir, text));
+        if is_root {
+            break;
+        }
+    }
+
+    let mut properties: HashMap<String, String> = HashMap::new();
+    for (dir, text) in files.iter().rev() {
+        let Some(relative) = relative_to(path, dir) else {
+            continue;
+        };
+        let mut in_matching_section = false;
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_matching_section = editorconfig_section_matches(section, &relative);
+            } else if in_matching_section {
+                if let Some((key, value)) = parse_property(line) {
+                    properties.insert(key, value);
+                }
+            }
+        }
+    }
+
+    let size = properties
+        .get("indent_size")
+        .or_else(|| properties.get("tab_width"))
+        .and_then(|s| s.parse::<u8>().ok());
+    StyleConfig {
+        indent: match (properties.get("indent_style").map(String::as_str), size) {
+            (Some("tab"), _) => Some(IndentStyle::Tabs),
+            (_, Some(size)) => Some(IndentStyle::Spaces(size)),
+            _ => None,
+        },
+        quote: match properties.get("quote_type").map(String::as_str) {
+            Some("single") => Some('\''),
+            Some("double") => Some('"'),
+            _ => None,
+        },
+        print_width: properties
+            .get("max_line_length")
+            .and_then(|s| s.parse().ok()),
+        line_ending: properties
+            .get("end_of_line")
+            .and_then(|s| parse_line_ending(s)),
+        ..StyleConfig::default()
+    }
+}
+
+/// `key = value`, lowercased; `None` for comments and blank lines.
+fn parse_property(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.starts_with('#') || line.starts_with(';') {
+        return None;
+    }
+    let (key, value) = line.split_once('=')?;
+    Some((key.trim().to_lowercase(), value.trim().to_lowercase()))
+}
+
+/// Sections without a `/` match the file name at any depth.
+fn editorconfig_section_matches(section: &str, relative: &str) -> bool {
+    if section.contains('/') {
+        glob_matches(section.trim_start_matches('/'), relative)
+    } else {
+        glob_matches(&format!("**/{section}"), relative) || glob_matches(section, relative)
+    }
+}
+
+/// The options of the nearest Prettier config, with matching `overrides`
+/// merged in. A nearest config that cannot be read is reported in
+/// `warnings` and stops the search, as it is the one Prettier would use.
+fn prettier_options(
+    path: &Path,
+    dirs: &[PathBuf],
+    warnings: &mut Vec<String>,
+) -> Option<Map<String, Value>> {
+    let unreadable = |warnings: &mut Vec<String>, file: &Path, reason: &str| {
+        warnings.push(format!(
+            "{}: {}; its formatting options were not applied",
+            file.display(),
+            reason
+        ));
+        None
+    };
+    for dir in dirs {
+        if let Some(file) = PRETTIER_JS_FILES
+            .iter()
+            .map(|f| dir.join(f))
+            .find(|f| f.exists())
+        {
+            return unreadable(
+                warnings,
+                &file,
+                "JavaScript Prettier configs cannot be read",
+            );
+        }
+        let mut found = None;
+        for name in PRETTIER_FILES {
+            let file = dir.join(name);
+            let Ok(text) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            match parse_prettierrc(&text) {
+                Some(options) => found = Some(options),
+                None => return unreadable(warnings, &file, "could not parse the Prettier config"),
+            }
+            break;
+        }
+        if found.is_none() {
+            let file = dir.join("package.json");
+            let prettier = std::fs::read_to_string(&file)
+                .ok()
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                .and_then(|mut package| package.get_mut("prettier").map(Value::take));
+            match prettier {
+                Some(Value::Object(options)) => found = Some(options),
+                Some(Value::String(shared)) => {
+                    let reason = format!("shared Prettier config '{}' cannot be read", shared);
+                    return unreadable(warnings, &file, &reason);
+                }
+                Some(_) => return unreadable(warnings, &file, "`prettier` is not an object"),
+                None => {}
+            }
+        }
+        let Some(mut options) = found else {
+            continue;
+        };
+
+        let relative = relative_to(path, dir).unwrap_or_default();
+        if let Some(Value::Array(overrides)) = options.remove("overrides") {
+            for entry in &overrides {
+                let matches = |key: &str| match entry.get(key) {
+                    Some(Value::String(p)) => prettier_glob_matches(p, &relative),
+                    Some(Value::Array(ps)) => ps
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .any(|p| prettier_glob_matches(p, &relative)),
+                    _ => false,
+                };
+                if matches("files") && !matches("excludeFiles") {
+                    if let Some(Value::Object(extra)) = entry.get("options") {
+                        options.extend(extra.clone());
+                    }
+                }
+            }
+        }
+        return Some(options);
+    }
+    None
+}
+
+/// JSON, or the flat `key: value` YAML Prettier configs usually are;
+/// `None` for anything else, such as the name of a shared config. Only
+/// top-level YAML keys are read: indented lines belong to a nested value
+/// such as `overrides`.
+fn parse_prettierrc(text: &str) -> Option<Map<String, Value>> {
+    match serde_json::from_str(text) {
+        Ok(Value::Object(options)) => return Some(options),
+        Ok(_) => return None,
+        Err(_) if text.trim_start().starts_with('{') => return None,
+        Err(_) => {}
+    }
+    let mut options = Map::new();
+    for line in text.lines() {
+        if line.starts_with([' ', '\t', '-', '#']) {
+            continue;
+        }
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = line.split_once(':')?;
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        if value.is_empty() {
+            continue;
+        }
+        let value = match value {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            v => v
+                .parse::<u64>()
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::String(v.to_string())),
+        };
+        options.insert(key.trim().to_string(), value);
+    }
+    Some(options)
+}
+
+/// Prettier matches patterns without a `/` against the file name.
+fn prettier_glob_matches(pattern: &str, relative: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.contains('/') {
+        glob_matches(pattern, relative)
+    } else {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        glob_matches(pattern, name)
+    }
+}
+
+/// Apply the options the config sets; the others keep what
+/// `.editorconfig` gave, or are left to be inferred from the source.
+fn apply_prettier(config: &mut StyleConfig, options: &Map<String, Value>) {
+    let flag = |key: &str| options.get(key).and_then(Value::as_bool);
+    let tab_width = options
+        .get("tabWidth")
+        .and_then(Value::as_u64)
+        .map(|w| w as u8);
+    let tabs = config.indent == Some(IndentStyle::Tabs);
+    match (flag("useTabs"), tab_width) {
+        (Some(true), _) => config.indent = Some(IndentStyle::Tabs),
+        (Some(false), Some(width)) => config.indent = Some(IndentStyle::Spaces(width)),
+        // Spaces, of a width set elsewhere or the file's own
+        (Some(false), None) if tabs => config.indent = None,
+        // `tabWidth` alone sizes tabs when `.editorconfig` asks for them
+        (None, Some(width)) if !tabs => config.indent = Some(IndentStyle::Spaces(width)),
+        _ => {}
+    }
+    if let Some(semi) = flag("semi") {
+        config.semicolons = Some(semi);
+    }
+    if let Some(single) = flag("singleQuote") {
+        config.quote = Some(if single { '\'' } else { '"' });
+    }
+    if let Some(commas) = options
+        .get("trailingComma")
+        .and_then(Value::as_str)
+        .and_then(parse_trailing_commas)
+    {
+        config.trailing_commas = Some(commas);
+    }
+    if let Some(width) = options.get("printWidth").and_then(Value::as_u64) {
+        config.print_width = Some(width as usize);
+    }
+    match options.get("endOfLine").and_then(Value::as_str) {
+        Some("auto") => config.line_ending = None,
+        Some(eol) => config.line_ending = parse_line_ending(eol).or(config.line_ending),
+        None => {}
+    }
+}
+
+/// The nearest Biome config's formatter settings: general ones with the
+/// JavaScript-specific ones on top.
+fn biome_formatter(dirs: &[PathBuf]) -> Option<Map<String, Value>> {
+    let text = dirs.iter().find_map(|dir| {
+        BIOME_FILES
+            .iter()
+            .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+    })?;
+    let config: Value = serde_json::from_str(&strip_json_comments(&text)).ok()?;
+    let general = config.get("formatter").and_then(Value::as_object);
+    let javascript = config
+        .pointer("/javascript/formatter")
+        .and_then(Value::as_object);
+    if general.is_none() && javascript.is_none() {
+        return None;
+    }
+    let mut settings = general.cloned().unwrap_or_default();
+    if settings.get("enabled") == Some(&Value::Bool(false)) {
+        return None;
+    }
+    settings.extend(javascript.cloned().unwrap_or_default());
+    Some(settings)
+}
+
+fn apply_biome(config: &mut StyleConfig, settings: &Map<String, Value>) {
+    let string = |key: &str| settings.get(key).and_then(Value::as_str);
+    let width = settings
+        .get("indentWidth")
+        .or_else(|| settings.get("indentSize"))
+        .and_then(Value::as_u64)
+        .unwrap_or(2) as u8;
+    config.indent = Some(match string("indentStyle") {
+        Some("space") => IndentStyle::Spaces(width),
+        _ => IndentStyle::Tabs,
+    });
+    config.quote = Some(if string("quoteStyle") == Some("single") {
+        '\''
+    } else {
+        '"'
+    });
+    config.semicolons = Some(string("semicolons") != Some("asNeeded"));
+    config.trailing_commas = Some(
+        string("trailingCommas")
+            .or_else(|| string("trailingComma"))
+            .and_then(parse_trailing_commas)
+            .unwrap_or(TrailingCommas::All),
+    );
+    config.print_width = Some(
+        settings
+            .get("lineWidth")
+            .and_then(Value::as_u64)
+            .unwrap_or(80) as usize,
+    );
+    config.line_ending = parse_line_ending(string("lineEnding").unwrap_or("lf"));
+}
+
+fn parse_line_ending(value: &str) -> Option<LineEnding> {
+    match value {
+        "lf" => Some(LineEnding::Lf),
+        "crlf" => Some(LineEnding::Crlf),
+        _ => None,
+    }
+}
+
+fn parse_trailing_commas(value: &str) -> Option<TrailingCommas> {
+    match value {
+        "none" => Some(TrailingCommas::None),
+        "es5" => Some(TrailingCommas::Es5),
+        "all" => Some(TrailingCommas::All),
+        _ => None,
+    }
+}
+
+/// Remove `//` and `/* */` comments outside strings (for `.jsonc`).
+fn strip_json_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            if c == '\\' {
+                result.extend(chars.next());
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                result.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Glob match with `*`, `**`, `?`, `[...]` and `{a,b}`.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    if let Some(open) = pattern.find('{') {
+        if let Some(close) = pattern[open..].find('}').map(|i| open + i) {
+            return pattern[open + 1..close].split(',').any(|alternative| {
+                let expanded = format!(
+                    "{}{}{}",
+                    &pattern[..open],
+                    alternative,
+                    &pattern[close + 1..]
+                );
+                glob_matches(&expanded, text)
+            });
+        }
+    }
+    match_from(pattern.as_bytes(), text.as_bytes())
+}
+
+fn match_from(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let rest = &pattern[2..];
+            // `**/` also matches no directory at all
+            if rest.first() == Some(&b'/') && match_from(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| match_from(rest, &text[i..]))
+        }
+        Some(b'*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if match_from(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&b'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some(b'?') => {
+            text.first().is_some_and(|&c| c != b'/') && match_from(&pattern[1..], &text[1..])
+        }
+        Some(b'[') => {
+            let Some(end) = pattern.iter().position(|&c| c == b']') else {
+                return text.first() == Some(&b'[') && match_from(&pattern[1..], &text[1..]);
+            };
+            let Some(&c) = text.first() else {
+                return false;
+            };
+            let (negated, class) = match pattern[1..end].strip_prefix(b"!") {
+                Some(class) => (true, class),
+                None => (false, &pattern[1..end]),
+            };
+            let mut in_class = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == b'-' {
+                    in_class |= (class[i]..=class[i + 2]).contains(&c);
+                    i += 3;
+                } else {
+                    in_class |= class[i] == c;
+                    i += 1;
+                }
+            }
+            in_class != negated && match_from(&pattern[end + 1..], &text[1..])
+        }
+        Some(&p) => text.first() == Some(&p) && match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.{js,ts}", "app.ts"));
+        assert!(!glob_matches("*.{js,ts}", "src/app.ts"));
+        assert!(glob_matches("src/**/*.ts", "src/app.ts"));
+        assert!(glob_matches("src/**/*.ts", "src/ui/app.ts"));
+        assert!(glob_matches("file[0-9].?s", "file1.js"));
+        assert!(!glob_matches("file[!0-9].js", "file1.js"));
+    }
+
+    #[test]
+    fn test_resolve_layers_editorconfig_prettier_and_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join(".editorconfig"),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 4\nmax_line_length = 100\n\n[*.md]\nindent_size = 2\n",
+        )
+        .unwrap();
+        let file = root.join("src/App.tsx");
+
+        let (config, warnings) = resolve_style_config(&file, Some(root));
+        assert_eq!(config.indent, Some(IndentStyle::Spaces(4)));
+        assert_eq!(config.print_width, Some(100));
+        assert_eq!(config.quote, None);
+        assert!(warnings.is_empty());
+
+        std::fs::write(
+            root.join(".prettierrc"),
+            "singleQuote: true\nsemi: false\noverrides:\n",
+        )
+        .unwrap();
+        let (config, _) = resolve_style_config(&file, Some(root));
+        assert_eq!(config.quote, Some('\''));
+        assert_eq!(config.semicolons, Some(false));
+        // Unset in Prettier: taken from .editorconfig, or left to the file
+        assert_eq!(config.indent, Some(IndentStyle::Spaces(4)));
+        assert_eq!(config.print_width, Some(100));
+        assert_eq!(config.trailing_commas, None);
+        assert_eq!(config.line_ending, None);
+
+        std::fs::write(
+            root.join(".prettierrc"),
+            r#"{"useTabs": true, "overrides": [{"files": "*.tsx", "options": {"semi": false, "trailingComma": "es5"}}]}"#,
+        )
+        .unwrap();
+        let (config, _) = resolve_style_config(&file, Some(root));
+        assert_eq!(config.indent, Some(IndentStyle::Tabs));
+        assert_eq!(config.semicolons, Some(false));
+        assert_eq!(config.trailing_commas, Some(TrailingCommas::Es5));
+        assert_eq!(config.quote, None);
+    }
+
+    #[test]
+    fn test_unreadable_prettier_configs_warn() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join(".editorconfig"), "[*]\nindent_size = 4\n").unwrap();
+        let file = root.join("index.ts");
+
+        std::fs::write(root.join(".prettierrc"), "{ \"semi\": false, }").unwrap();
+        let (config, warnings) = resolve_style_config(&file, Some(root));
+        assert_eq!(config.semicolons, None);
+        assert_eq!(config.indent, Some(IndentStyle::Spaces(4)));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(".prettierrc: could not parse"));
+
+        std::fs::write(root.join(".prettierrc"), "\"@company/prettier-config\"\n").unwrap();
+        let (_, warnings) = resolve_style_config(&file, Some(root));
+        assert_eq!(warnings.len(), 1);
+
+        std::fs::remove_file(root.join(".prettierrc")).unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"name": "app", "prettier": "@company/prettier-config"}"#,
+        )
+        .unwrap();
+        let (_, warnings) = resolve_style_config(&file, Some(root));
+        assert!(warnings[0].contains("shared Prettier config '@company/prettier-config'"));
+
+        std::fs::write(
+            root.join("prettier.config.js"),
+            "module.exports = { semi: false }\n",
+        )
+        .unwrap();
+        let (config, warnings) = resolve_style_config(&file, Some(root));
+        assert_eq!(config.semicolons, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("prettier.config.js: JavaScript Prettier configs"));
+    }
+
+    #[test]
+    fn test_yaml_prettierrc_reads_top_level_keys_only() {
+        let options = parse_prettierrc(
+            "semi: true\nsingleQuote: true # house style\noverrides:\n  - files: \"*.test.ts\"\n    options:\n      semi: false\n      singleQuote: false\n      tabWidth: 4\n",
+        )
+        .unwrap();
+        assert_eq!(options.len(), 2);
+        assert_eq!(options["semi"], Value::Bool(true));
+        assert_eq!(options["singleQuote"], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_resolve_biome_formatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("biome.jsonc"),
+            r#"{
+              // formatter settings
+              "formatter": { "indentStyle": "space", "indentWidth": 2, "lineWidth": 120 },
+              "javascript": { "formatter": { "quoteStyle": "single", "semicolons": "asNeeded" } }
+            }"#,
+        )
+        .unwrap();
+        let (config, _) = resolve_style_config(&root.join("index.ts"), Some(root));
+        assert_eq!(
+            config,
+            StyleConfig {
+                indent: Some(IndentStyle::Spaces(2)),
+                quote: Some('\''),
+                semicolons: Some(false),
+                trailing_commas: Some(TrailingCommas::All),
+                print_width: Some(120),
+                line_ending: Some(LineEnding::Lf),
+            }
+        );
+
+        // A biome.json used only for linting leaves formatting alone
+        std::fs::remove_file(root.join("biome.jsonc")).unwrap();
+        std::fs::write(root.join("biome.json"), r#"{"linter": {"enabled": true}}"#).unwrap();
+        let (config, _) = resolve_style_config(&root.join("index.ts"), Some(root));
+        assert_eq!(config, StyleConfig::default());
+    }
+}
@@ -0,0 +1,282 @@
+//! Svelte component support.
+//!
+//! A component has an instance `<script>`, an optional module script
+//! (`<script context="module">` in Svelte 4, `<script module>` in Svelte 5)
+//! and a `<style>` block; everything else is markup. Script operations run
+//! on the instance script, falling back to the module script when their
+//! target is only found there. Renames run on both scripts.
+
+use crate::sfc::{contains_word, execute_in_block, split_blocks, SfcBlock};
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::svelte::uses_runes;
+use ast_surgeon_core::operations::{Operation, OperationError, OperationResult};
+
+/// Apply operations to a `.svelte` file.
+pub fn execute_operations(
+    source: &str,
+    ops: &[Operation],
+    style_config: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    let runes = uses_runes(source) || source.contains("<svelte:options runes");
+    let (style_ops, script_ops): (Vec<Operation>, Vec<Operation>) =
+        ops.iter().cloned().partition(Operation::is_css);
+
+    let mut result = OperationResult {
+        content: source.to_string(),
+        changes: Vec::new(),
+        warnings: markup_warnings(source, &script_ops),
+    };
+
+    for op in script_ops {
+        let op = match op {
+            Operation::SvelteAddReactive {
+                file,
+                name,
+                expression,
+                runes: None,
+            } => Operation::SvelteAddReactive {
+                file,
+                name,
+                expression,
+                runes: Some(runes),
+            },
+            other => other,
+        };
+        if instance_script(&split_blocks(&result.content)).is_none() && is_additive(&op) {
+            result.content = format!("<script lang=\"ts\">\n</script>\n\n{}", result.content);
+        }
+        let step = execute_script_op(&result.content, &op, style_config)?;
+        merge(&mut result, step);
+    }
+
+    if !style_ops.is_empty() {
+        let blocks = split_blocks(&result.content);
+        let style = blocks.iter().find(|b| b.tag == "style").ok_or_else(|| {
+            OperationError::TargetNotFound {
+                description: "No <style> block in component".to_string(),
+            }
+        })?;
+        let step = execute_in_block(&result.content, style, &style_ops, style_config)?;
+        merge(&mut result, step);
+    }
+
+    Ok(result)
+}
+
+/// The instance `<script>` (the one without a module marker).
+pub fn instance_script(blocks: &[SfcBlock]) -> Option<&SfcBlock> {
+    blocks
+        .iter()
+        .find(|b| b.tag == "script" && !is_module_script(b))
+}
+
+/// The module-level `<script>`, shared by all instances.
+pub fn module_script(blocks: &[SfcBlock]) -> Option<&SfcBlock> {
+    blocks
+        .iter()
+        .find(|b| b.tag == "script" && is_module_script(b))
+}
+
+// --- Helper functions ---
+
+fn is_module_script(block: &SfcBlock) -> bool {
+    block.has_attribute("module") || block.attribute("context") == Some("module")
+}
+
+/// Operations that only add code and therefore belong in the instance script.
+fn is_additive(op: &Operation) -> bool {
+    matches!(
+        op,
+        Operation::SvelteAddReactive { .. }
+            | Operation::SvelteAddStore { .. }
+            | Operation::AddImport { .. }
+            | Operation::VueAddToSetup { .. }
+    )
+}
+
+fn execute_script_op(
+    source: &str,
+    op: &Operation,
+    style_config: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    let blocks = split_blocks(source);
+    let instance = instance_script(&blocks);
+    let module = module_script(&blocks);
+    let ops = std::slice::from_ref(op);
+
+    if let Operation::RenameSymbol { from, .. } = op {
+        // A module-level name can be used by the instance script as well
+        let mut content = source.to_string();
+        let mut changes = Vec::new();
+        let mut warnings = Vec::new();
+        for is_module in [true, false] {
+            let blocks = split_blocks(&content);
+            let block = if is_module {
+                module_script(&blocks)
+            } else {
+                instance_script(&blocks)
+            };
+            let Some(block) = block else { continue };
+            match execute_in_block(&content, block, ops, style_config) {
+                Ok(step) => {
+                    content = step.content;
+                    changes.extend(step.changes);
+                    warnings.extend(step.warnings);
+                }
+                Err(OperationError::TargetNotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if changes.is_empty() {
+            return Err(OperationError::TargetNotFound {
+                description: format!("Symbol '{}' not found in any <script>", from),
+            });
+        }
+        return Ok(OperationResult {
+            content,
+            changes,
+            warnings,
+        });
+    }
+
+    let Some(instance) = instance.or(module) else {
+        return Err(OperationError::TargetNotFound {
+            description: "No <script> block in component".to_string(),
+        });
+    };
+    let result = execute_in_block(source, instance, ops, style_config);
+    match (result, module) {
+        (Err(OperationError::TargetNotFound { .. }), Some(module))
+            if module != instance && !is_additive(op) =>
+        {
+            execute_in_block(source, module, ops, style_config)
+        }
+        (result, _) => result,
+    }
+}
+
+fn merge(result: &mut OperationResult, step: OperationResult) {
+    result.content = step.content;
+    result.changes.extend(step.changes);
+    result.warnings.extend(step.warnings);
+}
+
+/// Renames only touch the scripts; point out markup uses left behind.
+fn markup_warnings(source: &str, ops: &[Operation]) -> Vec<String> {
+    let blocks = split_blocks(source);
+    let mut markup = String::new();
+    let mut last = 0;
+    for block in blocks.iter().filter(|b| b.tag != "template") {
+        markup.push_str(&source[last..block.element_start]);
+        last = block.element_end;
+    }
+    markup.push_str(&source[last..]);
+
+    ops.iter()
+        .filter_map(|op| match op {
+            Operation::RenameSymbol { from, to, .. } if contains_word(&markup, from) => {
+                Some(format!(
+                    "Markup still refers to '{}'; rename it to '{}' there too",
+                    from, to
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVELTE4: &str = "<script context=\"module\" lang=\"ts\">\n  export const prerender = true\n</script>\n\n<script lang=\"ts\">\n  import Button from './Button.svelte'\n\n  let count = 0\n</script>\n\n{#if count > 0}\n  <Button on:click={() => count++}>{count}</Button>\n{/if}\n\n<style>\n  p { color: red; }\n</style>\n";
+
+    #[test]
+    fn test_instance_and_module_blocks() {
+        let blocks = split_blocks(SVELTE4);
+        let instance = instance_script(&blocks).unwrap();
+        assert!(instance.content(SVELTE4).contains("let count = 0"));
+        let module = module_script(&blocks).unwrap();
+        assert!(module.content(SVELTE4).contains("prerender"));
+    }
+
+    #[test]
+    fn test_add_reactive_svelte4() {
+        let ops = vec![Operation::SvelteAddReactive {
+            file: None,
+            name: "doubled".into(),
+            expression: "count * 2".into(),
+            runes: None,
+        }];
+        let result = execute_operations(SVELTE4, &ops, &StyleConfig::default()).unwrap();
+        assert!(result
+            .content
+            .contains("  let count = 0\n  $: doubled = count * 2\n</script>"));
+    }
+
+    #[test]
+    fn test_add_reactive_runes() {
+        let source = "<script>\n  let { initial } = $props();\n  let count = $state(initial);\n</script>\n\n<p>{count}</p>\n";
+        let ops = vec![Operation::SvelteAddReactive {
+            file: None,
+            name: "doubled".into(),
+            expression: "count * 2".into(),
+            runes: None,
+        }];
+        let result = execute_operations(source, &ops, &StyleConfig::default()).unwrap();
+        assert!(result
+            .content
+            .contains("  let doubled = $derived(count * 2);\n</script>"));
+    }
+
+    #[test]
+    fn test_ops_fall_back_to_module_script() {
+        let ops = vec![
+            Operation::AddImport {
+                file: None,
+                source: "svelte".into(),
+                specifiers: vec!["onMount".into()],
+                default_import: None,
+                type_only: false,
+            },
+            Operation::RenameSymbol {
+                file: None,
+                from: "prerender".into(),
+                to: "ssr".into(),
+                scope: None,
+            },
+            Operation::SetDeclaration {
+                file: None,
+                template: None,
+                selector: "p".into(),
+                property: "color".into(),
+                value: "blue".into(),
+                media: None,
+            },
+        ];
+        let result = execute_operations(SVELTE4, &ops, &StyleConfig::default()).unwrap();
+        assert!(result.content.contains("  export const ssr = true\n"));
+        assert!(result.content.contains(
+            "  import Button from './Button.svelte'\n  import { onMount } from 'svelte'\n"
+        ));
+        assert!(result.content.contains("p { color: blue; }"));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_add_store_creates_script() {
+        let source = "<h1>Hello</h1>\n";
+        let ops = vec![Operation::SvelteAddStore {
+            file: None,
+            name: "count".into(),
+            initial_value: "0".into(),
+            kind: "writable".into(),
+        }];
+        let result = execute_operations(source, &ops, &StyleConfig::default()).unwrap();
+        assert!(result
+            .content
+            .starts_with("<script lang=\"ts\">\nimport { writable } from 'svelte/store'"));
+        assert!(result.content.contains("const count = writable(0)"));
+        assert!(result.content.ends_with("</script>\n\n<h1>Hello</h1>\n"));
+    }
+}
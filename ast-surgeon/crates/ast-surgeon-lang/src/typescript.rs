@@ -100,44 +100,6 @@ pub fn import_insertion_point(source: &str, tree: &tree_sitter::Tree) -> usize {
     })
 }
 
-/// Detect the quote style used in imports (single or double).
-pub fn detect_quote_style(source: &str) -> char {
-    // Count single vs double quotes in import statements
-    let single_count = source.matches("from '").count();
-    let double_count = source.matches("from \"").count();
-
-    if single_count >= double_count {
-        '\''
-    } else {
-        '"'
-    }
-}
-
-/// Detect whether the file uses semicolons.
-pub fn detect_semicolons(source: &str) -> bool {
-    // Check first 20 lines for semicolons at end
-    let with_semi = source
-        .lines()
-        .take(20)
-        .filter(|l| l.trim_end().ends_with(';'))
-        .count();
-    let without_semi = source
-        .lines()
-        .take(20)
-        .filter(|l| {
-            let trimmed = l.trim();
-            !trimmed.is_empty()
-                && !trimmed.starts_with("//")
-                && !trimmed.starts_with("/*")
-                && !trimmed.ends_with('{')
-                && !trimmed.ends_with('}')
-                && !trimmed.ends_with(';')
-        })
-        .count();
-
-    with_semi >= without_semi
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,22 +129,4 @@ mod tests {
         let point = import_insertion_point(source, &tree);
         assert_eq!(point, 0);
     }
-
-    #[test]
-    fn test_detect_single_quotes() {
-        let source = "import { a } from './a';\nimport { b } from './b';";
-        assert_eq!(detect_quote_style(source), '\'');
-    }
-
-    #[test]
-    fn test_detect_double_quotes() {
-        let source = "import { a } from \"./a\";\nimport { b } from \"./b\";";
-        assert_eq!(detect_quote_style(source), '"');
-    }
-
-    #[test]
-    fn test_detect_semicolons_true() {
-        let source = "import { a } from './a';\nconst x = 1;\nconst y = 2;";
-        assert!(detect_semicolons(source));
-    }
 }
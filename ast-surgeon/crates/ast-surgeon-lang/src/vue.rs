@@ -0,0 +1,189 @@
+//! Vue single-file component support.
+//!
+//! Script operations run inside `<script setup>` (or the plain `<script>`
+//! when there is no setup block) and CSS operations inside the first
+//! `<style>` block. The template is left untouched.
+
+use crate::sfc::{contains_word, execute_in_block, split_blocks, SfcBlock};
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::{Operation, OperationError, OperationResult};
+
+/// Apply operations to a `.vue` file.
+pub fn execute_operations(
+    source: &str,
+    ops: &[Operation],
+    style_config: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    let (style_ops, script_ops): (Vec<Operation>, Vec<Operation>) =
+        ops.iter().cloned().partition(Operation::is_css);
+
+    let mut content = source.to_string();
+    let mut changes = Vec::new();
+    let mut warnings = Vec::new();
+
+    if !script_ops.is_empty() {
+        let needs_setup = script_ops.iter().any(is_setup_operation);
+        if needs_setup && script_block(&split_blocks(&content)).is_none() {
+            content = format!("<script setup lang=\"ts\">\n</script>\n\n{}", content);
+        }
+        let blocks = split_blocks(&content);
+        let script = script_block(&blocks).ok_or_else(|| OperationError::TargetNotFound {
+            description: "No <script> block in component".to_string(),
+        })?;
+        if needs_setup && !script.has_attribute("setup") {
+            return Err(OperationError::InvalidParams {
+                message: "vue_add_to_setup and vue_wrap_in_ref need a <script setup> block"
+                    .to_string(),
+            });
+        }
+        warnings.extend(template_warnings(&content, &blocks, &script_ops));
+
+        let result = execute_in_block(&content, script, &script_ops, style_config)?;
+        content = result.content;
+        changes.extend(result.changes);
+        warnings.extend(result.warnings);
+    }
+
+    if !style_ops.is_empty() {
+        let blocks = split_blocks(&content);
+        let style = blocks.iter().find(|b| b.tag == "style").ok_or_else(|| {
+            OperationError::TargetNotFound {
+                description: "No <style> block in component".to_string(),
+            }
+        })?;
+        let result = execute_in_block(&content, style, &style_ops, style_config)?;
+        content = result.content;
+        changes.extend(result.changes);
+        warnings.extend(result.warnings);
+    }
+
+    Ok(OperationResult {
+        content,
+        changes,
+        warnings,
+    })
+}
+
+/// `<script setup>` if present, else the first `<script>`.
+pub fn script_block(blocks: &[SfcBlock]) -> Option<&SfcBlock> {
+    blocks
+        .iter()
+        .find(|b| b.tag == "script" && b.has_attribute("setup"))
+        .or_else(|| blocks.iter().find(|b| b.tag == "script"))
+}
+
+// --- Helper functions ---
+
+fn is_setup_operation(op: &Operation) -> bool {
+    matches!(
+        op,
+        Operation::VueAddToSetup { .. } | Operation::VueWrapInRef { .. }
+    )
+}
+
+/// Renames only touch the script; point out template uses left behind.
+fn template_warnings(source: &str, blocks: &[SfcBlock], ops: &[Operation]) -> Vec<String> {
+    let Some(template) = blocks.iter().find(|b| b.tag == "template") else {
+        return vec![];
+    };
+    let text = template.content(source);
+    ops.iter()
+        .filter_map(|op| match op {
+            Operation::RenameSymbol { from, to, .. } if contains_word(text, from) => Some(format!(
+                "<template> still refers to '{}'; rename it to '{}' there too",
+                from, to
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SFC: &str = "<template>\n  <button @click=\"inc\">{{ count }}</button>\n</template>\n\n<script setup lang=\"ts\">\nimport { computed } from 'vue'\n\nlet count = 0\nconst label = computed(() => `Clicked ${count}`)\nfunction inc() {\n  count++\n}\n</script>\n\n<style scoped>\n.button {\n  color: red;\n}\n</style>\n";
+
+    #[test]
+    fn test_wrap_in_ref_inside_setup() {
+        let ops = vec![Operation::VueWrapInRef {
+            file: None,
+            variable_name: "count".into(),
+        }];
+        let result = execute_operations(SFC, &ops, &StyleConfig::default()).unwrap();
+        assert!(result
+            .content
+            .contains("import { computed, ref } from 'vue'\n\nconst count = ref(0)\n"));
+        assert!(result.content.contains("`Clicked ${count.value}`"));
+        assert!(result.content.contains("  count.value++\n"));
+        // Templates unwrap refs on their own
+        assert!(result.content.contains("{{ count }}"));
+    }
+
+    #[test]
+    fn test_ts_and_css_ops_route_to_their_blocks() {
+        let ops = vec![
+            Operation::AddImport {
+                file: None,
+                source: "./useTheme".into(),
+                specifiers: vec!["useTheme".into()],
+                default_import: None,
+                type_only: false,
+            },
+            Operation::SetDeclaration {
+                file: None,
+                template: None,
+                selector: ".button".into(),
+                property: "color".into(),
+                value: "blue".into(),
+                media: None,
+            },
+        ];
+        let result = execute_operations(SFC, &ops, &StyleConfig::default()).unwrap();
+        assert!(result
+            .content
+            .contains("import { useTheme } from './useTheme'\n"));
+        assert!(result.content.contains(".button {\n  color: blue;\n}"));
+        assert_eq!(result.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_add_to_setup_creates_block() {
+        let source = "<template>\n  <p>Hi</p>\n</template>\n";
+        let ops = vec![Operation::VueAddToSetup {
+            file: None,
+            code: "const msg = 'hi'".into(),
+        }];
+        let result = execute_operations(source, &ops, &StyleConfig::default()).unwrap();
+        assert_eq!(
+            result.content,
+            "<script setup lang=\"ts\">\nconst msg = 'hi'\n</script>\n\n<template>\n  <p>Hi</p>\n</template>\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_warns_about_template() {
+        let ops = vec![Operation::RenameSymbol {
+            file: None,
+            from: "inc".into(),
+            to: "increment".into(),
+            scope: None,
+        }];
+        let result = execute_operations(SFC, &ops, &StyleConfig::default()).unwrap();
+        assert!(result.content.contains("function increment()"));
+        assert_eq!(result.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_setup_ops_refused_for_options_api() {
+        let source = "<script>\nexport default { data() { return { a: 1 } } }\n</script>\n";
+        let ops = vec![Operation::VueAddToSetup {
+            file: None,
+            code: "const b = 2".into(),
+        }];
+        assert!(matches!(
+            execute_operations(source, &ops, &StyleConfig::default()),
+            Err(OperationError::InvalidParams { .. })
+        ));
+    }
+}
@@ -3,6 +3,7 @@
 //! Thin shell that deserializes JSON requests, calls core functions,
 //! and serializes JSON responses. All logic lives in ast-surgeon-core.
 
+use ast_surgeon_core::format::StyleConfig;
 use wasm_bindgen::prelude::*;
 
 mod protocol;
@@ -115,7 +116,8 @@ fn process_file_impl(request_json: &str) -> Result<String, Box<dyn std::error::E
     let ts_language = lang.ts_language();
 
     // Parse the source
-    let tree = ast_surgeon_core::validate::parse_best_effort(&request.content, &ts_language)
+    let tree = lang
+        .parse(&request.content)
         .map_err(|e| format!("Parse failed: {}", e))?;
 
     // Execute operations
@@ -124,6 +126,8 @@ fn process_file_impl(request_json: &str) -> Result<String, Box<dyn std::error::E
         &tree,
         &request.operations,
         &ts_language,
+        lang.parse_check(),
+        &StyleConfig::default(),
     );
 
     match result {
@@ -193,8 +197,7 @@ fn process_batch_impl(request_json: &str) -> Result<String, Box<dyn std::error::
 
         let ts_language = lang.ts_language();
 
-        let tree = match ast_surgeon_core::validate::parse_best_effort(&entry.content, &ts_language)
-        {
+        let tree = match lang.parse(&entry.content) {
             Ok(t) => t,
             Err(e) => {
                 errors.push(protocol::BatchFileError {
@@ -211,6 +214,8 @@ fn process_batch_impl(request_json: &str) -> Result<String, Box<dyn std::error::
             &tree,
             &entry.operations,
             &ts_language,
+            lang.parse_check(),
+            &StyleConfig::default(),
         ) {
             Ok(op_result) => {
                 let edits_count = op_result.changes.len();
@@ -270,5 +275,6 @@ fn error_code(e: &ast_surgeon_core::operations::OperationError) -> String {
         OperationError::SourceHasErrors { .. } => "SOURCE_HAS_ERRORS".to_string(),
         OperationError::UnsupportedLanguage { .. } => "UNSUPPORTED_LANGUAGE".to_string(),
         OperationError::InvalidParams { .. } => "INVALID_PARAMS".to_string(),
+        OperationError::Blocked { .. } => "BLOCKED".to_string(),
     }
 }
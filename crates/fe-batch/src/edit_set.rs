@@ -27,6 +27,13 @@ pub struct ValidatedCreate {
     pub content: String,
 }
 
+/// A validated delete operation with resolved absolute path.
+#[derive(Debug)]
+pub struct ValidatedDelete {
+    pub absolute_path: PathBuf,
+    pub relative_path: String,
+}
+
 /// Validate all edits and creates from the input. Returns validated operations
 /// or an error if any validation rule is violated.
 pub fn validate_input(
@@ -35,9 +42,10 @@ pub fn validate_input(
 ) -> Result<(Vec<ValidatedEdit>, Vec<ValidatedCreate>), BatchError> {
     let edits = input.edits.as_deref().unwrap_or(&[]);
     let creates = input.creates.as_deref().unwrap_or(&[]);
+    let deletes = input.deletes.as_deref().unwrap_or(&[]);
 
-    // Rule: at least one edit or create
-    if edits.is_empty() && creates.is_empty() {
+    // Rule: at least one edit, create or delete
+    if edits.is_empty() && creates.is_empty() && deletes.is_empty() {
         return Err(BatchError::EmptyTransaction);
     }
 
@@ -60,6 +68,50 @@ pub fn validate_input(
     Ok((validated_edits, validated_creates))
 }
 
+/// Validate the deletes from the input. A deleted file may not also be
+/// edited or created in the same transaction.
+pub fn validate_deletes(
+    project_root: &Path,
+    input: &BatchInput,
+) -> Result<Vec<ValidatedDelete>, BatchError> {
+    let mut seen_paths: HashSet<String> = input
+        .edits
+        .iter()
+        .flatten()
+        .map(|e| e.file.replace('\\', "/"))
+        .chain(
+            input
+                .creates
+                .iter()
+                .flatten()
+                .map(|c| c.file.replace('\\', "/")),
+        )
+        .collect();
+
+    let mut validated = Vec::new();
+    for file in input.deletes.iter().flatten() {
+        // Rule: path must resolve within project root
+        let absolute_path = resolve_within_root(project_root, file)
+            .map_err(|_| BatchError::PathTraversal(PathBuf::from(file)))?;
+
+        // Rule: no duplicate paths
+        if !seen_paths.insert(file.replace('\\', "/")) {
+            return Err(BatchError::DuplicatePath(file.clone()));
+        }
+
+        // Rule: file must exist for deletes
+        if !absolute_path.is_file() {
+            return Err(BatchError::FileNotFound(absolute_path));
+        }
+
+        validated.push(ValidatedDelete {
+            absolute_path,
+            relative_path: file.clone(),
+        });
+    }
+    Ok(validated)
+}
+
 fn validate_edit(
     project_root: &Path,
     edit: &EditOperation,
@@ -138,6 +190,7 @@ mod tests {
         BatchInput {
             edits: if edits.is_empty() { None } else { Some(edits) },
             creates: if creates.is_empty() { None } else { Some(creates) },
+            deletes: None,
             verify: Some(false),
             rollback_on_failure: Some(true),
         }
@@ -22,7 +22,7 @@ pub enum BatchError {
     #[error("Edit specifies neither 'content' nor 'operations' for: {0}")]
     EmptyEdit(String),
 
-    #[error("No edits or creates specified")]
+    #[error("No edits, creates or deletes specified")]
     EmptyTransaction,
 
     // File system errors
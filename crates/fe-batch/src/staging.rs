@@ -52,13 +52,13 @@ impl StagingArea {
 
         // Create parent directories in the staging area
         if let Some(parent) = staged_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| BatchError::StagingError(e))?;
+            fs::create_dir_all(parent).map_err(BatchError::StagingError)?;
         }
 
         // Write content to staged file
-        let mut file = fs::File::create(&staged_path).map_err(|e| BatchError::StagingError(e))?;
+        let mut file = fs::File::create(&staged_path).map_err(BatchError::StagingError)?;
         file.write_all(content.as_bytes())
-            .map_err(|e| BatchError::StagingError(e))?;
+            .map_err(BatchError::StagingError)?;
 
         self.staged_files.push(StagedFile {
             relative_path: relative_path.to_string(),
@@ -1,4 +1,4 @@
-use crate::edit_set::{EditChange, ValidatedCreate, ValidatedEdit};
+use crate::edit_set::{EditChange, ValidatedCreate, ValidatedDelete, ValidatedEdit};
 use crate::error::BatchError;
 use crate::file_ops::{atomic_create, atomic_write, FileBackupSet};
 use crate::staging::StagingArea;
@@ -30,6 +30,7 @@ pub struct Transaction<State = Pending> {
     project_root: PathBuf,
     edits: Vec<ValidatedEdit>,
     creates: Vec<ValidatedCreate>,
+    deletes: Vec<ValidatedDelete>,
     verify: bool,
     rollback_on_failure: bool,
     staging: Option<StagingArea>,
@@ -43,6 +44,7 @@ impl<State> std::fmt::Debug for Transaction<State> {
             .field("project_root", &self.project_root)
             .field("edits_count", &self.edits.len())
             .field("creates_count", &self.creates.len())
+            .field("deletes_count", &self.deletes.len())
             .field("verify", &self.verify)
             .field("rollback_on_failure", &self.rollback_on_failure)
             .finish()
@@ -59,11 +61,13 @@ impl Transaction<Pending> {
         let rollback_on_failure = input.rollback_on_failure();
 
         let (edits, creates) = crate::edit_set::validate_input(&project_root, &input)?;
+        let deletes = crate::edit_set::validate_deletes(&project_root, &input)?;
 
         Ok(Transaction {
             project_root,
             edits,
             creates,
+            deletes,
             verify,
             rollback_on_failure,
             staging: None,
@@ -99,6 +103,7 @@ impl Transaction<Pending> {
             project_root: self.project_root,
             edits: self.edits,
             creates: self.creates,
+            deletes: self.deletes,
             verify: self.verify,
             rollback_on_failure: self.rollback_on_failure,
             staging: Some(staging),
@@ -157,10 +162,26 @@ impl Transaction<Staged> {
             backups.record_creation(&create.absolute_path);
         }
 
+        // Apply deletes, keeping a backup to restore on rollback
+        for delete in &self.deletes {
+            let removed = backups.backup_file(&delete.absolute_path).and_then(|()| {
+                std::fs::remove_file(&delete.absolute_path).map_err(|e| BatchError::WriteError {
+                    path: delete.absolute_path.clone(),
+                    source: e,
+                })
+            });
+            if let Err(e) = removed {
+                tracing::error!("Delete failed for {}, initiating rollback: {e}", delete.relative_path);
+                let _ = backups.restore_all();
+                return Err(e);
+            }
+        }
+
         Ok(Transaction {
             project_root: self.project_root,
             edits: self.edits,
             creates: self.creates,
+            deletes: self.deletes,
             verify: self.verify,
             rollback_on_failure: self.rollback_on_failure,
             staging: self.staging,
@@ -209,6 +230,7 @@ impl Transaction<Applied> {
             project_root: self.project_root,
             edits: self.edits,
             creates: self.creates,
+            deletes: self.deletes,
             verify: self.verify,
             rollback_on_failure: self.rollback_on_failure,
             staging: None,
@@ -226,6 +248,7 @@ impl Transaction<Applied> {
             project_root: self.project_root,
             edits: self.edits,
             creates: self.creates,
+            deletes: self.deletes,
             verify: self.verify,
             rollback_on_failure: self.rollback_on_failure,
             staging: None,
@@ -243,6 +266,7 @@ impl Transaction<Committed> {
             status: BatchStatus::Success,
             files_modified: self.edits.iter().map(|e| e.relative_path.clone()).collect(),
             files_created: self.creates.iter().map(|c| c.relative_path.clone()).collect(),
+            files_deleted: self.deletes.iter().map(|d| d.relative_path.clone()).collect(),
             verification,
             errors: Vec::new(),
             rolled_back: false,
@@ -257,6 +281,7 @@ impl Transaction<Committed> {
             status: BatchStatus::VerificationFailed,
             files_modified: self.edits.iter().map(|e| e.relative_path.clone()).collect(),
             files_created: self.creates.iter().map(|c| c.relative_path.clone()).collect(),
+            files_deleted: self.deletes.iter().map(|d| d.relative_path.clone()).collect(),
             verification,
             errors: Vec::new(),
             rolled_back: false,
@@ -270,6 +295,7 @@ impl Transaction<RolledBack> {
             status: BatchStatus::RolledBack,
             files_modified: Vec::new(),
             files_created: Vec::new(),
+            files_deleted: Vec::new(),
             verification,
             errors: Vec::new(),
             rolled_back: true,
@@ -281,6 +307,7 @@ impl Transaction<RolledBack> {
             status: BatchStatus::RolledBack,
             files_modified: Vec::new(),
             files_created: Vec::new(),
+            files_deleted: Vec::new(),
             verification: None,
             errors: vec![BatchErrorDetail {
                 file: None,
@@ -302,6 +329,7 @@ mod tests {
         BatchInput {
             edits: if edits.is_empty() { None } else { Some(edits) },
             creates: if creates.is_empty() { None } else { Some(creates) },
+            deletes: None,
             verify: Some(false),
             rollback_on_failure: Some(true),
         }
@@ -313,6 +341,7 @@ mod tests {
         let input = BatchInput {
             edits: None,
             creates: None,
+            deletes: None,
             verify: Some(false),
             rollback_on_failure: Some(true),
         };
@@ -468,4 +497,50 @@ mod tests {
         let _rolled_back = applied.rollback().unwrap();
         assert!(!dir.path().join("new_file.ts").exists());
     }
+
+    #[test]
+    fn test_transaction_deletes_files_and_restores_on_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("old.js"), "original").unwrap();
+
+        let mut input = make_input(
+            vec![],
+            vec![CreateOperation {
+                file: "old.ts".to_string(),
+                content: "migrated".to_string(),
+            }],
+        );
+        input.deletes = Some(vec!["old.js".to_string()]);
+
+        let txn = Transaction::new(dir.path().to_path_buf(), input).unwrap();
+        let applied = txn.stage().unwrap().apply().unwrap();
+        assert!(!dir.path().join("old.js").exists());
+        assert!(dir.path().join("old.ts").exists());
+
+        let _rolled_back = applied.rollback().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("old.js")).unwrap(),
+            "original"
+        );
+        assert!(!dir.path().join("old.ts").exists());
+    }
+
+    #[test]
+    fn test_transaction_rejects_deleting_an_edited_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("file.ts"), "original").unwrap();
+
+        let mut input = make_input(
+            vec![EditOperation {
+                file: "file.ts".to_string(),
+                content: Some("edited".to_string()),
+                operations: None,
+            }],
+            vec![],
+        );
+        input.deletes = Some(vec!["file.ts".to_string()]);
+
+        let err = Transaction::new(dir.path().to_path_buf(), input).unwrap_err();
+        assert!(matches!(err, BatchError::DuplicatePath(_)));
+    }
 }
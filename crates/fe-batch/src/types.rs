@@ -9,6 +9,9 @@ pub struct BatchInput {
     /// Files to create (must NOT already exist).
     pub creates: Option<Vec<CreateOperation>>,
 
+    /// Files to delete (must exist). Restored on rollback.
+    pub deletes: Option<Vec<String>>,
+
     /// Run verification (lint/types/tests) after applying changes. Default: true.
     pub verify: Option<bool>,
 
@@ -61,6 +64,7 @@ pub struct BatchResult {
     pub status: BatchStatus,
     pub files_modified: Vec<String>,
     pub files_created: Vec<String>,
+    pub files_deleted: Vec<String>,
     pub verification: Option<fe_verify::types::VerificationSummary>,
     pub errors: Vec<BatchErrorDetail>,
     pub rolled_back: bool,
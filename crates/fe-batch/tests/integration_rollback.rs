@@ -6,6 +6,7 @@ fn make_input(edits: Vec<EditOperation>, creates: Vec<CreateOperation>) -> Batch
     BatchInput {
         edits: if edits.is_empty() { None } else { Some(edits) },
         creates: if creates.is_empty() { None } else { Some(creates) },
+        deletes: None,
         verify: Some(false),
         rollback_on_failure: Some(true),
     }
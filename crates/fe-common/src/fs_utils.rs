@@ -49,6 +49,90 @@ pub fn normalize_path(path: &Path) -> PathBuf {
     components.iter().collect()
 }
 
+/// Directories never searched for source files, even without a `.gitignore`.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "dist", "build", "coverage"];
+
+/// Extensions tried, in order, when an import specifier omits one.
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// List files under `root` with one of `extensions`, honoring `.gitignore`
+/// and skipping hidden and build directories. Paths are absolute and sorted.
+pub fn source_files(root: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    walk_files(root)
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.contains(&e))
+        })
+        .collect()
+}
+
+/// Whether a `file` argument is a glob pattern rather than a path. An
+/// existing file is always a path, so route directories such as
+/// `app/[slug]/page.tsx` are not taken for character classes.
+pub fn is_glob(root: &Path, pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '{']) && !root.join(pattern).is_file()
+}
+
+/// List files under `root` whose root-relative path matches `pattern`
+/// (`src/**/*.stories.tsx`; `*` stays within one directory), with the same
+/// filtering as [`source_files`]. Paths are absolute and sorted.
+pub fn glob_files(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let matcher = globset::GlobBuilder::new(pattern.trim_start_matches("./"))
+        .literal_separator(true)
+        .build()
+        .map_err(|e| format!("Invalid glob '{pattern}': {e}"))?
+        .compile_matcher();
+    Ok(walk_files(root)
+        .into_iter()
+        .filter(|path| {
+            path.strip_prefix(root)
+                .is_ok_and(|relative| matcher.is_match(relative))
+        })
+        .collect())
+}
+
+fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(|entry| {
+            !entry.file_type().is_some_and(|t| t.is_dir())
+                || !SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Resolve a relative import specifier (`./Card`, `../styles.module.css`)
+/// from `importer` to an existing file, trying the usual extensions and
+/// `index` files. Bare and aliased specifiers return `None`.
+pub fn resolve_relative_import(importer: &Path, specifier: &str) -> Option<PathBuf> {
+    if !specifier.starts_with("./") && !specifier.starts_with("../") {
+        return None;
+    }
+    let base = normalize_path(&importer.parent()?.join(specifier));
+    if base.is_file() {
+        return Some(base);
+    }
+    let file_name = base.file_name()?.to_string_lossy().into_owned();
+    for ext in RESOLVE_EXTENSIONS {
+        let candidate = base.with_file_name(format!("{file_name}.{ext}"));
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    RESOLVE_EXTENSIONS
+        .iter()
+        .map(|ext| base.join(format!("index.{ext}")))
+        .find(|candidate| candidate.is_file())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +171,85 @@ mod tests {
         let p = Path::new("/a/b/../c/./d");
         assert_eq!(normalize_path(p), PathBuf::from("/a/c/d"));
     }
+
+    #[test]
+    fn test_source_files_skips_ignored_and_build_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        std::fs::write(root.join("src/App.tsx"), "").unwrap();
+        std::fs::write(root.join("src/App.css"), "").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.ts"), "").unwrap();
+        std::fs::write(root.join("generated/api.ts"), "").unwrap();
+
+        let files = source_files(root, &["ts", "tsx"]);
+        assert_eq!(files, vec![root.join("src/App.tsx")]);
+    }
+
+    #[test]
+    fn test_resolve_relative_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/utils")).unwrap();
+        std::fs::write(root.join("src/Card.module.css"), "").unwrap();
+        std::fs::write(root.join("src/Button.tsx"), "").unwrap();
+        std::fs::write(root.join("src/utils/index.ts"), "").unwrap();
+        let importer = root.join("src/App.tsx");
+
+        assert_eq!(
+            resolve_relative_import(&importer, "./Card.module.css"),
+            Some(root.join("src/Card.module.css"))
+        );
+        assert_eq!(
+            resolve_relative_import(&importer, "./Button"),
+            Some(root.join("src/Button.tsx"))
+        );
+        assert_eq!(
+            resolve_relative_import(&importer, "./utils"),
+            Some(root.join("src/utils/index.ts"))
+        );
+        assert_eq!(resolve_relative_import(&importer, "react"), None);
+    }
+
+    #[test]
+    fn test_existing_bracketed_path_is_not_a_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("app/[slug]")).unwrap();
+        std::fs::write(root.join("app/[slug]/page.tsx"), "").unwrap();
+
+        assert!(!is_glob(root, "app/[slug]/page.tsx"));
+        assert!(is_glob(root, "app/[slug]/layout.tsx"));
+        assert!(is_glob(root, "app/{a,b}/page.tsx"));
+    }
+
+    #[test]
+    fn test_glob_files_honors_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/ui")).unwrap();
+        std::fs::create_dir_all(root.join("storybook-static")).unwrap();
+        std::fs::write(root.join(".gitignore"), "storybook-static/\n").unwrap();
+        std::fs::write(root.join("src/Button.stories.tsx"), "").unwrap();
+        std::fs::write(root.join("src/ui/Card.stories.tsx"), "").unwrap();
+        std::fs::write(root.join("src/ui/Card.tsx"), "").unwrap();
+        std::fs::write(root.join("storybook-static/A.stories.tsx"), "").unwrap();
+
+        assert!(is_glob(root, "src/**/*.stories.tsx") && !is_glob(root, "src/App.tsx"));
+        assert_eq!(
+            glob_files(root, "src/**/*.stories.tsx").unwrap(),
+            vec![
+                root.join("src/Button.stories.tsx"),
+                root.join("src/ui/Card.stories.tsx")
+            ]
+        );
+        assert_eq!(
+            glob_files(root, "src/*.stories.tsx").unwrap(),
+            vec![root.join("src/Button.stories.tsx")]
+        );
+        assert!(glob_files(root, "src/[").is_err());
+    }
 }
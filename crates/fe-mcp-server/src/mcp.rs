@@ -10,6 +10,7 @@ use serde_json::Value;
 
 #[derive(Deserialize, Debug)]
 pub struct JsonRpcRequest {
+    #[allow(dead_code)]
     pub jsonrpc: String,
     pub id: Option<Value>,
     pub method: String,
@@ -19,6 +20,7 @@ pub struct JsonRpcRequest {
 
 #[derive(Serialize)]
 pub struct JsonRpcResponse {
+    #[allow(dead_code)]
     pub jsonrpc: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<Value>,
@@ -1,7 +1,7 @@
 use crate::mcp::*;
 use crate::tools::ToolRegistry;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 
 /// Run the MCP server: read JSON-RPC from stdin, write responses to stdout.
@@ -43,7 +43,7 @@ pub async fn run(project_root: PathBuf) -> Result<(), Box<dyn std::error::Error>
 async fn handle_request(
     req: &JsonRpcRequest,
     registry: &ToolRegistry,
-    project_root: &PathBuf,
+    project_root: &Path,
 ) -> Option<JsonRpcResponse> {
     match req.method.as_str() {
         "initialize" => {
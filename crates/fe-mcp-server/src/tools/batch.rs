@@ -68,6 +68,11 @@ impl Tool for BatchTool {
                         },
                         "description": "Files to create (must NOT already exist)."
                     },
+                    "deletes": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files to delete (must exist; restored on rollback)."
+                    },
                     "verify": {
                         "type": "boolean",
                         "default": true,
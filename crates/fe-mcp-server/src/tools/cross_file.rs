@@ -0,0 +1,883 @@
+//! Operations that touch more than one file.
+//!
+//! Each operation is planned up front (every affected file is read, edited
+//! in memory and re-parsed), then written through a single fe-batch
+//! transaction so a failure leaves the project untouched.
+
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::css::RenameSelector;
+use ast_surgeon_core::operations::css_modules::{RenameComposedClass, RenameModuleClassUsages};
+use ast_surgeon_core::operations::i18n::{ExtractI18n, DEFAULT_ATTRIBUTES};
+use ast_surgeon_core::operations::json::JsonSet;
+use ast_surgeon_core::operations::migrate_typescript::MigrateToTypescript;
+use ast_surgeon_core::operations::update_paths::{MatchMode, UpdateImportPaths};
+use ast_surgeon_core::operations::{ChangeDescription, Executable};
+use ast_surgeon_core::validate::ParseCheck;
+use ast_surgeon_lang::registry::detect_language;
+use ast_surgeon_lang::SupportedLanguage;
+use ast_surgeon_lang::style::resolve_style_config;
+use fe_batch::types::{BatchInput, CreateOperation, EditOperation};
+use fe_batch::Transaction;
+use fe_common::fs_utils::{resolve_relative_import, resolve_within_root, source_files};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Language, Node, Tree};
+
+/// `op` names handled here rather than by the per-file executor.
+pub const CROSS_FILE_OPS: &[&str] = &[
+    "rename_css_module_class",
+    "extract_i18n",
+    "migrate_to_typescript",
+];
+
+/// Extensions of files that may import other modules.
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+const STYLE_EXTENSIONS: &[&str] = &["css", "scss", "less"];
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum CrossFileOperation {
+    RenameCssModuleClass {
+        /// The `.module.css` / `.module.scss` stylesheet.
+        file: String,
+        /// Class name, with or without the leading dot.
+        from: String,
+        to: String,
+    },
+    ExtractI18n(ExtractI18nParams),
+    MigrateToTypescript {
+        /// The `.js` / `.jsx` file; renamed to `.ts` / `.tsx`.
+        file: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractI18nParams {
+    /// The component file.
+    file: String,
+    /// Locale JSON the messages are written to; created when missing.
+    locale_file: String,
+    /// Key prefix, e.g. "home" for `t('home.welcomeBack')`.
+    namespace: String,
+    /// String attributes to extract; defaults to placeholder, title, alt,
+    /// aria-label and label.
+    #[serde(default)]
+    attributes: Option<Vec<String>>,
+    /// Translation function name.
+    #[serde(default = "default_translate_function")]
+    function: String,
+    /// Module `useTranslation` is imported from.
+    #[serde(default = "default_i18n_module")]
+    import_from: String,
+    /// Only extract inside this component.
+    #[serde(default)]
+    component: Option<String>,
+}
+
+fn default_translate_function() -> String {
+    "t".to_string()
+}
+
+fn default_i18n_module() -> String {
+    "react-i18next".to_string()
+}
+
+/// New content for one file, computed but not yet written.
+#[derive(Debug)]
+pub struct FileUpdate {
+    pub file: String,
+    pub content: String,
+    pub changes: Vec<ChangeDescription>,
+    pub warnings: Vec<String>,
+    pub kind: UpdateKind,
+}
+
+/// How a [`FileUpdate`] is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateKind {
+    /// Replace the content of an existing file.
+    Edit,
+    /// Write a file that does not exist yet.
+    Create,
+    /// Remove the file; `content` is unused.
+    Delete,
+}
+
+/// Whether an operation value must go through [`plan`].
+pub fn is_cross_file(op_value: &Value) -> bool {
+    op_value
+        .get("op")
+        .and_then(Value::as_str)
+        .is_some_and(|op| CROSS_FILE_OPS.contains(&op))
+}
+
+/// Compute every file update for a cross-file operation.
+pub fn plan(op_value: &Value, project_root: &Path) -> Result<Vec<FileUpdate>, String> {
+    let op: CrossFileOperation =
+        serde_json::from_value(op_value.clone()).map_err(|e| format!("Invalid operation: {e}"))?;
+
+    match op {
+        CrossFileOperation::RenameCssModuleClass { file, from, to } => {
+            plan_css_module_rename(project_root, &file, &from, &to)
+        }
+        CrossFileOperation::ExtractI18n(params) => plan_extract_i18n(project_root, params),
+        CrossFileOperation::MigrateToTypescript { file } => {
+            plan_migrate_to_typescript(project_root, &file)
+        }
+    }
+}
+
+/// Write planned updates as one transaction.
+pub fn commit(project_root: &Path, updates: &[FileUpdate]) -> Result<(), String> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+    let of_kind = |kind| updates.iter().filter(move |u: &&FileUpdate| u.kind == kind);
+    let input = BatchInput {
+        edits: Some(
+            of_kind(UpdateKind::Edit)
+                .map(|u| EditOperation {
+                    file: u.file.clone(),
+                    content: Some(u.content.clone()),
+                    operations: None,
+                })
+                .collect(),
+        ),
+        creates: Some(
+            of_kind(UpdateKind::Create)
+                .map(|u| CreateOperation {
+                    file: u.file.clone(),
+                    content: u.content.clone(),
+                })
+                .collect(),
+        ),
+        deletes: Some(
+            of_kind(UpdateKind::Delete)
+                .map(|u| u.file.clone())
+                .collect(),
+        ),
+        verify: Some(false),
+        rollback_on_failure: Some(true),
+    };
+    let txn = Transaction::new(project_root.to_path_buf(), input)
+        .map_err(|e| format!("Validation failed: {e}"))?;
+    let txn = txn.stage().map_err(|e| format!("Staging failed: {e}"))?;
+    let txn = txn.apply().map_err(|e| format!("Apply failed: {e}"))?;
+    txn.commit();
+    Ok(())
+}
+
+fn plan_css_module_rename(
+    project_root: &Path,
+    file: &str,
+    from: &str,
+    to: &str,
+) -> Result<Vec<FileUpdate>, String> {
+    if !file.contains(".module.") {
+        return Err(format!("{file}: not a CSS module (expected *.module.css)"));
+    }
+    let stylesheet = resolve_within_root(project_root, file)?;
+    let from = from.trim_start_matches('.');
+    let to = to.trim_start_matches('.');
+
+    let rename = RenameSelector::new(format!(".{from}"), format!(".{to}"));
+    let composes = RenameComposedClass::new(None, from.to_string(), to.to_string());
+    let mut updates = vec![apply_to_file(
+        project_root,
+        &stylesheet,
+        vec![Box::new(rename), Box::new(composes)],
+    )?
+    .ok_or_else(|| format!("{file}: class '.{from}' not found"))?];
+
+    // Other stylesheets can `composes: card from './Card.module.css'`
+    for other in source_files(project_root, STYLE_EXTENSIONS) {
+        if other == stylesheet {
+            continue;
+        }
+        let Ok((_, source, _, tree)) = parse_file(project_root, &other) else {
+            continue;
+        };
+        let specifiers = import_specifiers_of(&source, &tree, &other, &stylesheet);
+        if specifiers.is_empty() {
+            continue;
+        }
+        let composes =
+            RenameComposedClass::new(Some(specifiers), from.to_string(), to.to_string());
+        if let Some(update) = apply_to_file(project_root, &other, vec![Box::new(composes)])? {
+            updates.push(update);
+        }
+    }
+
+    for importer in source_files(project_root, SCRIPT_EXTENSIONS) {
+        let Ok((_, source, _, tree)) = parse_file(project_root, &importer) else {
+            continue;
+        };
+        let specifiers = import_specifiers_of(&source, &tree, &importer, &stylesheet);
+        if specifiers.is_empty() {
+            continue;
+        }
+        let usages = RenameModuleClassUsages::new(specifiers, from.to_string(), to.to_string());
+        if let Some(update) = apply_to_file(project_root, &importer, vec![Box::new(usages)])? {
+            updates.push(update);
+        }
+    }
+    Ok(updates)
+}
+
+fn plan_extract_i18n(
+    project_root: &Path,
+    params: ExtractI18nParams,
+) -> Result<Vec<FileUpdate>, String> {
+    let component = resolve_within_root(project_root, &params.file)?;
+    let locale_path = resolve_within_root(project_root, &params.locale_file)?;
+    let locale_file = &params.locale_file;
+    let locale = if locale_path.exists() {
+        Some(
+            std::fs::read_to_string(&locale_path)
+                .map_err(|e| format!("{locale_file}: Failed to read: {e}"))?,
+        )
+    } else {
+        None
+    };
+    let messages_root: Value = match &locale {
+        Some(text) => {
+            serde_json::from_str(text).map_err(|e| format!("{locale_file}: Not valid JSON: {e}"))?
+        }
+        None => json!({}),
+    };
+    let namespace: Vec<&str> = params
+        .namespace
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .collect();
+    let existing: BTreeMap<String, String> = namespace
+        .iter()
+        .try_fold(&messages_root, |value, key| value.get(key))
+        .and_then(Value::as_object)
+        .map(|messages| {
+            messages
+                .iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let attributes = params
+        .attributes
+        .unwrap_or_else(|| DEFAULT_ATTRIBUTES.iter().map(|a| a.to_string()).collect());
+    let extract = ExtractI18n::new(
+        params.namespace.clone(),
+        attributes,
+        params.function,
+        params.import_from,
+        params.component,
+    )
+    .with_existing(existing);
+    let (file, source, _, tree) = parse_file(project_root, &component)?;
+    // Only the messages are needed here; apply_to_file styles the edits
+    let messages = extract
+        .extract(&source, &tree, &StyleConfig::default())
+        .map_err(|e| format!("{file}: {e}"))?
+        .messages;
+    let mut updates = vec![
+        apply_to_file(project_root, &component, vec![Box::new(extract)])?
+            .ok_or_else(|| format!("{file}: no text to extract"))?,
+    ];
+    if messages.is_empty() {
+        return Ok(updates);
+    }
+
+    let update = match locale {
+        Some(mut content) => {
+            // One key at a time: sibling inserts would touch the same comma
+            let mut changes = Vec::new();
+            let (_, _, lang, _) = parse_file(project_root, &locale_path)?;
+            let language = lang.ts_language();
+            let (style, style_warnings) = resolve_style_config(&locale_path, Some(project_root));
+            for (key, text) in &messages {
+                let pointer: String = namespace
+                    .iter()
+                    .copied()
+                    .chain([key.as_str()])
+                    .map(|s| format!("/{}", s.replace('~', "~0").replace('/', "~1")))
+                    .collect();
+                let tree = ast_surgeon_core::validate::parse_best_effort(&content, &language)
+                    .map_err(|e| format!("{locale_file}: Parse error: {e:?}"))?;
+                let set: Box<dyn Executable> =
+                    Box::new(JsonSet::new(pointer, Value::String(text.clone())));
+                let result = ast_surgeon_core::execute_executables(
+                    &content,
+                    &tree,
+                    &[set],
+                    &language,
+                    lang.parse_check(),
+                    &style,
+                )
+                .map_err(|e| format!("{locale_file}: {e}"))?;
+                content = result.content;
+                changes.extend(result.changes);
+            }
+            FileUpdate {
+                file: relative_path(project_root, &locale_path),
+                content,
+                changes,
+                warnings: style_warnings,
+                kind: UpdateKind::Edit,
+            }
+        }
+        None => {
+            let mut value = json!({});
+            let mut object = &mut value;
+            for segment in &namespace {
+                object = object
+                    .as_object_mut()
+                    .expect("namespace levels are objects")
+                    .entry(segment.to_string())
+                    .or_insert_with(|| json!({}));
+            }
+            for (key, text) in &messages {
+                object[key] = Value::String(text.clone());
+            }
+            let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())? + "\n";
+            FileUpdate {
+                file: relative_path(project_root, &locale_path),
+                content,
+                changes: vec![ChangeDescription {
+                    kind: "created locale file".to_string(),
+                    line: 1,
+                    column: 1,
+                    summary: format!("added {} messages", messages.len()),
+                }],
+                warnings: vec![],
+                kind: UpdateKind::Create,
+            }
+        }
+    };
+    updates.push(update);
+    Ok(updates)
+}
+
+fn plan_migrate_to_typescript(project_root: &Path, file: &str) -> Result<Vec<FileUpdate>, String> {
+    let path = resolve_within_root(project_root, file)?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if !matches!(extension, "js" | "jsx") {
+        return Err(format!("{file}: expected a .js or .jsx file"));
+    }
+    let source =
+        std::fs::read_to_string(&path).map_err(|e| format!("{file}: Failed to read: {e}"))?;
+
+    // JSX in a .js file only parses with the TSX grammar
+    let typescript = detect_language("a.ts")
+        .expect("ts is supported")
+        .ts_language();
+    let tsx = detect_language("a.tsx")
+        .expect("tsx is supported")
+        .ts_language();
+    let parse = |language: &Language| {
+        ast_surgeon_core::validate::parse_best_effort(&source, language)
+            .map_err(|e| format!("{file}: Parse error: {e:?}"))
+    };
+    let new_extension = if extension == "jsx" {
+        "tsx"
+    } else {
+        let tree = parse(&typescript)?;
+        if tree.root_node().has_error() && !parse(&tsx)?.root_node().has_error() {
+            "tsx"
+        } else {
+            "ts"
+        }
+    };
+    let language = if new_extension == "tsx" {
+        tsx
+    } else {
+        typescript
+    };
+    let new_path = path.with_extension(new_extension);
+    let new_file = relative_path(project_root, &new_path);
+    if new_path.exists() {
+        return Err(format!("{new_file}: already exists"));
+    }
+
+    let tree = parse(&language)?;
+    let migrate: Box<dyn Executable> = Box::new(MigrateToTypescript::new());
+    let (style, style_warnings) = resolve_style_config(&new_path, Some(project_root));
+    let result = ast_surgeon_core::execute_executables(
+        &source,
+        &tree,
+        &[migrate],
+        &language,
+        ParseCheck::Clean,
+        &style,
+    )
+    .map_err(|e| format!("{file}: {e}"))?;
+    let mut changes = result.changes;
+    changes.push(ChangeDescription {
+        kind: "renamed file".to_string(),
+        line: 1,
+        column: 1,
+        summary: format!("{} -> {}", relative_path(project_root, &path), new_file),
+    });
+    let mut updates = vec![
+        FileUpdate {
+            file: new_file,
+            content: result.content,
+            changes,
+            warnings: style_warnings.into_iter().chain(result.warnings).collect(),
+            kind: UpdateKind::Create,
+        },
+        FileUpdate {
+            file: relative_path(project_root, &path),
+            content: String::new(),
+            changes: vec![],
+            warnings: vec![],
+            kind: UpdateKind::Delete,
+        },
+    ];
+
+    // Extensionless imports keep resolving. `./math.js` ones would point at
+    // a file that is gone: they spell out the new extension in projects
+    // that opt into `allowImportingTsExtensions`, and drop it otherwise.
+    let old_suffix = format!(".{extension}");
+    for importer in source_files(project_root, SCRIPT_EXTENSIONS) {
+        if importer == path {
+            continue;
+        }
+        let Ok((_, importer_source, _, importer_tree)) = parse_file(project_root, &importer) else {
+            continue;
+        };
+        let keep_extension = allows_importing_ts_extensions(project_root, &importer);
+        let rewrites: Vec<Box<dyn Executable>> =
+            import_specifiers_of(&importer_source, &importer_tree, &importer, &path)
+                .into_iter()
+                .filter(|specifier| specifier.ends_with(&old_suffix))
+                .map(|specifier| {
+                    let stem = &specifier[..specifier.len() - old_suffix.len()];
+                    let renamed = match keep_extension {
+                        true => format!("{stem}.{new_extension}"),
+                        false => stem.to_string(),
+                    };
+                    Box::new(UpdateImportPaths::new(specifier, renamed, MatchMode::Exact))
+                        as Box<dyn Executable>
+                })
+                .collect();
+        if rewrites.is_empty() {
+            continue;
+        }
+        if let Some(update) = apply_to_file(project_root, &importer, rewrites)? {
+            updates.push(update);
+        }
+    }
+    Ok(updates)
+}
+
+/// Whether the nearest `tsconfig.json` above `file` (within the project)
+/// sets `compilerOptions.allowImportingTsExtensions`. `extends` is not
+/// followed.
+fn allows_importing_ts_extensions(project_root: &Path, file: &Path) -> bool {
+    let Some(tsconfig) = file
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(project_root))
+        .map(|dir| dir.join("tsconfig.json"))
+        .find(|candidate| candidate.is_file())
+    else {
+        return false;
+    };
+    let Ok((_, source, _, tree)) = parse_file(project_root, &tsconfig) else {
+        return false;
+    };
+    let mut cursor = tree.walk();
+    let root = tree
+        .root_node()
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "object");
+    let options = json_member(root, "compilerOptions", &source);
+    json_member(options, "allowImportingTsExtensions", &source).is_some_and(|v| v.kind() == "true")
+}
+
+/// Value of `key` in a JSON object node.
+fn json_member<'a>(object: Option<Node<'a>>, key: &str, source: &str) -> Option<Node<'a>> {
+    let object = object.filter(|o| o.kind() == "object")?;
+    let mut cursor = object.walk();
+    let value = object
+        .named_children(&mut cursor)
+        .filter(|pair| pair.kind() == "pair")
+        .find(|pair| {
+            pair.child_by_field_name("key").is_some_and(|k| {
+                source[k.start_byte()..k.end_byte()].trim_matches('"') == key
+            })
+        })
+        .and_then(|pair| pair.child_by_field_name("value"));
+    value
+}
+
+/// Module specifiers in `source` that resolve to `target`: import and
+/// `export .. from` sources, `require()`/`import()` arguments, and in
+/// stylesheets `@import` / `composes: .. from` strings.
+fn import_specifiers_of(source: &str, tree: &Tree, importer: &Path, target: &Path) -> Vec<String> {
+    let mut strings = Vec::new();
+    collect_module_strings(tree.root_node(), source, &mut strings);
+    let mut specifiers: Vec<String> = Vec::new();
+    for node in strings {
+        let text = &source[node.start_byte()..node.end_byte()];
+        let Some(specifier) = text.get(1..text.len().saturating_sub(1)) else {
+            continue;
+        };
+        if !specifiers.iter().any(|s| s == specifier)
+            && resolve_relative_import(importer, specifier).as_deref() == Some(target)
+        {
+            specifiers.push(specifier.to_string());
+        }
+    }
+    specifiers
+}
+
+/// String nodes naming a module, in document order.
+fn collect_module_strings<'a>(node: Node<'a>, source: &str, out: &mut Vec<Node<'a>>) {
+    match node.kind() {
+        "import_statement" | "export_statement" => {
+            if let Some(source) = node.child_by_field_name("source") {
+                out.push(source);
+            }
+        }
+        "call_expression" => {
+            let is_loader = node.child_by_field_name("function").is_some_and(|f| {
+                f.kind() == "import" || &source[f.start_byte()..f.end_byte()] == "require"
+            });
+            let first = node
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(0))
+                .filter(|arg| arg.kind() == "string");
+            if let (true, Some(first)) = (is_loader, first) {
+                out.push(first);
+            }
+        }
+        "string_value" => out.push(node),
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_module_strings(child, source, out);
+    }
+}
+
+/// Run executables on a file; `None` when nothing changed.
+fn apply_to_file(
+    project_root: &Path,
+    path: &Path,
+    executables: Vec<Box<dyn Executable>>,
+) -> Result<Option<FileUpdate>, String> {
+    let (file, source, lang, tree) = parse_file(project_root, path)?;
+    let (style, style_warnings) = resolve_style_config(path, Some(project_root));
+    let result = ast_surgeon_core::execute_executables(
+        &source,
+        &tree,
+        &executables,
+        &lang.ts_language(),
+        lang.parse_check(),
+        &style,
+    )
+    .map_err(|e| format!("{file}: {e}"))?;
+    if result.content == source && result.warnings.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(FileUpdate {
+        file,
+        content: result.content,
+        changes: result.changes,
+        warnings: style_warnings.into_iter().chain(result.warnings).collect(),
+        kind: UpdateKind::Edit,
+    }))
+}
+
+/// Read and parse a file; returns its project-relative path too.
+fn parse_file(
+    project_root: &Path,
+    path: &Path,
+) -> Result<(String, String, SupportedLanguage, Tree), String> {
+    let file = relative_path(project_root, path);
+    let source =
+        std::fs::read_to_string(path).map_err(|e| format!("{file}: Failed to read: {e}"))?;
+    let lang = detect_language(&file).map_err(|_| format!("{file}: Unsupported file type"))?;
+    let tree = lang
+        .parse(&source)
+        .map_err(|e| format!("{file}: Parse error: {e:?}"))?;
+    Ok((file, source, lang, tree))
+}
+
+fn relative_path(project_root: &Path, path: &Path) -> String {
+    path.strip_prefix(project_root)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rename_css_module_class_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/components")).unwrap();
+        std::fs::write(
+            root.join("src/components/Card.module.css"),
+            ".card {\n  padding: 4px;\n}\n\n.card:hover {\n  color: red;\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/components/Card.tsx"),
+            "import styles from './Card.module.css';\n\nexport const Card = () => <div className={styles.card} />;\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/Page.tsx"),
+            "import s from \"./components/Card.module.css\";\nconst c = s['card'];\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/Other.tsx"), "const card = 1;\n").unwrap();
+
+        let op = json!({
+            "op": "rename_css_module_class",
+            "file": "src/components/Card.module.css",
+            "from": ".card",
+            "to": "tile"
+        });
+        assert!(is_cross_file(&op));
+        let updates = plan(&op, root).unwrap();
+        let files: Vec<&str> = updates.iter().map(|u| u.file.as_str()).collect();
+        assert_eq!(
+            files,
+            vec![
+                "src/components/Card.module.css",
+                "src/Page.tsx",
+                "src/components/Card.tsx"
+            ]
+        );
+
+        commit(root, &updates).unwrap();
+        let css = std::fs::read_to_string(root.join("src/components/Card.module.css")).unwrap();
+        assert!(css.contains(".tile {") && css.contains(".tile:hover {"));
+        let card = std::fs::read_to_string(root.join("src/components/Card.tsx")).unwrap();
+        assert!(card.contains("className={styles.tile}"));
+        let page = std::fs::read_to_string(root.join("src/Page.tsx")).unwrap();
+        assert!(page.contains("s['tile']"));
+    }
+
+    #[test]
+    fn test_import_specifiers_ignore_quotes_in_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let target = root.join("src/math.js");
+        std::fs::write(&target, "export const add = (a, b) => a + b;\n").unwrap();
+        let importer = root.join("src/app.js");
+        std::fs::write(
+            &importer,
+            "// don't inline these: './math.js' is shared\nimport { add } from './math.js';\nexport * from \"./math.js\";\nconst lazy = () => import('./math');\nconst { sub } = require('./math.js');\nconst label = './math.js';\n",
+        )
+        .unwrap();
+
+        let (_, source, _, tree) = parse_file(root, &importer).unwrap();
+        assert_eq!(
+            import_specifiers_of(&source, &tree, &importer, &target),
+            vec!["./math.js", "./math"]
+        );
+    }
+
+    #[test]
+    fn test_rename_scss_module_class_with_composes_and_destructuring() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/Card.module.scss"),
+            ".card {\n  padding: 4px;\n  &:hover {\n    color: red;\n  }\n}\n\n.title {\n  composes: card;\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/Page.module.css"),
+            ".hero {\n  composes: card from './Card.module.scss';\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/Card.tsx"),
+            "import styles from './Card.module.scss';\n\nconst { card } = styles;\nexport const Card = () => <div className={card} />;\nexport const all = { ...styles };\n",
+        )
+        .unwrap();
+
+        let op = json!({
+            "op": "rename_css_module_class",
+            "file": "src/Card.module.scss",
+            "from": "card",
+            "to": "tile"
+        });
+        let updates = plan(&op, root).unwrap();
+        let files: Vec<&str> = updates.iter().map(|u| u.file.as_str()).collect();
+        assert_eq!(
+            files,
+            vec!["src/Card.module.scss", "src/Page.module.css", "src/Card.tsx"]
+        );
+        let card_update = &updates[2];
+        assert_eq!(card_update.warnings.len(), 1);
+        assert!(card_update.warnings[0].contains("Line 5: `styles` is used as a value"));
+
+        commit(root, &updates).unwrap();
+        let scss = std::fs::read_to_string(root.join("src/Card.module.scss")).unwrap();
+        assert_eq!(
+            scss,
+            ".tile {\n  padding: 4px;\n  &:hover {\n    color: red;\n  }\n}\n\n.title {\n  composes: tile;\n}\n"
+        );
+        let page = std::fs::read_to_string(root.join("src/Page.module.css")).unwrap();
+        assert_eq!(
+            page,
+            ".hero {\n  composes: tile from './Card.module.scss';\n}\n"
+        );
+        let card = std::fs::read_to_string(root.join("src/Card.tsx")).unwrap();
+        assert!(card.contains("const { tile: card } = styles;"));
+        assert!(card.contains("className={card}"));
+    }
+
+    #[test]
+    fn test_extract_i18n_writes_component_and_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let component =
+            "export function Home() {\n  return <h1 title=\"Greeting\">Welcome back</h1>;\n}\n";
+        std::fs::write(root.join("src/Home.tsx"), component).unwrap();
+
+        let op = json!({
+            "op": "extract_i18n",
+            "file": "src/Home.tsx",
+            "locale_file": "public/locales/en/common.json",
+            "namespace": "home"
+        });
+        assert!(is_cross_file(&op));
+        let updates = plan(&op, root).unwrap();
+        commit(root, &updates).unwrap();
+        let home = std::fs::read_to_string(root.join("src/Home.tsx")).unwrap();
+        assert!(home.contains("<h1 title={t('home.greeting')}>{t('home.welcomeBack')}</h1>"));
+        assert!(home.contains("const { t } = useTranslation();"));
+        let locale = std::fs::read_to_string(root.join("public/locales/en/common.json")).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&locale).unwrap(),
+            json!({"home": {"greeting": "Greeting", "welcomeBack": "Welcome back"}})
+        );
+
+        // Existing locale files are edited in place, keeping other keys
+        std::fs::write(
+            root.join("src/Home.tsx"),
+            component.replace("Greeting", "Hi"),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("public/locales/en/common.json"),
+            "{\n  \"home\": {\n    \"welcomeBack\": \"Welcome back\"\n  }\n}\n",
+        )
+        .unwrap();
+        let updates = plan(&op, root).unwrap();
+        assert!(updates.iter().all(|u| u.kind == UpdateKind::Edit));
+        commit(root, &updates).unwrap();
+        let locale = std::fs::read_to_string(root.join("public/locales/en/common.json")).unwrap();
+        assert_eq!(
+            locale,
+            "{\n  \"home\": {\n    \"welcomeBack\": \"Welcome back\",\n    \"hi\": \"Hi\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_migrate_to_typescript_renames_and_updates_importers() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/math.js"),
+            "const assert = require('assert');\n\n/** @param {number} a */\nexport function double(a) {\n  return a * 2;\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/main.mjs"),
+            "import { double } from './math.js';\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/other.js"),
+            "import { double } from './math';\n",
+        )
+        .unwrap();
+
+        let op = json!({"op": "migrate_to_typescript", "file": "src/math.js"});
+        assert!(is_cross_file(&op));
+        let updates = plan(&op, root).unwrap();
+        let files: Vec<(&str, UpdateKind)> =
+            updates.iter().map(|u| (u.file.as_str(), u.kind)).collect();
+        assert_eq!(
+            files,
+            vec![
+                ("src/math.ts", UpdateKind::Create),
+                ("src/math.js", UpdateKind::Delete),
+                ("src/main.mjs", UpdateKind::Edit),
+            ]
+        );
+
+        commit(root, &updates).unwrap();
+        assert!(!root.join("src/math.js").exists());
+        let math = std::fs::read_to_string(root.join("src/math.ts")).unwrap();
+        assert_eq!(
+            math,
+            "import assert from 'assert';\n\n/** @param a */\nexport function double(a: number) {\n  return a * 2;\n}\n"
+        );
+        let main = std::fs::read_to_string(root.join("src/main.mjs")).unwrap();
+        assert_eq!(main, "import { double } from './math';\n");
+        let other = std::fs::read_to_string(root.join("src/other.js")).unwrap();
+        assert_eq!(other, "import { double } from './math';\n");
+    }
+
+    #[test]
+    fn test_migrate_to_typescript_with_ts_extension_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("tsconfig.json"),
+            "// generated\n{\n  \"compilerOptions\": {\n    \"allowImportingTsExtensions\": true\n  }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/math.js"), "export const one = 1;\n").unwrap();
+        std::fs::write(
+            root.join("src/main.ts"),
+            "import { one } from './math.js';\nimport { one as uno } from './math';\n",
+        )
+        .unwrap();
+
+        let op = json!({"op": "migrate_to_typescript", "file": "src/math.js"});
+        let updates = plan(&op, root).unwrap();
+        assert_eq!(updates.last().unwrap().file, "src/main.ts");
+        commit(root, &updates).unwrap();
+        let main = std::fs::read_to_string(root.join("src/main.ts")).unwrap();
+        assert_eq!(
+            main,
+            "import { one } from './math.ts';\nimport { one as uno } from './math';\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_missing_class_fails_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("A.module.css"), ".a {}\n").unwrap();
+        let op = json!({
+            "op": "rename_css_module_class",
+            "file": "A.module.css",
+            "from": "missing",
+            "to": "b"
+        });
+        assert!(plan(&op, root).is_err());
+    }
+}
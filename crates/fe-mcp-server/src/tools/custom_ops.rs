@@ -0,0 +1,135 @@
+//! Project-defined operations.
+//!
+//! Each `.fe-tools/operations/<name>.json` file holds one
+//! [`CustomOperationDef`]: a tree-sitter query, rewrite templates per
+//! capture and guards. They are loaded when the server starts and called
+//! like built-in operations, with the definition's name as `op` and its
+//! parameters as extra fields:
+//!
+//! ```json
+//! { "op": "console_to_logger", "file": "src/**/*.ts", "logger": "log" }
+//! ```
+
+use ast_surgeon_core::operations::custom::CustomOperationDef;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Where operation definitions live, relative to the project root.
+pub const OPERATIONS_DIR: &str = ".fe-tools/operations";
+
+/// Load every definition in the project, sorted by name. `name` defaults
+/// to the file stem. Invalid files and definitions that would shadow a
+/// name in `reserved` are logged and skipped.
+pub fn load_custom_operations(project_root: &Path, reserved: &[&str]) -> Vec<CustomOperationDef> {
+    let Ok(entries) = std::fs::read_dir(project_root.join(OPERATIONS_DIR)) else {
+        return vec![];
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .collect();
+    paths.sort();
+
+    let mut defs: Vec<CustomOperationDef> = Vec::new();
+    for path in paths {
+        match load_definition(&path) {
+            Ok(def) if reserved.contains(&def.name.as_str()) => {
+                tracing::warn!(
+                    "{}: '{}' is a built-in operation, skipping",
+                    path.display(),
+                    def.name
+                );
+            }
+            Ok(def) if defs.iter().any(|d| d.name == def.name) => {
+                tracing::warn!(
+                    "{}: duplicate operation '{}', skipping",
+                    path.display(),
+                    def.name
+                );
+            }
+            Ok(def) => defs.push(def),
+            Err(e) => tracing::warn!("{}: {e}", path.display()),
+        }
+    }
+    defs.sort_by(|a, b| a.name.cmp(&b.name));
+    defs
+}
+
+/// Turn a call of a project-defined operation into the `custom` operation
+/// the surgeon core executes; other operations are returned unchanged.
+pub fn resolve(op_value: Value, defs: &[CustomOperationDef]) -> Value {
+    let Some(def) = op_value
+        .get("op")
+        .and_then(|v| v.as_str())
+        .and_then(|name| defs.iter().find(|d| d.name == name))
+    else {
+        return op_value;
+    };
+    let Value::Object(mut fields) = op_value else {
+        return op_value;
+    };
+    fields.remove("op");
+    let file = fields.remove("file");
+    json!({
+        "op": "custom",
+        "file": file,
+        "definition": def,
+        "params": fields,
+    })
+}
+
+// --- Helper functions ---
+
+fn load_definition(path: &Path) -> Result<CustomOperationDef, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read: {e}"))?;
+    let mut value: Value =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid operation definition: {e}"))?;
+    if let (Value::Object(fields), Some(stem)) = (&mut value, path.file_stem()) {
+        fields
+            .entry("name")
+            .or_insert_with(|| Value::String(stem.to_string_lossy().into_owned()));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid operation definition: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_resolve_custom_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let ops_dir = root.join(OPERATIONS_DIR);
+        std::fs::create_dir_all(&ops_dir).unwrap();
+        std::fs::write(
+            ops_dir.join("console_to_logger.json"),
+            r#"{"query": "(call_expression) @call", "rewrite": {"call": "${logger}()"},
+                "params": {"logger": {"default": "logger"}}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            ops_dir.join("shadow.json"),
+            r#"{"name": "make_async", "query": "(x) @x", "rewrite": {}}"#,
+        )
+        .unwrap();
+        std::fs::write(ops_dir.join("broken.json"), "{").unwrap();
+
+        let defs = load_custom_operations(root, &["make_async"]);
+        assert_eq!(defs.len(), 1);
+        assert_eq!(defs[0].name, "console_to_logger");
+
+        let resolved = resolve(
+            json!({"op": "console_to_logger", "file": "src/a.ts", "logger": "log"}),
+            &defs,
+        );
+        assert_eq!(resolved["op"], "custom");
+        assert_eq!(resolved["file"], "src/a.ts");
+        assert_eq!(resolved["params"], json!({"logger": "log"}));
+        assert_eq!(resolved["definition"]["name"], "console_to_logger");
+
+        let builtin = json!({"op": "make_async", "file": "a.ts", "function_name": "f"});
+        assert_eq!(resolve(builtin.clone(), &defs), builtin);
+    }
+}
@@ -0,0 +1,163 @@
+use super::Tool;
+use crate::mcp::{ToolCallResult, ToolDefinition};
+use ast_surgeon_core::operations::directives::check_directives;
+use ast_surgeon_lang::registry::detect_language;
+use fe_common::fs_utils::{resolve_within_root, source_files};
+use fe_verify::types::{DiagnosticItem, StepResult};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// Files that can hold components, hooks or server actions.
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs"];
+
+pub struct DirectivesTool;
+
+#[derive(Deserialize, Default)]
+struct DirectivesParams {
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+impl DirectivesTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for DirectivesTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "fe_check_directives".into(),
+            description: "Check React Server Components directives (Next.js App Router). \
+                Flags client-only hooks (useState, useEffect, ...) and event handlers in \
+                files without 'use client', and server-only imports (next/headers, fs, \
+                server-only, node:*) in 'use client' files. Custom hooks (useX functions) \
+                are not flagged. Fix with fe_surgeon add_directive / remove_directive."
+                .into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "files": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files to check. Omit to check every script file in the project."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, params: Value, project_root: &Path) -> ToolCallResult {
+        let params: DirectivesParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolCallResult::error(format!("Invalid parameters: {e}")),
+        };
+        let files = if params.files.is_empty() {
+            source_files(project_root, SCRIPT_EXTENSIONS)
+        } else {
+            let mut resolved = Vec::new();
+            for file in &params.files {
+                match resolve_within_root(project_root, file) {
+                    Ok(path) => resolved.push(path),
+                    Err(e) => return ToolCallResult::error(e),
+                }
+            }
+            resolved
+        };
+
+        let step = check_files(project_root, &files);
+        match serde_json::to_string_pretty(&step) {
+            Ok(json) => ToolCallResult::text(json),
+            Err(e) => ToolCallResult::error(format!("Serialization error: {e}")),
+        }
+    }
+}
+
+/// Run the directive check on each file; unreadable or unsupported files
+/// are skipped.
+fn check_files(project_root: &Path, files: &[PathBuf]) -> StepResult {
+    let mut errors = Vec::new();
+    for path in files {
+        let file = path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let Ok(language) = detect_language(&file) else {
+            continue;
+        };
+        if !language.is_script() {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let Ok(tree) =
+            ast_surgeon_core::validate::parse_best_effort(&source, &language.ts_language())
+        else {
+            continue;
+        };
+        for issue in check_directives(&source, &tree) {
+            let suggestion = if issue.rule == "server-import-in-client" {
+                "Move this code to a server component or a 'use server' action"
+            } else {
+                "Add the directive with fe_surgeon add_directive (\"use client\"), or move \
+                 the interactive part into a client component"
+            };
+            errors.push(DiagnosticItem {
+                file: file.clone(),
+                line: issue.line,
+                column: issue.column,
+                message: issue.message,
+                rule: Some(issue.rule.to_string()),
+                severity: "error".to_string(),
+                suggestion: Some(suggestion.to_string()),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        return StepResult::pass();
+    }
+    StepResult {
+        status: "fail".to_string(),
+        error_count: errors.len(),
+        warning_count: 0,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_files_reports_issues_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        std::fs::write(
+            root.join("app/page.tsx"),
+            "import { useState } from 'react'\n\nexport default function Page() {\n  const [n] = useState(0)\n  return <p>{n}</p>\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("app/Counter.tsx"),
+            "'use client'\n\nimport { useState } from 'react'\n\nexport function Counter() {\n  return <button onClick={() => {}} />\n}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("app/page.module.css"), ".a { color: red; }\n").unwrap();
+
+        let step = check_files(root, &source_files(root, SCRIPT_EXTENSIONS));
+        assert_eq!(step.status, "fail");
+        assert_eq!(step.error_count, 1);
+        assert_eq!(step.errors[0].file, "app/page.tsx");
+        assert_eq!(step.errors[0].line, 4);
+        assert_eq!(
+            step.errors[0].rule.as_deref(),
+            Some("client-hook-without-directive")
+        );
+    }
+}
@@ -1,4 +1,9 @@
 pub mod batch;
+pub mod cross_file;
+pub mod custom_ops;
+pub mod directives;
+pub mod query;
+pub mod recipes;
 pub mod surgeon;
 pub mod verify;
 
@@ -21,10 +26,13 @@ pub struct ToolRegistry {
 
 impl ToolRegistry {
     pub fn new(project_root: &Path) -> Self {
-        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
-        tools.push(Box::new(verify::VerifyTool::new(project_root)));
-        tools.push(Box::new(batch::BatchTool::new(project_root)));
-        tools.push(Box::new(surgeon::SurgeonTool::new()));
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(verify::VerifyTool::new(project_root)),
+            Box::new(batch::BatchTool::new(project_root)),
+            Box::new(surgeon::SurgeonTool::new(project_root)),
+            Box::new(directives::DirectivesTool::new()),
+            Box::new(query::QueryTool::new()),
+        ];
         Self { tools }
     }
 
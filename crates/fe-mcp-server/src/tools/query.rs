@@ -0,0 +1,182 @@
+use super::Tool;
+use crate::mcp::{ToolCallResult, ToolDefinition};
+use ast_surgeon_lang::registry::detect_language;
+use ast_surgeon_lang::SupportedLanguage;
+use fe_common::fs_utils::{glob_files, is_glob, resolve_within_root};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+pub struct QueryTool;
+
+#[derive(Deserialize)]
+struct QueryParams {
+    query: String,
+    files: Vec<String>,
+    #[serde(default)]
+    embedded: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Capture {
+    file: String,
+    line: usize,
+    column: usize,
+    capture: String,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct QueryResult {
+    captures: Vec<Capture>,
+    /// Files that could not be read or parsed, with the reason.
+    errors: Vec<String>,
+}
+
+impl QueryTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl Tool for QueryTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "fe_query".into(),
+            description: "Run a tree-sitter query on files and return its captures with \
+                line/column (1-based). Use it to find code by structure before editing, e.g. \
+                '(call_expression function: (identifier) @fn)'. With 'embedded' set to \
+                'css' or 'html', the query runs inside tagged templates of script files \
+                instead (styled-components / emotion styles, lit html)."
+                .into(),
+            input_schema: json!({
+                "type": "object",
+                "required": ["query", "files"],
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Query in tree-sitter S-expression syntax."
+                    },
+                    "files": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Files (relative to project root) or globs such as 'src/**/*.tsx'."
+                    },
+                    "embedded": {
+                        "type": "string",
+                        "enum": ["css", "html"],
+                        "description": "Query templates of this language inside script files."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, params: Value, project_root: &Path) -> ToolCallResult {
+        let params: QueryParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolCallResult::error(format!("Invalid parameters: {e}")),
+        };
+        let embedded = match params.embedded.as_deref().map(SupportedLanguage::from_str) {
+            Some(Ok(lang)) => Some(lang),
+            Some(Err(e)) => return ToolCallResult::error(e.to_string()),
+            None => None,
+        };
+
+        let mut paths = Vec::new();
+        for file in &params.files {
+            let found = if is_glob(project_root, file) {
+                glob_files(project_root, file)
+            } else {
+                resolve_within_root(project_root, file).map(|p| vec![p])
+            };
+            match found {
+                Ok(found) => paths.extend(found),
+                Err(e) => return ToolCallResult::error(e),
+            }
+        }
+
+        let result = query_files(project_root, &paths, &params.query, embedded);
+        match serde_json::to_string_pretty(&result) {
+            Ok(json) => ToolCallResult::text(json),
+            Err(e) => ToolCallResult::error(format!("Serialization error: {e}")),
+        }
+    }
+}
+
+fn query_files(
+    project_root: &Path,
+    paths: &[PathBuf],
+    query: &str,
+    embedded: Option<SupportedLanguage>,
+) -> QueryResult {
+    let mut result = QueryResult {
+        captures: Vec::new(),
+        errors: Vec::new(),
+    };
+    for path in paths {
+        let file = path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let found = detect_language(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|lang| {
+                let source =
+                    std::fs::read_to_string(path).map_err(|e| format!("Failed to read: {e}"))?;
+                ast_surgeon_lang::query_source(&source, lang, query, embedded)
+                    .map_err(|e| e.to_string())
+            });
+        let captures = match found {
+            Ok(found) => found,
+            Err(e) => {
+                result.errors.push(format!("{file}: {e}"));
+                continue;
+            }
+        };
+        for c in captures {
+            result.captures.push(Capture {
+                file: file.clone(),
+                line: c.line,
+                column: c.column,
+                capture: c.name,
+                text: c.text,
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_files_reports_captures_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/Button.tsx"),
+            "const Button = styled.button`\n  color: red;\n`\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/notes.txt"), "color: red;\n").unwrap();
+
+        let paths = vec![root.join("src/Button.tsx"), root.join("src/notes.txt")];
+        let result = query_files(
+            root,
+            &paths,
+            "(declaration (property_name) @property)",
+            Some(SupportedLanguage::Css),
+        );
+        assert_eq!(result.captures.len(), 1);
+        assert_eq!(result.captures[0].file, "src/Button.tsx");
+        assert_eq!((result.captures[0].line, result.captures[0].column), (2, 3));
+        assert_eq!(result.captures[0].text, "color");
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("src/notes.txt:"));
+    }
+}
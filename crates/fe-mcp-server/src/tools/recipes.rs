@@ -0,0 +1,223 @@
+//! Named codemod recipes.
+//!
+//! A recipe is a JSON file in `.fe-tools/recipes/` holding a parameterized
+//! list of surgeon operations:
+//!
+//! ```json
+//! {
+//!   "description": "Rename a hook across a directory",
+//!   "params": { "from": {}, "to": {}, "dir": { "default": "src" } },
+//!   "operations": [
+//!     { "op": "rename_symbol", "file": "${dir}/**/*.tsx", "from": "${from}", "to": "${to}" }
+//!   ]
+//! }
+//! ```
+//!
+//! The recipe name is the file stem. `${param}` is replaced inside every
+//! string; a string that is exactly `${param}` takes the argument's JSON
+//! value, so booleans and arrays can be passed through.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Where recipes live, relative to the project root.
+pub const RECIPES_DIR: &str = ".fe-tools/recipes";
+
+#[derive(Debug, Deserialize)]
+pub struct Recipe {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, RecipeParam>,
+    pub operations: Vec<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RecipeParam {
+    #[serde(default)]
+    pub description: String,
+    /// Used when the caller omits the parameter; required otherwise.
+    #[serde(default)]
+    pub default: Option<Value>,
+}
+
+/// Every recipe in the project, sorted by name. Unreadable or invalid
+/// files are skipped.
+pub fn load_recipes(project_root: &Path) -> Vec<Recipe> {
+    let Ok(entries) = std::fs::read_dir(project_root.join(RECIPES_DIR)) else {
+        return vec![];
+    };
+    let mut recipes: Vec<Recipe> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?;
+            load_recipe(project_root, name).ok()
+        })
+        .collect();
+    recipes.sort_by(|a, b| a.name.cmp(&b.name));
+    recipes
+}
+
+/// Read and parse `.fe-tools/recipes/<name>.json`.
+pub fn load_recipe(project_root: &Path, name: &str) -> Result<Recipe, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid recipe name '{name}'"));
+    }
+    let path = project_root.join(RECIPES_DIR).join(format!("{name}.json"));
+    let text = std::fs::read_to_string(&path)
+        .map_err(|_| format!("Recipe '{name}' not found in {RECIPES_DIR}"))?;
+    let mut recipe: Recipe = serde_json::from_str(&text)
+        .map_err(|e| format!("{RECIPES_DIR}/{name}.json: Invalid recipe: {e}"))?;
+    recipe.name = name.to_string();
+    Ok(recipe)
+}
+
+impl Recipe {
+    /// The recipe's operations with `${param}` placeholders filled in.
+    pub fn expand(&self, args: &Map<String, Value>) -> Result<Vec<Value>, String> {
+        if let Some(unknown) = args.keys().find(|k| !self.params.contains_key(*k)) {
+            return Err(format!(
+                "Recipe '{}' has no parameter '{unknown}'",
+                self.name
+            ));
+        }
+        let mut values = Map::new();
+        for (name, param) in &self.params {
+            let value = args
+                .get(name)
+                .or(param.default.as_ref())
+                .ok_or_else(|| format!("Recipe '{}' requires parameter '{name}'", self.name))?;
+            values.insert(name.clone(), value.clone());
+        }
+        self.operations
+            .iter()
+            .map(|op| substitute(op, &values).map_err(|e| format!("Recipe '{}': {e}", self.name)))
+            .collect()
+    }
+
+    /// One-line summary for the tool schema.
+    pub fn summary(&self) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(name, p)| {
+                let mut param = match &p.default {
+                    Some(default) => format!("{name}={default}"),
+                    None => name.clone(),
+                };
+                if !p.description.is_empty() {
+                    param.push_str(&format!(" [{}]", p.description));
+                }
+                param
+            })
+            .collect();
+        let mut summary = format!("{}({})", self.name, params.join(", "));
+        if !self.description.is_empty() {
+            summary.push_str(": ");
+            summary.push_str(&self.description);
+        }
+        summary
+    }
+}
+
+// --- Helper functions ---
+
+fn substitute(value: &Value, params: &Map<String, Value>) -> Result<Value, String> {
+    match value {
+        Value::String(text) => {
+            if let Some(name) = text.strip_prefix("${").and_then(|t| t.strip_suffix('}')) {
+                if !name.contains(['$', '{', '}']) {
+                    return lookup(name, params).cloned();
+                }
+            }
+            let mut result = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("${") {
+                let end = rest[start..]
+                    .find('}')
+                    .ok_or_else(|| format!("Unclosed placeholder in '{text}'"))?;
+                result.push_str(&rest[..start]);
+                match lookup(&rest[start + 2..start + end], params)? {
+                    Value::String(s) => result.push_str(s),
+                    other => result.push_str(&other.to_string()),
+                }
+                rest = &rest[start + end + 1..];
+            }
+            result.push_str(rest);
+            Ok(Value::String(result))
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| substitute(item, params))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(k, v)| substitute(v, params).map(|v| (k.clone(), v)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+fn lookup<'a>(name: &str, params: &'a Map<String, Value>) -> Result<&'a Value, String> {
+    params
+        .get(name)
+        .ok_or_else(|| format!("Unknown placeholder '${{{name}}}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_load_and_expand_recipe() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(RECIPES_DIR)).unwrap();
+        std::fs::write(
+            root.join(RECIPES_DIR).join("rename-hook.json"),
+            r#"{
+              "description": "Rename a hook everywhere",
+              "params": {"from": {}, "to": {"description": "new name"}, "dir": {"default": "src"}, "typed": {"default": false}},
+              "operations": [
+                {"op": "rename_symbol", "file": "${dir}/**/*.tsx", "from": "${from}", "to": "${to}"},
+                {"op": "add_import", "file": "${dir}/App.tsx", "source": "./hooks/${to}", "specifiers": ["${to}"], "type_only": "${typed}"}
+              ]
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(root.join(RECIPES_DIR).join("broken.json"), "{").unwrap();
+
+        let recipes = load_recipes(root);
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(
+            recipes[0].summary(),
+            "rename-hook(dir=\"src\", from, to [new name], typed=false): Rename a hook everywhere"
+        );
+
+        let args = json!({"from": "useAuth", "to": "useSession"});
+        let ops = recipes[0].expand(args.as_object().unwrap()).unwrap();
+        assert_eq!(
+            ops,
+            vec![
+                json!({"op": "rename_symbol", "file": "src/**/*.tsx", "from": "useAuth", "to": "useSession"}),
+                json!({"op": "add_import", "file": "src/App.tsx", "source": "./hooks/useSession", "specifiers": ["useSession"], "type_only": false}),
+            ]
+        );
+
+        let missing = json!({"from": "useAuth"});
+        assert!(recipes[0].expand(missing.as_object().unwrap()).is_err());
+        let unknown = json!({"from": "a", "to": "b", "extra": 1});
+        assert!(recipes[0].expand(unknown.as_object().unwrap()).is_err());
+        assert!(load_recipe(root, "missing").is_err());
+        assert!(load_recipe(root, "../secrets").is_err());
+    }
+}
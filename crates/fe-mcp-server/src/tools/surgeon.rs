@@ -1,19 +1,80 @@
+use super::cross_file;
+use super::custom_ops;
+use super::recipes;
 use super::Tool;
 use crate::mcp::{ToolCallResult, ToolDefinition};
-use ast_surgeon_core::operations::{ChangeDescription, Operation, OperationError};
+use ast_surgeon_core::format::StyleConfig;
+use ast_surgeon_core::operations::custom::CustomOperationDef;
+use ast_surgeon_core::operations::{ChangeDescription, Operation, OperationError, OperationResult};
 use ast_surgeon_lang::registry::detect_language;
+use ast_surgeon_lang::style::resolve_style_config;
+use ast_surgeon_lang::SupportedLanguage;
 use serde::{Deserialize, Serialize};
+use fe_common::fs_utils::{glob_files, is_glob};
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
-pub struct SurgeonTool;
+/// Operations built into the surgeon core and the cross-file planner.
+pub const BUILTIN_OPS: &[&str] = &[
+    "rename_symbol", "add_import", "remove_import", "update_import_paths", "add_parameter",
+    "remove_parameter", "make_async", "wrap_in_block", "extract_to_variable", "add_type_member",
+    "remove_type_member", "set_member_optional", "add_union_member", "add_enum_member",
+    "extract_inline_type", "add_class_member", "remove_class_member", "rename_class_member",
+    "convert_function_style", "promise_to_async", "class_to_function_component",
+    "vue_add_to_setup", "vue_wrap_in_ref", "svelte_add_reactive", "svelte_add_store",
+    "add_rule", "remove_rule", "set_declaration", "remove_declaration", "rename_selector",
+    "set_custom_property", "json_set", "json_remove", "json_append", "add_dependency",
+    "config_set", "config_remove", "config_append", "add_directive", "remove_directive",
+    "cjs_to_esm", "add_test_case", "skip_test", "only_test", "remove_test_case",
+    "upsert_doc_comment", "fix_type_imports", "rename_css_module_class", "extract_i18n",
+    "migrate_to_typescript",
+];
+
+/// Default cap on the number of files glob patterns may expand to.
+const DEFAULT_MAX_FILES: usize = 100;
+
+pub struct SurgeonTool {
+    project_root: PathBuf,
+    /// Operations defined in `.fe-tools/operations/`, loaded at startup.
+    custom_ops: Vec<CustomOperationDef>,
+}
 
 #[derive(Deserialize)]
 struct SurgeonParams {
+    #[serde(default)]
     operations: Vec<Value>,
+    /// Name of a recipe in `.fe-tools/recipes/`, run before `operations`.
+    #[serde(default)]
+    recipe: Option<String>,
+    #[serde(default)]
+    params: serde_json::Map<String, Value>,
     #[serde(default)]
     dry_run: bool,
+    #[serde(default = "default_max_files")]
+    max_files: usize,
+}
+
+fn default_max_files() -> usize {
+    DEFAULT_MAX_FILES
+}
+
+/// `name(param, param=default): description` for the tool schema.
+fn custom_op_summary(def: &CustomOperationDef) -> String {
+    let params: Vec<String> = def
+        .params
+        .iter()
+        .map(|(name, p)| match &p.default {
+            Some(default) => format!("{name}='{default}'"),
+            None => name.clone(),
+        })
+        .collect();
+    let mut summary = format!("{}({})", def.name, params.join(", "));
+    if !def.description.is_empty() {
+        summary.push_str(": ");
+        summary.push_str(&def.description);
+    }
+    summary
 }
 
 #[derive(Serialize)]
@@ -21,10 +82,26 @@ struct SurgeonResult {
     status: String,
     files_modified: Vec<String>,
     changes: Vec<FileChanges>,
+    /// Files matched by a glob that the operations did not apply to.
+    files_skipped: Vec<SkippedFile>,
     warnings: Vec<String>,
     dry_run: bool,
 }
 
+#[derive(Serialize)]
+struct SkippedFile {
+    file: String,
+    reason: String,
+}
+
+/// Operations grouped by target file, with glob `file` patterns expanded.
+struct FileGroups {
+    ops_by_file: BTreeMap<String, Vec<Value>>,
+    /// Files reached through a glob: a missing target there is a skip.
+    globbed: HashSet<String>,
+    warnings: Vec<String>,
+}
+
 #[derive(Serialize)]
 struct FileChanges {
     file: String,
@@ -33,25 +110,101 @@ struct FileChanges {
 }
 
 impl SurgeonTool {
-    pub fn new() -> Self {
-        Self
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+            custom_ops: custom_ops::load_custom_operations(project_root, BUILTIN_OPS),
+        }
     }
 }
 
 #[async_trait::async_trait]
 impl Tool for SurgeonTool {
     fn definition(&self) -> ToolDefinition {
+        let recipes = recipes::load_recipes(&self.project_root);
+        let recipe_names: Vec<&str> = recipes.iter().map(|r| r.name.as_str()).collect();
+        let recipe_description = if recipes.is_empty() {
+            format!("Run a recipe from {}/ (none defined in this project).", recipes::RECIPES_DIR)
+        } else {
+            let summaries: Vec<String> = recipes.iter().map(|r| r.summary()).collect();
+            format!(
+                "Run a recipe from {}/ before 'operations'. Available: {}",
+                recipes::RECIPES_DIR,
+                summaries.join("; ")
+            )
+        };
+        let mut recipe_schema = json!({"type": "string", "description": recipe_description});
+        if !recipe_names.is_empty() {
+            recipe_schema["enum"] = json!(recipe_names);
+        }
+
+        let mut op_names: Vec<&str> = BUILTIN_OPS.to_vec();
+        op_names.extend(self.custom_ops.iter().map(|d| d.name.as_str()));
+        let custom_description = if self.custom_ops.is_empty() {
+            String::new()
+        } else {
+            let custom: Vec<String> = self.custom_ops.iter().map(custom_op_summary).collect();
+            format!(
+                " Project operations (from {}/): {}.",
+                custom_ops::OPERATIONS_DIR,
+                custom.join("; ")
+            )
+        };
+
         ToolDefinition {
             name: "fe_surgeon".into(),
             description: "Apply structured code operations instead of rewriting entire files. \
                 Operations: rename_symbol, add_import, remove_import, update_import_paths, \
                 add_parameter, remove_parameter, make_async, wrap_in_block, \
-                extract_to_variable. Faster and safer than generating modified source text — \
-                no syntax errors possible. Each operation must specify a 'file' field."
-                .into(),
+                extract_to_variable, add_type_member, remove_type_member, \
+                set_member_optional, add_union_member, add_enum_member, \
+                extract_inline_type, add_class_member, remove_class_member, \
+                rename_class_member, convert_function_style, promise_to_async, \
+                class_to_function_component, vue_add_to_setup, vue_wrap_in_ref \
+                (.vue files: script ops run in <script setup>, CSS ops in <style>), \
+                svelte_add_reactive ($: or $derived, detected), svelte_add_store, \
+                add_rule, remove_rule, set_declaration, remove_declaration, rename_selector, \
+                set_custom_property (CSS; in .ts/.tsx files they edit css`` / styled.x`` \
+                templates, picked by 'template': variable name), \
+                json_set, json_remove, json_append (JSON pointer addressed, comments kept), \
+                add_dependency (package.json, kept sorted), \
+                config_set, config_remove, config_append (dotted 'path' into the exported \
+                config object of vite/next/tailwind/jest configs; config_append dedupes and \
+                imports the plugin factory from 'import_from'), \
+                add_directive, remove_directive ('use client' / 'use server' at the top of \
+                the file, above imports), \
+                cjs_to_esm (top-level require calls to imports and module.exports / \
+                exports.x to exports; dynamic or nested requires are left and reported), \
+                add_test_case, skip_test, only_test, remove_test_case (Jest/Vitest tests \
+                addressed by 'describe_path' / 'test_path' in the reporter's 'Outer > Inner > \
+                title' form; add_test_case creates missing describes), \
+                upsert_doc_comment (creates or updates the /** */ comment of a function, \
+                class or interface; keeps the text, syncs @param with the parameters and \
+                adds or drops @returns), \
+                fix_type_imports (for verbatimModuleSyntax: imports used only in type \
+                positions become `import type` statements, or inline `type` specifiers with \
+                style 'inline'), \
+                rename_css_module_class (renames the class in a .module.css/.module.scss \
+                file, composes references to it, and every styles.x / styles['x'] access \
+                or const { x } = styles destructuring in its importers; other uses of the \
+                styles binding are reported as warnings), \
+                extract_i18n (replaces JSX text and string attributes with t('namespace.key'), \
+                adds useTranslation, and writes the messages to 'locale_file' in the same \
+                transaction), \
+                migrate_to_typescript (renames a .js/.jsx file to .ts/.tsx, turns require and \
+                module.exports into import/export, types parameters from JSDoc @param tags, \
+                literal defaults or any, and rewrites importers' './x.js' to './x.ts' when \
+                tsconfig sets allowImportingTsExtensions, to './x' otherwise). \
+                Faster and safer than generating modified source text — \
+                no syntax errors possible. Each operation must specify a 'file' field; a glob \
+                ('src/**/*.stories.tsx') applies it to every matching file, up to 'max_files'. \
+                Operations on the same file apply in order, each seeing the previous ones' \
+                changes; cross-file operations run first. Project recipes (named, parameterized operation lists) run with 'recipe' and \
+                'params'."
+                .to_string()
+                + &custom_description,
             input_schema: json!({
                 "type": "object",
-                "required": ["operations"],
                 "properties": {
                     "operations": {
                         "type": "array",
@@ -61,21 +214,27 @@ impl Tool for SurgeonTool {
                             "properties": {
                                 "op": {
                                     "type": "string",
-                                    "enum": [
-                                        "rename_symbol", "add_import", "remove_import",
-                                        "update_import_paths", "add_parameter", "remove_parameter",
-                                        "make_async", "wrap_in_block", "extract_to_variable"
-                                    ]
+                                    "enum": op_names
                                 },
-                                "file": {"type": "string", "description": "Target file (relative to project root)."}
+                                "file": {"type": "string", "description": "Target file (relative to project root), or a glob such as 'src/**/*.stories.tsx' to apply the operation to every matching file (.gitignore honored; files where the target is missing are reported in files_skipped)."}
                             }
                         },
                         "description": "Array of AST operations to apply. See operation-specific fields in the enum variants."
                     },
+                    "recipe": recipe_schema,
+                    "params": {
+                        "type": "object",
+                        "description": "Arguments for the recipe's ${param} placeholders."
+                    },
                     "dry_run": {
                         "type": "boolean",
                         "default": false,
                         "description": "Preview changes without writing to disk."
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "default": DEFAULT_MAX_FILES,
+                        "description": "Refuse to run when glob 'file' patterns match more files than this."
                     }
                 }
             }),
@@ -87,31 +246,73 @@ impl Tool for SurgeonTool {
             Ok(p) => p,
             Err(e) => return ToolCallResult::error(format!("Invalid parameters: {e}")),
         };
-
-        // Group operations by file
-        let mut ops_by_file: HashMap<String, Vec<Value>> = HashMap::new();
-        for op_value in &params.operations {
-            let file = match op_value.get("file").and_then(Value::as_str) {
-                Some(f) => f.to_string(),
-                None => {
-                    return ToolCallResult::error(
-                        "Every operation must have a 'file' field".into(),
-                    );
-                }
-            };
-            ops_by_file.entry(file).or_default().push(op_value.clone());
+        let mut operations = match &params.recipe {
+            Some(name) => match recipes::load_recipe(project_root, name)
+                .and_then(|recipe| recipe.expand(&params.params))
+            {
+                Ok(ops) => ops,
+                Err(e) => return ToolCallResult::error(e),
+            },
+            None => Vec::new(),
+        };
+        operations.extend(params.operations);
+        let operations: Vec<Value> = operations
+            .into_iter()
+            .map(|v| custom_ops::resolve(v, &self.custom_ops))
+            .collect();
+        if operations.is_empty() {
+            return ToolCallResult::error("Provide 'operations' or a 'recipe'".into());
         }
 
         let mut result = SurgeonResult {
             status: "success".into(),
             files_modified: Vec::new(),
             changes: Vec::new(),
+            files_skipped: Vec::new(),
             warnings: Vec::new(),
             dry_run: params.dry_run,
         };
 
+        // Cross-file operations run first, each as its own transaction
+        let (cross_file_ops, file_ops): (Vec<&Value>, Vec<&Value>) = operations
+            .iter()
+            .partition(|v| cross_file::is_cross_file(v));
+        for op_value in cross_file_ops {
+            let updates = match cross_file::plan(op_value, project_root) {
+                Ok(u) => u,
+                Err(e) => {
+                    result.status = "error".into();
+                    result.warnings.push(e);
+                    continue;
+                }
+            };
+            if !params.dry_run {
+                if let Err(e) = cross_file::commit(project_root, &updates) {
+                    result.status = "error".into();
+                    result.warnings.push(e);
+                    continue;
+                }
+            }
+            for update in updates {
+                result.files_modified.push(update.file.clone());
+                result.changes.push(FileChanges {
+                    file: update.file,
+                    changes: update.changes,
+                    warnings: update.warnings,
+                });
+            }
+        }
+
+        // Group operations by file, expanding globs
+        let groups = match group_by_file(&file_ops, project_root, params.max_files) {
+            Ok(g) => g,
+            Err(e) => return ToolCallResult::error(e),
+        };
+        result.warnings.extend(groups.warnings);
+
         // Process each file
-        for (file_path, op_values) in &ops_by_file {
+        for (file_path, op_values) in &groups.ops_by_file {
+            let globbed = groups.globbed.contains(file_path);
             let abs_path = project_root.join(file_path);
 
             // Read source
@@ -127,6 +328,13 @@ impl Tool for SurgeonTool {
             // Detect language from file extension
             let lang = match detect_language(file_path) {
                 Ok(l) => l,
+                Err(_) if globbed => {
+                    result.files_skipped.push(SkippedFile {
+                        file: file_path.clone(),
+                        reason: "unsupported file type".into(),
+                    });
+                    continue;
+                }
                 Err(_) => {
                     result.warnings.push(format!(
                         "{file_path}: Unsupported file type, skipping"
@@ -134,7 +342,6 @@ impl Tool for SurgeonTool {
                     continue;
                 }
             };
-            let ts_language = lang.ts_language();
 
             // Parse operations from JSON into the Operation enum
             let ops: Vec<Operation> = match op_values
@@ -152,20 +359,12 @@ impl Tool for SurgeonTool {
                 }
             };
 
-            // Parse source with tree-sitter
-            let tree = match ast_surgeon_core::validate::parse_best_effort(&source, &ts_language) {
-                Ok(t) => t,
-                Err(e) => {
-                    result.status = "error".into();
-                    result
-                        .warnings
-                        .push(format!("{file_path}: Parse error: {e:?}"));
-                    continue;
-                }
-            };
+            // Execute operations in the file's configured style; components
+            // are split into blocks first
+            let (style, style_warnings) = resolve_style_config(&abs_path, Some(project_root));
+            let executed = execute_in_order(&source, lang, &ops, &style);
 
-            // Execute operations
-            match ast_surgeon_core::execute_operations(&source, &tree, &ops, &ts_language) {
+            match executed {
                 Ok(op_result) => {
                     // Write back if not dry_run
                     if !params.dry_run {
@@ -182,7 +381,16 @@ impl Tool for SurgeonTool {
                     result.changes.push(FileChanges {
                         file: file_path.clone(),
                         changes: op_result.changes,
-                        warnings: op_result.warnings,
+                        warnings: style_warnings
+                            .into_iter()
+                            .chain(op_result.warnings)
+                            .collect(),
+                    });
+                }
+                Err(OperationError::TargetNotFound { description }) if globbed => {
+                    result.files_skipped.push(SkippedFile {
+                        file: file_path.clone(),
+                        reason: format!("target not found: {description}"),
                     });
                 }
                 Err(e) => {
@@ -204,6 +412,12 @@ impl Tool for SurgeonTool {
                                 errors.len()
                             )
                         }
+                        OperationError::Blocked { warnings } => {
+                            for w in warnings {
+                                result.warnings.push(format!("{file_path}: blocked: {w}"));
+                            }
+                            format!("{file_path}: Operation blocked, no changes made")
+                        }
                         _ => format!("{file_path}: {e}"),
                     };
                     result.warnings.push(msg);
@@ -217,3 +431,210 @@ impl Tool for SurgeonTool {
         }
     }
 }
+
+/// Apply a file's operations one after another, re-parsing in between, so
+/// each sees the changes of the ones before it (a recipe can rename a
+/// function and then add a parameter to it under its new name).
+fn execute_in_order(
+    source: &str,
+    lang: SupportedLanguage,
+    ops: &[Operation],
+    style: &StyleConfig,
+) -> Result<OperationResult, OperationError> {
+    let mut result = OperationResult {
+        content: source.to_string(),
+        changes: Vec::new(),
+        warnings: Vec::new(),
+    };
+    for op in ops {
+        let step = ast_surgeon_lang::execute_operations(
+            &result.content,
+            lang,
+            std::slice::from_ref(op),
+            style,
+        )?;
+        result.content = step.content;
+        result.changes.extend(step.changes);
+        result.warnings.extend(step.warnings);
+    }
+    Ok(result)
+}
+
+/// Group operations by their `file`; a glob pattern adds the operation to
+/// every matching file (honoring `.gitignore`). Fails when an operation
+/// has no file or the globs match more than `max_files` files.
+fn group_by_file(
+    op_values: &[&Value],
+    project_root: &Path,
+    max_files: usize,
+) -> Result<FileGroups, String> {
+    let mut groups = FileGroups {
+        ops_by_file: BTreeMap::new(),
+        globbed: HashSet::new(),
+        warnings: Vec::new(),
+    };
+    for op_value in op_values {
+        let Some(file) = op_value.get("file").and_then(Value::as_str) else {
+            return Err("Every operation must have a 'file' field".into());
+        };
+        if !is_glob(project_root, file) {
+            groups
+                .ops_by_file
+                .entry(file.to_string())
+                .or_default()
+                .push((*op_value).clone());
+            continue;
+        }
+
+        let matches = glob_files(project_root, file)?;
+        if matches.is_empty() {
+            groups.warnings.push(format!("{file}: no files match"));
+        }
+        for path in matches {
+            let relative = path
+                .strip_prefix(project_root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let mut op_value = (*op_value).clone();
+            op_value["file"] = Value::String(relative.clone());
+            groups.globbed.insert(relative.clone());
+            groups.ops_by_file.entry(relative).or_default().push(op_value);
+        }
+        if groups.globbed.len() > max_files {
+            return Err(format!(
+                "File patterns match more than max_files ({max_files}) files; \
+                 narrow the pattern or raise max_files"
+            ));
+        }
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_file_expands_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/ui")).unwrap();
+        std::fs::write(root.join("src/ui/Button.stories.tsx"), "").unwrap();
+        std::fs::write(root.join("src/ui/Card.stories.tsx"), "").unwrap();
+        std::fs::write(root.join("src/ui/Card.tsx"), "").unwrap();
+
+        let glob = json!({"op": "remove_import", "file": "src/**/*.stories.tsx", "source": "x"});
+        let single = json!({"op": "make_async", "file": "src/ui/Card.tsx", "function_name": "f"});
+        let groups = group_by_file(&[&glob, &single], root, 10).unwrap();
+        let files: Vec<&String> = groups.ops_by_file.keys().collect();
+        assert_eq!(
+            files,
+            vec![
+                "src/ui/Button.stories.tsx",
+                "src/ui/Card.stories.tsx",
+                "src/ui/Card.tsx"
+            ]
+        );
+        assert_eq!(
+            groups.ops_by_file["src/ui/Card.stories.tsx"][0]["file"],
+            "src/ui/Card.stories.tsx"
+        );
+        assert!(groups.globbed.contains("src/ui/Button.stories.tsx"));
+        assert!(!groups.globbed.contains("src/ui/Card.tsx"));
+
+        let route =
+            json!({"op": "make_async", "file": "app/[slug]/page.tsx", "function_name": "f"});
+        std::fs::create_dir_all(root.join("app/[slug]")).unwrap();
+        std::fs::write(root.join("app/[slug]/page.tsx"), "").unwrap();
+        let groups = group_by_file(&[&route], root, 10).unwrap();
+        assert!(groups.ops_by_file.contains_key("app/[slug]/page.tsx"));
+        assert!(groups.globbed.is_empty() && groups.warnings.is_empty());
+
+        assert!(group_by_file(&[&glob], root, 1).is_err());
+        let none = json!({"op": "remove_import", "file": "lib/*.ts", "source": "x"});
+        let groups = group_by_file(&[&none], root, 10).unwrap();
+        assert_eq!(groups.warnings, vec!["lib/*.ts: no files match"]);
+    }
+
+    #[tokio::test]
+    async fn test_recipe_runs_custom_operation() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(custom_ops::OPERATIONS_DIR)).unwrap();
+        std::fs::create_dir_all(root.join(recipes::RECIPES_DIR)).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join(custom_ops::OPERATIONS_DIR).join("console_to_logger.json"),
+            r#"{
+              "query": "(call_expression function: (member_expression object: (identifier) @obj property: (property_identifier) @method) arguments: (arguments) @args) @call",
+              "guards": [{"capture": "obj", "equals": "console"}],
+              "rewrite": {"call": "${logger}.{{method}}{{args}}"},
+              "params": {"logger": {"default": "logger"}}
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join(recipes::RECIPES_DIR).join("quiet.json"),
+            r#"{"params": {"files": {}},
+                "operations": [{"op": "console_to_logger", "file": "${files}", "logger": "log"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("src/a.ts"), "console.warn('a');\n").unwrap();
+        std::fs::write(root.join("src/b.ts"), "foo();\n").unwrap();
+
+        let tool = SurgeonTool::new(root);
+        let schema = tool.definition().input_schema;
+        let op_names = &schema["properties"]["operations"]["items"]["properties"]["op"]["enum"];
+        assert!(op_names
+            .as_array()
+            .unwrap()
+            .contains(&json!("console_to_logger")));
+        assert_eq!(schema["properties"]["recipe"]["enum"], json!(["quiet"]));
+
+        let result = tool
+            .call(
+                json!({"recipe": "quiet", "params": {"files": "src/*.ts"}}),
+                root,
+            )
+            .await;
+        assert!(!result.is_error, "{}", result.content[0].text);
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/a.ts")).unwrap(),
+            "log.warn('a');\n"
+        );
+        let output: Value = serde_json::from_str(&result.content[0].text).unwrap();
+        assert_eq!(output["files_skipped"][0]["file"], "src/b.ts");
+    }
+
+    #[tokio::test]
+    async fn test_recipe_steps_see_earlier_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(recipes::RECIPES_DIR)).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join(recipes::RECIPES_DIR).join("session.json"),
+            r#"{"operations": [
+                {"op": "rename_symbol", "file": "src/auth.ts", "from": "useAuth", "to": "useSession"},
+                {"op": "add_parameter", "file": "src/auth.ts", "function_name": "useSession", "param_name": "options", "param_type": "Options"}
+            ]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/auth.ts"),
+            "export function useAuth() {\n  return null;\n}\n",
+        )
+        .unwrap();
+
+        let tool = SurgeonTool::new(root);
+        let result = tool.call(json!({"recipe": "session"}), root).await;
+        assert!(!result.is_error, "{}", result.content[0].text);
+        let output: Value = serde_json::from_str(&result.content[0].text).unwrap();
+        assert_eq!(output["status"], "success", "{}", output);
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/auth.ts")).unwrap(),
+            "export function useSession(options: Options) {\n  return null;\n}\n"
+        );
+    }
+}
@@ -16,8 +16,10 @@ struct VerifyParams {
     #[serde(default)]
     files: Vec<String>,
     #[serde(default)]
+    #[allow(dead_code)]
     checks: Vec<String>,
     #[serde(default)]
+    #[allow(dead_code)]
     fix: bool,
 }
 